@@ -1,7 +1,77 @@
+use std::{collections::HashSet, fs::OpenOptions, io::Write, sync::RwLock};
+
+use glob::Pattern;
 use serde::Deserialize;
 
+use crate::{
+    sherlock_error,
+    utils::{
+        config::ConfigGuard,
+        errors::{SherlockError, SherlockErrorType},
+        soft_delete::StagedChange,
+    },
+};
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct AppLauncher {
     #[serde(default)]
     pub use_keywords: bool,
 }
+
+/// Data indices of entries hidden during this session. Hidden entries are only removed from
+/// the results; the `sherlockignore` file takes over after the next restart.
+static HIDDEN: RwLock<Option<HashSet<usize>>> = RwLock::new(None);
+
+pub fn hidden_entries() -> HashSet<usize> {
+    HIDDEN
+        .read()
+        .ok()
+        .and_then(|hidden| hidden.clone())
+        .unwrap_or_default()
+}
+
+/// Forgets the hidden entries once the launchers are loaded again, as their indices are no
+/// longer valid and the `sherlockignore` file already covers them
+pub fn clear_hidden() {
+    if let Ok(mut hidden) = HIDDEN.write() {
+        *hidden = None;
+    }
+}
+
+/// Hides an application and adds it to the `sherlockignore` file
+pub struct HideEntry {
+    pub name: String,
+    pub index: usize,
+}
+
+impl StagedChange for HideEntry {
+    fn describe(&self) -> String {
+        format!("Hidden '{}'", self.name)
+    }
+    fn apply(&self) {
+        if let Ok(mut hidden) = HIDDEN.write() {
+            hidden.get_or_insert_default().insert(self.index);
+        }
+    }
+    fn revert(&self) {
+        if let Ok(mut hidden) = HIDDEN.write() {
+            hidden.get_or_insert_default().remove(&self.index);
+        }
+    }
+    fn persist(&self) -> Result<(), SherlockError> {
+        let path = ConfigGuard::read()?.files.ignore.clone();
+        let write_err = |e: std::io::Error| {
+            sherlock_error!(
+                SherlockErrorType::FileWriteError(path.clone()),
+                e.to_string()
+            )
+        };
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .map_err(write_err)?;
+        writeln!(file, "{}", Pattern::escape(&self.name.to_lowercase())).map_err(write_err)
+    }
+}
@@ -7,21 +7,100 @@ use std::path::PathBuf;
 use std::sync::Arc;
 use zbus::blocking::{Connection, Proxy};
 
+use crate::loader::utils::ApplicationAction;
 use crate::sherlock_error;
 use crate::utils::config::ConfigGuard;
 use crate::utils::errors::{SherlockError, SherlockErrorType};
+use crate::utils::stats;
 
 use super::utils::MprisData;
 
 #[derive(Debug, Clone, Default)]
 pub struct MusicPlayerLauncher {}
+
+/// Change of the volume per volume up/down action
+const VOLUME_STEP: f64 = 0.1;
+/// Offset of a seek step in microseconds
+pub const SEEK_STEP: i64 = 10_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MprisCommand {
+    PlayPause,
+    Next,
+    Previous,
+    Raise,
+    VolumeUp,
+    VolumeDown,
+    /// Relative seek in microseconds
+    Seek(i64),
+}
+impl MprisCommand {
+    /// Maps the method of a context menu action to its command
+    pub fn from_method(method: &str) -> Option<Self> {
+        match method {
+            "mpris_next" => Some(Self::Next),
+            "mpris_previous" => Some(Self::Previous),
+            "mpris_raise" => Some(Self::Raise),
+            "mpris_volume_up" => Some(Self::VolumeUp),
+            "mpris_volume_down" => Some(Self::VolumeDown),
+            _ => None,
+        }
+    }
+    pub fn run(&self, player: &str) -> Result<(), SherlockError> {
+        match self {
+            Self::PlayPause => MprisData::playpause(player),
+            Self::Next => MprisData::next(player),
+            Self::Previous => MprisData::previous(player),
+            Self::Raise => MprisData::raise(player),
+            Self::VolumeUp => MprisData::change_volume(player, VOLUME_STEP),
+            Self::VolumeDown => MprisData::change_volume(player, -VOLUME_STEP),
+            Self::Seek(offset) => MprisData::seek(player, *offset),
+        }
+    }
+}
+
 impl MprisData {
-    /// Get current image
+    /// Context menu entries for controlling `player`
+    pub fn context_actions(player: &str) -> Arc<[Arc<ApplicationAction>]> {
+        [
+            ("Next", "mpris_next"),
+            ("Previous", "mpris_previous"),
+            ("Volume Up", "mpris_volume_up"),
+            ("Volume Down", "mpris_volume_down"),
+            ("Raise Player", "mpris_raise"),
+        ]
+        .into_iter()
+        .map(|(name, method)| {
+            let mut action = ApplicationAction::new(method);
+            action.name = Some(name.into());
+            action.exec = Some(player.to_string());
+            Arc::new(action)
+        })
+        .collect()
+    }
+    /// Playback progress between 0 and 1
+    pub fn progress(&self) -> Option<f32> {
+        let length = self.metadata.length_us().filter(|l| *l > 0)?;
+        let position = self.position?;
+        Some((position as f32 / length as f32).clamp(0.0, 1.0))
+    }
+    /// Get current image without touching the network
+    /// Only returns covers that are either cached already or local files
+    pub fn get_cached_image(&self) -> Option<Arc<Image>> {
+        let art_url = self.metadata.art.as_ref()?;
+        let loc = art_url.split("/").last()?;
+        let bytes = match Self::read_cached_cover(loc) {
+            Ok(b) => b,
+            Err(_) if art_url.starts_with("file") => Self::read_image_file(art_url).ok()?,
+            Err(_) => return None,
+        };
+        Self::image_from_bytes(bytes)
+    }
+    /// Loads the cover for `art_url`, downloading and caching it if required
     /// Return:
     /// image: Pixbuf
     /// was_cached: bool
-    pub async fn get_image(&self) -> Option<(Arc<Image>, bool)> {
-        let art_url = self.metadata.art.as_ref()?;
+    pub async fn fetch_cover(art_url: &str) -> Option<(Arc<Image>, bool)> {
         let loc = art_url.split("/").last()?.to_string();
         let mut was_cached = true;
         let bytes = match Self::read_cached_cover(&loc) {
@@ -39,12 +118,15 @@ impl MprisData {
             }
         };
 
+        stats::record_async_fetch(was_cached);
+        Self::image_from_bytes(bytes).map(|image| (image, was_cached))
+    }
+    fn image_from_bytes(bytes: Vec<u8>) -> Option<Arc<Image>> {
         // mimetype parsing
         let mime = identify_image_type(&bytes);
         let format = ImageFormat::from_mime_type(mime)?;
 
-        let image_arc = Arc::new(Image::from_bytes(format, bytes));
-        Some((image_arc, was_cached))
+        Some(Arc::new(Image::from_bytes(format, bytes)))
     }
     fn cache_cover(image: &Bytes, loc: &str) -> Result<(), SherlockError> {
         // Create dir and parents
@@ -131,32 +213,87 @@ impl MprisData {
         Ok(buffer)
     }
     pub fn playpause(player: &str) -> Result<(), SherlockError> {
-        Self::player_method(player, "PlayPause")
+        Self::player_method(player, "org.mpris.MediaPlayer2.Player", "PlayPause")
     }
     pub fn next(player: &str) -> Result<(), SherlockError> {
-        Self::player_method(player, "Next")
+        Self::player_method(player, "org.mpris.MediaPlayer2.Player", "Next")
     }
     pub fn previous(player: &str) -> Result<(), SherlockError> {
-        Self::player_method(player, "Previous")
+        Self::player_method(player, "org.mpris.MediaPlayer2.Player", "Previous")
     }
-    fn player_method(player: &str, method: &str) -> Result<(), SherlockError> {
-        let conn = Connection::session()
-            .map_err(|e| sherlock_error!(SherlockErrorType::DBusConnectionError, e.to_string()))?;
+    pub fn raise(player: &str) -> Result<(), SherlockError> {
+        Self::player_method(player, "org.mpris.MediaPlayer2", "Raise")
+    }
+    /// Returns the volume of `player` or `None` if it does not expose one
+    pub fn get_volume(player: &str) -> Option<f64> {
+        let conn = Connection::session().ok()?;
         let proxy = Proxy::new(
             &conn,
             player,
             "/org/mpris/MediaPlayer2",
             "org.mpris.MediaPlayer2.Player",
         )
-        .map_err(|e| {
+        .ok()?;
+        proxy.get_property::<f64>("Volume").ok()
+    }
+    /// Sets the volume of `player`, clamped to 0.0–1.0
+    pub fn set_volume(player: &str, volume: f64) -> Result<(), SherlockError> {
+        let conn = Connection::session()
+            .map_err(|e| sherlock_error!(SherlockErrorType::DBusConnectionError, e.to_string()))?;
+        let proxy = Self::player_proxy(&conn, player, "org.mpris.MediaPlayer2.Player", "Volume")?;
+        proxy
+            .set_property("Volume", volume.clamp(0.0, 1.0))
+            .map_err(|e| {
+                sherlock_error!(
+                    SherlockErrorType::DBusMessageSendError(format!("Volume to {}", player)),
+                    e.to_string()
+                )
+            })
+    }
+    /// Changes the volume by `delta`. Players without volume support are left untouched.
+    fn change_volume(player: &str, delta: f64) -> Result<(), SherlockError> {
+        match Self::get_volume(player) {
+            Some(volume) => Self::set_volume(player, volume + delta),
+            None => Ok(()),
+        }
+    }
+    /// Moves the playback position by `offset_micros`, backwards if negative. Players that
+    /// can't seek, e.g. live streams, are left untouched.
+    pub fn seek(player: &str, offset_micros: i64) -> Result<(), SherlockError> {
+        let conn = Connection::session()
+            .map_err(|e| sherlock_error!(SherlockErrorType::DBusConnectionError, e.to_string()))?;
+        let proxy = Self::player_proxy(&conn, player, "org.mpris.MediaPlayer2.Player", "Seek")?;
+        if !proxy.get_property::<bool>("CanSeek").unwrap_or(false) {
+            return Ok(());
+        }
+        proxy.call_method("Seek", &(offset_micros,)).map_err(|e| {
             sherlock_error!(
-                SherlockErrorType::DBusMessageConstructError(format!("PlayPause for {}", player)),
+                SherlockErrorType::DBusMessageSendError(format!("Seek to {}", player)),
                 e.to_string()
             )
         })?;
+        Ok(())
+    }
+    fn player_proxy<'a>(
+        conn: &'a Connection,
+        player: &'a str,
+        interface: &'a str,
+        method: &str,
+    ) -> Result<Proxy<'a>, SherlockError> {
+        Proxy::new(conn, player, "/org/mpris/MediaPlayer2", interface).map_err(|e| {
+            sherlock_error!(
+                SherlockErrorType::DBusMessageConstructError(format!("{} for {}", method, player)),
+                e.to_string()
+            )
+        })
+    }
+    fn player_method(player: &str, interface: &str, method: &str) -> Result<(), SherlockError> {
+        let conn = Connection::session()
+            .map_err(|e| sherlock_error!(SherlockErrorType::DBusConnectionError, e.to_string()))?;
+        let proxy = Self::player_proxy(&conn, player, interface, method)?;
         proxy.call_method(method, &()).map_err(|e| {
             sherlock_error!(
-                SherlockErrorType::DBusMessageSendError(format!("PlayPause to {}", player)),
+                SherlockErrorType::DBusMessageSendError(format!("{} to {}", method, player)),
                 e.to_string()
             )
         })?;
@@ -1,13 +1,17 @@
 use gpui::SharedString;
 use rusqlite::Connection;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 
 use crate::launcher::Launcher;
+use crate::launcher::browser_history;
+use crate::launcher::children::NameHighlight;
+use crate::launcher::provenance::Transforms;
 use crate::loader::application_loader::file_has_changed;
 use crate::loader::resolve_icon_path;
-use crate::loader::utils::{AppData, construct_search};
+use crate::loader::utils::{AppData, MatchPolicy, construct_search, folds_diacritics};
 use crate::utils::cache::BinaryCache;
 use crate::utils::errors::{SherlockError, SherlockErrorType};
 use crate::utils::files::home_dir;
@@ -16,39 +20,187 @@ use crate::{sher_log, sherlock_error};
 
 #[derive(Clone, Debug)]
 pub struct BookmarkLauncher {
+    /// A browser like `firefox` or the command starting it, or `auto` to read the bookmarks of
+    /// every browser found
     pub target_browser: String,
+    /// Also offers the most recently visited pages, up to `history_limit`
+    pub history: bool,
+    pub history_limit: usize,
 }
 impl BookmarkLauncher {
-    pub fn find_bookmarks(
-        browser: &str,
-        launcher: Arc<Launcher>,
-    ) -> Result<Vec<AppData>, SherlockError> {
-        match browser.to_lowercase().as_str() {
-            "zen" | "zen-browser" | "/opt/zen-browser-bin/zen-bin %u" => {
-                BookmarkParser::zen(launcher)
-            }
-            "brave" | "brave %u" => BookmarkParser::brave(launcher),
-            "firefox" | "/usr/lib/firefox/firefox %u" => BookmarkParser::firefox(launcher),
-            "chrome" | "google-chrome" | "/usr/bin/google-chrome-stable %u" => {
-                BookmarkParser::chrome(launcher)
+    /// Returns true if the bookmarks are gathered from every browser, which are then opened in
+    /// the default browser
+    pub fn is_auto(&self) -> bool {
+        self.target_browser.eq_ignore_ascii_case("auto")
+    }
+
+    pub fn find_bookmarks(&self, launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
+        let (browsers, mut bookmarks) = if self.is_auto() {
+            (Browser::ALL.to_vec(), BookmarkParser::auto(&launcher)?)
+        } else {
+            let browser = Browser::from_target(&self.target_browser)
+                .ok_or_else(|| unsupported_browser(&self.target_browser))?;
+            (vec![browser], browser.bookmarks(&launcher)?)
+        };
+
+        if self.history {
+            match browser_history::recent_history(
+                &self.target_browser,
+                &browsers,
+                self.history_limit,
+            ) {
+                Ok(entries) => {
+                    // pages that are bookmarked are only listed once
+                    let bookmarked: HashSet<String> =
+                        bookmarks.iter().filter_map(|b| b.exec.clone()).collect();
+                    let max_visits = entries.iter().map(|e| e.visits).max().unwrap_or(0);
+                    bookmarks.extend(
+                        entries
+                            .into_iter()
+                            .filter(|entry| !bookmarked.contains(&entry.url))
+                            .map(|entry| entry.into_child(&launcher, max_visits)),
+                    );
+                }
+                Err(e) => {
+                    let _ = sher_log!(format!("Failed to read the browser history: {e}"));
+                }
             }
-            "thorium" | "/usr/bin/thorium-browser %u" => BookmarkParser::thorium(launcher),
-            _ => {
-                sher_log!(format!(
-                    r#"Failed to gather bookmarks for browser: "{}""#,
-                    browser
-                ))?;
-                Err(sherlock_error!(
-                    SherlockErrorType::UnsupportedBrowser(browser.to_string()),
-                    format!(
-                        "The browser \"<i>{}</i>\" is either not supported or not recognized.\n\
-                        Check the \
-                        <span foreground=\"#247BA0\"><u><a href=\"https://github.com/Skxxtz/sherlock/blob/main/docs/launchers.md#bookmark-launcher\">documentation</a></u></span> \
-                        for more information.\n\
-                        ",
-                        browser
-                    )
-                ))
+        }
+        Ok(bookmarks)
+    }
+}
+
+fn unsupported_browser(browser: &str) -> SherlockError {
+    let _ = sher_log!(format!(
+        r#"Failed to gather bookmarks for browser: "{}""#,
+        browser
+    ));
+    sherlock_error!(
+        SherlockErrorType::UnsupportedBrowser(browser.to_string()),
+        format!(
+            "The browser \"<i>{}</i>\" is either not supported or not recognized.\n\
+            Check the \
+            <span foreground=\"#247BA0\"><u><a href=\"https://github.com/Skxxtz/sherlock/blob/main/docs/launchers.md#bookmark-launcher\">documentation</a></u></span> \
+            for more information.\n\
+            ",
+            browser
+        )
+    )
+}
+
+/// Browsers whose bookmarks can be read
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Browser {
+    Firefox,
+    Zen,
+    Chromium,
+    Chrome,
+    Brave,
+    Thorium,
+}
+impl Browser {
+    pub const ALL: [Browser; 6] = [
+        Self::Firefox,
+        Self::Zen,
+        Self::Chromium,
+        Self::Chrome,
+        Self::Brave,
+        Self::Thorium,
+    ];
+
+    /// Recognizes a browser by its name or the command starting it, e.g.
+    /// `/usr/lib/firefox/firefox %u`
+    fn from_target(target: &str) -> Option<Self> {
+        let program = target.split_whitespace().next()?;
+        let name = Path::new(program).file_name()?.to_str()?.to_lowercase();
+        Some(match name.as_str() {
+            "firefox" | "firefox-esr" => Self::Firefox,
+            "zen" | "zen-browser" | "zen-bin" => Self::Zen,
+            "chromium" | "chromium-browser" => Self::Chromium,
+            "chrome" | "google-chrome" | "google-chrome-stable" => Self::Chrome,
+            "brave" | "brave-browser" => Self::Brave,
+            "thorium" | "thorium-browser" => Self::Thorium,
+            _ => return None,
+        })
+    }
+
+    /// Prefix of the files kept in the cache for this browser
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Firefox => "firefox",
+            Self::Zen => "zen",
+            Self::Chromium => "chromium",
+            Self::Chrome => "chrome",
+            Self::Brave => "brave",
+            Self::Thorium => "thorium",
+        }
+    }
+
+    /// The config directory of a chromium based browser, relative to the home directory
+    pub fn chromium_dir(self) -> Option<&'static str> {
+        match self {
+            Self::Firefox | Self::Zen => None,
+            Self::Chromium => Some(".config/chromium"),
+            Self::Chrome => Some(".config/google-chrome"),
+            Self::Brave => Some(".config/BraveSoftware/Brave-Browser"),
+            Self::Thorium => Some(".config/thorium"),
+        }
+    }
+
+    /// The directories holding the profiles of a firefox based browser
+    fn mozilla_roots(self, home: &Path) -> Vec<PathBuf> {
+        match self {
+            // native, XDG (since Firefox 147), snap and flatpak installs
+            Self::Firefox => vec![
+                home.join(".mozilla/firefox"),
+                home.join(".config/mozilla/firefox"),
+                home.join("snap/firefox/common/.mozilla/firefox"),
+                home.join(".var/app/org.mozilla.firefox/.mozilla/firefox"),
+            ],
+            Self::Zen => vec![home.join(".zen")],
+            _ => Vec::new(),
+        }
+    }
+
+    /// The `places.sqlite` of a firefox based browser
+    fn places(self) -> Result<PathBuf, SherlockError> {
+        let roots = self.mozilla_roots(&home_dir()?);
+        newest_places(&roots).ok_or_else(|| {
+            sherlock_error!(
+                SherlockErrorType::FileExistError(
+                    roots
+                        .first()
+                        .cloned()
+                        .unwrap_or_default()
+                        .join("*/places.sqlite")
+                ),
+                "File does not exist"
+            )
+        })
+    }
+
+    /// The database the history of the browser is kept in, of the default profile for chromium
+    /// based browsers
+    pub fn history_database(self) -> Result<PathBuf, SherlockError> {
+        let Some(dir) = self.chromium_dir() else {
+            return self.places();
+        };
+        let path = home_dir()?.join(dir).join("Default/History");
+        if !path.is_file() {
+            return Err(sherlock_error!(
+                SherlockErrorType::FileExistError(path),
+                "File does not exist"
+            ));
+        }
+        Ok(path)
+    }
+
+    fn bookmarks(self, launcher: &Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
+        match self.chromium_dir() {
+            Some(dir) => BookmarkParser::chromium_based(launcher, dir),
+            None => {
+                let parser = MozillaSqliteParser::new(self.places()?, self.name());
+                parser.read(Arc::clone(launcher), self.name())
             }
         }
     }
@@ -56,97 +208,220 @@ impl BookmarkLauncher {
 
 struct BookmarkParser;
 impl BookmarkParser {
-    fn brave(launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
-        let path = home_dir()?.join(".config/BraveSoftware/Brave-Browser/Default/Bookmarks");
-        let data = fs::read_to_string(&path)
-            .map_err(|e| sherlock_error!(SherlockErrorType::FileReadError(path), e.to_string()))?;
-
-        ChromeParser::parse(launcher, data)
+    /// The bookmarks of every browser found, each url only once
+    fn auto(launcher: &Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
+        let mut found = false;
+        let mut seen = HashSet::new();
+        let mut bookmarks = Vec::new();
+        for browser in Browser::ALL {
+            let Ok(from_browser) = browser.bookmarks(launcher) else {
+                continue;
+            };
+            found = true;
+            bookmarks.extend(
+                from_browser
+                    .into_iter()
+                    .filter(|bookmark| seen.insert(bookmark.exec.clone())),
+            );
+        }
+        if !found {
+            return Err(sherlock_error!(
+                SherlockErrorType::UnsupportedBrowser(String::from("auto")),
+                "No bookmarks of a supported browser were found"
+            ));
+        }
+        Ok(bookmarks)
     }
-    fn thorium(launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
-        let path = home_dir()?.join(".config/thorium/Default/Bookmarks");
+    /// Reads the `Bookmarks` file of the default profile of a chromium based browser in `dir`,
+    /// relative to the home directory
+    fn chromium_based(launcher: &Arc<Launcher>, dir: &str) -> Result<Vec<AppData>, SherlockError> {
+        let path = home_dir()?.join(dir).join("Default/Bookmarks");
         let data = fs::read_to_string(&path)
             .map_err(|e| sherlock_error!(SherlockErrorType::FileReadError(path), e.to_string()))?;
-        ChromeParser::parse(launcher, data)
+        ChromeParser::parse(Arc::clone(launcher), data)
     }
-    fn chrome(launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
-        let path = home_dir()?.join(".config/google-chrome/Default/Bookmarks");
-        let data = fs::read_to_string(&path)
-            .map_err(|e| sherlock_error!(SherlockErrorType::FileReadError(path), e.to_string()))?;
-        ChromeParser::parse(launcher, data)
+}
+
+/// The most recently used `places.sqlite` of the profiles in `roots`
+fn newest_places(roots: &[PathBuf]) -> Option<PathBuf> {
+    roots
+        .iter()
+        .filter_map(|root| fs::read_dir(root).ok())
+        .flatten()
+        .filter_map(|entry| {
+            let places = entry.ok()?.path().join("places.sqlite");
+            let modified = places.metadata().ok()?.modified().ok()?;
+            Some((modified, places))
+        })
+        .max_by_key(|(modified, _)| *modified)
+        .map(|(_, places)| places)
+}
+
+/// A copy of the database `file` in the cache, named `name`, since browsers lock their
+/// databases while running. The copy is refreshed whenever the database changed.
+pub fn local_copy(file: &Path, name: &str) -> PathBuf {
+    match get_cache_dir() {
+        Ok(cache) => {
+            let target = cache.join("bookmarks").join(name);
+            copy_if_needed(file, &target);
+            target
+        }
+        Err(_) => file.to_path_buf(),
     }
+}
 
-    fn zen(launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
-        fn get_path() -> Option<PathBuf> {
-            let zen_root = home_dir().ok()?.join(".zen");
-            fs::read_dir(&zen_root)
-                .ok()?
-                .filter_map(|entry| {
-                    let path = entry.ok()?.path();
-                    if path.is_dir() && path.join("places.sqlite").exists() {
-                        Some(path.join("places.sqlite"))
-                    } else {
-                        None
-                    }
-                })
-                .next()
+/// The write-ahead log next to a database, holding changes the browser has not checkpointed
+pub fn wal_path(database: &Path) -> PathBuf {
+    with_suffix(database, "-wal")
+}
+
+fn should_update_cache(dest: &Path, source: &Path) -> bool {
+    if !dest.exists() {
+        return true;
+    }
+
+    let source_mod = fs::metadata(source)
+        .ok()
+        .and_then(|meta| meta.modified().ok());
+    let dest_mod = fs::metadata(dest)
+        .ok()
+        .and_then(|meta| meta.modified().ok());
+
+    if let (Some(source), Some(dest)) = (source_mod, dest_mod) {
+        return source > dest;
+    }
+    true
+}
+fn copy_if_needed(src: &Path, dst: &Path) {
+    let (src_wal, dst_wal) = (wal_path(src), wal_path(dst));
+    if should_update_cache(dst, src)
+        || (src_wal.exists() && should_update_cache(&dst_wal, &src_wal))
+    {
+        let _ = sher_log!(format!(
+            r#"Bookmark database "{}" is copied to "{}""#,
+            src.display(),
+            dst.display()
+        ));
+        if let Some(parent) = dst.parent() {
+            let _ = fs::create_dir_all(parent);
+        }
+        let _ = fs::remove_file(with_suffix(dst, "-shm"));
+        let _ = fs::copy(src, dst);
+        if src_wal.exists() {
+            let _ = fs::copy(&src_wal, &dst_wal);
+        } else {
+            let _ = fs::remove_file(&dst_wal);
         }
-        let path = get_path().ok_or_else(|| {
-            sherlock_error!(
-                SherlockErrorType::FileExistError(PathBuf::from("~/.zen/../places.sqlite")),
-                "File does not exist"
-            )
-        })?;
-        let parser = MozillaSqliteParser::new(path, "zen");
-        parser.read(launcher, "zen")
     }
-    fn firefox(launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
-        fn get_path() -> Option<PathBuf> {
-            let zen_root = home_dir().ok()?.join(".mozilla/firefox/");
-            fs::read_dir(&zen_root)
-                .ok()?
-                .filter_map(|entry| {
-                    let path = entry.ok()?.path();
-                    if path.is_dir() && path.join("places.sqlite").exists() {
-                        Some(path.join("places.sqlite"))
-                    } else {
-                        None
-                    }
-                })
-                .next()
+}
+
+/// A bookmark with the folders it is filed in, e.g. `Work / Docs`
+fn bookmark(launcher: &Launcher, title: &str, url: String, folder: String) -> AppData {
+    let search = if folder.is_empty() {
+        url.clone()
+    } else {
+        format!("{url};{folder}")
+    };
+    AppData {
+        name: Some(SharedString::from(title)),
+        icon: resolve_icon_path("sherlock-bookmark"),
+        icon_name: Some(String::from("sherlock-bookmark")),
+        search_string: construct_search(Some(title), &search, true),
+        exec: Some(url),
+        desktop_file: None,
+        priority: Some(launcher.priority as f32 + 1.0),
+        actions: Arc::new([]),
+        vars: vec![],
+        terminal: false,
+        transforms: Transforms::default(),
+        match_policy: MatchPolicy::default(),
+        accent: None,
+        subtitle: (!folder.is_empty()).then(|| SharedString::from(folder)),
+        confirm: false,
+        name_highlight: NameHighlight::default(),
+    }
+}
+
+/// Joins the names of nested folders, outermost first
+fn join_folders<'a>(names: impl IntoIterator<Item = &'a str>) -> String {
+    names
+        .into_iter()
+        .filter(|name| !name.is_empty())
+        .collect::<Vec<_>>()
+        .join(" / ")
+}
+
+/// A folder of the `moz_bookmarks` table
+struct Folder {
+    parent: i64,
+    title: String,
+    guid: String,
+}
+
+/// Root folders of places databases, left out of folder paths
+const MOZ_ROOTS: &[&str] = &[
+    "root________",
+    "menu________",
+    "toolbar_____",
+    "unfiled_____",
+    "mobile______",
+];
+/// Holds a folder per tag, listing the tagged bookmarks a second time
+const MOZ_TAGS: &str = "tags________";
+
+/// The folders from a root folder down to `parent`. None for the entries of the tags folder.
+fn folder_path(folders: &HashMap<i64, Folder>, mut parent: i64) -> Option<String> {
+    let mut names = Vec::new();
+    // bounded in case a broken database contains a cycle
+    for _ in 0..=folders.len() {
+        let Some(folder) = folders.get(&parent) else {
+            break;
+        };
+        if folder.guid == MOZ_TAGS {
+            return None;
         }
-        let path = get_path().ok_or_else(|| {
-            sherlock_error!(
-                SherlockErrorType::FileExistError(PathBuf::from(
-                    "~/.mozilla/firefox/../places.sqlite",
-                )),
-                "File does not exist"
-            )
-        })?;
-        let parser = MozillaSqliteParser::new(path, "firefox");
-        parser.read(launcher, "firefox")
+        if MOZ_ROOTS.contains(&folder.guid.as_str()) {
+            break;
+        }
+        names.push(folder.title.as_str());
+        parent = folder.parent;
     }
+    Some(join_folders(names.into_iter().rev()))
+}
+
+/// `path` with `suffix` appended to its file name, e.g. `places.sqlite-wal`
+fn with_suffix(path: &Path, suffix: &str) -> PathBuf {
+    let mut path = path.as_os_str().to_owned();
+    path.push(suffix);
+    PathBuf::from(path)
 }
+
 struct MozillaSqliteParser {
     path: PathBuf,
 }
 impl MozillaSqliteParser {
+    /// Works on a copy of the database, see [`local_copy`]
     fn new(file: PathBuf, prefix: &str) -> Self {
-        let path = if let Ok(cache) = get_cache_dir() {
-            let target = cache.join(format!("bookmarks/{}-places.sqlite", prefix));
-            Self::copy_if_needed(&file, &target);
-            target
-        } else {
-            file.to_path_buf()
-        };
-        Self { path }
+        Self {
+            path: local_copy(&file, &format!("{}-places.sqlite", prefix)),
+        }
     }
     fn read(&self, launcher: Arc<Launcher>, prefix: &str) -> Result<Vec<AppData>, SherlockError> {
         let cache_dir = get_cache_dir()?;
         let cache = cache_dir.join(format!("bookmarks/{}-cache.bin", prefix));
 
+        // the search strings depend on `search.fold_diacritics`, which is cached along with them
+        let folded = folds_diacritics();
         if !file_has_changed(&cache, &self.path) {
-            if let Ok(app_data) = BinaryCache::read::<Vec<AppData>, _>(&cache) {
+            // an outdated cache format reads as empty and is rebuilt from the database
+            if let Ok((cached_folded, mut app_data)) =
+                BinaryCache::read::<(bool, Vec<AppData>), _>(&cache)
+                && cached_folded == folded
+                && !app_data.is_empty()
+            {
+                for data in &mut app_data {
+                    data.normalize_cached_search();
+                }
                 return Ok(app_data);
             }
         }
@@ -155,82 +430,58 @@ impl MozillaSqliteParser {
         rayon::spawn_fifo({
             let bookmarks = bookmarks.clone();
             move || {
-                let _ = BinaryCache::write(&cache, &bookmarks);
+                let _ = BinaryCache::write(&cache, &(folded, bookmarks));
             }
         });
         Ok(bookmarks)
     }
     fn read_new(&self, launcher: Arc<Launcher>) -> Result<Vec<AppData>, SherlockError> {
         let mut res: Vec<AppData> = Vec::new();
+        let conn = Connection::open(&self.path)
+            .map_err(|e| sherlock_error!(SherlockErrorType::SqlConnectionError(), e.to_string()))?;
+
+        let folders: HashMap<i64, Folder> = conn
+            .prepare("SELECT id, parent, title, guid FROM moz_bookmarks WHERE type = 2;")
+            .and_then(|mut stmt| {
+                stmt.query_map([], |row| {
+                    let folder = Folder {
+                        parent: row.get(1)?,
+                        title: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                        guid: row.get(3)?,
+                    };
+                    Ok((row.get(0)?, folder))
+                })?
+                .collect()
+            })
+            .unwrap_or_default();
+
         let query = "
-            SELECT b.title, p.url
+            SELECT b.title, p.url, b.parent
             FROM moz_bookmarks b
             JOIN moz_places p ON b.fk = p.id
             WHERE b.type = 1
             AND b.title IS NOT NULL
-            AND p.url IS NOT NULL
-            AND b.parent != 7;
+            AND p.url IS NOT NULL;
             ";
-        let conn = Connection::open(&self.path)
-            .map_err(|e| sherlock_error!(SherlockErrorType::SqlConnectionError(), e.to_string()))?;
-
         if let Ok(mut stmt) = conn.prepare(query) {
             let event_iter = stmt.query_map([], |row| {
                 let title: String = row.get(0)?;
                 let url: String = row.get(1)?;
+                let parent: i64 = row.get(2)?;
 
-                Ok((title, url))
+                Ok((title, url, parent))
             });
 
             if let Ok(rows) = event_iter {
-                for row in rows.flatten() {
-                    let bookmark = AppData {
-                        name: Some(SharedString::from(&row.0)),
-                        icon: resolve_icon_path("sherlock-bookmark"),
-                        search_string: construct_search(Some(&row.0), &row.1, true),
-                        exec: Some(row.1),
-                        desktop_file: None,
-                        priority: Some(launcher.priority as f32 + 1.0),
-                        actions: Arc::new([]),
-                        vars: vec![],
-                        terminal: false,
-                    };
-                    res.push(bookmark);
+                for (title, url, parent) in rows.flatten() {
+                    if let Some(folder) = folder_path(&folders, parent) {
+                        res.push(bookmark(&launcher, &title, url, folder));
+                    }
                 }
             }
         }
         Ok(res)
     }
-    fn should_update_cache(dest: &PathBuf, source: &PathBuf) -> bool {
-        if !dest.exists() {
-            return true;
-        }
-
-        let source_mod = fs::metadata(source)
-            .ok()
-            .and_then(|meta| meta.modified().ok());
-        let dest_mod = fs::metadata(dest)
-            .ok()
-            .and_then(|meta| meta.modified().ok());
-
-        if let (Some(source), Some(dest)) = (source_mod, dest_mod) {
-            return source > dest;
-        }
-        true
-    }
-    fn copy_if_needed(src: &PathBuf, dst: &PathBuf) {
-        if Self::should_update_cache(dst, src) {
-            let _ = sher_log!(format!(
-                r#"Bookmark database "{}" is copied to "{}""#,
-                src.display(),
-                dst.display()
-            ));
-            if let Some(parent) = dst.parent() {
-                let _ = fs::create_dir_all(parent);
-            }
-            let _ = fs::copy(src, dst);
-        }
-    }
 }
 struct ChromeParser;
 impl ChromeParser {
@@ -258,42 +509,106 @@ impl ChromeParser {
         let file = serde_json::from_str::<parser::ChromeFile>(&data)
             .map_err(|e| sherlock_error!(SherlockErrorType::FlagLoadError, e.to_string()))?;
 
-        fn process_bookmark(
-            launcher: Arc<Launcher>,
+        fn process_bookmark<'a>(
+            launcher: &Launcher,
             bookmarks: &mut Vec<AppData>,
-            bookmark: parser::ChromeBookmark,
+            folders: &mut Vec<&'a str>,
+            node: &'a parser::ChromeBookmark,
         ) {
-            match bookmark.r#type.as_ref() {
+            match node.r#type.as_ref() {
                 "folder" => {
-                    if let Some(children) = bookmark.children {
-                        for child in children {
-                            process_bookmark(Arc::clone(&launcher), bookmarks, child);
-                        }
+                    folders.push(&node.name);
+                    for child in node.children.iter().flatten() {
+                        process_bookmark(launcher, bookmarks, folders, child);
                     }
+                    folders.pop();
                 }
                 "url" => {
-                    if let Some(url) = bookmark.url {
-                        bookmarks.push(AppData {
-                            name: Some(SharedString::from(&bookmark.name)),
-                            icon: resolve_icon_path("sherlock-bookmark"),
-                            exec: Some(url.clone()),
-                            search_string: construct_search(Some(&bookmark.name), &url, true),
-                            desktop_file: None,
-                            priority: Some(launcher.priority as f32 + 1.0),
-                            actions: Arc::new([]),
-                            vars: vec![],
-                            terminal: false,
-                        });
+                    if let Some(url) = node.url.as_ref() {
+                        let folder = join_folders(folders.iter().copied());
+                        bookmarks.push(bookmark(launcher, &node.name, url.clone(), folder));
                     }
                 }
                 _ => {}
             };
         }
 
-        for (_name, bookmark) in file.roots {
-            process_bookmark(launcher.clone(), &mut bookmarks, bookmark);
+        // roots like "Bookmarks bar" are left out of folder paths
+        for root in file.roots.values() {
+            for bookmark in root.children.iter().flatten() {
+                process_bookmark(&launcher, &mut bookmarks, &mut Vec::new(), bookmark);
+            }
         }
 
         Ok(bookmarks)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_browser_targets() {
+        assert_eq!(
+            Browser::from_target("/usr/lib/firefox/firefox %u"),
+            Some(Browser::Firefox)
+        );
+        assert_eq!(Browser::from_target("chromium %U"), Some(Browser::Chromium));
+        assert_eq!(Browser::from_target("Brave"), Some(Browser::Brave));
+        assert_eq!(
+            Browser::from_target("/opt/zen-browser-bin/zen-bin %u"),
+            Some(Browser::Zen)
+        );
+        assert_eq!(Browser::from_target("lynx"), None);
+        assert_eq!(Browser::from_target(""), None);
+    }
+
+    #[test]
+    fn test_folder_paths() {
+        let folder = |parent, title: &str, guid: &str| Folder {
+            parent,
+            title: title.to_string(),
+            guid: guid.to_string(),
+        };
+        let folders = HashMap::from([
+            (1, folder(0, "", "root________")),
+            (3, folder(1, "toolbar", "toolbar_____")),
+            (4, folder(1, "tags", "tags________")),
+            (10, folder(3, "Work", "aaaaaaaaaaaa")),
+            (11, folder(10, "Docs", "bbbbbbbbbbbb")),
+            (12, folder(4, "rust", "cccccccccccc")),
+        ]);
+
+        assert_eq!(folder_path(&folders, 11).as_deref(), Some("Work / Docs"));
+        assert_eq!(folder_path(&folders, 3).as_deref(), Some(""));
+        assert_eq!(folder_path(&folders, 12), None);
+
+        let cycle = HashMap::from([(1, folder(2, "a", "x")), (2, folder(1, "b", "y"))]);
+        assert!(folder_path(&cycle, 1).is_some());
+    }
+
+    #[test]
+    fn test_chrome_folders() {
+        let data = r#"{"roots": {
+            "bookmark_bar": {"name": "Bookmarks bar", "type": "folder", "children": [
+                {"name": "Rust", "type": "url", "url": "https://rust-lang.org"},
+                {"name": "Work", "type": "folder", "children": [
+                    {"name": "Tracker", "type": "url", "url": "https://example.com/tracker"}
+                ]}
+            ]},
+            "other": {"name": "Other bookmarks", "type": "folder", "children": []}
+        }}"#;
+        let bookmarks =
+            ChromeParser::parse(Arc::new(Launcher::default()), data.to_string()).unwrap();
+
+        assert_eq!(bookmarks.len(), 2);
+        assert_eq!(bookmarks[0].subtitle, None);
+        assert_eq!(bookmarks[1].subtitle.as_deref(), Some("Work"));
+        assert_eq!(
+            bookmarks[1].exec.as_deref(),
+            Some("https://example.com/tracker")
+        );
+        assert!(bookmarks[1].search_string.contains("work"));
+    }
+}
@@ -0,0 +1,276 @@
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use gpui::SharedString;
+use rusqlite::Connection;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    launcher::{
+        Launcher,
+        bookmark_launcher::{Browser, local_copy, wal_path},
+        children::NameHighlight,
+        provenance::Transforms,
+    },
+    loader::{
+        application_loader::file_has_changed,
+        resolve_icon_path,
+        utils::{AppData, MatchPolicy, construct_search},
+    },
+    sher_log, sherlock_error,
+    utils::{
+        errors::{SherlockError, SherlockErrorType},
+        paths::get_cache_dir,
+    },
+};
+
+/// Entries read per browser unless the launcher sets `history_limit`
+pub const DEFAULT_LIMIT: usize = 500;
+
+/// A page visited in a browser
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct HistoryEntry {
+    pub title: Option<String>,
+    pub url: String,
+    /// Visits counted by the browser
+    pub visits: u32,
+}
+impl HistoryEntry {
+    /// The child of the entry. Frequently visited pages rank higher, entries of the page visited
+    /// most often share the priority of bookmarks.
+    pub fn into_child(self, launcher: &Launcher, max_visits: u32) -> AppData {
+        let title = self.title.as_deref().unwrap_or(&self.url).to_string();
+        AppData {
+            search_string: construct_search(Some(&title), &self.url, true),
+            name: Some(SharedString::from(title)),
+            icon: resolve_icon_path("sherlock-web"),
+            icon_name: Some(String::from("sherlock-web")),
+            desktop_file: None,
+            priority: Some(visit_priority(
+                launcher.priority as f32 + 1.0,
+                self.visits,
+                max_visits,
+            )),
+            actions: Arc::new([]),
+            vars: vec![],
+            terminal: false,
+            transforms: Transforms::default(),
+            match_policy: MatchPolicy::default(),
+            accent: None,
+            subtitle: Some(SharedString::from(url_host(&self.url).to_string())),
+            confirm: false,
+            name_highlight: NameHighlight::default(),
+            exec: Some(self.url),
+        }
+    }
+}
+
+/// Extracted entries, kept in `~/.cache/sherlock/history.json` until a browser database changes
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct HistoryCache {
+    target: String,
+    limit: usize,
+    entries: Vec<HistoryEntry>,
+}
+
+/// The `limit` most recently visited pages of each of `browsers`, every url once. `target` is
+/// the browser the launcher was configured with, a cache of another target is not used.
+pub fn recent_history(
+    target: &str,
+    browsers: &[Browser],
+    limit: usize,
+) -> Result<Vec<HistoryEntry>, SherlockError> {
+    let databases: Vec<(Browser, PathBuf)> = browsers
+        .iter()
+        .filter_map(|browser| Some((*browser, browser.history_database().ok()?)))
+        .collect();
+    if databases.is_empty() {
+        return Err(sherlock_error!(
+            SherlockErrorType::UnsupportedBrowser(target.to_string()),
+            "No browser history was found"
+        ));
+    }
+
+    let cache = get_cache_dir()?.join("history.json");
+    if let Some(entries) = read_cache(&cache, target, limit, &databases) {
+        return Ok(entries);
+    }
+
+    let lists = databases
+        .iter()
+        .filter_map(|(browser, database)| {
+            let name = database.file_name()?.to_str()?;
+            let copy = local_copy(database, &format!("{}-{}", browser.name(), name));
+            read_database(&copy, *browser, limit)
+                .map_err(|e| sher_log!(format!("{e}")))
+                .ok()
+        })
+        .collect();
+    let entries = merge_history(lists);
+
+    let content = HistoryCache {
+        target: target.to_string(),
+        limit,
+        entries,
+    };
+    if let Err(e) = simd_json::to_string(&content)
+        .map_err(|e| e.to_string())
+        .and_then(|json| std::fs::write(&cache, json).map_err(|e| e.to_string()))
+    {
+        let _ = sher_log!(format!("Failed to cache the browser history: {e}"));
+    }
+    Ok(content.entries)
+}
+
+/// The cached entries, if they were read for the same launcher and no database changed since
+fn read_cache(
+    cache: &Path,
+    target: &str,
+    limit: usize,
+    databases: &[(Browser, PathBuf)],
+) -> Option<Vec<HistoryEntry>> {
+    let changed = databases.iter().any(|(_, database)| {
+        let wal = wal_path(database);
+        file_has_changed(database, cache) || (wal.exists() && file_has_changed(&wal, cache))
+    });
+    if changed {
+        return None;
+    }
+    let cached: HistoryCache = simd_json::from_reader(File::open(cache).ok()?).ok()?;
+    (cached.target == target && cached.limit == limit).then_some(cached.entries)
+}
+
+fn read_database(
+    path: &Path,
+    browser: Browser,
+    limit: usize,
+) -> Result<Vec<HistoryEntry>, SherlockError> {
+    let query = if browser.chromium_dir().is_some() {
+        "
+        SELECT title, url, visit_count
+        FROM urls
+        WHERE hidden = 0
+        AND visit_count > 0
+        AND url LIKE 'http%'
+        ORDER BY last_visit_time DESC
+        LIMIT ?1;
+        "
+    } else {
+        "
+        SELECT title, url, visit_count
+        FROM moz_places
+        WHERE hidden = 0
+        AND visit_count > 0
+        AND last_visit_date IS NOT NULL
+        AND url LIKE 'http%'
+        ORDER BY last_visit_date DESC
+        LIMIT ?1;
+        "
+    };
+    let sql_error = |e: rusqlite::Error| {
+        sherlock_error!(SherlockErrorType::SqlConnectionError(), e.to_string())
+    };
+
+    let conn = Connection::open(path).map_err(sql_error)?;
+    let mut stmt = conn.prepare(query).map_err(sql_error)?;
+    let rows = stmt
+        .query_map([limit as i64], |row| {
+            Ok(HistoryEntry {
+                title: row
+                    .get::<_, Option<String>>(0)?
+                    .filter(|title| !title.is_empty()),
+                url: row.get(1)?,
+                visits: row.get(2)?,
+            })
+        })
+        .map_err(sql_error)?;
+    Ok(rows.flatten().collect())
+}
+
+/// Joins the entries of several browsers, keeping their order. Visits of a page found in more
+/// than one browser are added up.
+fn merge_history(lists: Vec<Vec<HistoryEntry>>) -> Vec<HistoryEntry> {
+    let mut merged: Vec<HistoryEntry> = Vec::new();
+    let mut positions: HashMap<String, usize> = HashMap::new();
+    for entry in lists.into_iter().flatten() {
+        match positions.get(&entry.url) {
+            Some(&i) => {
+                let known = &mut merged[i];
+                known.visits = known.visits.saturating_add(entry.visits);
+                if known.title.is_none() {
+                    known.title = entry.title;
+                }
+            }
+            None => {
+                positions.insert(entry.url.clone(), merged.len());
+                merged.push(entry);
+            }
+        }
+    }
+    merged
+}
+
+/// Folds the visits of a page into `base`, the more visits the lower and thus better the
+/// priority. Stays below the next whole number like the launch counts of apps.
+fn visit_priority(base: f32, visits: u32, max_visits: u32) -> f32 {
+    let share = visits as f32 / max_visits.max(1) as f32;
+    base + 0.99 * (1.0 - share.min(1.0))
+}
+
+/// The host of `url`, e.g. `docs.rs` for `https://docs.rs/gpui`
+fn url_host(url: &str) -> &str {
+    let rest = url.split_once("://").map_or(url, |(_, rest)| rest);
+    rest.split(['/', '?', '#']).next().unwrap_or(rest)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(url: &str, title: Option<&str>, visits: u32) -> HistoryEntry {
+        HistoryEntry {
+            title: title.map(String::from),
+            url: url.to_string(),
+            visits,
+        }
+    }
+
+    #[test]
+    fn test_merge_history() {
+        let firefox = vec![
+            entry("https://docs.rs", None, 3),
+            entry("https://crates.io", Some("crates.io"), 1),
+        ];
+        let chromium = vec![
+            entry("https://github.com", Some("GitHub"), 7),
+            entry("https://docs.rs", Some("Docs.rs"), 2),
+        ];
+
+        assert_eq!(
+            merge_history(vec![firefox, chromium]),
+            vec![
+                entry("https://docs.rs", Some("Docs.rs"), 5),
+                entry("https://crates.io", Some("crates.io"), 1),
+                entry("https://github.com", Some("GitHub"), 7),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_visit_priority() {
+        assert_eq!(visit_priority(3.0, 10, 10), 3.0);
+        assert!(visit_priority(3.0, 1, 10) > visit_priority(3.0, 5, 10));
+        assert!(visit_priority(3.0, 0, 0) < 4.0);
+    }
+
+    #[test]
+    fn test_url_host() {
+        assert_eq!(url_host("https://docs.rs/gpui/latest"), "docs.rs");
+        assert_eq!(url_host("http://localhost:8080?q=1"), "localhost:8080");
+        assert_eq!(url_host("example.com"), "example.com");
+    }
+}
@@ -0,0 +1,232 @@
+use std::{
+    collections::VecDeque,
+    fs,
+    path::PathBuf,
+    sync::{
+        Arc, RwLock,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    sherlock_error,
+    utils::{
+        errors::{SherlockError, SherlockErrorType},
+        paths,
+        soft_delete::StagedChange,
+    },
+};
+
+#[derive(Clone, Debug, Serialize, Deserialize, PartialEq)]
+pub struct HistoryEntry {
+    pub expression: String,
+    pub result: String,
+}
+
+/// Recently copied calculator results, newest first
+#[derive(Debug, Default)]
+pub struct CalcHistory {
+    entries: VecDeque<HistoryEntry>,
+    capacity: usize,
+    path: Option<PathBuf>,
+}
+
+impl CalcHistory {
+    /// Reads the history from `calc_history.json` in the data directory. A missing or
+    /// unreadable file results in an empty history.
+    pub fn load(capacity: usize) -> Self {
+        let path = paths::get_data_dir()
+            .ok()
+            .map(|dir| dir.join("calc_history.json"));
+        let mut entries: VecDeque<HistoryEntry> = path
+            .as_ref()
+            .and_then(|path| fs::read(path).ok())
+            .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+            .unwrap_or_default();
+        entries.truncate(capacity);
+
+        Self {
+            entries,
+            capacity,
+            path,
+        }
+    }
+
+    /// Adds a calculation to the front of the history, dropping the oldest entries beyond the
+    /// capacity. Repeating the most recent expression only updates its result.
+    pub fn push(&mut self, expression: &str, result: &str) {
+        if self.capacity == 0 {
+            return;
+        }
+        let expression = expression.trim();
+        match self.entries.front_mut() {
+            Some(last) if last.expression == expression => last.result = result.to_string(),
+            _ => self.entries.push_front(HistoryEntry {
+                expression: expression.to_string(),
+                result: result.to_string(),
+            }),
+        }
+        self.entries.truncate(self.capacity);
+    }
+
+    pub fn get(&self, index: usize) -> Option<&HistoryEntry> {
+        self.entries.get(index)
+    }
+
+    pub fn save(&self) -> Result<(), SherlockError> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        let content = serde_json::to_vec(&self.entries)
+            .map_err(|e| sherlock_error!(SherlockErrorType::SerializationError, e.to_string()))?;
+        fs::write(path, content).map_err(|e| {
+            sherlock_error!(
+                SherlockErrorType::FileWriteError(path.clone()),
+                e.to_string()
+            )
+        })
+    }
+}
+
+/// A calculation that enters the history once its result is copied, see
+/// [`ExecMode::Copy`](crate::launcher::ExecMode::Copy)
+pub struct HistoryRecord {
+    pub history: Arc<RwLock<CalcHistory>>,
+    pub expression: String,
+    pub result: String,
+}
+
+impl HistoryRecord {
+    /// Adds the calculation to the history and writes it to disk
+    pub fn record(self) {
+        if let Ok(mut history) = self.history.write() {
+            history.push(&self.expression, &self.result);
+            if let Err(e) = history.save() {
+                eprintln!("{e}");
+            }
+        }
+    }
+}
+
+/// Removes a single entry from the history
+pub struct RemoveEntry {
+    history: Arc<RwLock<CalcHistory>>,
+    index: usize,
+    entry: HistoryEntry,
+    /// Set if `apply` found the entry, only then `revert` puts it back
+    removed: AtomicBool,
+}
+
+impl RemoveEntry {
+    pub fn new(history: Arc<RwLock<CalcHistory>>, index: usize, entry: HistoryEntry) -> Self {
+        Self {
+            history,
+            index,
+            entry,
+            removed: AtomicBool::new(false),
+        }
+    }
+}
+
+impl StagedChange for RemoveEntry {
+    fn describe(&self) -> String {
+        format!("Removed '{}' from history", self.entry.expression)
+    }
+    fn apply(&self) {
+        if let Ok(mut history) = self.history.write()
+            && history.get(self.index) == Some(&self.entry)
+        {
+            history.entries.remove(self.index);
+            self.removed.store(true, Ordering::SeqCst);
+        }
+    }
+    fn revert(&self) {
+        if !self.removed.swap(false, Ordering::SeqCst) {
+            return;
+        }
+        if let Ok(mut history) = self.history.write() {
+            let index = self.index.min(history.entries.len());
+            history.entries.insert(index, self.entry.clone());
+        }
+    }
+    fn persist(&self) -> Result<(), SherlockError> {
+        match self.history.read() {
+            Ok(history) => history.save(),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_dedup_and_cap() {
+        let mut history = CalcHistory {
+            capacity: 2,
+            ..Default::default()
+        };
+        history.push("1+1", "= 2");
+        history.push("1+1 ", "= 2");
+        assert_eq!(history.entries.len(), 1);
+
+        history.push("2*3", "= 6");
+        history.push("10 km in mi", "6.21 mi");
+        assert_eq!(history.entries.len(), 2);
+        assert_eq!(history.get(0).unwrap().expression, "10 km in mi");
+        assert_eq!(history.get(1).unwrap().expression, "2*3");
+    }
+
+    #[test]
+    fn test_history_record() {
+        let history = Arc::new(RwLock::new(CalcHistory {
+            capacity: 2,
+            ..Default::default()
+        }));
+        let record = HistoryRecord {
+            history: Arc::clone(&history),
+            expression: String::from("1+1"),
+            result: String::from("= 2"),
+        };
+        assert!(history.read().unwrap().get(0).is_none());
+
+        record.record();
+        assert_eq!(history.read().unwrap().get(0).unwrap().expression, "1+1");
+    }
+
+    #[test]
+    fn test_revert_removal() {
+        let history = Arc::new(RwLock::new(CalcHistory {
+            capacity: 3,
+            ..Default::default()
+        }));
+        history.write().unwrap().push("1+1", "= 2");
+        history.write().unwrap().push("2*3", "= 6");
+        let entry = history.read().unwrap().get(1).cloned().unwrap();
+        let expressions = || -> Vec<String> {
+            history
+                .read()
+                .unwrap()
+                .entries
+                .iter()
+                .map(|entry| entry.expression.clone())
+                .collect()
+        };
+
+        let remove = RemoveEntry::new(Arc::clone(&history), 1, entry.clone());
+        remove.apply();
+        assert_eq!(expressions(), ["2*3"]);
+        remove.revert();
+        assert_eq!(expressions(), ["2*3", "1+1"]);
+
+        // the entry moved meanwhile, so neither step touches the history
+        history.write().unwrap().push("10 km in mi", "6.21 mi");
+        let stale = RemoveEntry::new(Arc::clone(&history), 1, entry);
+        stale.apply();
+        stale.revert();
+        assert_eq!(expressions(), ["10 km in mi", "2*3", "1+1"]);
+    }
+}
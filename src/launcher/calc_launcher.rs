@@ -5,6 +5,10 @@ use crate::{
         files::home_dir,
     },
 };
+use meval::{
+    shunting_yard::to_rpn,
+    tokenizer::{Operation, Token, tokenize},
+};
 use serde::{Deserialize, Serialize};
 use simd_json::{
     OwnedValue,
@@ -22,62 +26,113 @@ use std::{
 #[derive(Clone, Debug)]
 pub struct CalculatorLauncher {}
 
+/// How meval reads a math expression, with its grouping spelled out: `2+3*4` becomes
+/// `2 + (3 × 4)` and `-2^2` becomes `-(2^2)`. Returns `None` for input meval rejects.
+pub fn interpret_math(expr: &str) -> Option<String> {
+    let rpn = to_rpn(&tokenize(expr).ok()?).ok()?;
+
+    // operands and whether they need parentheses inside another operation
+    let mut stack: Vec<(String, bool)> = Vec::new();
+    let group = |(text, compound): (String, bool)| {
+        if compound { format!("({text})") } else { text }
+    };
+    for token in rpn {
+        let operand = match token {
+            Token::Number(n) => (n.to_string(), false),
+            Token::Var(name) => (name, false),
+            Token::Unary(Operation::Plus) => stack.pop()?,
+            Token::Unary(Operation::Minus) => (format!("-{}", group(stack.pop()?)), false),
+            Token::Binary(op) => {
+                let rhs = group(stack.pop()?);
+                let lhs = group(stack.pop()?);
+                let text = match op {
+                    Operation::Plus => format!("{lhs} + {rhs}"),
+                    Operation::Minus => format!("{lhs} - {rhs}"),
+                    Operation::Times => format!("{lhs} × {rhs}"),
+                    Operation::Div => format!("{lhs} / {rhs}"),
+                    Operation::Rem => format!("{lhs} % {rhs}"),
+                    Operation::Pow => format!("{lhs}^{rhs}"),
+                };
+                (text, true)
+            }
+            Token::Func(name, Some(argc)) => {
+                let args = stack.split_off(stack.len().checked_sub(argc)?);
+                let args: Vec<String> = args.into_iter().map(|(text, _)| text).collect();
+                (format!("{name}({})", args.join(", ")), false)
+            }
+            _ => return None,
+        };
+        stack.push(operand);
+    }
+
+    let (text, _) = stack.pop()?;
+    stack.is_empty().then_some(text)
+}
+
 pub static CURRENCIES: OnceLock<Option<Currency>> = OnceLock::new();
 
-#[derive(Debug, Deserialize, Serialize)]
+/// Quote currencies requested from TradingView. Adding a code here is enough to make it
+/// available to conversions.
+const CURRENCY_CODES: &[&str] = &[
+    "EUR", "JPY", "GBP", "AUD", "CAD", "CHF", "CNY", "NZD", "SEK", "NOK", "MXN", "SGD", "HKD",
+    "KRW", "PLN", "INR", "BRL", "TRY",
+];
+
+/// Exchange rates keyed by lowercase currency code, relative to the US dollar
+///
+/// Serialized as a flat map, which is also the shape of caches written by older versions.
+#[derive(Debug, Default, Deserialize, Serialize)]
+#[serde(transparent)]
 pub struct Currency {
-    pub usd: f32, // US Dollar
-    pub eur: f32, // Euro
-    pub jpy: f32, // Japanese Yen
-    pub gbp: f32, // British Pound Sterling
-    pub aud: f32, // Australian Dollar
-    pub cad: f32, // Canadian Dollar
-    pub chf: f32, // Swiss Franc
-    pub cny: f32, // Chinese Yuan
-    pub nzd: f32, // New Zealand Dollar
-    pub sek: f32, // Swedish Krona
-    pub nok: f32, // Norwegian Krone
-    pub mxn: f32, // Mexican Peso
-    pub sgd: f32, // Singapore Dollar
-    pub hkd: f32, // Hong Kong Dollar
-    pub krw: f32, // South Korean Won
-    pub pln: f32, // Polish złoty
+    rates: HashMap<String, f32>,
 }
 impl Currency {
+    /// Builds the rates from whatever currencies were received. Returns `None` only if no
+    /// usable rate is left.
     pub fn from_map(mut map: HashMap<String, f32>) -> Option<Self> {
-        Some(Self {
-            usd: 1.0,
-            eur: map.remove("eur")?,
-            jpy: map.remove("jpy")?,
-            gbp: map.remove("gbp")?,
-            aud: map.remove("aud")?,
-            cad: map.remove("cad")?,
-            chf: map.remove("chf")?,
-            cny: map.remove("cny")?,
-            nzd: map.remove("nzd")?,
-            sek: map.remove("sek")?,
-            nok: map.remove("nok")?,
-            mxn: map.remove("mxn")?,
-            sgd: map.remove("sgd")?,
-            hkd: map.remove("hkd")?,
-            krw: map.remove("krw")?,
-            pln: map.remove("pln")?,
-        })
+        map.retain(|_, rate| rate.is_finite() && *rate > 0.0);
+        if map.is_empty() {
+            return None;
+        }
+        map.insert(String::from("usd"), 1.0);
+        Some(Self { rates: map })
+    }
+
+    /// Rate of a currency code, case insensitive
+    pub fn rate(&self, code: &str) -> Option<f32> {
+        self.rates
+            .get(code)
+            .or_else(|| self.rates.get(&code.to_lowercase()))
+            .copied()
+    }
+
+    pub fn contains(&self, code: &str) -> bool {
+        self.rate(code).is_some()
+    }
+
+    /// Converts `value` between two currency codes. The error names the first code without
+    /// a rate.
+    pub fn convert(&self, value: f64, from: &str, to: &str) -> Result<f64, String> {
+        let rate = |code: &str| {
+            self.rate(code)
+                .map(f64::from)
+                .ok_or_else(|| format!("unknown currency {}", code.to_uppercase()))
+        };
+        let (from_rate, to_rate) = (rate(from)?, rate(to)?);
+        Ok(value * (to_rate / from_rate))
     }
 
     fn load_cached<P: AsRef<Path>>(loc: P, update_interval: u64) -> Option<Currency> {
         let absolute = loc.as_ref();
-        if absolute.is_file() {
-            let mtime = absolute.metadata().ok()?.modified().ok()?;
-            let time_since = SystemTime::now().duration_since(mtime).ok()?;
-            // then was cached
-            if time_since < Duration::from_secs(60 * update_interval) {
-                File::open(&absolute)
-                    .ok()
-                    .and_then(|file| simd_json::from_reader(file).ok())?
-            }
+        let mtime = absolute.metadata().ok()?.modified().ok()?;
+        let time_since = SystemTime::now().duration_since(mtime).ok()?;
+        // then was cached
+        if time_since >= Duration::from_secs(60 * update_interval) {
+            return None;
         }
-        None
+        let file = File::open(absolute).ok()?;
+        let cached: HashMap<String, f32> = simd_json::from_reader(file).ok()?;
+        Currency::from_map(cached)
     }
     fn cache<P: AsRef<Path>>(&self, loc: P) -> Result<(), SherlockError> {
         let absolute = loc.as_ref();
@@ -106,22 +161,17 @@ impl Currency {
     pub async fn get_exchange(update_interval: u64) -> Result<Currency, SherlockError> {
         let home = home_dir()?;
         let absolute = home.join(".cache/sherlock/currency/currency.json");
-        match Currency::load_cached(&absolute, update_interval) {
-            Some(curr) => return Ok(curr),
-            _ => {}
-        };
+        if let Some(cached) = Currency::load_cached(&absolute, update_interval) {
+            return Ok(cached);
+        }
 
         let url = "https://scanner.tradingview.com/forex/scan?label-product=related-symbols";
 
-        let json_body = r#"{
-            "columns": [
-                "name",
-                "type",
-                "close"
-            ],
+        let json_body = serde_json::json!({
+            "columns": ["name", "type", "close"],
             "ignore_unknown_fields": true,
             "options": { "lang": "en" },
-            "range": [0,15],
+            "range": [0, CURRENCY_CODES.len()],
             "sort": {
                 "sortBy": "popularity_rank",
                 "sortOrder": "asc"
@@ -132,10 +182,11 @@ impl Currency {
                     { "expression": { "left": "type", "operation": "equal", "right": "forex" } },
                     { "expression": { "left": "exchange", "operation": "equal", "right": "FX_IDC" } },
                     { "expression": { "left": "currency_id", "operation": "equal", "right": "USD" } },
-                    { "expression": { "left": "base_currency_id", "operation": "in_range", "right": ["EUR", "JPY", "GBP", "AUD", "CAD", "CHF", "CNY", "NZD", "SEK", "NOK", "MXN", "SGD", "HKD", "KRW", "PLN"] } }
+                    { "expression": { "left": "base_currency_id", "operation": "in_range", "right": CURRENCY_CODES } }
                 ]
             }
-        }"#;
+        })
+        .to_string();
 
         let client = reqwest::Client::new();
         let res = client
@@ -193,8 +244,96 @@ impl Currency {
             }
             _ => Err(sherlock_error!(
                 SherlockErrorType::DeserializationError,
-                String::from("Failed to deserialize currency map into 'Currency' object.")
+                String::from("Received no exchange rates.")
             )),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_interpret_math() {
+        let cases = [
+            ("2+3*4", "2 + (3 × 4)"),
+            ("(2+3)*4", "(2 + 3) × 4"),
+            ("2^3^2", "2^(3^2)"),
+            ("-2^2", "-(2^2)"),
+            ("1 - 2 - 3", "(1 - 2) - 3"),
+            ("sqrt(16)/2", "sqrt(16) / 2"),
+            ("max(1, 2+3)", "max(1, 2 + 3)"),
+            ("0.5 * pi", "0.5 × pi"),
+        ];
+        for (input, expected) in cases {
+            assert_eq!(interpret_math(input).as_deref(), Some(expected), "{input}");
+        }
+        assert_eq!(interpret_math("2 +"), None);
+    }
+
+    #[test]
+    fn test_partial_rates() {
+        let rates = Currency::from_map(HashMap::from([
+            (String::from("eur"), 0.5),
+            (String::from("inr"), 80.0),
+            (String::from("brl"), f32::NAN),
+        ]))
+        .unwrap();
+
+        assert_eq!(rates.convert(10.0, "usd", "eur"), Ok(5.0));
+        assert_eq!(rates.convert(1.0, "EUR", "inr"), Ok(160.0));
+        assert_eq!(
+            rates.convert(1.0, "brl", "usd"),
+            Err(String::from("unknown currency BRL"))
+        );
+        assert!(Currency::from_map(HashMap::new()).is_none());
+    }
+
+    #[test]
+    fn test_legacy_cache_shape() {
+        // written by the old struct with one field per currency
+        let rates: Currency =
+            serde_json::from_str(r#"{"usd":1.0,"eur":0.92,"jpy":150.1,"pln":3.9}"#).unwrap();
+        assert_eq!(rates.rate("jpy"), Some(150.1));
+
+        let written = serde_json::to_string(&rates).unwrap();
+        let reloaded: Currency = serde_json::from_str(&written).unwrap();
+        assert_eq!(reloaded.rate("pln"), Some(3.9));
+    }
+
+    #[test]
+    fn test_load_cached() {
+        let path =
+            std::env::temp_dir().join(format!("sherlock-currency-{}.json", std::process::id()));
+        std::fs::write(&path, r#"{"usd":1.0,"eur":0.92,"jpy":150.1,"pln":3.9}"#).unwrap();
+        let rates = Currency::load_cached(&path, 60).unwrap();
+        assert_eq!(rates.rate("jpy"), Some(150.1));
+
+        rates.cache(&path).unwrap();
+        let reloaded = Currency::load_cached(&path, 60).unwrap();
+        assert_eq!(reloaded.rate("pln"), Some(3.9));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn test_cache_expires() {
+        let path = std::env::temp_dir().join(format!(
+            "sherlock-currency-stale-{}.json",
+            std::process::id()
+        ));
+        std::fs::write(&path, r#"{"usd":1.0,"eur":0.92}"#).unwrap();
+        assert!(Currency::load_cached(&path, 60).is_some());
+
+        // written two hours ago, older than the interval of an hour
+        let written = SystemTime::now() - Duration::from_secs(2 * 60 * 60);
+        File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(written))
+            .unwrap();
+        assert!(Currency::load_cached(&path, 60).is_none());
+        assert!(Currency::load_cached(&path, 180).is_some());
+        std::fs::remove_file(path).ok();
+    }
+}
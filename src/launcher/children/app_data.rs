@@ -1,14 +1,172 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, Image, ImageSource, IntoElement, ParentElement, Styled, div, img, px, rgb};
+use gpui::{
+    AnyElement, Div, FontWeight, HighlightStyle, Image, ImageSource, IntoElement, ParentElement,
+    SharedString, Styled, StyledText, div, img, px, rgb,
+};
 
 use crate::{
-    launcher::{ExecMode, Launcher, children::RenderableChildImpl},
-    loader::utils::AppData,
+    launcher::{
+        ExecMode, Launcher, LauncherType,
+        children::{Highlight, RenderableChildImpl},
+        web_launcher::{engine_label, split_bang},
+    },
+    loader::utils::{AppData, ApplicationAction},
+    utils::{bidi::BidiText, palette::Palette},
 };
 
+impl AppData {
+    /// Returns true for the children of a web launcher that repeat a past search, see
+    /// [`recent_search`](crate::launcher::web_history::recent_search)
+    pub fn is_recent_search(&self, launcher: &Launcher) -> bool {
+        matches!(launcher.launcher_type, LauncherType::Web(_)) && self.exec.is_some()
+    }
+
+    /// Where the search row of a web launcher sends a query starting with a known bang
+    fn bang_engine(&self, launcher: &Launcher, query: &str) -> Option<SharedString> {
+        let LauncherType::Web(web) = &launcher.launcher_type else {
+            return None;
+        };
+        if self.exec.is_some() {
+            return None;
+        }
+        let (engine, _) = split_bang(&web.engines, query)?;
+        Some(format!("Search {}", engine_label(engine)).into())
+    }
+
+    /// The app id of the windows of this application, taken from the name of its desktop file
+    pub fn app_id(&self) -> Option<String> {
+        self.desktop_file
+            .as_deref()?
+            .file_stem()?
+            .to_str()
+            .map(String::from)
+    }
+
+    /// The configured actions, followed by "Show Source" and "Hide this entry" for applications.
+    /// Apps that are raised when running also offer to start a new instance.
+    pub fn context_actions(&self, launcher: &Launcher) -> Arc<[Arc<ApplicationAction>]> {
+        let launch = match (&launcher.launcher_type, self.exec.as_ref()) {
+            (LauncherType::App(_), Some(exec)) if launcher.prefer_raise => {
+                let mut launch = ApplicationAction::new("app_launcher");
+                launch.name = Some("Launch new instance".into());
+                launch.exec = Some(exec.clone());
+                launch.icon = self.icon.clone();
+                launch.terminal = self.terminal;
+                Some(launch)
+            }
+            _ => None,
+        };
+
+        let mut source = ApplicationAction::new("show_source");
+        source.name = Some("Show Source".into());
+        source.icon = self.icon.clone();
+
+        let hide = match (&launcher.launcher_type, self.name.as_ref()) {
+            (LauncherType::App(_), Some(name)) => {
+                let mut hide = ApplicationAction::new("hide");
+                hide.name = Some("Hide this entry".into());
+                hide.exec = Some(name.to_string());
+                hide.icon = self.icon.clone();
+                Some(hide)
+            }
+            _ => None,
+        };
+
+        self.actions
+            .iter()
+            .cloned()
+            .chain(
+                launch
+                    .into_iter()
+                    .chain(std::iter::once(source))
+                    .chain(hide)
+                    .map(Arc::new),
+            )
+            .collect()
+    }
+
+    /// The name of the entry with the matched characters highlighted
+    fn styled_name(&self, launcher: &Launcher, highlight: Highlight) -> Option<Div> {
+        let palette = Palette::current();
+        self.name
+            .as_ref()
+            .or(launcher.display_name.as_ref())
+            .map(|name| {
+                // each row picks its base direction from its own first strong character
+                let bidi = BidiText::new(name);
+                let display: SharedString = if bidi.is_reordered() {
+                    bidi.as_str().to_string().into()
+                } else {
+                    name.clone()
+                };
+                let ranges = bidi.visual_ranges(self.name_highlight.ranges(name, highlight));
+                if ranges.is_empty() {
+                    return div().child(display);
+                }
+                let style = HighlightStyle {
+                    color: Some(palette.fg(rgb(0xffffff))),
+                    font_weight: Some(FontWeight::BOLD),
+                    ..Default::default()
+                };
+                div().child(
+                    StyledText::new(display)
+                        .with_highlights(ranges.into_iter().map(|range| (range, style))),
+                )
+            })
+    }
+
+    /// Icon above the name, used by the grid layout
+    pub fn render_tile(
+        &self,
+        launcher: &Launcher,
+        is_selected: bool,
+        highlight: Highlight,
+    ) -> AnyElement {
+        let palette = Palette::current();
+        div()
+            .p_2()
+            .w_full()
+            .flex()
+            .flex_col()
+            .gap_2()
+            .items_center()
+            .child(if let Some(icon) = self.icon.as_ref() {
+                img(Arc::clone(icon)).size(px(48.)).into_any_element()
+            } else {
+                img(ImageSource::Image(Arc::new(Image::empty())))
+                    .size(px(48.))
+                    .into_any_element()
+            })
+            .child(
+                div()
+                    .w_full()
+                    .flex()
+                    .justify_center()
+                    .text_size(palette.text_size(px(13.)))
+                    .text_color(palette.fg(if is_selected {
+                        rgb(0xffffff)
+                    } else {
+                        rgb(0xcccccc)
+                    }))
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .children(self.styled_name(launcher, highlight)),
+            )
+            .into_any_element()
+    }
+}
+
 impl<'a> RenderableChildImpl<'a> for AppData {
-    fn render(&self, launcher: &Arc<Launcher>, is_selected: bool) -> AnyElement {
+    fn render(
+        &self,
+        launcher: &Arc<Launcher>,
+        is_selected: bool,
+        highlight: Highlight,
+    ) -> AnyElement {
+        let palette = Palette::current();
+        let name = self.styled_name(launcher, highlight);
         div()
             .px_4()
             .py_2()
@@ -30,31 +188,31 @@ impl<'a> RenderableChildImpl<'a> for AppData {
                     .items_center()
                     .child(
                         div()
-                            .text_sm()
-                            .text_color(if is_selected {
+                            .text_size(palette.text_size(px(14.)))
+                            .text_color(palette.fg(if is_selected {
                                 rgb(0xffffff)
                             } else {
                                 rgb(0xcccccc)
-                            })
+                            }))
                             .overflow_hidden()
                             .text_ellipsis()
                             .whitespace_nowrap()
-                            .children(
-                                self.name
-                                    .as_ref()
-                                    .or(launcher.display_name.as_ref())
-                                    .map(|name| div().child(name.clone())),
-                            ),
+                            .children(name),
                     )
                     .child(
                         div()
-                            .text_xs()
-                            .text_color(if is_selected {
+                            .text_size(palette.text_size(px(12.)))
+                            .text_color(palette.fg(if is_selected {
                                 rgb(0x999999)
                             } else {
                                 rgb(0x666666)
-                            })
-                            .children(launcher.name.as_ref().map(|name| div().child(name.clone()))),
+                            }))
+                            .children(
+                                self.bang_engine(launcher, highlight.query)
+                                    .or_else(|| self.subtitle.clone())
+                                    .or_else(|| launcher.name.clone().map(SharedString::from))
+                                    .map(|subtitle| div().child(subtitle)),
+                            ),
                     ),
             )
             .into_any_element()
@@ -68,4 +226,7 @@ impl<'a> RenderableChildImpl<'a> for AppData {
     fn search(&'a self, _launcher: &Arc<Launcher>) -> &'a str {
         &self.search_string
     }
+    fn confirm(&self) -> bool {
+        self.confirm
+    }
 }
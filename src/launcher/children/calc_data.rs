@@ -1,26 +1,113 @@
-use std::sync::{Arc, RwLock};
+use std::{
+    path::Path,
+    sync::{Arc, RwLock},
+};
 
-use gpui::{IntoElement, ParentElement, SharedString, Styled, div, px, rgb};
+use gpui::{IntoElement, ParentElement, SharedString, Styled, div, img, px, rgb};
 
 use crate::{
-    launcher::{ExecMode, Launcher, children::RenderableChildImpl},
-    utils::intent::{Capabilities, Intent},
+    launcher::{
+        ExecMode, Launcher,
+        calc_history::{CalcHistory, HistoryRecord, RemoveEntry},
+        calc_launcher::interpret_math,
+        children::{Highlight, RenderableChildImpl},
+    },
+    loader::utils::ApplicationAction,
+    utils::{
+        intent::{Capabilities, Intent},
+        palette::Palette,
+    },
 };
 
 #[derive(Clone)]
 pub struct CalcData {
     capabilities: Capabilities,
-    result: Arc<RwLock<Option<(SharedString, SharedString)>>>,
+    /// Decimal places of converted values
+    decimals: usize,
+    /// Result of the current query
+    result: Arc<RwLock<Option<CalcResult>>>,
+    /// Shows how the query was read below the result
+    show_interpretation: bool,
+    history: Arc<RwLock<CalcHistory>>,
+    /// Set for children recalling an entry of the history instead of evaluating the query
+    history_slot: Option<usize>,
+    /// Shown next to history entries
+    icon: Option<Arc<Path>>,
+}
+
+struct CalcResult {
+    expression: SharedString,
+    result: SharedString,
+    /// The query with its grouping or units spelled out, see [`interpret_math`]
+    interpretation: Option<SharedString>,
 }
 
 impl CalcData {
-    pub fn new(capabilities: Capabilities) -> Self {
+    pub fn new(
+        capabilities: Capabilities,
+        decimals: usize,
+        history: Arc<RwLock<CalcHistory>>,
+    ) -> Self {
         Self {
             capabilities,
+            decimals,
             result: Arc::new(RwLock::new(None)),
+            show_interpretation: true,
+            history,
+            history_slot: None,
+            icon: None,
+        }
+    }
+    /// A child showing the `slot`-th most recent calculation
+    pub fn history_entry(
+        capabilities: Capabilities,
+        decimals: usize,
+        history: Arc<RwLock<CalcHistory>>,
+        slot: usize,
+    ) -> Self {
+        Self {
+            history_slot: Some(slot),
+            ..Self::new(capabilities, decimals, history)
+        }
+    }
+    pub fn with_icon(mut self, icon: Option<Arc<Path>>) -> Self {
+        self.icon = icon;
+        self
+    }
+    pub fn with_interpretation(mut self, show: bool) -> Self {
+        self.show_interpretation = show;
+        self
+    }
+    pub fn history_slot(&self) -> Option<usize> {
+        self.history_slot
+    }
+    /// Context actions of history entries
+    pub fn actions(&self) -> Option<Arc<[Arc<ApplicationAction>]>> {
+        self.history_slot?;
+        let mut remove = ApplicationAction::new("calc_history_remove");
+        remove.name = Some("Remove from history".into());
+        Some(Arc::new([Arc::new(remove)]))
+    }
+    /// Removes the shown history entry, revertible through the soft-delete mechanism
+    pub fn remove_exec(&self) -> ExecMode {
+        let slot = self.history_slot;
+        let entry = slot.and_then(|slot| self.history.read().ok()?.get(slot).cloned());
+        match (slot, entry) {
+            (Some(index), Some(entry)) => ExecMode::SoftDelete(Box::new(RemoveEntry::new(
+                Arc::clone(&self.history),
+                index,
+                entry,
+            ))),
+            _ => ExecMode::None,
         }
     }
-    pub fn based_show(&self, keyword: &str) -> bool {
+    pub fn based_show(&self, keyword: &str, mode: &str) -> bool {
+        // the history is shown in the calculator mode while the query is empty
+        if let Some(slot) = self.history_slot {
+            return keyword.trim().is_empty()
+                && mode != "all"
+                && self.history.read().is_ok_and(|h| h.get(slot).is_some());
+        }
         if keyword.trim().is_empty() {
             return false;
         }
@@ -32,7 +119,12 @@ impl CalcData {
             if let Ok(r) = meval::eval_str(trimmed_keyword) {
                 let r = r.to_string();
                 if &r != trimmed_keyword {
-                    result = Some((r.clone(), format!("= {}", r)));
+                    let interpretation = interpret_math(trimmed_keyword);
+                    result = Some((
+                        trimmed_keyword.to_string(),
+                        format!("= {}", r),
+                        interpretation,
+                    ));
                 }
             }
         }
@@ -40,19 +132,28 @@ impl CalcData {
         {
             let intent = Intent::parse(keyword, &self.capabilities);
             let r = match intent {
-                Intent::ColorConvert { .. } => intent.execute(),
-                Intent::Conversion { .. } => intent.execute(),
+                Intent::ColorConvert { .. } => intent.execute(self.decimals),
+                Intent::Conversion { .. } => intent.execute(self.decimals),
+                Intent::CurrencyConvert { .. } => intent.execute(self.decimals),
+                Intent::BaseConvert { .. } => intent.execute(self.decimals),
+                Intent::DateCalc(_) => intent.execute(self.decimals),
                 _ => None,
             };
 
             if let Some(r) = r {
-                result = Some((r.clone(), r));
+                result = Some((keyword.trim().to_string(), r, intent.interpretation()));
             }
         }
 
         let show = result.is_some();
         if let Ok(mut writer) = self.result.write() {
-            *writer = result.map(|(o, r)| (SharedString::from(o), SharedString::from(r)));
+            *writer = result.map(|(expression, result, interpretation)| CalcResult {
+                expression: expression.into(),
+                result: result.into(),
+                interpretation: interpretation
+                    .filter(|_| self.show_interpretation)
+                    .map(SharedString::from),
+            });
         }
         show
     }
@@ -63,26 +164,54 @@ impl<'a> RenderableChildImpl<'a> for CalcData {
         ""
     }
     fn build_exec(&self, _launcher: &Arc<Launcher>) -> Option<ExecMode> {
+        if let Some(slot) = self.history_slot {
+            let history = self.history.read().ok()?;
+            return Some(ExecMode::Copy {
+                content: history.get(slot)?.result.clone().into(),
+                record: None,
+            });
+        }
+
         let lock = self.result.read().ok()?;
-        let (_, res) = lock.as_ref()?;
+        let CalcResult {
+            expression, result, ..
+        } = lock.as_ref()?;
+
+        // copied results enter the history
         Some(ExecMode::Copy {
-            content: res.clone(),
+            content: result.clone(),
+            record: Some(HistoryRecord {
+                history: Arc::clone(&self.history),
+                expression: expression.to_string(),
+                result: result.to_string(),
+            }),
         })
     }
     fn priority(&self, launcher: &std::sync::Arc<crate::launcher::Launcher>) -> f32 {
-        launcher.priority as f32
+        // keeps history entries in order, newest first
+        launcher.priority as f32 + self.history_slot.map_or(0.0, |slot| slot as f32 * 0.01)
     }
     fn render(
         &self,
         _launcher: &std::sync::Arc<crate::launcher::Launcher>,
         is_selected: bool,
+        _highlight: Highlight,
     ) -> gpui::AnyElement {
-        let result = {
+        if let Some(slot) = self.history_slot {
+            return self.render_history_entry(slot, is_selected);
+        }
+
+        let (result, interpretation) = {
             let guard = self.result.read().unwrap();
-            let Some((_, res)) = guard.as_ref() else {
+            let Some(CalcResult {
+                result,
+                interpretation,
+                ..
+            }) = guard.as_ref()
+            else {
                 return div().into_any_element();
             };
-            res.clone()
+            (result.clone(), interpretation.clone())
         };
 
         div()
@@ -90,22 +219,80 @@ impl<'a> RenderableChildImpl<'a> for CalcData {
             .py_7()
             .size_full()
             .flex()
-            .gap_5()
+            .flex_col()
+            .gap_1()
             .items_center()
             .justify_center()
             .child(
                 div()
                     .text_size(px(24.0))
-                    .text_color(if is_selected {
+                    .text_color(Palette::current().fg(if is_selected {
                         rgb(0xDDD5D0)
                     } else {
                         rgb(0x6E6E6E)
-                    })
+                    }))
                     .overflow_hidden()
                     .text_ellipsis()
                     .whitespace_nowrap()
                     .child(result),
             )
+            .children(interpretation.map(|interpretation| {
+                div()
+                    .text_size(Palette::current().text_size(px(12.)))
+                    .text_color(Palette::current().fg(rgb(0x6E6E6E)))
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .child(interpretation)
+            }))
+            .into_any_element()
+    }
+}
+
+impl CalcData {
+    fn render_history_entry(&self, slot: usize, is_selected: bool) -> gpui::AnyElement {
+        let Some(entry) = self
+            .history
+            .read()
+            .ok()
+            .and_then(|history| history.get(slot).cloned())
+        else {
+            return div().into_any_element();
+        };
+
+        let palette = Palette::current();
+        div()
+            .px_4()
+            .py_2()
+            .w_full()
+            .flex()
+            .gap_5()
+            .items_center()
+            .children(
+                self.icon
+                    .as_ref()
+                    .map(|icon| img(Arc::clone(icon)).size(px(24.))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .text_size(palette.text_size(px(14.)))
+                    .text_color(palette.fg(rgb(0x6E6E6E)))
+                    .overflow_hidden()
+                    .text_ellipsis()
+                    .whitespace_nowrap()
+                    .child(entry.expression),
+            )
+            .child(
+                div()
+                    .text_size(px(16.0))
+                    .text_color(palette.fg(if is_selected {
+                        rgb(0xDDD5D0)
+                    } else {
+                        rgb(0x999999)
+                    }))
+                    .child(entry.result),
+            )
             .into_any_element()
     }
 }
@@ -0,0 +1,222 @@
+use std::{path::Path, sync::Arc};
+
+use gpui::{
+    AnyElement, FontWeight, HighlightStyle, IntoElement, ParentElement, SharedString, Styled,
+    StyledText, div, img, px, rgb,
+};
+
+use crate::{
+    launcher::{
+        ExecMode, Launcher,
+        children::{Highlight, RenderableChildImpl},
+        doc_launcher::{DocLookup, DocPage},
+    },
+    loader::utils::ApplicationAction,
+    utils::palette::Palette,
+};
+
+/// The `slot`-th result of the latest documentation lookup
+#[derive(Clone)]
+pub struct DocData {
+    lookup: Arc<DocLookup>,
+    slot: usize,
+    icon: Option<Arc<Path>>,
+}
+
+impl DocData {
+    pub fn new(lookup: Arc<DocLookup>, slot: usize, icon: Option<Arc<Path>>) -> Self {
+        Self { lookup, slot, icon }
+    }
+    /// The lookup of all children of the launcher, started by the first one
+    pub fn lookup(&self) -> Option<&Arc<DocLookup>> {
+        (self.slot == 0).then_some(&self.lookup)
+    }
+    pub fn page(&self) -> Option<DocPage> {
+        self.lookup.page(self.slot)
+    }
+    pub fn based_show(&self, keyword: &str, mode: &str) -> bool {
+        // results of the previous query stay until the lookup of the current one is done
+        !keyword.trim().is_empty() && mode != "all" && self.page().is_some()
+    }
+    /// Pages with both sources can show the tldr page, tldr pages can copy their first example
+    pub fn actions(&self) -> Option<Arc<[Arc<ApplicationAction>]>> {
+        let page = self.page()?;
+        page.tldr.as_ref()?;
+
+        let mut actions = Vec::new();
+        if page.section.is_some() {
+            let mut show = ApplicationAction::new("doc_tldr");
+            show.name = Some("Show tldr page".into());
+            actions.push(Arc::new(show));
+        }
+        let mut copy = ApplicationAction::new("doc_copy_example");
+        copy.name = Some("Copy example".into());
+        actions.push(Arc::new(copy));
+        Some(actions.into())
+    }
+    /// Copies the first example of the tldr page without its placeholder braces
+    pub fn copy_example_exec(&self) -> ExecMode {
+        let example = self
+            .page()
+            .and_then(|page| self.lookup.tldr_page(page.tldr.as_deref()?))
+            .and_then(|tldr| Some(tldr.common_example()?.formatted().0));
+        match example {
+            Some(command) => ExecMode::Copy {
+                content: command.into(),
+                record: None,
+            },
+            None => ExecMode::None,
+        }
+    }
+}
+
+impl<'a> RenderableChildImpl<'a> for DocData {
+    fn render(
+        &self,
+        _launcher: &Arc<Launcher>,
+        is_selected: bool,
+        highlight: Highlight,
+    ) -> AnyElement {
+        let Some(page) = self.page() else {
+            return div().into_any_element();
+        };
+        let palette = Palette::current();
+        let title = match page.section.as_ref() {
+            Some(section) => format!("{}({section})", page.name),
+            None => page.name.clone(),
+        };
+        let style = HighlightStyle {
+            color: Some(palette.fg(rgb(0xffffff))),
+            font_weight: Some(FontWeight::BOLD),
+            ..Default::default()
+        };
+        let ranges = highlight.ranges(&title);
+        let source = match (page.section.is_some(), page.tldr.is_some()) {
+            (true, true) => "man · tldr",
+            (true, false) => "man",
+            _ => "tldr",
+        };
+
+        div()
+            .px_4()
+            .py_2()
+            .w_full()
+            .flex()
+            .gap_5()
+            .items_center()
+            .children(
+                self.icon
+                    .as_ref()
+                    .map(|icon| img(Arc::clone(icon)).size(px(24.))),
+            )
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .text_size(palette.text_size(px(14.)))
+                            .text_color(palette.fg(if is_selected {
+                                rgb(0xffffff)
+                            } else {
+                                rgb(0xcccccc)
+                            }))
+                            .whitespace_nowrap()
+                            .child(
+                                StyledText::new(SharedString::from(title))
+                                    .with_highlights(ranges.into_iter().map(|r| (r, style))),
+                            ),
+                    )
+                    .child(
+                        div()
+                            .text_size(palette.text_size(px(12.)))
+                            .text_color(palette.fg(if is_selected {
+                                rgb(0x999999)
+                            } else {
+                                rgb(0x666666)
+                            }))
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .child(page.description),
+                    ),
+            )
+            .child(
+                div()
+                    .text_size(palette.text_size(px(11.)))
+                    .text_color(palette.fg(rgb(0x666666)))
+                    .child(source),
+            )
+            .into_any_element()
+    }
+    fn build_exec(&self, _launcher: &Arc<Launcher>) -> Option<ExecMode> {
+        let page = self.page()?;
+        Some(match page.man_command() {
+            Some(exec) => ExecMode::App {
+                exec,
+                terminal: true,
+                raise: None,
+            },
+            // pages only found in the tldr cache are read in place
+            None => ExecMode::Next,
+        })
+    }
+    fn priority(&self, launcher: &Arc<Launcher>) -> f32 {
+        // keeps the order of the lookup
+        launcher.priority as f32 + self.slot as f32 * 0.01
+    }
+    fn search(&'a self, _launcher: &Arc<Launcher>) -> &'a str {
+        ""
+    }
+    fn render_next(&self, _launcher: &Arc<Launcher>) -> Option<AnyElement> {
+        let page = self.page()?;
+        let tldr = self.lookup.tldr_page(page.tldr.as_deref()?)?;
+        let palette = Palette::current();
+        let placeholder = HighlightStyle {
+            color: Some(palette.fg(rgb(0x8fb8de))),
+            ..Default::default()
+        };
+
+        Some(
+            div()
+                .px_4()
+                .pb_2()
+                .flex()
+                .flex_col()
+                .gap_1()
+                .text_size(palette.text_size(px(12.)))
+                .children(tldr.description.iter().map(|line| {
+                    div()
+                        .text_color(palette.fg(rgb(0x999999)))
+                        .child(line.clone())
+                }))
+                .children(tldr.examples.iter().map(|example| {
+                    let (command, placeholders) = example.formatted();
+                    div()
+                        .pt_1()
+                        .flex()
+                        .flex_col()
+                        .gap_1()
+                        .child(
+                            div()
+                                .text_color(palette.fg(rgb(0xcccccc)))
+                                .child(example.description.clone()),
+                        )
+                        .child(
+                            div()
+                                .px_2()
+                                .py_1()
+                                .rounded_md()
+                                .bg(palette.bg(rgb(0x262626)))
+                                .text_color(palette.fg(rgb(0xffffff)))
+                                .child(
+                                    StyledText::new(SharedString::from(command)).with_highlights(
+                                        placeholders.into_iter().map(|r| (r, placeholder)),
+                                    ),
+                                ),
+                        )
+                }))
+                .into_any_element(),
+        )
+    }
+}
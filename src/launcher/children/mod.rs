@@ -1,22 +1,37 @@
-use std::sync::Arc;
+use std::{
+    collections::HashSet,
+    future::Future,
+    ops::Range,
+    path::Path,
+    sync::{Arc, OnceLock},
+    time::Duration,
+};
 
-use gpui::{AnyElement, SharedString};
+use gpui::{AnyElement, Image, IntoElement, ParentElement, SharedString, Styled, div, px, rgb};
+use smallvec::SmallVec;
 
 pub mod app_data;
 pub mod calc_data;
+pub mod doc_data;
 pub mod mpris_data;
+pub mod url_data;
 pub mod weather_data;
 
 use crate::{
     launcher::{
-        ExecMode, Launcher, LauncherType, audio_launcher::AudioLauncherFunctions,
-        utils::MprisState, weather_launcher::WeatherData,
+        ExecMode, Launcher, LauncherType,
+        audio_launcher::AudioLauncherFunctions,
+        utils::{MprisData, MprisState},
+        weather_launcher::WeatherData,
+        web_launcher::split_bang,
     },
-    loader::utils::{AppData, ApplicationAction, ExecVariable},
-    utils::config::HomeType,
+    loader::utils::{AppData, ApplicationAction, ExecVariable, MatchPolicy, normalize_search},
+    utils::{accent::TagColor, config::HomeType, palette::Palette},
 };
 
 use calc_data::CalcData;
+use doc_data::DocData;
+use url_data::UrlData;
 
 /// Creates enum RenderableChild,
 /// ## Example:
@@ -45,14 +60,25 @@ macro_rules! renderable_enum {
         }
 
         impl<'a> RenderableChildDelegate<'a> for $name {
-            fn render(&self, is_selected: bool) -> AnyElement {
+            fn render(&self, is_selected: bool, highlight: Highlight) -> AnyElement {
                 match self {
-                    $(Self::$variant {inner, launcher} => inner.render(launcher, is_selected)),*
+                    $(Self::$variant {inner, launcher} => inner.render(launcher, is_selected, highlight)),*
                 }
             }
 
             fn build_action_exec(&self, action: &ApplicationAction) -> ExecMode {
                 match self {
+                    Self::CalcLike { inner, .. } if action.method == "calc_history_remove" => {
+                        inner.remove_exec()
+                    }
+                    Self::DocLike { .. } if action.method == "doc_tldr" => ExecMode::Next,
+                    Self::DocLike { inner, .. } if action.method == "doc_copy_example" => {
+                        inner.copy_example_exec()
+                    }
+                    Self::AppLike { inner, .. } if action.method == "http" => ExecMode::Http {
+                        action: action.clone(),
+                        name: inner.name.as_ref().map(|name| name.to_string()).unwrap_or_default(),
+                    },
                     $(Self::$variant {launcher, ..} => { ExecMode::from_app_action(action, launcher) }),*
                 }
             }
@@ -69,6 +95,18 @@ macro_rules! renderable_enum {
                 }
             }
 
+            fn render_next(&self) -> Option<AnyElement> {
+                match self {
+                    $(Self::$variant {inner, launcher} => inner.render_next(launcher)),*
+                }
+            }
+
+            fn confirm(&self) -> bool {
+                match self {
+                    $(Self::$variant {inner, ..} => inner.confirm()),*
+                }
+            }
+
 
             fn vars(&self) -> Option<&[ExecVariable]> {
                 match self {
@@ -79,7 +117,10 @@ macro_rules! renderable_enum {
 
             fn actions(&self) -> Option<Arc<[Arc<ApplicationAction>]>> {
                 match self {
-                    Self::AppLike { inner, ..} => Some(inner.actions.clone()),
+                    Self::AppLike { inner, launcher } => Some(inner.context_actions(launcher)),
+                    Self::CalcLike { inner, ..} => inner.actions(),
+                    Self::DocLike { inner, ..} => inner.actions(),
+                    Self::MusicLike { inner, ..} => Some(inner.actions.clone()),
                     _ => None
                 }
             }
@@ -95,13 +136,23 @@ macro_rules! renderable_enum {
             }
 
             fn home(&self) -> HomeType {
-                self.launcher().home
+                match self {
+                    // recent searches only show up once they match the query
+                    Self::AppLike { inner, launcher } if inner.is_recent_search(launcher) => {
+                        HomeType::Search
+                    }
+                    _ => self.launcher().home,
+                }
             }
 
             fn is_async(&self) -> bool {
                 self.launcher().r#async
             }
 
+            fn async_timeout(&self) -> Duration {
+                self.launcher().async_timeout
+            }
+
             fn alias(&'a self) -> Option<&'a str> {
                 self.launcher().alias.as_deref()
             }
@@ -125,7 +176,7 @@ macro_rules! renderable_enum {
 
         impl <'a> $name {
             #[inline(always)]
-            fn launcher(&'a self) -> &'a Launcher {
+            pub fn launcher(&'a self) -> &'a Launcher {
                 match self {
                     $(Self::$variant {launcher, ..} => &launcher),*
                 }
@@ -135,9 +186,13 @@ macro_rules! renderable_enum {
     };
 }
 impl RenderableChild {
-    pub fn based_show(&self, query: &str) -> Option<bool> {
+    /// Whether the child shows up for `query` regardless of its search string, or `None` if it
+    /// is filtered like any other child. `typed` is the query before it was lowercased.
+    pub fn based_show(&self, query: &str, typed: &str, mode: &str) -> Option<bool> {
         match self {
-            Self::CalcLike { inner, .. } => Some(inner.based_show(query)),
+            Self::CalcLike { inner, .. } => Some(inner.based_show(query, mode)),
+            Self::DocLike { inner, .. } => Some(inner.based_show(query, mode)),
+            Self::UrlLike { inner, .. } => Some(inner.based_show(typed)),
             Self::MusicLike { inner, .. } => {
                 // this skips early if the music launcher is empty
                 if inner.raw.is_some() {
@@ -146,38 +201,72 @@ impl RenderableChild {
                     Some(false)
                 }
             }
+            // the search row shows up whenever a known bang is typed
+            Self::AppLike { launcher, inner } => match &launcher.launcher_type {
+                LauncherType::Web(web)
+                    if inner.exec.is_none() && split_bang(&web.engines, query).is_some() =>
+                {
+                    Some(true)
+                }
+                _ => None,
+            },
             _ => None,
         }
     }
     pub async fn update_async(mut self) -> Option<Self> {
         match &mut self {
             Self::MusicLike { inner, .. } => {
-                let new_inner = AudioLauncherFunctions::new().and_then(|launcher| {
-                    launcher
-                        .get_current_player()
-                        .and_then(|player| launcher.get_metadata(&player))
-                });
+                // the dbus calls block, which the timeout of `refresh_async` can't interrupt
+                let (player, new_inner) = tokio::task::spawn_blocking(|| {
+                    AudioLauncherFunctions::new().and_then(|launcher| {
+                        let player = launcher.get_current_player()?;
+                        let metadata = launcher.get_metadata(&player)?;
+                        Some((Some(player), Some(metadata)))
+                    })
+                })
+                .await
+                .ok()
+                .flatten()
+                .unwrap_or_default();
+
+                let same_track = new_inner.as_ref().and_then(|i| i.metadata.title.as_ref())
+                    == inner.raw.as_ref().and_then(|i| i.metadata.title.as_ref());
+                let same_position = new_inner.as_ref().and_then(|i| i.position)
+                    == inner.raw.as_ref().and_then(|i| i.position);
 
                 // early return if nothing has changed
-                if new_inner.as_ref().and_then(|i| i.metadata.title.as_ref())
-                    == inner.raw.as_ref().and_then(|i| i.metadata.title.as_ref())
-                {
+                if same_track && same_position && player == inner.player {
                     return None;
                 }
 
-                if let Some(new_inner) = &new_inner {
-                    inner.image = new_inner.get_image().await.map(|(image, _)| image);
+                // only cached covers are loaded here; remote covers are fetched in a follow-up
+                // task so the title and artist show up immediately
+                if !same_track {
+                    inner.image = new_inner.as_ref().and_then(|i| i.get_cached_image());
+                    if inner.image.is_some() {
+                        inner.image_generation.bump();
+                    }
+                }
+                if player != inner.player {
+                    inner.actions = player
+                        .as_deref()
+                        .map(MprisData::context_actions)
+                        .unwrap_or_default();
                 }
                 inner.raw = new_inner;
+                inner.player = player;
             }
             Self::WeatherLike { inner, launcher } => {
                 let LauncherType::Weather(wtr) = &launcher.launcher_type else {
                     unreachable!("WeatherLike variant must have LauncherType::Weather");
                 };
 
-                let (new_weather_data, changed) = WeatherData::fetch_async(wtr).await?;
+                let (mut new_weather_data, changed) = WeatherData::fetch_async(wtr).await?;
 
                 if changed {
+                    if new_weather_data.icon != inner.icon {
+                        new_weather_data.icon_generation.bump();
+                    }
                     *inner = new_weather_data;
                 } else {
                     return None;
@@ -188,12 +277,62 @@ impl RenderableChild {
 
         Some(self)
     }
+
+    /// Returns the cover url of a music tile that was updated without its cover art
+    pub fn pending_cover(&self) -> Option<&str> {
+        match self {
+            Self::MusicLike { inner, .. } if inner.image.is_none() => {
+                inner.raw.as_ref()?.metadata.art.as_deref()
+            }
+            _ => None,
+        }
+    }
+
+    /// Sets the cover art if the tile still shows the track it was fetched for
+    pub fn patch_cover(&mut self, art_url: &str, image: Arc<Image>) -> bool {
+        match self {
+            Self::MusicLike { inner, .. }
+                if inner.raw.as_ref().and_then(|r| r.metadata.art.as_deref()) == Some(art_url) =>
+            {
+                inner.image = Some(image);
+                inner.image_generation.bump();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Runs [`update_async`](Self::update_async) bounded by the launcher's `async_timeout`
+    pub async fn refresh_async(self) -> AsyncRefresh<Self> {
+        let timeout = self.async_timeout();
+        with_timeout(timeout, self.update_async()).await
+    }
+}
+
+/// Outcome of a single async refresh
+pub enum AsyncRefresh<T> {
+    Updated(T),
+    Unchanged,
+    TimedOut,
+}
+
+pub async fn with_timeout<T, F>(timeout: Duration, update: F) -> AsyncRefresh<T>
+where
+    F: Future<Output = Option<T>>,
+{
+    match tokio::time::timeout(timeout, update).await {
+        Ok(Some(update)) => AsyncRefresh::Updated(update),
+        Ok(None) => AsyncRefresh::Unchanged,
+        Err(_) => AsyncRefresh::TimedOut,
+    }
 }
 renderable_enum! {
     enum RenderableChild {
         AppLike(AppData),
         CalcLike(CalcData),
+        DocLike(DocData),
         MusicLike(MprisState),
+        UrlLike(UrlData),
         WeatherLike(WeatherData),
     }
 }
@@ -205,13 +344,90 @@ impl RenderableChild {
             _ => None,
         }
     }
+
+    /// Number of distinct launchers the children belong to
+    pub fn count_launchers(children: &[RenderableChild]) -> usize {
+        children
+            .iter()
+            .map(|child| child.launcher() as *const Launcher)
+            .collect::<HashSet<_>>()
+            .len()
+    }
+
+    /// How queries have to match [`search`](RenderableChildDelegate::search) for the child to
+    /// show up
+    pub fn match_policy(&self) -> MatchPolicy {
+        match self {
+            Self::AppLike { inner, .. } => inner.match_policy,
+            _ => MatchPolicy::Fuzzy,
+        }
+    }
+
+    /// Name the icon was resolved from, see [`AppData::set_icon`]
+    pub fn icon_name(&self) -> Option<&str> {
+        match self {
+            Self::AppLike { inner, .. } => inner.icon_name.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Applications can be shown as tiles of a grid, see `appearance.grid_columns`
+    pub fn is_tile(&self) -> bool {
+        match self {
+            Self::AppLike { launcher, .. } => {
+                matches!(launcher.launcher_type, LauncherType::App(_))
+            }
+            _ => false,
+        }
+    }
+
+    /// The child as a tile of a grid, children that are no tiles render as usual
+    pub fn render_tile(&self, is_selected: bool, highlight: Highlight) -> AnyElement {
+        match self {
+            Self::AppLike { inner, launcher } => {
+                inner.render_tile(launcher, is_selected, highlight)
+            }
+            _ => self.render(is_selected, highlight),
+        }
+    }
+
+    pub fn set_icon_path(&mut self, icon: Option<Arc<Path>>) {
+        if let Self::AppLike { inner, .. } = self {
+            inner.icon = icon;
+        }
+    }
+
+    /// Color the result is tagged with, falling back to the one of its launcher
+    pub fn accent(&self) -> Option<&TagColor> {
+        match self {
+            Self::AppLike { inner, launcher } => inner.accent.as_ref().or(launcher.accent.as_ref()),
+            _ => self.launcher().accent.as_ref(),
+        }
+    }
+
+    /// Key used to detect the same target across launchers (exec command or url)
+    pub fn dedup_key(&self) -> Option<&str> {
+        match self {
+            Self::AppLike { inner, launcher } => match launcher.launcher_type {
+                LauncherType::Web(_) => None,
+                _ => inner.exec.as_deref(),
+            },
+            _ => None,
+        }
+    }
 }
 
+/// Implemented by [`RenderableChild`], forwards to the [`RenderableChildImpl`] of the variant
+/// together with its launcher
 pub trait RenderableChildDelegate<'a> {
-    fn render(&self, is_selected: bool) -> AnyElement;
+    fn render(&self, is_selected: bool, highlight: Highlight) -> AnyElement;
     fn build_action_exec(&'a self, action: &'a ApplicationAction) -> ExecMode;
     fn build_exec(&self) -> Option<ExecMode>;
+    /// See [`RenderableChildImpl::search`]
     fn search(&'a self) -> &'a str;
+    fn render_next(&self) -> Option<AnyElement>;
+    /// See [`RenderableChildImpl::confirm`]
+    fn confirm(&self) -> bool;
     fn vars(&self) -> Option<&[ExecVariable]>;
     fn actions(&self) -> Option<Arc<[Arc<ApplicationAction>]>>;
 }
@@ -223,55 +439,234 @@ pub trait LauncherValues<'a> {
     fn alias(&'a self) -> Option<&'a str>;
     fn priority(&self) -> f32;
     fn is_async(&self) -> bool;
+    fn async_timeout(&self) -> Duration;
     fn home(&self) -> HomeType;
     fn spawn_focus(&self) -> bool;
     fn launcher_type(&'a self) -> &'a LauncherType;
 }
 
+/// Implemented by the data held in each variant of [`RenderableChild`]
 pub trait RenderableChildImpl<'a> {
-    fn render(&self, launcher: &Arc<Launcher>, is_selected: bool) -> AnyElement;
+    fn render(
+        &self,
+        launcher: &Arc<Launcher>,
+        is_selected: bool,
+        highlight: Highlight,
+    ) -> AnyElement;
     fn build_exec(&self, launcher: &Arc<Launcher>) -> Option<ExecMode>;
     fn priority(&self, launcher: &Arc<Launcher>) -> f32;
+    /// Text the query is matched against. It is normalized with
+    /// [`normalize_search`](crate::loader::utils::normalize_search) once when the child is
+    /// built, never per query, so the filter can compare it without allocating.
     fn search(&'a self, launcher: &Arc<Launcher>) -> &'a str;
+
+    /// Content shown below the tile after it was activated with `method = "next"`. Defaults
+    /// to the launcher's `next_content`.
+    fn render_next(&self, launcher: &Arc<Launcher>) -> Option<AnyElement> {
+        let content = launcher.next_content.clone()?;
+        let palette = Palette::current();
+        Some(
+            div()
+                .px_4()
+                .pb_2()
+                .text_size(palette.text_size(px(13.)))
+                .text_color(palette.fg(rgb(0xcccccc)))
+                .child(content)
+                .into_any_element(),
+        )
+    }
+
+    /// Whether the exec key has to be pressed a second time before the child runs
+    fn confirm(&self) -> bool {
+        false
+    }
 }
 
-pub trait SherlockSearch {
-    /// Both self and substring should already be lowercased to increase performance
-    fn fuzzy_match<'a>(&'a self, substring: &'a str) -> bool;
+/// The lowercased query, handed to the rows so they can highlight why they matched
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Highlight<'a> {
+    pub query: &'a str,
+    /// Fuzzy window of the search, see [`SherlockSearch::fuzzy_match`]
+    pub window: usize,
 }
 
-impl<T: AsRef<str>> SherlockSearch for T {
-    fn fuzzy_match(&self, pattern: &str) -> bool {
-        let t_bytes = self.as_ref().as_bytes();
-        let p_bytes = pattern.as_bytes();
+impl Highlight<'_> {
+    /// Byte ranges of `text` matched by the query, following the rules of
+    /// [`SherlockSearch::fuzzy_match`]. `text` is normalized like the search strings, so it can
+    /// be the displayed name. Empty if the query does not match `text`.
+    pub fn ranges(&self, text: &str) -> SmallVec<[Range<usize>; 4]> {
+        if text.is_ascii() {
+            // normalizing ASCII only lowercases it, so every byte stays where it was
+            let indices = normalize_search(text).fuzzy_match_indices(self.query, self.window);
+            return merge_ranges(indices.into_iter().flatten().map(|pos| pos..pos + 1));
+        }
+        self.ranges_in(&NormalizedText::new(text))
+    }
 
-        // Early return for empty bytes
-        if p_bytes.is_empty() {
-            return true;
+    /// Like [`ranges`](Self::ranges) for text that was normalized before
+    pub fn ranges_in(&self, text: &NormalizedText) -> SmallVec<[Range<usize>; 4]> {
+        let Some(indices) = text.normalized.fuzzy_match_indices(self.query, self.window) else {
+            return SmallVec::new();
+        };
+        if text.origins.is_empty() {
+            merge_ranges(indices.into_iter().map(|pos| pos..pos + 1))
+        } else {
+            merge_ranges(indices.into_iter().map(|pos| text.origins[pos].clone()))
         }
-        if t_bytes.is_empty() {
-            return false;
+    }
+}
+
+/// Joins adjacent and overlapping ranges, e.g. the bytes of one character
+fn merge_ranges(origins: impl Iterator<Item = Range<usize>>) -> SmallVec<[Range<usize>; 4]> {
+    let mut ranges: SmallVec<[Range<usize>; 4]> = SmallVec::new();
+    for origin in origins {
+        match ranges.last_mut() {
+            Some(last) if last.end >= origin.start => last.end = last.end.max(origin.end),
+            _ => ranges.push(origin),
         }
+    }
+    ranges
+}
 
-        let mut current_target = t_bytes;
+/// Text normalized like the search strings, along with the characters of the original text
+/// each byte came from
+#[derive(Clone, Debug, Default)]
+pub struct NormalizedText {
+    normalized: String,
+    /// Empty for ASCII text, whose bytes keep their positions
+    origins: Vec<Range<usize>>,
+}
 
-        // memchr find first search byte
-        while let Some(pos) = memchr::memchr(p_bytes[0], current_target) {
-            if sequential_check(p_bytes, &current_target[pos..], 5) {
-                return true;
-            }
-            // Move past the current match to find the next possible start
-            if pos + 1 >= current_target.len() {
-                break;
+impl NormalizedText {
+    pub fn new(text: &str) -> Self {
+        if text.is_ascii() {
+            return Self {
+                normalized: text.to_ascii_lowercase(),
+                origins: Vec::new(),
+            };
+        }
+        let mut normalized = String::with_capacity(text.len());
+        let mut origins: Vec<Range<usize>> = Vec::with_capacity(text.len());
+        for (start, c) in text.char_indices() {
+            let origin = start..start + c.len_utf8();
+            normalized.push_str(&normalize_search(&text[origin.clone()]));
+            origins.resize(normalized.len(), origin);
+        }
+        Self {
+            normalized,
+            origins,
+        }
+    }
+}
+
+/// [`NormalizedText`] of a name, built the first time the name is highlighted so rows don't
+/// normalize it on every render. It isn't part of the identity of the data holding it.
+#[derive(Clone, Debug, Default)]
+pub struct NameHighlight(OnceLock<(SharedString, NormalizedText)>);
+
+impl PartialEq for NameHighlight {
+    fn eq(&self, _other: &Self) -> bool {
+        true
+    }
+}
+
+impl NameHighlight {
+    /// Byte ranges of `name` matched by the query, see [`Highlight::ranges`]
+    pub fn ranges(&self, name: &SharedString, highlight: Highlight) -> SmallVec<[Range<usize>; 4]> {
+        let (cached, text) = self
+            .0
+            .get_or_init(|| (name.clone(), NormalizedText::new(name)));
+        if cached == name {
+            highlight.ranges_in(text)
+        } else {
+            highlight.ranges(name)
+        }
+    }
+}
+
+pub trait SherlockSearch {
+    /// Both self and substring should already be normalized by
+    /// [`normalize_search`](crate::loader::utils::normalize_search) to increase performance.
+    /// Consecutive characters of the substring may be at most `window` characters apart.
+    fn fuzzy_match<'a>(&'a self, substring: &'a str, window: usize) -> bool;
+    /// Like [`fuzzy_match`](SherlockSearch::fuzzy_match), but returns the byte position each
+    /// byte of the substring was matched at
+    fn fuzzy_match_indices(&self, substring: &str, window: usize) -> Option<Vec<usize>>;
+}
+
+impl<T: AsRef<str>> SherlockSearch for T {
+    fn fuzzy_match(&self, pattern: &str, window: usize) -> bool {
+        let text = self.as_ref();
+        if text.is_ascii() && pattern.is_ascii() {
+            find_match(text.as_bytes(), pattern.as_bytes(), window).is_some()
+        } else {
+            find_char_match(text, pattern, window).is_some()
+        }
+    }
+    fn fuzzy_match_indices(&self, pattern: &str, window: usize) -> Option<Vec<usize>> {
+        let text = self.as_ref();
+        let mut indices = Vec::with_capacity(pattern.len());
+        if text.is_ascii() && pattern.is_ascii() {
+            let (t_bytes, p_bytes) = (text.as_bytes(), pattern.as_bytes());
+            let start = find_match(t_bytes, p_bytes, window)?;
+            if p_bytes.is_empty() {
+                return Some(indices);
             }
-            current_target = &current_target[pos + 1..];
+            indices.push(start);
+            sequential_check(p_bytes, &t_bytes[start..], window, &mut |pos| {
+                indices.push(start + pos)
+            });
+        } else {
+            let start = find_char_match(text, pattern, window)?;
+            let mut chars = pattern.chars();
+            let Some(first) = chars.next() else {
+                return Some(indices);
+            };
+            let rest = start + first.len_utf8();
+            indices.extend(start..rest);
+            char_check(chars.as_str(), &text[rest..], window, &mut |pos, len| {
+                indices.extend(rest + pos..rest + pos + len)
+            });
         }
+        Some(indices)
+    }
+}
 
-        false
+/// Returns the position the first complete match starts at. Both have to be ASCII, so every
+/// byte is a whole character.
+fn find_match(t_bytes: &[u8], p_bytes: &[u8], window: usize) -> Option<usize> {
+    // Early return for empty bytes
+    if p_bytes.is_empty() {
+        return Some(0);
+    }
+    if t_bytes.is_empty() {
+        return None;
     }
+
+    let mut offset = 0;
+
+    // memchr find first search byte
+    while let Some(pos) = memchr::memchr(p_bytes[0], &t_bytes[offset..]) {
+        if sequential_check(p_bytes, &t_bytes[offset + pos..], window, &mut |_| {}) {
+            return Some(offset + pos);
+        }
+        // Move past the current match to find the next possible start
+        offset += pos + 1;
+        if offset >= t_bytes.len() {
+            break;
+        }
+    }
+
+    None
 }
 
-fn sequential_check(pattern: &[u8], target: &[u8], window_size: usize) -> bool {
+/// `record` receives the position each following pattern byte was matched at
+fn sequential_check(
+    pattern: &[u8],
+    target: &[u8],
+    window_size: usize,
+    record: &mut impl FnMut(usize),
+) -> bool {
     // pattern[0] was already matched by memchr at target[0]
     let mut t_idx = 1;
 
@@ -283,6 +678,7 @@ fn sequential_check(pattern: &[u8], target: &[u8], window_size: usize) -> bool {
 
         while t_idx < limit {
             if target[t_idx] == pattern_char {
+                record(t_idx);
                 t_idx += 1; // Start searching for the NEXT char from here
                 found = true;
                 break;
@@ -298,3 +694,180 @@ fn sequential_check(pattern: &[u8], target: &[u8], window_size: usize) -> bool {
 
     true
 }
+
+/// Like [`find_match`] for text that is not ASCII. Compares whole characters, so the window
+/// counts characters rather than bytes.
+fn find_char_match(text: &str, pattern: &str, window: usize) -> Option<usize> {
+    let mut chars = pattern.chars();
+    let Some(first) = chars.next() else {
+        return Some(0);
+    };
+    let rest = chars.as_str();
+    text.match_indices(first)
+        .map(|(start, _)| start)
+        .find(|&start| {
+            char_check(
+                rest,
+                &text[start + first.len_utf8()..],
+                window,
+                &mut |_, _| {},
+            )
+        })
+}
+
+/// Matches `pattern` in `target`, right after the match of the preceding character. `record`
+/// receives the byte position and length each character was matched at.
+fn char_check(
+    pattern: &str,
+    target: &str,
+    window_size: usize,
+    record: &mut impl FnMut(usize, usize),
+) -> bool {
+    let mut remaining = target.char_indices();
+    for pattern_char in pattern.chars() {
+        // the window only covers the characters following the previous match
+        let Some((pos, _)) = remaining
+            .by_ref()
+            .take(window_size)
+            .find(|&(_, c)| c == pattern_char)
+        else {
+            return false;
+        };
+        record(pos, pattern_char.len_utf8());
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use futures::StreamExt;
+    use futures::stream::FuturesUnordered;
+    use std::time::Instant;
+
+    async fn updater(idx: usize, hang: bool, timeout: Duration) -> (usize, AsyncRefresh<usize>) {
+        let update = async move {
+            if hang {
+                futures::future::pending::<()>().await;
+            }
+            Some(idx)
+        };
+        (idx, with_timeout(timeout, update).await)
+    }
+
+    #[tokio::test]
+    async fn test_fast_refresh_is_not_blocked_by_hanging_one() {
+        let timeout = Duration::from_millis(200);
+        let start = Instant::now();
+
+        let mut updates = FuturesUnordered::new();
+        updates.push(updater(0, true, timeout));
+        updates.push(updater(1, false, timeout));
+
+        // the fast updater must arrive first and well before the timeout
+        let (idx, first) = updates.next().await.unwrap();
+        assert_eq!(idx, 1);
+        assert!(matches!(first, AsyncRefresh::Updated(1)));
+        assert!(start.elapsed() < timeout);
+
+        // the hanging updater gets marked as timed out
+        let (idx, second) = updates.next().await.unwrap();
+        assert_eq!(idx, 0);
+        assert!(matches!(second, AsyncRefresh::TimedOut));
+        assert!(start.elapsed() >= timeout);
+    }
+
+    #[test]
+    fn test_fuzzy_match_keeps_chars_whole() {
+        // "ü" and "¼" share their last byte, "ö" and "ü" their first
+        assert!(!"ö¼".fuzzy_match("ü", 5));
+        assert!("ö¼ü".fuzzy_match("ü", 5));
+        assert!("öl".fuzzy_match("öl", 5));
+        assert!(!"ól".fuzzy_match("öl", 5));
+        assert!("ö-l".fuzzy_match("öl", 5));
+        assert!("мир".fuzzy_match("мр", 5));
+    }
+
+    #[test]
+    fn test_fuzzy_match_unicode() {
+        assert!("café".fuzzy_match("cfé", 5));
+        assert!(!"cafe".fuzzy_match("café", 5));
+        assert!(!"café".fuzzy_match("cafe", 5));
+        // the window counts characters, not the three bytes of each of them
+        assert!("日本語".fuzzy_match("日語", 2));
+        assert!(!"日本語".fuzzy_match("日語", 1));
+        assert!("日本語の本".fuzzy_match("語本", 2));
+        assert!(!"日本語の本".fuzzy_match("語本", 1));
+        assert!(!"日本".fuzzy_match("本日", 5));
+        // ASCII queries on text that is not ASCII
+        assert!("🦊 firefox".fuzzy_match("ff", 5));
+        assert!("fire🔥fox".fuzzy_match("efo", 2));
+        assert!(!"fire🔥fox".fuzzy_match("efo", 1));
+        assert!("🦊🔥".fuzzy_match("🔥", 5));
+        assert!(!"🦊".fuzzy_match("🔥", 5));
+    }
+
+    #[test]
+    fn test_fuzzy_match_indices() {
+        assert_eq!("firefox".fuzzy_match_indices("fox", 5), Some(vec![0, 5, 6]));
+        assert_eq!(
+            "gnome terminal".fuzzy_match_indices("ntr", 5),
+            Some(vec![1, 6, 8])
+        );
+        // the bytes of "ü" stay together
+        assert_eq!("ö¼ü".fuzzy_match_indices("ü", 5), Some(vec![4, 5]));
+        assert_eq!("café".fuzzy_match_indices("cé", 5), Some(vec![0, 3, 4]));
+        assert_eq!("日本語".fuzzy_match_indices("語", 5), Some(vec![6, 7, 8]));
+        assert_eq!("é".fuzzy_match_indices("", 5), Some(vec![]));
+        assert_eq!("firefox".fuzzy_match_indices("", 5), Some(vec![]));
+        assert_eq!("firefox".fuzzy_match_indices("xyz", 5), None);
+    }
+
+    #[test]
+    fn test_highlight_ranges() {
+        let highlight = |query| Highlight { query, window: 5 };
+        let ranges = |query, text| highlight(query).ranges(text).into_vec();
+
+        assert_eq!(ranges("fir", "Firefox"), vec![0..3]);
+        assert_eq!(ranges("ntr", "Gnome Terminal"), vec![1..2, 6..7, 8..9]);
+        // the first start that matches, just like fuzzy_match
+        assert_eq!(ranges("te", "Kate Text Editor"), vec![2..4]);
+        assert_eq!(ranges("fox", "Firefox"), vec![0..1, 5..7]);
+        // matches only in the keywords leave the name plain
+        assert!(ranges("browser", "Firefox").is_empty());
+        assert!(ranges("", "Firefox").is_empty());
+        // the display text is normalized like the query
+        assert_eq!(ranges("tu", "Über Tüten"), vec![6..9]);
+        assert_eq!(ranges("cafe", "Café"), vec![0..5]);
+        assert_eq!(ranges("olb", "Ölbild"), vec![0..4]);
+        assert_eq!(ranges("тер", "Терминал"), vec![0..6]);
+        assert_eq!(ranges("fil", "ﬁle"), vec![0..4]);
+
+        // names normalized once match like the text itself, a changed name isn't matched
+        // against the old one
+        let name = NameHighlight::default();
+        for text in ["Gnome Terminal", "Café", "Ölbild", "ﬁle"] {
+            let text = SharedString::from(text);
+            for query in ["ntr", "cafe", "olb", "fil", "xyz"] {
+                assert_eq!(
+                    name.ranges(&text, highlight(query)).into_vec(),
+                    ranges(query, &text),
+                    "{query:?} in {text:?}"
+                );
+            }
+        }
+
+        for (query, text) in [
+            ("fir", "firefox"),
+            ("ntr", "gnome terminal"),
+            ("gte", "gnome terminal"),
+            ("xyz", "firefox"),
+        ] {
+            assert_eq!(
+                text.fuzzy_match(query, 5),
+                !ranges(query, text).is_empty(),
+                "{query:?} in {text:?}"
+            );
+        }
+    }
+}
@@ -1,11 +1,29 @@
 use std::sync::Arc;
 
-use gpui::{AnyElement, Image, ImageSource, IntoElement, ParentElement, Styled, div, img, px, rgb};
+use gpui::{
+    AnyElement, Image, ImageSource, IntoElement, ParentElement, Styled, StyledImage, div, img, px,
+    relative, rgb,
+};
 
-use crate::launcher::{ExecMode, Launcher, children::RenderableChildImpl, utils::MprisState};
+use crate::{
+    launcher::{
+        ExecMode, Launcher,
+        audio_launcher::MprisCommand,
+        children::{Highlight, RenderableChildImpl},
+        utils::MprisState,
+    },
+    ui::image::{fade_in, placeholder},
+    utils::palette::Palette,
+};
 
 impl<'a> RenderableChildImpl<'a> for MprisState {
-    fn render(&self, _launcher: &Arc<Launcher>, is_selected: bool) -> AnyElement {
+    fn render(
+        &self,
+        _launcher: &Arc<Launcher>,
+        is_selected: bool,
+        _highlight: Highlight,
+    ) -> AnyElement {
+        let palette = Palette::current();
         div()
             .px_4()
             .py_2()
@@ -14,25 +32,35 @@ impl<'a> RenderableChildImpl<'a> for MprisState {
             .gap_5()
             .items_center()
             .child(if let Some(icon) = &self.image {
-                img(ImageSource::Image(Arc::clone(icon)))
-                    .size(px(64.))
-                    .rounded_md()
+                fade_in(
+                    img(ImageSource::Image(Arc::clone(icon)))
+                        .size(px(64.))
+                        .rounded_md()
+                        .with_loading(|| placeholder(px(64.))),
+                    &self.image_generation,
+                )
+            } else if self.raw.as_ref().is_some_and(|r| r.metadata.art.is_some()) {
+                // cover is still being fetched
+                placeholder(px(64.))
             } else {
-                img(ImageSource::Image(Arc::new(Image::empty()))).size(px(24.))
+                img(ImageSource::Image(Arc::new(Image::empty())))
+                    .size(px(24.))
+                    .into_any_element()
             })
             .child(
                 div()
-                    .text_color(if is_selected {
+                    .text_color(palette.fg(if is_selected {
                         rgb(0xffffff)
                     } else {
                         rgb(0xcccccc)
-                    })
+                    }))
+                    .flex_1()
                     .flex_col()
                     .justify_between()
                     .items_center()
                     .child(
                         div()
-                            .text_sm()
+                            .text_size(palette.text_size(px(14.)))
                             .overflow_hidden()
                             .text_ellipsis()
                             .whitespace_nowrap()
@@ -44,18 +72,41 @@ impl<'a> RenderableChildImpl<'a> for MprisState {
                             ),
                     )
                     .child(
-                        div().text_xs().children(
+                        div().text_size(palette.text_size(px(12.))).children(
                             self.raw
                                 .as_ref()
                                 .and_then(|s| s.metadata.artists.as_ref())
                                 .map(|arts| arts.join(", ").to_string()),
                         ),
+                    )
+                    .children(
+                        self.raw
+                            .as_ref()
+                            .and_then(|s| s.progress())
+                            .map(|progress| {
+                                div()
+                                    .mt_2()
+                                    .h(px(3.))
+                                    .w_full()
+                                    .rounded_full()
+                                    .bg(palette.bg(rgb(0x3a3a3a)))
+                                    .child(
+                                        div()
+                                            .h_full()
+                                            .w(relative(progress))
+                                            .rounded_full()
+                                            .bg(palette.fg(rgb(0xcccccc))),
+                                    )
+                            }),
                     ),
             )
             .into_any_element()
     }
     fn build_exec(&self, _launcher: &Arc<Launcher>) -> Option<ExecMode> {
-        None
+        Some(ExecMode::Mpris {
+            player: self.player.clone()?,
+            command: MprisCommand::PlayPause,
+        })
     }
     fn priority(&self, launcher: &Arc<Launcher>) -> f32 {
         launcher.priority as f32
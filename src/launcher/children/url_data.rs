@@ -0,0 +1,145 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
+
+use gpui::{AnyElement, IntoElement, ParentElement, SharedString, Styled, div, img, px, rgb};
+
+use crate::{
+    launcher::{
+        ExecMode, Launcher, LauncherType,
+        children::{Highlight, RenderableChildImpl},
+        url_launcher::OpenTarget,
+    },
+    utils::{files::home_dir, palette::Palette},
+};
+
+/// Opens the query directly if it is a url or an existing path
+#[derive(Clone)]
+pub struct UrlData {
+    /// Target of the current query
+    target: Arc<RwLock<Option<OpenTarget>>>,
+    home: Option<PathBuf>,
+    /// Shown for urls
+    icon: Option<Arc<Path>>,
+    /// Shown for paths
+    folder_icon: Option<Arc<Path>>,
+    file_icon: Option<Arc<Path>>,
+}
+
+impl UrlData {
+    pub fn new(
+        icon: Option<Arc<Path>>,
+        folder_icon: Option<Arc<Path>>,
+        file_icon: Option<Arc<Path>>,
+    ) -> Self {
+        Self {
+            target: Arc::new(RwLock::new(None)),
+            home: home_dir().ok(),
+            icon,
+            folder_icon,
+            file_icon,
+        }
+    }
+    /// `keyword` is the query as typed, since paths are case sensitive
+    pub fn based_show(&self, keyword: &str) -> bool {
+        let target = OpenTarget::parse(keyword, self.home.as_deref());
+        let show = target.is_some();
+        if let Ok(mut writer) = self.target.write() {
+            *writer = target;
+        }
+        show
+    }
+    pub fn target(&self) -> Option<OpenTarget> {
+        self.target.read().ok()?.clone()
+    }
+}
+
+impl<'a> RenderableChildImpl<'a> for UrlData {
+    fn render(
+        &self,
+        launcher: &Arc<Launcher>,
+        is_selected: bool,
+        _highlight: Highlight,
+    ) -> AnyElement {
+        let Some(target) = self.target() else {
+            return div().into_any_element();
+        };
+        let (title, subtitle, icon) = match target {
+            OpenTarget::Url(url) => (url, "Open in browser", self.icon.as_ref()),
+            OpenTarget::Path(path) if path.is_dir() => (
+                path.display().to_string(),
+                "Open folder",
+                self.folder_icon.as_ref(),
+            ),
+            OpenTarget::Path(path) => (
+                path.display().to_string(),
+                "Open with default application",
+                self.file_icon.as_ref(),
+            ),
+        };
+        let subtitle = launcher.name.as_deref().map_or_else(
+            || subtitle.to_string(),
+            |name| format!("{subtitle} · {name}"),
+        );
+
+        let palette = Palette::current();
+        div()
+            .px_4()
+            .py_2()
+            .w_full()
+            .flex()
+            .gap_5()
+            .items_center()
+            .children(icon.map(|icon| img(Arc::clone(icon)).size(px(24.))))
+            .child(
+                div()
+                    .flex_1()
+                    .overflow_hidden()
+                    .child(
+                        div()
+                            .text_size(palette.text_size(px(14.)))
+                            .text_color(palette.fg(if is_selected {
+                                rgb(0xffffff)
+                            } else {
+                                rgb(0xcccccc)
+                            }))
+                            .overflow_hidden()
+                            .text_ellipsis()
+                            .whitespace_nowrap()
+                            .child(SharedString::from(title)),
+                    )
+                    .child(
+                        div()
+                            .text_size(palette.text_size(px(12.)))
+                            .text_color(palette.fg(if is_selected {
+                                rgb(0x999999)
+                            } else {
+                                rgb(0x666666)
+                            }))
+                            .child(subtitle),
+                    ),
+            )
+            .into_any_element()
+    }
+    fn build_exec(&self, launcher: &Arc<Launcher>) -> Option<ExecMode> {
+        Some(match self.target()? {
+            OpenTarget::Url(url) => ExecMode::Web {
+                engine: Some(String::from("plain")),
+                browser: match &launcher.launcher_type {
+                    LauncherType::Url(open) => open.browser.clone(),
+                    _ => None,
+                },
+                exec: Some(url),
+                bangs: Default::default(),
+            },
+            OpenTarget::Path(path) => ExecMode::Open { path },
+        })
+    }
+    fn priority(&self, launcher: &Arc<Launcher>) -> f32 {
+        launcher.priority as f32
+    }
+    fn search(&'a self, _launcher: &Arc<Launcher>) -> &'a str {
+        ""
+    }
+}
@@ -1,17 +1,23 @@
 use std::sync::Arc;
 
 use gpui::{
-    AnyElement, Image, ImageSource, IntoElement, ParentElement, Styled, div, img, linear_gradient,
-    px,
+    AnyElement, Background, Image, ImageSource, IntoElement, ParentElement, Styled, StyledImage,
+    div, hsla, img, linear_gradient, prelude::FluentBuilder, px,
 };
 
-use crate::launcher::{
-    ExecMode, Launcher, children::RenderableChildImpl, weather_launcher::WeatherData,
+use crate::{
+    launcher::{
+        ExecMode, Launcher, LauncherType,
+        children::{Highlight, RenderableChildImpl},
+        weather_launcher::WeatherData,
+    },
+    ui::image::{fade_in, placeholder},
+    utils::palette::Palette,
 };
 
 impl<'a> RenderableChildImpl<'a> for WeatherData {
-    fn build_exec(&self, _launcher: &Arc<Launcher>) -> Option<ExecMode> {
-        None
+    fn build_exec(&self, launcher: &Arc<Launcher>) -> Option<ExecMode> {
+        (launcher.method == "next").then_some(ExecMode::Next)
     }
     fn priority(&self, launcher: &Arc<Launcher>) -> f32 {
         launcher.priority as f32
@@ -19,20 +25,35 @@ impl<'a> RenderableChildImpl<'a> for WeatherData {
     fn search(&self, _launcher: &Arc<Launcher>) -> &'a str {
         ""
     }
-    fn render(&self, _launcher: &Arc<Launcher>, _is_selected: bool) -> AnyElement {
+    fn render(
+        &self,
+        launcher: &Arc<Launcher>,
+        _is_selected: bool,
+        _highlight: Highlight,
+    ) -> AnyElement {
+        let show_forecast = matches!(
+            &launcher.launcher_type,
+            LauncherType::Weather(wtr) if wtr.show_forecast
+        );
+        let palette = Palette::current();
         div()
             .px_4()
             .py_2()
             .rounded_md()
             .bg({
                 let (p1, p2) = self.css.background();
-                linear_gradient(90., p1, p2)
+                // the gradients are too light for high contrast text
+                if palette.high_contrast {
+                    Background::from(palette.bg(p1.color))
+                } else {
+                    linear_gradient(90., p1, p2)
+                }
             })
-            .text_color(self.css.color())
+            .text_color(palette.fg(self.css.color()))
             .flex_col()
             .gap_5()
             .items_center()
-            .text_size(px(12.0))
+            .text_size(palette.text_size(px(12.0)))
             .child(self.format_str.clone())
             .child(
                 div()
@@ -40,12 +61,64 @@ impl<'a> RenderableChildImpl<'a> for WeatherData {
                     .items_center()
                     .gap_5()
                     .child(if let Some(icon) = self.icon.as_ref() {
-                        img(Arc::clone(&icon)).size(px(48.))
+                        fade_in(
+                            img(Arc::clone(&icon))
+                                .size(px(48.))
+                                .with_loading(|| placeholder(px(48.))),
+                            &self.icon_generation,
+                        )
                     } else {
-                        img(ImageSource::Image(Arc::new(Image::empty()))).size(px(24.))
+                        img(ImageSource::Image(Arc::new(Image::empty())))
+                            .size(px(24.))
+                            .into_any_element()
                     })
                     .child(div().text_size(px(40.0)).child(self.temperature.clone())),
             )
+            .when(show_forecast, |this| this.children(self.forecast_strip()))
             .into_any_element()
     }
+    fn render_next(&self, _launcher: &Arc<Launcher>) -> Option<AnyElement> {
+        let strip = self.forecast_strip()?;
+        let palette = Palette::current();
+        Some(
+            div()
+                .px_4()
+                .pb_2()
+                .text_size(palette.text_size(px(12.0)))
+                .text_color(palette.fg(self.css.color()))
+                .child(strip)
+                .into_any_element(),
+        )
+    }
+}
+
+impl WeatherData {
+    fn forecast_strip(&self) -> Option<AnyElement> {
+        if self.forecast.is_empty() {
+            return None;
+        }
+        Some(
+            div()
+                .flex()
+                .gap_2()
+                .children(self.forecast.iter().map(|day| {
+                    div()
+                        .flex_1()
+                        .flex()
+                        .flex_col()
+                        .items_center()
+                        .py_1()
+                        .rounded_md()
+                        .bg(Palette::current().bg(hsla(0., 0., 1., 0.1)))
+                        .child(day.day.clone())
+                        .child(if let Some(icon) = day.icon.as_ref() {
+                            img(Arc::clone(&icon)).size(px(20.))
+                        } else {
+                            img(ImageSource::Image(Arc::new(Image::empty()))).size(px(20.))
+                        })
+                        .child(format!("{} / {}", day.high, day.low))
+                }))
+                .into_any_element(),
+        )
+    }
 }
@@ -0,0 +1,108 @@
+use std::{
+    io::{BufRead, Write},
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+};
+
+use gpui::SharedString;
+
+use crate::{
+    launcher::{Launcher, LauncherType, children::RenderableChild},
+    loader::utils::{AppData, normalize_search},
+    utils::config::HomeType,
+};
+
+/// Set once a line was printed, so that closing the window exits successfully
+static SELECTED: AtomicBool = AtomicBool::new(false);
+
+/// Items read from stdin in dmenu mode, see `--dmenu`. Executing an item prints it, or its
+/// line number with `print_index`.
+#[derive(Clone, Debug)]
+pub struct DmenuLauncher {
+    pub print_index: bool,
+}
+
+impl DmenuLauncher {
+    /// One child per non-empty line of `input`, in input order. Line numbers start at 0.
+    pub fn children(self, input: impl BufRead) -> Vec<RenderableChild> {
+        let print_index = self.print_index;
+        let launcher = Arc::new(Launcher {
+            name: Some(String::from("dmenu")),
+            priority: 1,
+            home: HomeType::Home,
+            launcher_type: LauncherType::Dmenu(self),
+            ..Default::default()
+        });
+
+        input
+            .lines()
+            .map_while(Result::ok)
+            .enumerate()
+            .filter(|(_, line)| !line.is_empty())
+            .map(|(i, line)| {
+                let mut inner = AppData::new();
+                inner.search_string = normalize_search(&line).into_owned();
+                inner.exec = Some(if print_index {
+                    i.to_string()
+                } else {
+                    line.clone()
+                });
+                inner.name = Some(SharedString::from(line));
+                // equal priorities keep the input order
+                inner.priority = Some(1.0);
+                RenderableChild::AppLike {
+                    launcher: Arc::clone(&launcher),
+                    inner,
+                }
+            })
+            .collect()
+    }
+}
+
+/// Prints the selection to stdout
+pub fn print_selection(output: &str) {
+    let mut stdout = std::io::stdout().lock();
+    if writeln!(stdout, "{output}")
+        .and_then(|_| stdout.flush())
+        .is_ok()
+    {
+        SELECTED.store(true, Ordering::SeqCst);
+    }
+}
+
+/// Exits once the window is closed, with status 1 if nothing was selected like dmenu
+pub fn exit() -> ! {
+    std::process::exit(if SELECTED.load(Ordering::SeqCst) {
+        0
+    } else {
+        1
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::ExecMode;
+    use crate::launcher::children::RenderableChildDelegate;
+
+    fn outputs(print_index: bool) -> Vec<String> {
+        let input = "Firefox\n\nÖlbild\nkitty\n".as_bytes();
+        DmenuLauncher { print_index }
+            .children(input)
+            .iter()
+            .filter_map(|child| match child.build_exec() {
+                Some(ExecMode::Print { output }) => Some(output),
+                _ => None,
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_children_print_lines_or_indices() {
+        assert_eq!(outputs(false), ["Firefox", "Ölbild", "kitty"]);
+        // the empty line keeps its number
+        assert_eq!(outputs(true), ["0", "2", "3"]);
+    }
+}
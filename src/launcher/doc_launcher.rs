@@ -0,0 +1,470 @@
+use std::{
+    collections::HashMap,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    process::Stdio,
+    sync::{Arc, Mutex, OnceLock, RwLock},
+};
+
+use serde::Deserialize;
+use tokio::process::Command;
+
+use crate::utils::{config::OtherDefaults, files::home_dir};
+
+/// Looks up command documentation while typing in the launcher's mode: manual pages through
+/// `man -k` and the pages of a local tldr cache. Either source can be turned off and a missing
+/// tldr cache only disables that source.
+#[derive(Clone, Debug, Deserialize)]
+pub struct DocLauncher {
+    #[serde(default = "OtherDefaults::bool_true")]
+    pub man: bool,
+    #[serde(default = "OtherDefaults::bool_true")]
+    pub tldr: bool,
+    /// Directory containing the tldr `pages/`, the caches of common tldr clients if unset
+    #[serde(default)]
+    pub tldr_path: Option<PathBuf>,
+    #[serde(default = "DocLauncher::default_max_results")]
+    pub max_results: usize,
+}
+
+impl DocLauncher {
+    fn default_max_results() -> usize {
+        10
+    }
+}
+
+/// A documentation page matching the query
+#[derive(Clone, Debug, PartialEq)]
+pub struct DocPage {
+    pub name: String,
+    /// Section of the manual page, unset for pages only found in the tldr cache
+    pub section: Option<String>,
+    pub description: String,
+    pub tldr: Option<PathBuf>,
+}
+
+impl DocPage {
+    /// Command opening the manual page in the pager
+    pub fn man_command(&self) -> Option<String> {
+        let section = self.section.as_ref()?;
+        Some(format!("man {section} {}", self.name))
+    }
+}
+
+/// Results of the latest lookup, shared by all children of a documentation launcher
+#[derive(Debug)]
+pub struct DocLookup {
+    man: bool,
+    tldr: Option<Arc<TldrPages>>,
+    max_results: usize,
+    state: RwLock<LookupState>,
+    /// The most recently opened tldr page
+    opened: Mutex<Option<(PathBuf, Arc<TldrPage>)>>,
+}
+
+#[derive(Debug, Default)]
+struct LookupState {
+    /// Query of the latest lookup, which might still be running
+    requested: String,
+    pages: Vec<DocPage>,
+}
+
+impl DocLookup {
+    pub fn new(launcher: &DocLauncher) -> Self {
+        let tldr = launcher
+            .tldr
+            .then(|| TldrPages::find(launcher.tldr_path.as_deref()))
+            .flatten();
+        Self {
+            man: launcher.man,
+            tldr: tldr.map(Arc::new),
+            max_results: launcher.max_results,
+            state: RwLock::new(LookupState::default()),
+            opened: Mutex::new(None),
+        }
+    }
+
+    /// Registers a lookup for `query`. Returns false if the query was already looked up or is
+    /// empty, which also clears the results.
+    pub fn request(&self, query: &str) -> bool {
+        let query = query.trim();
+        let Ok(mut state) = self.state.write() else {
+            return false;
+        };
+        if state.requested == query {
+            return false;
+        }
+        state.requested = query.to_string();
+        if query.is_empty() {
+            state.pages.clear();
+            return false;
+        }
+        true
+    }
+
+    /// Runs the lookup of `query`. Nothing is stored if another lookup was requested meanwhile.
+    /// Dropping the future kills a running `man` process.
+    pub async fn run(self: Arc<Self>, query: String) -> bool {
+        let query = query.trim().to_string();
+        let man = if self.man {
+            apropos(&query).await
+        } else {
+            Vec::new()
+        };
+        let tldr = match self.tldr.as_ref() {
+            Some(tldr) => {
+                let (tldr, needle, limit) = (Arc::clone(tldr), query.clone(), self.max_results);
+                tokio::task::spawn_blocking(move || tldr.search(&needle, limit))
+                    .await
+                    .unwrap_or_default()
+            }
+            None => Vec::new(),
+        };
+        let pages = merge_pages(&query, man, tldr, self.max_results);
+
+        let Ok(mut state) = self.state.write() else {
+            return false;
+        };
+        if state.requested != query {
+            return false;
+        }
+        state.pages = pages;
+        true
+    }
+
+    pub fn page(&self, slot: usize) -> Option<DocPage> {
+        self.state.read().ok()?.pages.get(slot).cloned()
+    }
+
+    /// The parsed tldr page at `path`, kept around while it is shown
+    pub fn tldr_page(&self, path: &Path) -> Option<Arc<TldrPage>> {
+        let mut opened = self.opened.lock().ok()?;
+        if let Some((opened_path, page)) = opened.as_ref()
+            && opened_path == path
+        {
+            return Some(Arc::clone(page));
+        }
+        let page = Arc::new(TldrPage::parse(&fs::read_to_string(path).ok()?));
+        *opened = Some((path.to_path_buf(), Arc::clone(&page)));
+        Some(page)
+    }
+}
+
+/// Runs `man -k` for the query, an unavailable `man` yields no pages
+async fn apropos(query: &str) -> Vec<DocPage> {
+    let output = Command::new("man")
+        .arg("-k")
+        .arg("--")
+        .arg(query)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .kill_on_drop(true)
+        .output()
+        .await;
+    match output {
+        Ok(output) => String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(parse_apropos_line)
+            .collect(),
+        Err(_) => Vec::new(),
+    }
+}
+
+/// Parses a line of `man -k`, e.g. `ls (1) - list directory contents`. Lines listing several
+/// names for one page keep the first.
+fn parse_apropos_line(line: &str) -> Option<DocPage> {
+    let (page, description) = line.split_once(" - ")?;
+    let (names, section) = page.trim().strip_suffix(')')?.rsplit_once('(')?;
+    let name = names.split(',').next()?.trim();
+    let section = section.trim();
+
+    // both end up in a command line
+    let valid_name = name
+        .chars()
+        .all(|c| c.is_alphanumeric() || matches!(c, '.' | '_' | '-' | '+' | ':' | '@'));
+    if name.is_empty() || !valid_name || !section.chars().all(|c| c.is_ascii_alphanumeric()) {
+        return None;
+    }
+    Some(DocPage {
+        name: name.to_string(),
+        section: Some(section.to_string()),
+        description: description.trim().to_string(),
+        tldr: None,
+    })
+}
+
+/// How well the name of a page matches the query, lower is better
+fn match_rank(name: &str, query: &str) -> u8 {
+    let name = name.to_lowercase();
+    if name == query {
+        0
+    } else if name.starts_with(query) {
+        1
+    } else if name.contains(query) {
+        2
+    } else {
+        3
+    }
+}
+
+/// Combines the pages of both sources, tldr pages are attached to the manual page of the same
+/// command
+fn merge_pages(query: &str, man: Vec<DocPage>, tldr: Vec<DocPage>, limit: usize) -> Vec<DocPage> {
+    let query = query.to_lowercase();
+    let mut pages = man;
+    for page in tldr {
+        match pages.iter_mut().find(|p| p.name == page.name) {
+            Some(existing) => existing.tldr = page.tldr,
+            None => pages.push(page),
+        }
+    }
+    pages.sort_by_key(|page| match_rank(&page.name, &query));
+    pages.truncate(limit);
+    pages
+}
+
+/// Pages of a local tldr cache
+#[derive(Debug)]
+pub struct TldrPages {
+    root: PathBuf,
+    /// Page of every command, platform pages take precedence over common ones
+    index: OnceLock<HashMap<String, PathBuf>>,
+}
+
+impl TldrPages {
+    /// Uses `path` or the first cache of a known tldr client that contains pages
+    fn find(path: Option<&Path>) -> Option<Self> {
+        let candidates: Vec<PathBuf> = match path {
+            Some(path) => vec![path.to_path_buf()],
+            None => {
+                let cache = xdg::BaseDirectories::new().get_cache_home();
+                let home = home_dir().ok();
+                [
+                    cache.as_ref().map(|c| c.join("tealdeer/tldr-pages")),
+                    cache.as_ref().map(|c| c.join("tldr")),
+                    home.as_ref().map(|h| h.join(".tldr/cache")),
+                ]
+                .into_iter()
+                .flatten()
+                .collect()
+            }
+        };
+        let root = candidates
+            .into_iter()
+            .find(|dir| dir.join("pages").is_dir())?;
+        Some(Self {
+            root,
+            index: OnceLock::new(),
+        })
+    }
+
+    fn index(&self) -> &HashMap<String, PathBuf> {
+        self.index.get_or_init(|| {
+            let mut index = HashMap::new();
+            for platform in ["common", "linux"] {
+                let Ok(entries) = fs::read_dir(self.root.join("pages").join(platform)) else {
+                    continue;
+                };
+                for path in entries.flatten().map(|entry| entry.path()) {
+                    if path.extension().is_some_and(|ext| ext == "md")
+                        && let Some(name) = path.file_stem().and_then(|s| s.to_str())
+                    {
+                        index.insert(name.to_string(), path.clone());
+                    }
+                }
+            }
+            index
+        })
+    }
+
+    /// Pages whose command contains the query, best matches first
+    fn search(&self, query: &str, limit: usize) -> Vec<DocPage> {
+        let query = query.to_lowercase();
+        let mut matches: Vec<(&String, &PathBuf)> = self
+            .index()
+            .iter()
+            .filter(|(name, _)| name.contains(&query))
+            .collect();
+        matches.sort_by(|a, b| {
+            match_rank(a.0, &query)
+                .cmp(&match_rank(b.0, &query))
+                .then_with(|| a.0.cmp(b.0))
+        });
+        matches
+            .into_iter()
+            .take(limit)
+            .map(|(name, path)| DocPage {
+                name: name.clone(),
+                section: None,
+                description: fs::read_to_string(path)
+                    .map(|content| TldrPage::parse(&content).summary())
+                    .unwrap_or_default(),
+                tldr: Some(path.clone()),
+            })
+            .collect()
+    }
+}
+
+/// A tldr page, see <https://github.com/tldr-pages/tldr/blob/main/contributing-guides/style-guide.md>
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TldrPage {
+    pub title: String,
+    pub description: Vec<String>,
+    pub examples: Vec<TldrExample>,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct TldrExample {
+    pub description: String,
+    /// Command with `{{placeholders}}`
+    pub command: String,
+}
+
+impl TldrPage {
+    pub fn parse(markdown: &str) -> Self {
+        let mut page = Self::default();
+        for line in markdown.lines().map(str::trim) {
+            if let Some(title) = line.strip_prefix("# ") {
+                page.title = title.trim().to_string();
+            } else if let Some(description) = line.strip_prefix('>') {
+                page.description.push(strip_links(description.trim()));
+            } else if let Some(description) = line.strip_prefix("- ") {
+                page.examples.push(TldrExample {
+                    description: description.trim().trim_end_matches(':').to_string(),
+                    command: String::new(),
+                });
+            } else if let Some(command) = line
+                .strip_prefix('`')
+                .and_then(|line| line.strip_suffix('`'))
+                && let Some(example) = page.examples.last_mut()
+                && example.command.is_empty()
+            {
+                example.command = command.to_string();
+            }
+        }
+        page.examples.retain(|example| !example.command.is_empty());
+        page
+    }
+
+    /// The first line of the description
+    pub fn summary(&self) -> String {
+        self.description.first().cloned().unwrap_or_default()
+    }
+
+    /// Command of the first example, usually the most common use
+    pub fn common_example(&self) -> Option<&TldrExample> {
+        self.examples.first()
+    }
+}
+
+impl TldrExample {
+    /// The command without placeholder braces together with the ranges of the placeholders in
+    /// it, e.g. `tar cf {{target.tar}}` becomes `tar cf target.tar` with `7..17`
+    pub fn formatted(&self) -> (String, Vec<Range<usize>>) {
+        let mut text = String::with_capacity(self.command.len());
+        let mut placeholders = Vec::new();
+        let mut rest = self.command.as_str();
+        while let Some(start) = rest.find("{{") {
+            let Some(len) = rest[start + 2..].find("}}") else {
+                break;
+            };
+            text.push_str(&rest[..start]);
+            let begin = text.len();
+            text.push_str(&rest[start + 2..start + 2 + len]);
+            placeholders.push(begin..text.len());
+            rest = &rest[start + 2 + len + 2..];
+        }
+        text.push_str(rest);
+        (text, placeholders)
+    }
+}
+
+/// Removes the angle brackets of markdown autolinks
+fn strip_links(text: &str) -> String {
+    text.replace(['<', '>'], "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_apropos() {
+        let page = parse_apropos_line("ls (1)               - list directory contents").unwrap();
+        assert_eq!(page.name, "ls");
+        assert_eq!(page.section.as_deref(), Some("1"));
+        assert_eq!(page.description, "list directory contents");
+        assert_eq!(page.man_command().as_deref(), Some("man 1 ls"));
+
+        // mandoc lists every name of a page
+        let page = parse_apropos_line("gzip, gunzip, zcat(1) - compress or expand files").unwrap();
+        assert_eq!(page.name, "gzip");
+        assert_eq!(page.section.as_deref(), Some("1"));
+
+        assert!(parse_apropos_line("rm; reboot (1) - not a page").is_none());
+        assert!(parse_apropos_line("nothing appropriate.").is_none());
+    }
+
+    #[test]
+    fn test_parse_tldr_page() {
+        let page = TldrPage::parse(
+            "# tar\n\n> Archiving utility.\n> More information: <https://www.gnu.org/software/tar>.\n\n\
+             - [c]reate an archive from files:\n\n`tar cf {{path/to/target.tar}} {{file1 file2}}`\n\n\
+             - E[x]tract an archive:\n\n`tar xf {{source.tar}}`\n",
+        );
+        assert_eq!(page.title, "tar");
+        assert_eq!(page.summary(), "Archiving utility.");
+        assert_eq!(
+            page.description[1],
+            "More information: https://www.gnu.org/software/tar."
+        );
+        assert_eq!(page.examples.len(), 2);
+
+        let example = page.common_example().unwrap();
+        assert_eq!(example.description, "[c]reate an archive from files");
+        let (command, placeholders) = example.formatted();
+        assert_eq!(command, "tar cf path/to/target.tar file1 file2");
+        assert_eq!(placeholders, vec![7..25, 26..37]);
+    }
+
+    #[test]
+    fn test_merge_pages() {
+        let man = |name: &str| DocPage {
+            name: name.to_string(),
+            section: Some(String::from("1")),
+            description: String::new(),
+            tldr: None,
+        };
+        let tldr = |name: &str| DocPage {
+            name: name.to_string(),
+            section: None,
+            description: String::new(),
+            tldr: Some(PathBuf::from(format!("{name}.md"))),
+        };
+        let pages = merge_pages(
+            "tar",
+            vec![man("bsdtar"), man("tar")],
+            vec![tldr("tar"), tldr("tardy")],
+            10,
+        );
+        let names: Vec<&str> = pages.iter().map(|p| p.name.as_str()).collect();
+        assert_eq!(names, vec!["tar", "tardy", "bsdtar"]);
+        assert!(pages[0].section.is_some() && pages[0].tldr.is_some());
+    }
+
+    #[test]
+    fn test_lookup_requests() {
+        let lookup = DocLookup {
+            man: false,
+            tldr: None,
+            max_results: 10,
+            state: RwLock::new(LookupState::default()),
+            opened: Mutex::new(None),
+        };
+        assert!(lookup.request("tar"));
+        assert!(!lookup.request("tar "));
+        assert!(!lookup.request(""));
+        assert!(lookup.request("tar"));
+    }
+}
@@ -1,13 +1,22 @@
 pub mod app_launcher;
 pub mod audio_launcher;
 pub mod bookmark_launcher;
+pub mod browser_history;
+pub mod calc_history;
 pub mod calc_launcher;
 pub mod category_launcher;
 pub mod children;
+pub mod dmenu_launcher;
+pub mod doc_launcher;
 pub mod event_launcher;
+pub mod provenance;
+pub mod ssh_launcher;
 pub mod system_cmd_launcher;
+pub mod url_launcher;
 pub mod utils;
 pub mod weather_launcher;
+pub mod weather_provider;
+pub mod web_history;
 pub mod web_launcher;
 // Integrate later: TODO
 // pub mod clipboard_launcher;
@@ -20,32 +29,51 @@ pub mod web_launcher;
 // pub mod theme_picker;
 
 use serde::de::IntoDeserializer;
-use std::{collections::HashMap, sync::Arc, vec};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+    time::Duration,
+    vec,
+};
 
 use crate::{
     launcher::{
-        children::{RenderableChild, calc_data::CalcData},
+        calc_history::{CalcHistory, HistoryRecord},
+        children::{RenderableChild, calc_data::CalcData, doc_data::DocData, url_data::UrlData},
+        doc_launcher::DocLookup,
         weather_launcher::WeatherData,
     },
     loader::{
         Loader,
         application_loader::parse_priority,
         resolve_icon_path,
-        utils::{AppData, ApplicationAction, RawLauncher, deserialize_named_appdata},
+        utils::{
+            AppData, ApplicationAction, RawLauncher, deserialize_named_appdata, normalize_search,
+        },
     },
     ui::main_window::LauncherMode,
-    utils::{config::HomeType, intent::Capabilities},
+    utils::{
+        accent::TagColor,
+        config::{ConfigGuard, HomeType},
+        intent::Capabilities,
+        soft_delete::StagedChange,
+    },
 };
 
 use app_launcher::AppLauncher;
-use audio_launcher::MusicPlayerLauncher;
+use audio_launcher::{MprisCommand, MusicPlayerLauncher};
 use bookmark_launcher::BookmarkLauncher;
 use calc_launcher::CalculatorLauncher;
 use category_launcher::CategoryLauncher;
+use dmenu_launcher::DmenuLauncher;
+use doc_launcher::DocLauncher;
 use event_launcher::EventLauncher;
 use gpui::SharedString;
 use serde_json::Value;
+use ssh_launcher::SshLauncher;
 use system_cmd_launcher::CommandLauncher;
+use url_launcher::UrlLauncher;
 use weather_launcher::WeatherLauncher;
 use web_launcher::WebLauncher;
 
@@ -65,8 +93,12 @@ pub enum LauncherType {
     Calc(CalculatorLauncher),
     Category(CategoryLauncher),
     Command(CommandLauncher),
+    Dmenu(DmenuLauncher),
+    Doc(DocLauncher),
     Event(EventLauncher),
     MusicPlayer(MusicPlayerLauncher),
+    Ssh(SshLauncher),
+    Url(UrlLauncher),
     Weather(WeatherLauncher),
     Web(WebLauncher),
     #[default]
@@ -83,6 +115,43 @@ pub enum LauncherType {
 }
 
 impl LauncherType {
+    /// The `type` of the launcher in the config
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            Self::App(_) => "app_launcher",
+            Self::Bookmark(_) => "bookmarks",
+            Self::Calc(_) => "calculation",
+            Self::Category(_) => "categories",
+            Self::Command(_) => "command",
+            Self::Dmenu(_) => "dmenu",
+            Self::Doc(_) => "documentation",
+            Self::Event(_) => "teams_event",
+            Self::MusicPlayer(_) => "audio_sink",
+            Self::Ssh(_) => "ssh",
+            Self::Url(_) => "url_launcher",
+            Self::Weather(_) => "weather",
+            Self::Web(_) => "web_launcher",
+            Self::Empty => "empty",
+        }
+    }
+    /// Built-in icon for children without an icon of their own, see
+    /// [`Launcher::fallback_icon`]
+    pub fn default_icon(&self) -> Option<&'static str> {
+        match self {
+            Self::App(_) => Some("sherlock-app"),
+            Self::Bookmark(_) => Some("sherlock-bookmark"),
+            Self::Calc(_) => Some("sherlock-calc"),
+            Self::Category(_) => Some("sherlock-category"),
+            Self::Command(_) | Self::Ssh(_) => Some("sherlock-command"),
+            Self::Doc(_) => Some("sherlock-docs"),
+            Self::Url(_) | Self::Web(_) => Some("sherlock-web"),
+            Self::Dmenu(_)
+            | Self::Event(_)
+            | Self::MusicPlayer(_)
+            | Self::Weather(_)
+            | Self::Empty => None,
+        }
+    }
     pub fn get_render_obj(
         &self,
         launcher: Arc<Launcher>,
@@ -92,12 +161,16 @@ impl LauncherType {
     ) -> Option<Vec<RenderableChild>> {
         match self {
             Self::App(app) => {
+                let fallback = launcher.fallback_icon();
                 Loader::load_applications(Arc::clone(&launcher), counts, decimals, app.use_keywords)
                     .map(|ad| {
                         ad.into_iter()
-                            .map(|inner| RenderableChild::AppLike {
-                                launcher: Arc::clone(&launcher),
-                                inner,
+                            .map(|mut inner| {
+                                inner.apply_fallback_icon(fallback.as_deref());
+                                RenderableChild::AppLike {
+                                    launcher: Arc::clone(&launcher),
+                                    inner,
+                                }
                             })
                             .collect()
                     })
@@ -105,12 +178,16 @@ impl LauncherType {
             }
 
             Self::Bookmark(bkm) => {
-                BookmarkLauncher::find_bookmarks(&bkm.target_browser, Arc::clone(&launcher))
+                let fallback = launcher.fallback_icon();
+                bkm.find_bookmarks(Arc::clone(&launcher))
                     .map(|ad| {
                         ad.into_iter()
-                            .map(|inner| RenderableChild::AppLike {
-                                launcher: Arc::clone(&launcher),
-                                inner,
+                            .map(|mut inner| {
+                                inner.apply_fallback_icon(fallback.as_deref());
+                                RenderableChild::AppLike {
+                                    launcher: Arc::clone(&launcher),
+                                    inner,
+                                }
                             })
                             .collect()
                     })
@@ -126,13 +203,47 @@ impl LauncherType {
                     _ => vec![String::from("calc.math"), String::from("calc.units")],
                 };
                 let caps = Capabilities::from_strings(&capabilities);
-                let inner = CalcData::new(caps);
+                // decimal places of converted values
+                let precision = opts.get("decimals").and_then(Value::as_u64).unwrap_or(2) as usize;
+                // how the query was read, shown below the result
+                let interpretation = opts
+                    .get("show_interpretation")
+                    .and_then(Value::as_bool)
+                    .unwrap_or(true);
+
+                // one child per history entry, shown in the calculator mode with an empty query
+                let history_size = opts
+                    .get("history_size")
+                    .and_then(Value::as_u64)
+                    .unwrap_or(10) as usize;
+                let history = Arc::new(RwLock::new(CalcHistory::load(history_size)));
+                let icon = launcher
+                    .fallback_icon()
+                    .and_then(|name| resolve_icon_path(&name));
 
-                Some(vec![RenderableChild::CalcLike { launcher, inner }])
+                let mut children = vec![RenderableChild::CalcLike {
+                    launcher: Arc::clone(&launcher),
+                    inner: CalcData::new(caps.clone(), precision, Arc::clone(&history))
+                        .with_interpretation(interpretation),
+                }];
+                children.extend((0..history_size).map(|slot| {
+                    RenderableChild::CalcLike {
+                        launcher: Arc::clone(&launcher),
+                        inner: CalcData::history_entry(
+                            caps.clone(),
+                            precision,
+                            Arc::clone(&history),
+                            slot,
+                        )
+                        .with_icon(icon.clone()),
+                    }
+                }));
+                Some(children)
             }
 
             Self::Category(_) => {
                 let cmds = opts.get("categories")?;
+                let fallback = launcher.fallback_icon();
                 let app_data =
                     deserialize_named_appdata(cmds.clone().into_deserializer()).unwrap_or_default();
 
@@ -145,9 +256,12 @@ impl LauncherType {
                             .and_then(|exec| counts.get(exec))
                             .copied()
                             .unwrap_or(0u32);
-                        inner.icon = inner
-                            .icon
-                            .and_then(|i| i.to_str().and_then(resolve_icon_path));
+                        // the config holds the icon name, not a path
+                        if let Some(icon) = inner.icon.take() {
+                            inner.set_icon(&icon.to_string_lossy());
+                        }
+                        inner.apply_fallback_icon(fallback.as_deref());
+                        inner.search_string = normalize_search(&inner.search_string).into_owned();
                         inner.priority =
                             Some(parse_priority(launcher.priority as f32, count, decimals));
                         RenderableChild::AppLike {
@@ -162,6 +276,7 @@ impl LauncherType {
 
             Self::Command(_) => {
                 let cmds = opts.get("commands")?;
+                let fallback = launcher.fallback_icon();
                 let app_data =
                     deserialize_named_appdata(cmds.clone().into_deserializer()).unwrap_or_default();
                 let children: Vec<RenderableChild> = app_data
@@ -173,9 +288,12 @@ impl LauncherType {
                             .and_then(|exec| counts.get(exec))
                             .copied()
                             .unwrap_or(0u32);
-                        inner.icon = inner
-                            .icon
-                            .and_then(|i| i.to_str().and_then(resolve_icon_path));
+                        // the config holds the icon name, not a path
+                        if let Some(icon) = inner.icon.take() {
+                            inner.set_icon(&icon.to_string_lossy());
+                        }
+                        inner.apply_fallback_icon(fallback.as_deref());
+                        inner.search_string = normalize_search(&inner.search_string).into_owned();
                         inner.priority =
                             Some(parse_priority(launcher.priority as f32, count, decimals));
                         RenderableChild::AppLike {
@@ -188,11 +306,62 @@ impl LauncherType {
                 Some(children)
             }
 
+            Self::Doc(doc) => {
+                // one child per result, filled by the lookup of the current query
+                let lookup = Arc::new(DocLookup::new(doc));
+                let icon = launcher
+                    .fallback_icon()
+                    .and_then(|name| resolve_icon_path(&name));
+                Some(
+                    (0..doc.max_results)
+                        .map(|slot| RenderableChild::DocLike {
+                            launcher: Arc::clone(&launcher),
+                            inner: DocData::new(Arc::clone(&lookup), slot, icon.clone()),
+                        })
+                        .collect(),
+                )
+            }
+
+            Self::Ssh(ssh) => {
+                let fallback = launcher.fallback_icon();
+                ssh.find_hosts()
+                    .map(|hosts| {
+                        hosts
+                            .into_iter()
+                            .map(|mut inner| {
+                                let count = inner
+                                    .exec
+                                    .as_deref()
+                                    .and_then(|exec| counts.get(exec))
+                                    .copied()
+                                    .unwrap_or(0u32);
+                                inner.apply_fallback_icon(fallback.as_deref());
+                                inner.priority =
+                                    Some(parse_priority(launcher.priority as f32, count, decimals));
+                                RenderableChild::AppLike {
+                                    launcher: Arc::clone(&launcher),
+                                    inner,
+                                }
+                            })
+                            .collect()
+                    })
+                    .ok()
+            }
+
+            Self::Url(_) => {
+                let icon = launcher
+                    .fallback_icon()
+                    .and_then(|name| resolve_icon_path(&name));
+                let inner = UrlData::new(
+                    icon,
+                    resolve_icon_path("folder"),
+                    resolve_icon_path("text-x-generic"),
+                );
+                Some(vec![RenderableChild::UrlLike { launcher, inner }])
+            }
+
             Self::MusicPlayer(_) => {
-                let inner = utils::MprisState {
-                    raw: None,
-                    image: None,
-                };
+                let inner = utils::MprisState::default();
                 Some(vec![RenderableChild::MusicLike { launcher, inner }])
             }
 
@@ -209,14 +378,31 @@ impl LauncherType {
                 }
             }
 
-            Self::Web(_) => {
+            Self::Web(web) => {
                 let mut inner = AppData::new();
-                inner.icon = opts
-                    .get("icon")
-                    .and_then(Value::as_str)
-                    .and_then(|i| resolve_icon_path(i));
+                if let Some(icon) = opts.get("icon").and_then(Value::as_str) {
+                    inner.set_icon(icon);
+                }
+                inner.apply_fallback_icon(launcher.fallback_icon().as_deref());
 
-                Some(vec![RenderableChild::AppLike { launcher, inner }])
+                let recent: Vec<RenderableChild> = web
+                    .history
+                    .as_ref()
+                    .and_then(|history| history.read().ok())
+                    .map(|history| {
+                        history
+                            .entries()
+                            .enumerate()
+                            .map(|(slot, query)| {
+                                web_history::recent_search(&launcher, query, slot, &inner)
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default();
+
+                let mut children = vec![RenderableChild::AppLike { launcher, inner }];
+                children.extend(recent);
+                Some(children)
             }
 
             _ => None,
@@ -240,29 +426,38 @@ impl LauncherType {
 /// - **priority:** Base priority all children inherit from. Children priority will be a combination
 /// of this together with their execution counts and levenshtein similarity
 /// - **r#async:** Specifies whether the tile should be loaded/executed asynchronously
+/// - **async_timeout:** Maximum time an async refresh may take before the tile is marked as stale
 /// - **home:** Specifies whether the children should show on the `home` mode (empty
 /// search entry & mode == `all`)
 /// - **launcher_type:** Used to specify the kind of launcher and subsequently its children
 /// - **shortcut:** Specifies whether the child tile should show `modekey + number` shortcuts
 /// - **spawn_focus:** Specifies whether the tile should have focus whenever Sherlock launches
 /// search entry & mode == `all`)
+/// - **prefer_raise:** Specifies whether apps that already have a window are focused instead of
+/// started again, see [`compositor`](crate::utils::compositor)
 #[derive(Clone, Debug, Default)]
 pub struct Launcher {
     pub name: Option<String>,
     pub display_name: Option<SharedString>,
-    pub icon: Option<String>, // nu
+    pub icon: Option<String>,
     pub alias: Option<String>,
-    pub method: String,               // nu
-    pub exit: bool,                   // nu
-    pub next_content: Option<String>, // nu
+    pub method: String, // nu
+    pub exit: bool,     // nu
+    pub next_content: Option<String>,
     pub priority: u32,
     pub r#async: bool, // nu
+    pub async_timeout: Duration,
     pub home: HomeType,
     pub launcher_type: LauncherType,
-    pub shortcut: bool,                              // nu
+    pub shortcut: bool,
     pub spawn_focus: bool,                           // nu
     pub actions: Option<Vec<ApplicationAction>>,     // nu
     pub add_actions: Option<Vec<ApplicationAction>>, // nu
+    pub prefer_raise: bool,
+    /// File the launcher was read from, shared by all launchers of that file
+    pub config_file: Option<Arc<Path>>,
+    /// Color all results of the launcher are tagged with
+    pub accent: Option<TagColor>,
 }
 impl Launcher {
     pub fn from_raw(
@@ -281,20 +476,39 @@ impl Launcher {
             next_content: raw.next_content,
             priority: raw.priority as u32,
             r#async: raw.r#async,
+            async_timeout: Duration::from_millis(raw.async_timeout_ms),
             home: raw.home,
             launcher_type,
             shortcut: raw.shortcut,
             spawn_focus: raw.spawn_focus,
             actions: raw.actions,
             add_actions: raw.add_actions,
+            prefer_raise: raw.prefer_raise,
+            config_file: None,
+            accent: raw.accent,
         }
     }
+
+    /// Name of the icon shown for children without one: the `icon` of the launcher, the entry
+    /// of its type in `appearance.fallback_icons`, or the default of its type
+    pub fn fallback_icon(&self) -> Option<String> {
+        self.icon
+            .clone()
+            .or_else(|| {
+                let config = ConfigGuard::read().ok()?;
+                let type_name = self.launcher_type.type_name();
+                config.appearance.fallback_icons.get(type_name).cloned()
+            })
+            .or_else(|| self.launcher_type.default_icon().map(str::to_string))
+    }
 }
 
 pub enum ExecMode {
     App {
         exec: String,
         terminal: bool,
+        /// App id of the windows focused instead of starting the app again
+        raise: Option<String>,
     },
     Commmand {
         exec: String,
@@ -306,23 +520,62 @@ pub enum ExecMode {
         engine: Option<String>,
         browser: Option<String>,
         exec: Option<String>,
+        /// Engines picked by a leading bang of the query, see [`web_launcher::split_bang`]
+        bangs: HashMap<String, String>,
     },
     Copy {
         content: SharedString,
+        /// Calculation that enters the calculator history once it is copied
+        record: Option<HistoryRecord>,
+    },
+    Mpris {
+        player: String,
+        command: MprisCommand,
+    },
+    /// Toggles the `next_content` view of the selected tile
+    Next,
+    /// Toggles the "Source" section of the selected tile
+    ShowSource,
+    /// Hides the selected application, see [`HideEntry`](app_launcher::HideEntry)
+    Hide {
+        name: String,
+    },
+    SoftDelete(Box<dyn StagedChange>),
+    /// Sends the request of an `http` action, `name` fills its `{name}` placeholder
+    Http {
+        action: ApplicationAction,
+        name: String,
+    },
+    /// Opens a file or folder with its default application
+    Open {
+        path: PathBuf,
+    },
+    /// Prints the selected line in dmenu mode
+    Print {
+        output: String,
     },
     None,
 }
 impl ExecMode {
     pub fn from_appdata(app_data: &AppData, launcher: &Arc<Launcher>) -> Self {
+        if launcher.method == "next" {
+            return Self::Next;
+        }
         match &launcher.launcher_type {
             LauncherType::App(_) => Self::App {
                 exec: app_data.exec.clone().unwrap_or_default(),
                 terminal: app_data.terminal,
+                // windows of terminal apps belong to the terminal
+                raise: (launcher.prefer_raise && !app_data.terminal)
+                    .then(|| app_data.app_id())
+                    .flatten(),
             },
             LauncherType::Bookmark(bkm) => Self::Web {
                 engine: None,
-                browser: Some(bkm.target_browser.clone()),
+                // bookmarks of every browser open in the default one
+                browser: (!bkm.is_auto()).then(|| bkm.target_browser.clone()),
                 exec: app_data.exec.clone(),
+                bangs: HashMap::new(),
             },
             LauncherType::Category(_) => Self::Category {
                 category: LauncherMode::Alias {
@@ -334,24 +587,231 @@ impl ExecMode {
                     name: app_data.name.clone().unwrap_or_default(),
                 },
             },
-            LauncherType::Command(_) => Self::Commmand {
+            LauncherType::Command(_) | LauncherType::Ssh(_) => Self::Commmand {
                 exec: app_data.exec.clone().unwrap_or_default(),
             },
             LauncherType::Web(web) => Self::Web {
                 engine: Some(web.engine.clone()),
                 browser: web.browser.clone(),
                 exec: app_data.exec.clone(),
+                bangs: web.engines.clone(),
+            },
+            LauncherType::Dmenu(_) => Self::Print {
+                output: app_data.exec.clone().unwrap_or_default(),
             },
             _ => Self::None,
         }
     }
+    /// The action run instead of this one when the exec key is held: apps open in a terminal
+    /// and web searches use the secondary engine of their launcher
+    pub fn alternative(self, launcher: &Launcher) -> Option<Self> {
+        match (self, &launcher.launcher_type) {
+            (
+                Self::App {
+                    exec,
+                    terminal: false,
+                    ..
+                },
+                _,
+            ) => Some(Self::App {
+                exec,
+                terminal: true,
+                raise: None,
+            }),
+            (
+                Self::Web {
+                    browser,
+                    exec,
+                    bangs,
+                    ..
+                },
+                LauncherType::Web(web),
+            ) => Some(Self::Web {
+                engine: Some(web.secondary_engine.clone()?),
+                browser,
+                exec,
+                bangs,
+            }),
+            _ => None,
+        }
+    }
     pub fn from_app_action(action: &ApplicationAction, _launcher: &Arc<Launcher>) -> Self {
         match action.method.as_str() {
-            "app_launcher" | "command" => Self::Commmand {
+            // always starts a new instance, e.g. "Launch new instance"
+            "app_launcher" => Self::App {
+                exec: action.exec.clone().unwrap_or_default(),
+                terminal: action.terminal,
+                raise: None,
+            },
+            "command" => Self::Commmand {
                 exec: action.exec.clone().unwrap_or_default(),
             },
+            "show_source" => Self::ShowSource,
+            "hide" => Self::Hide {
+                name: action.exec.clone().unwrap_or_default(),
+            },
+            method if method.starts_with("mpris_") => {
+                match (MprisCommand::from_method(method), action.exec.clone()) {
+                    (Some(command), Some(player)) => Self::Mpris { player, command },
+                    _ => Self::None,
+                }
+            }
 
             _ => Self::None,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_icons() {
+        use crate::loader::assets::Assets;
+
+        let web = LauncherType::Web(WebLauncher {
+            engine: String::from("google"),
+            browser: None,
+            secondary_engine: None,
+            engines: HashMap::new(),
+            history: None,
+        });
+        assert_eq!(web.default_icon(), Some("sherlock-web"));
+        assert_eq!(
+            LauncherType::Calc(CalculatorLauncher {}).default_icon(),
+            Some("sherlock-calc")
+        );
+        assert_eq!(LauncherType::Empty.default_icon(), None);
+
+        // the launcher's own icon takes precedence over the one of its type
+        let launcher = Launcher {
+            launcher_type: web.clone(),
+            ..Default::default()
+        };
+        assert_eq!(launcher.fallback_icon().as_deref(), Some("sherlock-web"));
+        let launcher = Launcher {
+            icon: Some(String::from("firefox")),
+            ..launcher
+        };
+        assert_eq!(launcher.fallback_icon().as_deref(), Some("firefox"));
+
+        // built-in defaults have to ship with the binary
+        let types = [
+            web,
+            LauncherType::App(AppLauncher {
+                use_keywords: false,
+            }),
+            LauncherType::Bookmark(BookmarkLauncher {
+                target_browser: String::new(),
+                history: false,
+                history_limit: browser_history::DEFAULT_LIMIT,
+            }),
+            LauncherType::Calc(CalculatorLauncher {}),
+            LauncherType::Category(CategoryLauncher {}),
+            LauncherType::Command(CommandLauncher {}),
+            LauncherType::Doc(DocLauncher {
+                man: true,
+                tldr: true,
+                tldr_path: None,
+                max_results: 10,
+            }),
+            LauncherType::Ssh(SshLauncher::default()),
+            LauncherType::Url(UrlLauncher::default()),
+        ];
+        for launcher_type in types {
+            let name = launcher_type.default_icon().unwrap();
+            assert!(
+                Assets::get(&format!("icons/{name}.svg")).is_some(),
+                "{name} is missing"
+            );
+        }
+    }
+
+    #[test]
+    fn test_alternative_exec() {
+        let app = Launcher::default();
+        let Some(ExecMode::App { terminal, .. }) = ExecMode::App {
+            exec: String::from("htop"),
+            terminal: false,
+            raise: None,
+        }
+        .alternative(&app) else {
+            panic!("apps open in a terminal");
+        };
+        assert!(terminal);
+
+        // already runs in a terminal
+        let terminal_app = ExecMode::App {
+            exec: String::from("htop"),
+            terminal: true,
+            raise: None,
+        };
+        assert!(terminal_app.alternative(&app).is_none());
+
+        let web = |secondary_engine: Option<&str>| Launcher {
+            launcher_type: LauncherType::Web(WebLauncher {
+                engine: String::from("google"),
+                browser: None,
+                secondary_engine: secondary_engine.map(String::from),
+                engines: HashMap::new(),
+                history: None,
+            }),
+            ..Default::default()
+        };
+        let search = || ExecMode::Web {
+            engine: Some(String::from("google")),
+            browser: None,
+            exec: None,
+            bangs: HashMap::new(),
+        };
+        let Some(ExecMode::Web { engine, .. }) = search().alternative(&web(Some("duckduckgo")))
+        else {
+            panic!("web searches use the secondary engine");
+        };
+        assert_eq!(engine.as_deref(), Some("duckduckgo"));
+        assert!(search().alternative(&web(None)).is_none());
+    }
+
+    #[test]
+    fn test_prefer_raise() {
+        let launcher = |prefer_raise| {
+            Arc::new(Launcher {
+                launcher_type: LauncherType::App(AppLauncher {
+                    use_keywords: false,
+                }),
+                prefer_raise,
+                ..Default::default()
+            })
+        };
+        let mut app = AppData::new();
+        app.exec = Some(String::from("firefox %u"));
+        app.desktop_file = Some("/usr/share/applications/firefox.desktop".into());
+        let raise =
+            |app: &AppData, launcher: &Arc<Launcher>| match ExecMode::from_appdata(app, launcher) {
+                ExecMode::App { raise, .. } => raise,
+                _ => panic!("apps are launched as apps"),
+            };
+
+        assert_eq!(raise(&app, &launcher(true)).as_deref(), Some("firefox"));
+        assert_eq!(raise(&app, &launcher(false)), None);
+
+        // a new instance can still be started from the context menu
+        let actions = app.context_actions(&launcher(true));
+        assert_eq!(actions[0].name.as_deref(), Some("Launch new instance"));
+        let ExecMode::App {
+            exec,
+            raise: new_instance,
+            ..
+        } = ExecMode::from_app_action(&actions[0], &launcher(true))
+        else {
+            panic!("the action launches the app");
+        };
+        assert_eq!(exec, "firefox %u");
+        assert_eq!(new_instance, None);
+        assert!(app.context_actions(&launcher(false)).len() < actions.len());
+
+        app.terminal = true;
+        assert_eq!(raise(&app, &launcher(true)), None);
+    }
+}
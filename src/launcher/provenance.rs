@@ -0,0 +1,199 @@
+use std::{path::Path, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::launcher::{
+    LauncherType,
+    children::{LauncherValues, RenderableChild},
+    url_launcher::OpenTarget,
+};
+
+fn display_name(child: &RenderableChild) -> Option<String> {
+    match child {
+        RenderableChild::AppLike { inner, .. } => inner.name.as_ref().map(|n| n.to_string()),
+        RenderableChild::DocLike { inner, .. } => inner.page().map(|page| page.name),
+        _ => child.display_name().map(|n| n.to_string()),
+    }
+}
+
+/// Changes applied to an entry after it was read from its source record
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Transforms(u8);
+impl Transforms {
+    pub const ALIAS_NAME: u8 = 1 << 0;
+    pub const ALIAS_ICON: u8 = 1 << 1;
+    pub const ALIAS_EXEC: u8 = 1 << 2;
+    pub const ALIAS_KEYWORDS: u8 = 1 << 3;
+    pub const ALIAS_ACTIONS: u8 = 1 << 4;
+    pub const ALIAS_VARIABLES: u8 = 1 << 5;
+    pub const ALIAS_MATCH: u8 = 1 << 6;
+
+    const DESCRIPTIONS: [(u8, &'static str); 7] = [
+        (Self::ALIAS_NAME, "name overridden by alias file"),
+        (Self::ALIAS_ICON, "icon overridden by alias file"),
+        (Self::ALIAS_EXEC, "exec overridden by alias file"),
+        (Self::ALIAS_KEYWORDS, "keywords overridden by alias file"),
+        (Self::ALIAS_ACTIONS, "actions merged from alias file"),
+        (Self::ALIAS_VARIABLES, "variables merged from alias file"),
+        (Self::ALIAS_MATCH, "match policy overridden by alias file"),
+    ];
+
+    #[inline]
+    pub fn insert(&mut self, flag: u8) {
+        self.0 |= flag;
+    }
+
+    pub fn describe(&self) -> impl Iterator<Item = &'static str> {
+        Self::DESCRIPTIONS
+            .into_iter()
+            .filter(|(flag, _)| self.0 & flag != 0)
+            .map(|(_, description)| description)
+    }
+}
+
+/// Where a child comes from, answering "why does this row exist and which file do I edit?"
+///
+/// Built on demand for the "Source" section of a tile and the `sources` socket command. The
+/// only per-child state is [`Transforms`]; the config path is shared by all launchers of a file.
+#[derive(Serialize, Debug)]
+pub struct Provenance {
+    pub name: Option<String>,
+    /// Name of the launcher, or its type for unnamed launchers
+    pub launcher: String,
+    /// File the launcher is configured in
+    pub config: Option<Arc<Path>>,
+    /// Record the child was built from, e.g. a desktop file or a key of the `commands` map
+    pub record: Option<String>,
+    pub transforms: Vec<String>,
+}
+
+impl Provenance {
+    /// Collects the provenance of `data[idx]`. Other children with the same target are listed,
+    /// since only one of them is shown with `search.dedup`.
+    pub fn of(data: &[RenderableChild], idx: usize) -> Option<Self> {
+        let child = data.get(idx)?;
+        let launcher = child.launcher();
+
+        let (record, transforms) = match child {
+            RenderableChild::AppLike { inner, .. } => {
+                let record = match (&launcher.launcher_type, inner.desktop_file.as_ref()) {
+                    (_, Some(desktop_file)) => Some(desktop_file.display().to_string()),
+                    (LauncherType::Bookmark(bkm), _) => {
+                        Some(format!("{} bookmarks", bkm.target_browser))
+                    }
+                    (LauncherType::Command(_), _) => inner
+                        .name
+                        .as_ref()
+                        .map(|name| format!("commands.\"{name}\"")),
+                    (LauncherType::Category(_), _) => inner
+                        .name
+                        .as_ref()
+                        .map(|name| format!("categories.\"{name}\"")),
+                    (LauncherType::Ssh(_), _) => {
+                        inner.name.as_ref().map(|name| format!("ssh host {name}"))
+                    }
+                    (LauncherType::Web(web), _) => Some(format!("search engine {}", web.engine)),
+                    _ => None,
+                };
+                (
+                    record,
+                    inner.transforms.describe().map(String::from).collect(),
+                )
+            }
+            RenderableChild::CalcLike { inner, .. } => (
+                inner
+                    .history_slot()
+                    .map(|slot| format!("calculator history, entry {}", slot + 1)),
+                Vec::new(),
+            ),
+            RenderableChild::DocLike { inner, .. } => (
+                inner.page().map(|page| match page.section {
+                    Some(section) => format!("man -k, section {section}"),
+                    None => String::from("tldr cache"),
+                }),
+                Vec::new(),
+            ),
+            RenderableChild::MusicLike { inner, .. } => (inner.player.clone(), Vec::new()),
+            RenderableChild::UrlLike { inner, .. } => (
+                inner.target().map(|target| match target {
+                    OpenTarget::Url(_) => String::from("url in the query"),
+                    OpenTarget::Path(_) => String::from("path in the query"),
+                }),
+                Vec::new(),
+            ),
+            RenderableChild::WeatherLike { .. } => match &launcher.launcher_type {
+                LauncherType::Weather(wtr) if !wtr.is_auto() => {
+                    (Some(format!("location {}", wtr.location)), Vec::new())
+                }
+                LauncherType::Weather(_) => (Some(String::from("detected location")), Vec::new()),
+                _ => (None, Vec::new()),
+            },
+        };
+
+        let mut provenance = Self {
+            name: display_name(child),
+            launcher: launcher
+                .name
+                .clone()
+                .unwrap_or_else(|| launcher.launcher_type.type_name().to_string()),
+            config: launcher.config_file.clone(),
+            record,
+            transforms,
+        };
+
+        if let Some(key) = child.dedup_key() {
+            provenance.transforms.extend(
+                data.iter()
+                    .enumerate()
+                    .filter(|(i, other)| *i != idx && other.dedup_key() == Some(key))
+                    .map(|(_, other)| {
+                        format!(
+                            "same target as '{}' from {}",
+                            display_name(other).unwrap_or_default(),
+                            other
+                                .launcher()
+                                .name
+                                .as_deref()
+                                .unwrap_or("another launcher")
+                        )
+                    }),
+            );
+        }
+
+        Some(provenance)
+    }
+
+    /// `(label, value)` rows for the "Source" section of a tile
+    pub fn rows(&self) -> Vec<(&'static str, String)> {
+        let mut rows = vec![("Launcher", self.launcher.clone())];
+        if let Some(config) = &self.config {
+            rows.push(("Config", config.display().to_string()));
+        }
+        if let Some(record) = &self.record {
+            rows.push(("Record", record.clone()));
+        }
+        rows.extend(self.transforms.iter().map(|t| ("Changed", t.clone())));
+        rows
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_transforms_describe() {
+        let mut transforms = Transforms::default();
+        assert_eq!(transforms.describe().count(), 0);
+
+        transforms.insert(Transforms::ALIAS_ICON);
+        transforms.insert(Transforms::ALIAS_NAME);
+        assert_eq!(
+            transforms.describe().collect::<Vec<_>>(),
+            vec![
+                "name overridden by alias file",
+                "icon overridden by alias file"
+            ]
+        );
+    }
+}
@@ -0,0 +1,344 @@
+use gpui::SharedString;
+use serde::Deserialize;
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::loader::utils::{AppData, construct_search};
+use crate::sher_log;
+use crate::utils::errors::SherlockError;
+use crate::utils::files::home_dir;
+
+/// `Include` directives nested deeper than this are ignored, like ssh does
+const MAX_INCLUDE_DEPTH: usize = 16;
+
+/// Offers the hosts of `~/.ssh/config`, opened with `ssh` in the configured terminal
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct SshLauncher {
+    /// Also offers the hosts of `~/.ssh/known_hosts` that have no `Host` entry
+    #[serde(default)]
+    pub known_hosts: bool,
+}
+impl SshLauncher {
+    pub fn find_hosts(&self) -> Result<Vec<AppData>, SherlockError> {
+        let ssh_dir = home_dir()?.join(".ssh");
+        let mut hosts = Vec::new();
+        parse_config(&ssh_dir.join("config"), &ssh_dir, 0, &mut hosts);
+        let mut hosts = merge_hosts(hosts);
+
+        if self.known_hosts {
+            match fs::read_to_string(ssh_dir.join("known_hosts")) {
+                Ok(content) => {
+                    // hosts with a `Host` entry are listed once, under their alias
+                    let mut seen: HashSet<String> = hosts
+                        .iter()
+                        .flat_map(|host| [Some(&host.alias), host.hostname.as_ref()])
+                        .flatten()
+                        .map(|name| name.to_lowercase())
+                        .collect();
+                    hosts.extend(
+                        parse_known_hosts(&content)
+                            .into_iter()
+                            .filter(|host| seen.insert(host.alias.to_lowercase())),
+                    );
+                }
+                Err(e) => {
+                    let _ = sher_log!(format!("Failed to read known_hosts: {e}"));
+                }
+            }
+        }
+
+        Ok(hosts.into_iter().map(SshHost::into_child).collect())
+    }
+}
+
+/// A host ssh can connect to by name
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SshHost {
+    /// Name passed to `ssh`, the `Host` of a config block
+    pub alias: String,
+    pub hostname: Option<String>,
+    pub user: Option<String>,
+    /// Only set for hosts of `known_hosts` listed as `[host]:port`
+    pub port: Option<u16>,
+}
+impl SshHost {
+    /// Shown below the alias, e.g. `me@example.com`
+    fn subtitle(&self) -> Option<String> {
+        match (&self.user, &self.hostname) {
+            (Some(user), Some(hostname)) => Some(format!("{user}@{hostname}")),
+            (Some(user), None) => Some(format!("{user}@{}", self.alias)),
+            (None, Some(hostname)) => Some(hostname.clone()),
+            (None, None) => None,
+        }
+    }
+
+    fn exec(&self) -> String {
+        match self.port {
+            Some(port) => format!("{{terminal}} ssh -p {port} {}", self.alias),
+            None => format!("{{terminal}} ssh {}", self.alias),
+        }
+    }
+
+    fn into_child(self) -> AppData {
+        let subtitle = self.subtitle();
+        let search = subtitle.as_deref().unwrap_or_default();
+        let mut inner = AppData::new();
+        inner.search_string = construct_search(Some(&self.alias), search, true);
+        inner.exec = Some(self.exec());
+        inner.subtitle = subtitle.map(SharedString::from);
+        inner.name = Some(SharedString::from(self.alias));
+        inner
+    }
+}
+
+/// Collects the hosts of the ssh config at `path` and the files it includes. Relative includes
+/// are resolved against `ssh_dir`.
+fn parse_config(path: &Path, ssh_dir: &Path, depth: usize, hosts: &mut Vec<SshHost>) {
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    // indices into `hosts` of the aliases of the current `Host` block
+    let mut block: Vec<usize> = Vec::new();
+
+    for line in content.lines() {
+        let Some((key, value)) = split_option(line) else {
+            continue;
+        };
+        match key.to_ascii_lowercase().as_str() {
+            "host" => {
+                block.clear();
+                for alias in split_args(value) {
+                    if !is_pattern(&alias) {
+                        block.push(hosts.len());
+                        hosts.push(SshHost {
+                            alias,
+                            ..Default::default()
+                        });
+                    }
+                }
+            }
+            // options of `Match` blocks depend on conditions that can't be checked here
+            "match" => block.clear(),
+            "include" if depth < MAX_INCLUDE_DEPTH => {
+                for pattern in split_args(value) {
+                    for include in expand_include(&pattern, ssh_dir) {
+                        parse_config(&include, ssh_dir, depth + 1, hosts);
+                    }
+                }
+            }
+            // the first value of an option counts, like in ssh
+            "hostname" => {
+                for &i in &block {
+                    hosts[i].hostname.get_or_insert_with(|| value.to_string());
+                }
+            }
+            "user" => {
+                for &i in &block {
+                    hosts[i].user.get_or_insert_with(|| value.to_string());
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Joins the entries of hosts named in several blocks, keeping the first value of each option
+fn merge_hosts(hosts: Vec<SshHost>) -> Vec<SshHost> {
+    let mut merged: Vec<SshHost> = Vec::with_capacity(hosts.len());
+    for host in hosts {
+        match merged.iter_mut().find(|h| h.alias == host.alias) {
+            Some(existing) => {
+                existing.hostname = existing.hostname.take().or(host.hostname);
+                existing.user = existing.user.take().or(host.user);
+            }
+            None => merged.push(host),
+        }
+    }
+    merged
+}
+
+/// Splits a config line into its keyword and arguments. Both `Key value` and `Key=value` are
+/// allowed.
+fn split_option(line: &str) -> Option<(&str, &str)> {
+    let line = line.trim();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    let end = line.find(|c: char| c.is_whitespace() || c == '=')?;
+    let (key, rest) = line.split_at(end);
+    let rest = rest.trim_start();
+    let rest = rest.strip_prefix('=').unwrap_or(rest).trim();
+    (!rest.is_empty()).then_some((key, rest))
+}
+
+/// Splits the arguments of an option at whitespace, double quotes group words
+fn split_args(value: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in value.chars() {
+        match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !current.is_empty() {
+                    args.push(std::mem::take(&mut current));
+                }
+            }
+            c => current.push(c),
+        }
+    }
+    if !current.is_empty() {
+        args.push(current);
+    }
+    args
+}
+
+/// Patterns like `*.example.com` or `!bastion` match hosts rather than name one. Names with
+/// spaces can't be passed on to `ssh` either.
+fn is_pattern(host: &str) -> bool {
+    host.contains(['*', '?', '!']) || host.contains(char::is_whitespace)
+}
+
+/// The files an `Include` argument refers to, in the order ssh reads them
+fn expand_include(pattern: &str, ssh_dir: &Path) -> Vec<PathBuf> {
+    let path = if pattern == "~" || pattern.starts_with("~/") {
+        match ssh_dir.parent() {
+            Some(home) => home.join(pattern.trim_start_matches('~').trim_start_matches('/')),
+            None => return Vec::new(),
+        }
+    } else if Path::new(pattern).is_absolute() {
+        PathBuf::from(pattern)
+    } else {
+        ssh_dir.join(pattern)
+    };
+    let Ok(paths) = glob::glob(&path.to_string_lossy()) else {
+        return Vec::new();
+    };
+    let mut paths: Vec<PathBuf> = paths.filter_map(Result::ok).collect();
+    paths.sort();
+    paths
+}
+
+/// Reads the first name of each line of a `known_hosts` file. Hashed names can't be read and
+/// revoked keys are skipped.
+fn parse_known_hosts(content: &str) -> Vec<SshHost> {
+    content
+        .lines()
+        .filter_map(|line| {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                return None;
+            }
+            let mut fields = line.split_whitespace();
+            let names = fields.next()?;
+            // every line holds a key type and key after the names
+            fields.next()?;
+            // `@cert-authority` lines name patterns signed by a CA, `@revoked` ones are distrusted
+            if names.starts_with(['@', '|']) {
+                return None;
+            }
+            // further names are usually the address of the first
+            parse_known_host(names.split(',').next()?)
+        })
+        .collect()
+}
+
+/// Reads `host` or `[host]:port`
+fn parse_known_host(name: &str) -> Option<SshHost> {
+    let (alias, port) = match name.strip_prefix('[') {
+        Some(rest) => {
+            let (host, port) = rest.split_once("]:")?;
+            (host, Some(port.parse::<u16>().ok()?))
+        }
+        None => (name, None),
+    };
+    if alias.is_empty() || is_pattern(alias) {
+        return None;
+    }
+    Some(SshHost {
+        alias: alias.to_string(),
+        // the default port needs no flag
+        port: port.filter(|&port| port != 22),
+        ..Default::default()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let dir = std::env::temp_dir().join(format!("sherlock-ssh-{}", std::process::id()));
+        fs::create_dir_all(dir.join("config.d")).unwrap();
+        fs::write(
+            dir.join("config"),
+            "# servers\n\
+             Host web web-alias\n\
+             \x20   HostName example.com\n\
+             \x20   User deploy\n\
+             Host *.internal !bastion\n\
+             \x20   User nobody\n\
+             Include config.d/*\n\
+             Host db\n\
+             \x20   HostName=10.0.0.5\n\
+             \x20   HostName ignored.example.com\n\
+             Match host db\n\
+             \x20   User matched\n\
+             Host web\n\
+             \x20   User other\n\
+             \x20   Port 2222\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("config.d/work"),
+            "Host \"work box\" work\n  User me\n",
+        )
+        .unwrap();
+
+        let mut hosts = Vec::new();
+        parse_config(&dir.join("config"), &dir, 0, &mut hosts);
+        let hosts = merge_hosts(hosts);
+        fs::remove_dir_all(&dir).unwrap();
+
+        let summary: Vec<(&str, Option<String>)> = hosts
+            .iter()
+            .map(|host| (host.alias.as_str(), host.subtitle()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                ("web", Some(String::from("deploy@example.com"))),
+                ("web-alias", Some(String::from("deploy@example.com"))),
+                ("work", Some(String::from("me@work"))),
+                ("db", Some(String::from("10.0.0.5"))),
+            ]
+        );
+        assert_eq!(hosts[0].exec(), "{terminal} ssh web");
+    }
+
+    #[test]
+    fn test_parse_known_hosts() {
+        let content = "\
+            github.com,140.82.121.4 ssh-ed25519 AAAAC3Nz\n\
+            |1|JfKTdBh7rNbXkVAQCRp4OQoPfmI=|USECr3SWf1JUPsms5AqfD5QfxkM= ssh-rsa AAAAB3Nz\n\
+            [git.example.com]:2222 ssh-ed25519 AAAAC3Nz\n\
+            [local]:22 ssh-ed25519 AAAAC3Nz\n\
+            @cert-authority *.example.com ssh-rsa AAAAB3Nz\n\
+            @revoked old.example.com ssh-rsa AAAAB3Nz\n\
+            *.lan ssh-ed25519 AAAAC3Nz\n\
+            broken-line\n\
+            # comment\n";
+        let hosts = parse_known_hosts(content);
+        let execs: Vec<String> = hosts.iter().map(SshHost::exec).collect();
+        assert_eq!(
+            execs,
+            vec![
+                "{terminal} ssh github.com",
+                "{terminal} ssh -p 2222 git.example.com",
+                "{terminal} ssh local",
+            ]
+        );
+    }
+}
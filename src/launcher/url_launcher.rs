@@ -0,0 +1,176 @@
+use std::{
+    net::Ipv4Addr,
+    path::{Path, PathBuf},
+};
+
+use reqwest::Url;
+use serde::Deserialize;
+
+use crate::utils::files::expand_path;
+
+/// Offers to open the query if it is a url or an existing path, see
+/// [`UrlData`](crate::launcher::children::url_data::UrlData)
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct UrlLauncher {
+    /// Opens urls, the default browser if unset
+    pub browser: Option<String>,
+}
+
+/// Top level domains recognized without a scheme. Endings that are more often file extensions,
+/// like `.rs`, `.sh` or `.zip`, need a scheme.
+const KNOWN_TLDS: &[&str] = &[
+    "com", "org", "net", "edu", "gov", "int", "io", "dev", "app", "info", "biz", "xyz", "site",
+    "online", "tech", "blog", "cloud", "page", "co", "me", "tv", "fm", "ai", "gg", "us", "uk",
+    "de", "fr", "it", "es", "nl", "be", "ch", "at", "se", "no", "dk", "fi", "cz", "pt", "ru", "ua",
+    "jp", "cn", "kr", "in", "au", "nz", "ca", "br", "mx", "ar", "eu",
+];
+
+/// Something the query can be opened as
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OpenTarget {
+    /// Opened in the browser, always with a scheme
+    Url(String),
+    /// Opened with `xdg-open`
+    Path(PathBuf),
+}
+
+impl OpenTarget {
+    /// Reads `query` as a url or a path that exists. `~` is expanded to `home`.
+    pub fn parse(query: &str, home: Option<&Path>) -> Option<Self> {
+        let query = query.trim();
+        if query.is_empty() {
+            return None;
+        }
+        parse_url(query)
+            .map(Self::Url)
+            .or_else(|| parse_path(query, home).map(Self::Path))
+    }
+}
+
+/// The url `query` stands for. Bare hosts need a known top level domain, or to be `localhost` or
+/// an IPv4 address, so that e.g. `node.js` stays a search.
+pub fn parse_url(query: &str) -> Option<String> {
+    let query = query.trim();
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return None;
+    }
+
+    if query.contains("://") {
+        let url = Url::parse(query).ok()?;
+        let known = matches!(url.scheme(), "http" | "https" | "ftp");
+        return (known && url.host_str().is_some_and(|host| !host.is_empty()))
+            .then(|| query.to_string());
+    }
+
+    let authority = query.split(['/', '?', '#']).next()?;
+    let (host, port) = match authority.rsplit_once(':') {
+        Some((host, port)) => (host, Some(port)),
+        None => (authority, None),
+    };
+    if port.is_some_and(|port| port.is_empty() || !port.bytes().all(|b| b.is_ascii_digit())) {
+        return None;
+    }
+
+    // local servers rarely serve https
+    let scheme = if host.eq_ignore_ascii_case("localhost") || host.parse::<Ipv4Addr>().is_ok() {
+        "http"
+    } else if is_domain(host) {
+        "https"
+    } else {
+        return None;
+    };
+    let url = format!("{scheme}://{query}");
+    Url::parse(&url).ok().map(|_| url)
+}
+
+fn is_domain(host: &str) -> bool {
+    let labels: Vec<&str> = host.split('.').collect();
+    let Some(tld) = labels.last() else {
+        return false;
+    };
+    labels.len() >= 2
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && label.len() <= 63
+                && !label.starts_with('-')
+                && !label.ends_with('-')
+                && label
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-')
+        })
+        && KNOWN_TLDS
+            .iter()
+            .any(|known| known.eq_ignore_ascii_case(tld))
+}
+
+/// The existing path `query` points to. Only absolute paths, paths below `~` and `file://` urls
+/// are considered.
+fn parse_path(query: &str, home: Option<&Path>) -> Option<PathBuf> {
+    let path = if let Some(path) = query.strip_prefix("file://") {
+        PathBuf::from(path)
+    } else if query == "~" || query.starts_with("~/") {
+        expand_path(query, home?)
+    } else if query.starts_with('/') {
+        PathBuf::from(query)
+    } else {
+        return None;
+    };
+    path.exists().then_some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_url() {
+        let cases = [
+            ("https://example.com/path", Some("https://example.com/path")),
+            ("example.com/path", Some("https://example.com/path")),
+            ("Docs.Example.org", Some("https://Docs.Example.org")),
+            ("localhost:8080", Some("http://localhost:8080")),
+            ("192.168.0.1/admin", Some("http://192.168.0.1/admin")),
+            ("ftp://ftp.example.com", Some("ftp://ftp.example.com")),
+            ("node.js", None),
+            ("main.rs", None),
+            ("1.5", None),
+            ("rust lifetimes", None),
+            ("a b.com", None),
+            ("example.com:http", None),
+            ("mailto:me@example.com", None),
+            ("javascript://alert", None),
+            ("https://", None),
+        ];
+        for (query, expected) in cases {
+            assert_eq!(parse_url(query).as_deref(), expected, "{query:?}");
+        }
+    }
+
+    #[test]
+    fn test_parse_path() {
+        let home = std::env::temp_dir();
+        let dir = home.join("sherlock-url-test");
+        std::fs::create_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            OpenTarget::parse("~/sherlock-url-test", Some(&home)),
+            Some(OpenTarget::Path(dir.clone()))
+        );
+        assert_eq!(
+            OpenTarget::parse(&format!("file://{}", dir.display()), Some(&home)),
+            Some(OpenTarget::Path(dir.clone()))
+        );
+        assert_eq!(
+            OpenTarget::parse(&dir.display().to_string(), None),
+            Some(OpenTarget::Path(dir.clone()))
+        );
+        assert_eq!(
+            OpenTarget::parse("~/sherlock-url-missing", Some(&home)),
+            None
+        );
+        assert_eq!(OpenTarget::parse("~/sherlock-url-test", None), None);
+        assert_eq!(OpenTarget::parse("sherlock-url-test", Some(&home)), None);
+
+        std::fs::remove_dir(&dir).unwrap();
+    }
+}
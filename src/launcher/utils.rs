@@ -2,12 +2,19 @@ use std::sync::Arc;
 
 use gpui::Image;
 use serde::Deserialize;
-use zbus::zvariant::{DeserializeDict, Type};
+use zbus::zvariant::{DeserializeDict, OwnedValue, Type};
+
+use crate::loader::utils::ApplicationAction;
+use crate::ui::image::ImageGeneration;
 
 #[derive(Clone, Default)]
 pub struct MprisState {
     pub raw: Option<MprisData>,
     pub image: Option<Arc<Image>>,
+    pub image_generation: ImageGeneration,
+    /// Bus name of the player shown in the tile
+    pub player: Option<String>,
+    pub actions: Arc<[Arc<ApplicationAction>]>,
 }
 
 #[derive(DeserializeDict, Type, Debug, Clone, Default)]
@@ -19,6 +26,10 @@ pub struct MprisData {
 
     #[zvariant(rename = "Metadata")]
     pub metadata: MetaData,
+
+    /// Playback position in microseconds
+    #[zvariant(rename = "Position")]
+    pub position: Option<i64>,
 }
 #[derive(DeserializeDict, Type, Debug, Clone, Default)]
 #[zvariant(signature = "a{sv}")]
@@ -38,6 +49,21 @@ pub struct MetaData {
 
     #[zvariant(rename = "mpris:artUrl")]
     pub art: Option<String>,
+
+    #[zvariant(rename = "mpris:length")]
+    pub length: Option<OwnedValue>,
+}
+impl MetaData {
+    /// Track length in microseconds
+    ///
+    /// The spec demands a signed integer, but some players send an unsigned one instead.
+    pub fn length_us(&self) -> Option<i64> {
+        let length = self.length.as_ref()?;
+        length
+            .downcast_ref::<i64>()
+            .ok()
+            .or_else(|| length.downcast_ref::<u64>().ok().map(|l| l as i64))
+    }
 }
 
 pub fn to_title_case(input_str: &str) -> String {
@@ -1,7 +1,5 @@
 use gpui::{Hsla, LinearColorStop, hsla, linear_color_stop, rgb};
 use serde::{Deserialize, Serialize};
-use simd_json::base::{ValueAsArray, ValueAsScalar};
-use std::collections::HashSet;
 use std::fs::{self, File};
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
@@ -9,9 +7,12 @@ use std::time::{Duration, SystemTime};
 use strum::Display;
 
 use super::utils::to_title_case;
+use super::weather_provider::{WeatherProvider, WeatherProviderKind, geoip_city};
 use crate::loader::resolve_icon_path;
-use crate::utils::config::ConfigGuard;
+use crate::ui::image::ImageGeneration;
 use crate::utils::files::home_dir;
+use crate::utils::sleep::resumed_since;
+use crate::utils::stats;
 
 #[derive(Clone, Debug, Deserialize)]
 pub enum WeatherIconTheme {
@@ -21,10 +22,57 @@ pub enum WeatherIconTheme {
 
 #[derive(Clone, Debug, Deserialize)]
 pub struct WeatherLauncher {
+    /// Left empty or set to `auto` to detect the location from the IP address
+    #[serde(default)]
     pub location: String,
+    /// Used if the location has to be detected but the lookup fails
+    #[serde(default)]
+    pub fallback_location: Option<String>,
     pub update_interval: u64,
     pub icon_theme: WeatherIconTheme,
     pub show_datetime: bool,
+    #[serde(default)]
+    pub show_forecast: bool,
+    #[serde(default)]
+    pub provider: WeatherProviderKind,
+}
+
+impl WeatherLauncher {
+    pub fn is_auto(&self) -> bool {
+        matches!(self.location.trim(), "" | "auto")
+    }
+    /// Name of the cache file. Detected locations share one file, so that the resolved city is
+    /// cached together with its weather and only looked up again once the cache expires.
+    fn cache_key(&self) -> &str {
+        if self.is_auto() {
+            "auto"
+        } else {
+            &self.location
+        }
+    }
+    /// Returns the configured location or, for automatic locations, the city detected from the
+    /// IP address. Falls back to `fallback_location` if the lookup fails.
+    pub async fn resolve_location(&self) -> Option<String> {
+        if !self.is_auto() {
+            return Some(self.location.clone());
+        }
+        match geoip_city().await {
+            Some(city) => Some(city),
+            None => self.fallback_location.clone(),
+        }
+    }
+}
+
+/// Bumped whenever the cached weather data changes shape
+const CACHE_VERSION: u32 = 3;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ForecastDay {
+    pub day: String,
+    pub high: String,
+    pub low: String,
+    pub icon: Option<Arc<Path>>,
+    pub css: WeatherClass,
 }
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -36,6 +84,12 @@ pub struct WeatherData {
     pub css: WeatherClass,
     pub sunset: chrono::NaiveTime,
     pub init: bool,
+    #[serde(default)]
+    pub forecast: Vec<ForecastDay>,
+    #[serde(default)]
+    pub version: u32,
+    #[serde(skip)]
+    pub icon_generation: ImageGeneration,
 }
 impl WeatherData {
     pub fn uninitialized() -> Self {
@@ -47,41 +101,59 @@ impl WeatherData {
             css: WeatherClass::None,
             sunset: chrono::NaiveTime::default(),
             init: false,
+            forecast: Vec::new(),
+            version: CACHE_VERSION,
+            icon_generation: ImageGeneration::default(),
+        }
+    }
+    /// Shown instead of the weather if the location could not be detected
+    fn unavailable(launcher: &WeatherLauncher) -> Self {
+        Self {
+            temperature: String::from("--"),
+            icon: Self::icon(launcher, &WeatherClass::None),
+            format_str: String::from("Location unavailable"),
+            init: true,
+            ..Self::uninitialized()
         }
     }
     pub fn from_cache(launcher: &WeatherLauncher) -> Option<Self> {
         let mut path = home_dir().ok()?;
         path.push(format!(
             ".cache/sherlock/weather/{}.json",
-            launcher.location
+            launcher.cache_key()
         ));
         fn modtime(path: &PathBuf) -> Option<SystemTime> {
             fs::metadata(path).ok().and_then(|m| m.modified().ok())
         }
         let mtime = modtime(&path)?;
+        // data cached before a suspend is outdated regardless of its age
+        if resumed_since(mtime) {
+            return None;
+        }
         let time_since = SystemTime::now().duration_since(mtime).ok()?;
         if time_since < Duration::from_secs(60 * launcher.update_interval) {
             let mut cached_data: Self = File::open(&path)
                 .ok()
                 .and_then(|f| simd_json::from_reader(f).ok())?;
+            // written by an older version, might be missing the forecast or only have it when
+            // `show_forecast` was set
+            if cached_data.version < CACHE_VERSION {
+                return None;
+            }
 
-            cached_data.icon = if matches!(launcher.icon_theme, WeatherIconTheme::Sherlock) {
-                resolve_icon_path(&format!(
-                    "weather-icons/sherlock-weather-{}",
-                    cached_data.css
-                ))
-            } else {
-                resolve_icon_path(&format!("weather-{}", cached_data.css))
-            };
+            cached_data.icon = Self::icon(launcher, &cached_data.css);
+            for day in cached_data.forecast.iter_mut() {
+                day.icon = Self::icon(launcher, &day.css);
+            }
 
             return Some(cached_data);
         } else {
             return None;
         }
     }
-    fn cache(&self) -> Option<()> {
+    fn cache(&self, key: &str) -> Option<()> {
         let mut path = home_dir().ok()?;
-        path.push(format!(".cache/sherlock/weather/{}.json", self.location));
+        path.push(format!(".cache/sherlock/weather/{}.json", key));
         if let Some(parent) = path.parent() {
             fs::create_dir_all(parent).ok()?;
         }
@@ -96,82 +168,60 @@ impl WeatherData {
         None
     }
     pub async fn fetch_async(launcher: &WeatherLauncher) -> Option<(WeatherData, bool)> {
-        let config = ConfigGuard::read().ok()?;
         // try read cache
         if let Some(data) = WeatherData::from_cache(launcher) {
+            stats::record_async_fetch(true);
             return Some((data, false));
         };
+        stats::record_async_fetch(false);
 
-        let url = format!("https://de.wttr.in/{}?format=j2", launcher.location);
-
-        let response = reqwest::get(url).await.ok()?.text().await.ok()?;
-        let mut response_bytes = response.into_bytes();
-        let json: simd_json::OwnedValue = simd_json::to_owned_value(&mut response_bytes).ok()?;
-        let current_condition = json["current_condition"].as_array()?.get(0)?;
-
-        // Get sunset time
-        let astronomy = json["weather"].as_array()?.get(0)?["astronomy"]
-            .as_array()?
-            .get(0)?;
-        let sunset_raw = astronomy["sunset"].as_str()?;
-        let sunset = chrono::NaiveTime::parse_from_str(sunset_raw, "%I:%M %p").ok()?;
-
-        // Parse Temperature
-        let temperature = match config.units.temperatures.as_str() {
-            "f" | "F" => format!("{}°F", current_condition["temp_F"].as_str()?),
-            _ => format!("{}°C", current_condition["temp_C"].as_str()?),
+        // detected locations are resolved again whenever the cache expires
+        let Some(location) = launcher.resolve_location().await else {
+            return Some((Self::unavailable(launcher), true));
         };
-
-        // Parse Icon
-        let code = current_condition["weatherCode"].as_str()?;
-        let icon = if matches!(launcher.icon_theme, WeatherIconTheme::Sherlock) {
-            resolve_icon_path(&format!(
-                "weather-icons/sherlock-weather-{}",
-                Self::match_weather_code(code)
-            ))
-        } else {
-            resolve_icon_path(&format!("weather-{}", Self::match_weather_code(code)))
+        let resolved = WeatherLauncher {
+            location,
+            ..launcher.clone()
         };
 
-        // Parse wind dir
-        let wind_deg = current_condition["winddirDegree"]
-            .as_str()?
-            .parse::<f32>()
-            .ok()?;
-        let sector_size: f32 = 45.0;
-        let index = ((wind_deg + sector_size / 2.0) / sector_size).floor() as usize % 8;
-        let win_dirs = ["↑", "↗", "→", "↘", "↓", "↙", "←", "↖"];
-        let wind_dir = win_dirs.get(index)?;
-
-        // Parse wind speed
-        let imperials: HashSet<&str> = HashSet::from([
-            "inches", "inch", "in", "feet", "foot", "ft", "yards", "yard", "yd", "miles", "mile",
-            "mi",
-        ]);
-        let wind = if imperials.contains(config.units.lengths.to_lowercase().as_str()) {
-            let speed = current_condition["windspeedMiles"].as_str()?;
-            format!("{} {}mph", wind_dir, speed)
-        } else {
-            let speed = current_condition["windspeedKmph"].as_str()?;
-            format!("{} {}km/h", wind_dir, speed)
-        };
+        let data = launcher.provider.fetch(&resolved).await?;
+        data.cache(launcher.cache_key());
 
+        Some((data, true))
+    }
+    /// Assembles the weather data from the values parsed by a [`WeatherProvider`]
+    ///
+    /// [`WeatherProvider`]: super::weather_provider::WeatherProvider
+    pub(super) fn new(
+        launcher: &WeatherLauncher,
+        temperature: String,
+        css: WeatherClass,
+        wind: String,
+        sunset: chrono::NaiveTime,
+        forecast: Vec<ForecastDay>,
+    ) -> Self {
         let loc = to_title_case(&launcher.location);
-        let format_str = format!("{}  {}", loc, wind);
-        let data = WeatherData {
+        Self {
             temperature,
-            icon,
-            format_str,
+            icon: Self::icon(launcher, &css),
+            format_str: format!("{}  {}", loc, wind),
             location: launcher.location.clone(),
-            css: Self::match_weather_code(code),
+            css,
             sunset,
             init: true,
-        };
-        data.cache();
-
-        Some((data, true))
+            forecast,
+            version: CACHE_VERSION,
+            icon_generation: ImageGeneration::default(),
+        }
     }
-    fn match_weather_code(code: &str) -> WeatherClass {
+    pub(super) fn icon(launcher: &WeatherLauncher, css: &WeatherClass) -> Option<Arc<Path>> {
+        if matches!(launcher.icon_theme, WeatherIconTheme::Sherlock) {
+            resolve_icon_path(&format!("weather-icons/sherlock-weather-{}", css))
+        } else {
+            resolve_icon_path(&format!("weather-{}", css))
+        }
+    }
+    pub(super) fn match_weather_code(code: &str) -> WeatherClass {
         match code {
             "113" => WeatherClass::Clear,
             "116" => WeatherClass::FewClouds,
@@ -283,3 +333,45 @@ impl WeatherClass {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_old_cache_deserializes() {
+        let mut cached = r#"{
+            "temperature": "12°C",
+            "icon": null,
+            "format_str": "Berlin  ↑ 5km/h",
+            "location": "berlin",
+            "css": "weather-clear",
+            "sunset": "18:12:00",
+            "init": true
+        }"#
+        .as_bytes()
+        .to_vec();
+        let data: WeatherData = simd_json::from_slice(&mut cached).unwrap();
+
+        assert!(data.forecast.is_empty());
+        assert!(data.version < CACHE_VERSION);
+    }
+
+    #[test]
+    fn test_auto_locations_share_cache() {
+        let launcher = |location: &str| -> WeatherLauncher {
+            serde_json::from_value(serde_json::json!({
+                "location": location,
+                "update_interval": 60,
+                "icon_theme": "None",
+                "show_datetime": false,
+            }))
+            .unwrap()
+        };
+
+        assert_eq!(launcher("").cache_key(), "auto");
+        assert_eq!(launcher("auto").cache_key(), "auto");
+        assert_eq!(launcher("berlin").cache_key(), "berlin");
+        assert!(!launcher("berlin").is_auto());
+    }
+}
@@ -0,0 +1,347 @@
+use std::collections::HashSet;
+
+use serde::Deserialize;
+use simd_json::base::{ValueAsArray, ValueAsScalar};
+
+use super::weather_launcher::{ForecastDay, WeatherClass, WeatherData, WeatherLauncher};
+use crate::utils::config::ConfigGuard;
+
+/// A source of weather data
+///
+/// Providers only parse their API responses. Caching is handled by
+/// [`WeatherData::fetch_async`].
+pub trait WeatherProvider {
+    async fn fetch(&self, launcher: &WeatherLauncher) -> Option<WeatherData>;
+}
+
+#[derive(Clone, Copy, Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum WeatherProviderKind {
+    #[default]
+    Wttr,
+    OpenMeteo,
+}
+impl WeatherProvider for WeatherProviderKind {
+    async fn fetch(&self, launcher: &WeatherLauncher) -> Option<WeatherData> {
+        match self {
+            Self::Wttr => Wttr.fetch(launcher).await,
+            Self::OpenMeteo => OpenMeteo.fetch(launcher).await,
+        }
+    }
+}
+
+/// Units configured in the `units` section of the config
+struct Units {
+    fahrenheit: bool,
+    imperial: bool,
+}
+impl Units {
+    fn from_config() -> Option<Self> {
+        let config = ConfigGuard::read().ok()?;
+        let imperials: HashSet<&str> = HashSet::from([
+            "inches", "inch", "in", "feet", "foot", "ft", "yards", "yard", "yd", "miles", "mile",
+            "mi",
+        ]);
+        Some(Self {
+            fahrenheit: matches!(config.units.temperatures.as_str(), "f" | "F"),
+            imperial: imperials.contains(config.units.lengths.to_lowercase().as_str()),
+        })
+    }
+    fn wind(&self, degrees: f32, speed: impl std::fmt::Display) -> String {
+        let sector_size: f32 = 45.0;
+        let index = ((degrees + sector_size / 2.0) / sector_size).floor() as usize % 8;
+        let win_dirs = ["↑", "↗", "→", "↘", "↓", "↙", "←", "↖"];
+        let unit = if self.imperial { "mph" } else { "km/h" };
+        format!("{} {}{}", win_dirs[index], speed, unit)
+    }
+}
+
+async fn get_json(url: reqwest::Url) -> Option<simd_json::OwnedValue> {
+    let response = reqwest::get(url).await.ok()?.text().await.ok()?;
+    let mut response_bytes = response.into_bytes();
+    simd_json::to_owned_value(&mut response_bytes).ok()
+}
+
+/// Looks up the city of the current IP address through [ip-api](https://ip-api.com)
+pub async fn geoip_city() -> Option<String> {
+    let url = reqwest::Url::parse("http://ip-api.com/json/?fields=status,city").ok()?;
+    parse_geoip(&get_json(url).await?)
+}
+
+fn parse_geoip(json: &simd_json::OwnedValue) -> Option<String> {
+    if json["status"].as_str()? != "success" {
+        return None;
+    }
+    json["city"]
+        .as_str()
+        .filter(|city| !city.is_empty())
+        .map(String::from)
+}
+
+/// [wttr.in](https://wttr.in)
+pub struct Wttr;
+impl WeatherProvider for Wttr {
+    async fn fetch(&self, launcher: &WeatherLauncher) -> Option<WeatherData> {
+        let units = Units::from_config()?;
+
+        // j2 omits the hourly data needed for the daily conditions. The forecast is always
+        // parsed so that it is cached together with the current conditions.
+        let url = format!("https://de.wttr.in/{}?format=j1", launcher.location);
+        let json = get_json(reqwest::Url::parse(&url).ok()?).await?;
+        let current_condition = json["current_condition"].as_array()?.get(0)?;
+
+        // Get sunset time
+        let astronomy = json["weather"].as_array()?.get(0)?["astronomy"]
+            .as_array()?
+            .get(0)?;
+        let sunset_raw = astronomy["sunset"].as_str()?;
+        let sunset = chrono::NaiveTime::parse_from_str(sunset_raw, "%I:%M %p").ok()?;
+
+        // Parse Temperature
+        let temperature = if units.fahrenheit {
+            format!("{}°F", current_condition["temp_F"].as_str()?)
+        } else {
+            format!("{}°C", current_condition["temp_C"].as_str()?)
+        };
+
+        // Parse condition
+        let code = current_condition["weatherCode"].as_str()?;
+        let css = WeatherData::match_weather_code(code);
+
+        // Parse forecast
+        let forecast = json["weather"]
+            .as_array()?
+            .iter()
+            .take(3)
+            .filter_map(|day| Self::parse_forecast_day(launcher, day, units.fahrenheit))
+            .collect();
+
+        // Parse wind
+        let wind_deg = current_condition["winddirDegree"]
+            .as_str()?
+            .parse::<f32>()
+            .ok()?;
+        let speed = if units.imperial {
+            current_condition["windspeedMiles"].as_str()?
+        } else {
+            current_condition["windspeedKmph"].as_str()?
+        };
+        let wind = units.wind(wind_deg, speed);
+
+        Some(WeatherData::new(
+            launcher,
+            temperature,
+            css,
+            wind,
+            sunset,
+            forecast,
+        ))
+    }
+}
+impl Wttr {
+    fn parse_forecast_day(
+        launcher: &WeatherLauncher,
+        day: &simd_json::OwnedValue,
+        fahrenheit: bool,
+    ) -> Option<ForecastDay> {
+        let date = chrono::NaiveDate::parse_from_str(day["date"].as_str()?, "%Y-%m-%d").ok()?;
+        let (high, low) = if fahrenheit {
+            (
+                format!("{}°", day["maxtempF"].as_str()?),
+                format!("{}°", day["mintempF"].as_str()?),
+            )
+        } else {
+            (
+                format!("{}°", day["maxtempC"].as_str()?),
+                format!("{}°", day["mintempC"].as_str()?),
+            )
+        };
+
+        // use the condition around noon as the condition of the day
+        let css = day["hourly"]
+            .as_array()
+            .and_then(|hours| hours.get(hours.len() / 2))
+            .and_then(|hour| hour["weatherCode"].as_str())
+            .map_or(WeatherClass::None, WeatherData::match_weather_code);
+
+        Some(ForecastDay {
+            day: date.format("%a").to_string(),
+            high,
+            low,
+            icon: WeatherData::icon(launcher, &css),
+            css,
+        })
+    }
+}
+
+/// [Open-Meteo](https://open-meteo.com), does not require an API key
+pub struct OpenMeteo;
+impl WeatherProvider for OpenMeteo {
+    async fn fetch(&self, launcher: &WeatherLauncher) -> Option<WeatherData> {
+        let units = Units::from_config()?;
+
+        // Resolve the location to coordinates
+        let url = reqwest::Url::parse_with_params(
+            "https://geocoding-api.open-meteo.com/v1/search",
+            &[("name", launcher.location.as_str()), ("count", "1")],
+        )
+        .ok()?;
+        let places = get_json(url).await?;
+        let place = places["results"].as_array()?.get(0)?;
+        let latitude = place["latitude"].cast_f64()?.to_string();
+        let longitude = place["longitude"].cast_f64()?.to_string();
+
+        let url = reqwest::Url::parse_with_params(
+            "https://api.open-meteo.com/v1/forecast",
+            &[
+                ("latitude", latitude.as_str()),
+                ("longitude", longitude.as_str()),
+                (
+                    "current",
+                    "temperature_2m,weather_code,wind_speed_10m,wind_direction_10m",
+                ),
+                (
+                    "daily",
+                    "weather_code,temperature_2m_max,temperature_2m_min,sunset",
+                ),
+                ("forecast_days", "3"),
+                ("timezone", "auto"),
+                (
+                    "temperature_unit",
+                    if units.fahrenheit {
+                        "fahrenheit"
+                    } else {
+                        "celsius"
+                    },
+                ),
+                (
+                    "wind_speed_unit",
+                    if units.imperial { "mph" } else { "kmh" },
+                ),
+            ],
+        )
+        .ok()?;
+        let json = get_json(url).await?;
+        let current = &json["current"];
+        let daily = &json["daily"];
+
+        // Get sunset time
+        let sunset_raw = daily["sunset"].as_array()?.get(0)?.as_str()?;
+        let sunset = chrono::NaiveDateTime::parse_from_str(sunset_raw, "%Y-%m-%dT%H:%M")
+            .ok()?
+            .time();
+
+        // Parse Temperature
+        let temperature = format!(
+            "{:.0}°{}",
+            current["temperature_2m"].cast_f64()?,
+            if units.fahrenheit { "F" } else { "C" }
+        );
+
+        // Parse condition
+        let css = Self::match_wmo_code(current["weather_code"].as_u64()?);
+
+        // Parse forecast
+        let forecast = {
+            let dates = daily["time"].as_array()?;
+            let codes = daily["weather_code"].as_array()?;
+            let highs = daily["temperature_2m_max"].as_array()?;
+            let lows = daily["temperature_2m_min"].as_array()?;
+            (0..dates.len())
+                .filter_map(|i| {
+                    let date =
+                        chrono::NaiveDate::parse_from_str(dates[i].as_str()?, "%Y-%m-%d").ok()?;
+                    let css = codes
+                        .get(i)
+                        .and_then(|c| c.as_u64())
+                        .map_or(WeatherClass::None, Self::match_wmo_code);
+                    Some(ForecastDay {
+                        day: date.format("%a").to_string(),
+                        high: format!("{:.0}°", highs.get(i)?.cast_f64()?),
+                        low: format!("{:.0}°", lows.get(i)?.cast_f64()?),
+                        icon: WeatherData::icon(launcher, &css),
+                        css,
+                    })
+                })
+                .collect()
+        };
+
+        // Parse wind
+        let wind_deg = current["wind_direction_10m"].cast_f64()? as f32;
+        let speed = current["wind_speed_10m"].cast_f64()?.round();
+        let wind = units.wind(wind_deg, speed);
+
+        Some(WeatherData::new(
+            launcher,
+            temperature,
+            css,
+            wind,
+            sunset,
+            forecast,
+        ))
+    }
+}
+impl OpenMeteo {
+    /// Maps WMO weather interpretation codes as used by Open-Meteo
+    fn match_wmo_code(code: u64) -> WeatherClass {
+        match code {
+            0 => WeatherClass::Clear,
+            1 | 2 => WeatherClass::FewClouds,
+            3 => WeatherClass::ManyClouds,
+            45 | 48 => WeatherClass::Mist,
+            51 | 53 | 55 | 61 => WeatherClass::ShowersScattered,
+            63 | 65 | 80 | 81 | 82 => WeatherClass::Showers,
+            56 | 57 | 66 | 67 => WeatherClass::FreezingScatteredRain,
+            71 | 77 => WeatherClass::SnowScatteredDay,
+            73 | 75 => WeatherClass::SnowStorm,
+            85 | 86 => WeatherClass::SnowScatteredStorm,
+            95 => WeatherClass::Storm,
+            96 | 99 => WeatherClass::FreezingScatteredRainStorm,
+            _ => WeatherClass::None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wind_direction() {
+        let metric = Units {
+            fahrenheit: false,
+            imperial: false,
+        };
+        assert_eq!(metric.wind(0.0, 12), "↑ 12km/h");
+        assert_eq!(metric.wind(100.0, 3), "→ 3km/h");
+        assert_eq!(metric.wind(350.0, 3), "↑ 3km/h");
+
+        let imperial = Units {
+            fahrenheit: true,
+            imperial: true,
+        };
+        assert_eq!(imperial.wind(225.0, 7), "↙ 7mph");
+    }
+
+    #[test]
+    fn test_parse_geoip() {
+        let mut ok = br#"{"status":"success","city":"Berlin"}"#.to_vec();
+        let ok = simd_json::to_owned_value(&mut ok).unwrap();
+        assert_eq!(parse_geoip(&ok), Some(String::from("Berlin")));
+
+        let mut failed = br#"{"status":"fail","message":"private range"}"#.to_vec();
+        let failed = simd_json::to_owned_value(&mut failed).unwrap();
+        assert_eq!(parse_geoip(&failed), None);
+    }
+
+    #[test]
+    fn test_wmo_codes() {
+        assert!(matches!(OpenMeteo::match_wmo_code(0), WeatherClass::Clear));
+        assert!(matches!(OpenMeteo::match_wmo_code(45), WeatherClass::Mist));
+        assert!(matches!(OpenMeteo::match_wmo_code(95), WeatherClass::Storm));
+        assert!(matches!(
+            OpenMeteo::match_wmo_code(1000),
+            WeatherClass::None
+        ));
+    }
+}
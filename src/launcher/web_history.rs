@@ -0,0 +1,165 @@
+use std::{
+    collections::{HashMap, VecDeque},
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use gpui::SharedString;
+
+use crate::{
+    launcher::{Launcher, children::RenderableChild},
+    loader::utils::{AppData, normalize_search},
+    sherlock_error,
+    utils::{
+        errors::{SherlockError, SherlockErrorType},
+        paths,
+    },
+};
+
+/// Recent queries of a web launcher, newest first
+///
+/// All engines share `web_history.json` in the data directory, each under its own key.
+#[derive(Debug, Default)]
+pub struct WebHistory {
+    engine: String,
+    entries: VecDeque<String>,
+    capacity: usize,
+    path: Option<PathBuf>,
+}
+
+impl WebHistory {
+    /// Reads the history of `engine`. A missing or unreadable file results in an empty history.
+    pub fn load(engine: &str, capacity: usize) -> Self {
+        let path = paths::get_data_dir()
+            .ok()
+            .map(|dir| dir.join("web_history.json"));
+        let mut entries = path
+            .as_deref()
+            .and_then(|path| read_all(path).remove(engine))
+            .unwrap_or_default();
+        entries.truncate(capacity);
+
+        Self {
+            engine: engine.to_string(),
+            entries,
+            capacity,
+            path,
+        }
+    }
+
+    /// Moves `query` to the front of the history, dropping the oldest entries beyond the
+    /// capacity
+    pub fn push(&mut self, query: &str) {
+        let query = query.trim();
+        if self.capacity == 0 || query.is_empty() {
+            return;
+        }
+        self.entries.retain(|entry| entry != query);
+        self.entries.push_front(query.to_string());
+        self.entries.truncate(self.capacity);
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &str> {
+        self.entries.iter().map(String::as_str)
+    }
+
+    /// Writes the history, keeping the entries of other engines
+    pub fn save(&self) -> Result<(), SherlockError> {
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        let mut all = read_all(path);
+        all.insert(self.engine.clone(), self.entries.clone());
+
+        let content = serde_json::to_vec(&all)
+            .map_err(|e| sherlock_error!(SherlockErrorType::SerializationError, e.to_string()))?;
+        fs::write(path, content).map_err(|e| {
+            sherlock_error!(
+                SherlockErrorType::FileWriteError(path.clone()),
+                e.to_string()
+            )
+        })
+    }
+}
+
+fn read_all(path: &Path) -> HashMap<String, VecDeque<String>> {
+    fs::read(path)
+        .ok()
+        .and_then(|bytes| serde_json::from_slice(&bytes).ok())
+        .unwrap_or_default()
+}
+
+/// Child that runs the search for `query` again. `slot` is its position in the history, the
+/// icon is the one of the `search` child.
+pub fn recent_search(
+    launcher: &Arc<Launcher>,
+    query: &str,
+    slot: usize,
+    search: &AppData,
+) -> RenderableChild {
+    let mut inner = AppData::new();
+    inner.name = Some(SharedString::from(query.to_string()));
+    inner.exec = Some(query.to_string());
+    inner.search_string = normalize_search(query).into_owned();
+    inner.icon = search.icon.clone();
+    inner.icon_name = search.icon_name.clone();
+    // below the search itself, newest first
+    inner.priority = Some(launcher.priority as f32 + (slot + 1) as f32 * 0.01);
+
+    RenderableChild::AppLike {
+        launcher: Arc::clone(launcher),
+        inner,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_history_dedup_and_cap() {
+        let mut history = WebHistory {
+            capacity: 2,
+            ..Default::default()
+        };
+        history.push("rust lifetimes");
+        history.push("gpui");
+        history.push(" rust lifetimes ");
+        assert_eq!(
+            history.entries().collect::<Vec<_>>(),
+            vec!["rust lifetimes", "gpui"]
+        );
+
+        history.push("wayland layer shell");
+        history.push("");
+        assert_eq!(
+            history.entries().collect::<Vec<_>>(),
+            vec!["wayland layer shell", "rust lifetimes"]
+        );
+    }
+
+    #[test]
+    fn test_save_keeps_other_engines() {
+        let path =
+            std::env::temp_dir().join(format!("sherlock-web-history-{}.json", std::process::id()));
+        let history = |engine: &str| WebHistory {
+            engine: engine.to_string(),
+            capacity: 5,
+            path: Some(path.clone()),
+            ..Default::default()
+        };
+
+        let mut google = history("google");
+        google.push("first");
+        google.save().unwrap();
+        let mut ddg = history("duckduckgo");
+        ddg.push("second");
+        ddg.save().unwrap();
+
+        let all = read_all(&path);
+        assert_eq!(all["google"], ["first"]);
+        assert_eq!(all["duckduckgo"], ["second"]);
+        fs::remove_file(path).ok();
+    }
+}
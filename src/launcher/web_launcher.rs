@@ -1,8 +1,94 @@
+use std::collections::HashMap;
+use std::sync::{Arc, RwLock};
+
 use serde::Deserialize;
 
+use crate::launcher::web_history::WebHistory;
+
 #[derive(Clone, Debug, Deserialize)]
 pub struct WebLauncher {
     #[serde(rename = "search_engine")]
     pub engine: String,
     pub browser: Option<String>,
+    /// Engine used by the alternative action, see `behavior.hold_for_alternative`
+    #[serde(default, rename = "secondary_search_engine")]
+    pub secondary_engine: Option<String>,
+    /// Engines picked by a leading bang like `!w`, either by name or as a url template with a
+    /// `{}` or `{keyword}` placeholder
+    #[serde(default)]
+    pub engines: HashMap<String, String>,
+    /// Recent searches, only kept with a `history_size` above 0
+    #[serde(skip)]
+    pub history: Option<Arc<RwLock<WebHistory>>>,
+}
+
+/// Splits a leading bang off `query`, e.g. `!w rust lifetimes`, and returns the engine it
+/// picks together with the rest of the query. Bangs missing from `engines` are part of the query.
+pub fn split_bang<'a>(
+    engines: &'a HashMap<String, String>,
+    query: &'a str,
+) -> Option<(&'a str, &'a str)> {
+    let query = query.trim_start();
+    let (bang, rest) = query
+        .strip_prefix('!')?
+        .split_once(char::is_whitespace)
+        .unwrap_or((&query[1..], ""));
+    if bang.is_empty() {
+        return None;
+    }
+    engines
+        .iter()
+        .find(|(key, _)| key.trim_start_matches('!').eq_ignore_ascii_case(bang))
+        .map(|(_, engine)| (engine.as_str(), rest.trim_start()))
+}
+
+/// The name shown for an engine, the host for url templates
+pub fn engine_label(engine: &str) -> &str {
+    match engine.split_once("://") {
+        Some((_, rest)) => rest.split(['/', '?', '#']).next().unwrap_or(rest),
+        None => engine,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_bang() {
+        let engines = HashMap::from([
+            (
+                String::from("w"),
+                String::from("https://en.wikipedia.org/w/?search={}"),
+            ),
+            (
+                String::from("!gh"),
+                String::from("https://github.com/search?q={}"),
+            ),
+            (String::from("ddg"), String::from("duckduckgo")),
+        ]);
+
+        assert_eq!(
+            split_bang(&engines, "!w rust lifetimes"),
+            Some(("https://en.wikipedia.org/w/?search={}", "rust lifetimes"))
+        );
+        assert_eq!(
+            split_bang(&engines, "!GH  sherlock"),
+            Some(("https://github.com/search?q={}", "sherlock"))
+        );
+        assert_eq!(split_bang(&engines, "!ddg"), Some(("duckduckgo", "")));
+        // unknown bangs stay part of the query
+        assert_eq!(split_bang(&engines, "!x rust"), None);
+        assert_eq!(split_bang(&engines, "! rust"), None);
+        assert_eq!(split_bang(&engines, "rust !w"), None);
+    }
+
+    #[test]
+    fn test_engine_label() {
+        assert_eq!(
+            engine_label("https://en.wikipedia.org/w/?search={}"),
+            "en.wikipedia.org"
+        );
+        assert_eq!(engine_label("duckduckgo"), "duckduckgo");
+    }
 }
@@ -6,6 +6,7 @@ use simd_json::prelude::ArrayTrait;
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs::{self, File};
+use std::mem;
 use std::path::{Path, PathBuf};
 use std::sync::{Arc, RwLock};
 use std::time::SystemTime;
@@ -71,6 +72,8 @@ impl Loader {
             Some(apps) => apps,
             _ => get_desktop_files(system_apps),
         };
+        let locales = current_locales();
+        let desktops = current_desktops();
 
         // Parellize opening of all .desktop files and parsing them into AppData
         let apps: Vec<AppData> = desktop_files
@@ -79,10 +82,11 @@ impl Loader {
                 let r_path = entry.to_str()?;
                 match read_lines(r_path) {
                     Ok(content) => {
-                        let mut buffer = Vec::new();
                         let mut data = AppData::new();
                         let mut current_section = None;
-                        let mut current_action = ApplicationAction::new("app_launcher");
+                        let mut declared_actions: Option<Vec<String>> = None;
+                        let mut desktop_actions: Vec<DesktopAction> = Vec::new();
+                        let mut comment: Option<String> = None;
                         data.desktop_file = Some(entry);
                         for line in content.flatten() {
                             let line = line.trim();
@@ -91,11 +95,11 @@ impl Loader {
                                 continue;
                             }
                             if line.starts_with('[') && line.ends_with(']') {
-                                current_section = Some(line[1..line.len() - 1].to_string());
-                                if current_action.is_valid() {
-                                    buffer.push(Arc::new(current_action))
+                                let section = &line[1..line.len() - 1];
+                                if let Some(id) = section.strip_prefix("Desktop Action ") {
+                                    desktop_actions.push(DesktopAction::new(id));
                                 }
-                                current_action = ApplicationAction::new("app_launcher");
+                                current_section = Some(section.to_string());
                                 continue;
                             }
                             if current_section.is_none() {
@@ -114,9 +118,7 @@ impl Loader {
                                                 Some(SharedString::from(value.to_string()))
                                             }
                                         }
-                                        "icon" => {
-                                            data.icon = resolve_icon_path(value);
-                                        }
+                                        "icon" => data.set_icon(value),
                                         "exec" => data.exec = Some(value.to_string()),
                                         "nodisplay" if value.eq_ignore_ascii_case("true") => {
                                             return None;
@@ -128,30 +130,35 @@ impl Loader {
                                             data.terminal = value.eq_ignore_ascii_case("true");
                                         }
                                         "keywords" => data.search_string = value.to_lowercase(),
+                                        "comment" => comment = Some(value.to_lowercase()),
+                                        "actions" => declared_actions = Some(split_list(value)),
                                         _ => {}
                                     }
-                                } else {
-                                    // Application Actions
-                                    match key.as_ref() {
-                                        "name" => {
-                                            current_action.name =
-                                                Some(SharedString::from(value.to_string()))
-                                        }
-                                        "exec" => current_action.exec = Some(value.to_string()),
-                                        "icon" => current_action.icon = resolve_icon_path(value),
-                                        _ => {}
-                                    }
-                                    if current_action.icon.is_none() {
-                                        current_action.icon = data.icon.clone();
-                                    }
-                                    if current_action.is_full() {
-                                        buffer.push(Arc::new(current_action));
-                                        current_action = ApplicationAction::new("app_launcher");
-                                        current_section = None;
-                                    }
+                                } else if current_section
+                                    .as_deref()
+                                    .is_some_and(|s| s.starts_with("Desktop Action "))
+                                    && let Some(action) = desktop_actions.last_mut()
+                                {
+                                    action.set(&key, value, &locales);
                                 }
                             }
                         }
+                        let buffer: Vec<Arc<ApplicationAction>> =
+                            declared(desktop_actions, declared_actions.as_deref())
+                                .into_iter()
+                                .filter_map(|action| {
+                                    action.into_action(data.terminal, data.icon.as_ref(), &desktops)
+                                })
+                                .map(Arc::new)
+                                .collect();
+                        // the comment is searched as a secondary field after the keywords
+                        if let Some(comment) = comment {
+                            if !data.search_string.is_empty() && !data.search_string.ends_with(';')
+                            {
+                                data.search_string.push(';');
+                            }
+                            data.search_string.push_str(&comment);
+                        }
                         let alias = {
                             let mut aliases = aliases.write().unwrap();
                             aliases.remove(data.name.as_ref().unwrap().as_str())
@@ -238,13 +245,20 @@ impl Loader {
             || file_has_changed(&config.files.ignore, &config.caching.cache)
             || file_has_changed(&config.files.config, &config.caching.cache);
 
-        if !changed {
+        // an outdated cache format reads as empty and is rebuilt from disk
+        let cached_apps: Vec<AppData> = if changed {
+            Vec::new()
+        } else {
+            BinaryCache::read(&config.caching.cache)?
+        };
+
+        if !cached_apps.is_empty() {
             let _ = sher_log!("Loading cached apps");
-            let cached_apps: Vec<AppData> = BinaryCache::read(&config.caching.cache)?;
 
             let cleaned_apps: Vec<AppData> = cached_apps
                 .into_iter()
                 .map(|mut v| {
+                    v.normalize_cached_search();
                     let count = v
                         .exec
                         .as_ref()
@@ -295,6 +309,167 @@ impl Loader {
     }
 }
 
+/// A `[Desktop Action <id>]` group of a desktop file
+#[derive(Debug, Default)]
+struct DesktopAction {
+    id: String,
+    name: Option<String>,
+    /// Name in the best matching locale so far, with its position in the locale list
+    localized_name: Option<(usize, String)>,
+    exec: Option<String>,
+    icon: Option<String>,
+    only_show_in: Option<String>,
+    not_show_in: Option<String>,
+}
+
+impl DesktopAction {
+    fn new(id: &str) -> Self {
+        Self {
+            id: id.trim().to_string(),
+            ..Default::default()
+        }
+    }
+
+    /// Reads a lowercased key of the group, localized keys keep their locale, e.g. `name[de]`
+    fn set(&mut self, key: &str, value: &str, locales: &[String]) {
+        match key {
+            "name" => self.name = Some(value.to_string()),
+            "exec" => self.exec = Some(value.to_string()),
+            "icon" => self.icon = Some(value.to_string()),
+            "onlyshowin" => self.only_show_in = Some(value.to_string()),
+            "notshowin" => self.not_show_in = Some(value.to_string()),
+            _ => {
+                let Some(rank) = locale_rank(key, "name", locales) else {
+                    return;
+                };
+                if self
+                    .localized_name
+                    .as_ref()
+                    .is_none_or(|(best, _)| rank < *best)
+                {
+                    self.localized_name = Some((rank, value.to_string()));
+                }
+            }
+        }
+    }
+
+    /// The context menu entry of the action. Actions without a name or exec and those hidden
+    /// in the current `desktops` are left out.
+    fn into_action(
+        self,
+        terminal: bool,
+        app_icon: Option<&Arc<Path>>,
+        desktops: &[String],
+    ) -> Option<ApplicationAction> {
+        if !shown_in(
+            self.only_show_in.as_deref(),
+            self.not_show_in.as_deref(),
+            desktops,
+        ) {
+            return None;
+        }
+        let name = self.localized_name.map(|(_, name)| name).or(self.name)?;
+        let mut action = ApplicationAction::new("app_launcher");
+        action.exec = Some(self.exec?);
+        action.name = Some(SharedString::from(name));
+        action.icon = self
+            .icon
+            .as_deref()
+            .and_then(resolve_icon_path)
+            .or_else(|| app_icon.cloned());
+        action.terminal = terminal;
+        Some(action)
+    }
+}
+
+/// The actions listed in the `Actions` key, in its order. Files without the key show all of
+/// their action groups.
+fn declared(mut actions: Vec<DesktopAction>, ids: Option<&[String]>) -> Vec<DesktopAction> {
+    let Some(ids) = ids else {
+        return actions;
+    };
+    ids.iter()
+        .filter_map(|id| {
+            let pos = actions.iter().position(|action| &action.id == id)?;
+            Some(mem::take(&mut actions[pos]))
+        })
+        .collect()
+}
+
+/// Entries of a `;` separated desktop file list
+fn split_list(value: &str) -> Vec<String> {
+    value
+        .split(';')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Whether an entry with the `OnlyShowIn` and `NotShowIn` lists is shown in one of the
+/// `desktops`
+fn shown_in(only: Option<&str>, not: Option<&str>, desktops: &[String]) -> bool {
+    let listed = |list: &str| {
+        split_list(list)
+            .iter()
+            .any(|desktop| desktops.iter().any(|d| d.eq_ignore_ascii_case(desktop)))
+    };
+    only.is_none_or(listed) && !not.is_some_and(listed)
+}
+
+/// The desktops of `XDG_CURRENT_DESKTOP`
+fn current_desktops() -> Vec<String> {
+    env::var("XDG_CURRENT_DESKTOP")
+        .map(|desktops| desktops.split(':').map(str::to_string).collect())
+        .unwrap_or_default()
+}
+
+/// Locales to look for in localized keys, best match first, see [`locale_variants`]
+fn current_locales() -> Vec<String> {
+    ["LC_ALL", "LC_MESSAGES", "LANG"]
+        .into_iter()
+        .find_map(|var| env::var(var).ok().filter(|locale| !locale.is_empty()))
+        .map(|locale| locale_variants(&locale))
+        .unwrap_or_default()
+}
+
+/// The lowercased locales a localized key can use for `locale`, best match first, e.g.
+/// `de_AT.UTF-8@euro` matches `de_at@euro`, `de_at`, `de@euro` and `de`
+fn locale_variants(locale: &str) -> Vec<String> {
+    let locale = locale.to_ascii_lowercase();
+    let (rest, modifier) = match locale.split_once('@') {
+        Some((rest, modifier)) => (rest, Some(modifier)),
+        None => (locale.as_str(), None),
+    };
+    let rest = rest.split('.').next().unwrap_or(rest);
+    let (lang, country) = match rest.split_once('_') {
+        Some((lang, country)) => (lang, Some(country)),
+        None => (rest, None),
+    };
+
+    let mut variants = Vec::with_capacity(4);
+    if let (Some(country), Some(modifier)) = (country, modifier) {
+        variants.push(format!("{lang}_{country}@{modifier}"));
+    }
+    if let Some(country) = country {
+        variants.push(format!("{lang}_{country}"));
+    }
+    if let Some(modifier) = modifier {
+        variants.push(format!("{lang}@{modifier}"));
+    }
+    variants.push(lang.to_string());
+    variants
+}
+
+/// Position of the locale of `key`, e.g. `name[de]` for `base` "name", in `locales`
+fn locale_rank(key: &str, base: &str, locales: &[String]) -> Option<usize> {
+    let locale = key
+        .strip_prefix(base)?
+        .strip_prefix('[')?
+        .strip_suffix(']')?;
+    locales.iter().position(|l| l == locale)
+}
+
 fn should_ignore(ignore_apps: &Vec<Pattern>, app: &str) -> bool {
     let app_name = app.to_lowercase();
     ignore_apps.iter().any(|pattern| pattern.matches(&app_name))
@@ -420,6 +595,60 @@ fn test_get_applications_dir() {
     assert_eq!(res, expected_app_dirs);
 }
 
+#[test]
+fn test_desktop_actions() {
+    let locales = locale_variants("de_AT.UTF-8");
+    assert_eq!(locales, vec!["de_at", "de"]);
+    let desktops = vec![String::from("sway")];
+
+    let mut private = DesktopAction::new("new-private-window");
+    private.set("name", "New Private Window", &locales);
+    private.set("name[de]", "Neues privates Fenster", &locales);
+    private.set("name[fr]", "Nouvelle fenêtre privée", &locales);
+    private.set("exec", "firefox --private-window %u", &locales);
+
+    let mut gnome = DesktopAction::new("gnome-only");
+    gnome.set("name", "Settings", &locales);
+    gnome.set("exec", "firefox --settings", &locales);
+    gnome.set("onlyshowin", "GNOME;Unity;", &locales);
+
+    let mut unnamed = DesktopAction::new("unnamed");
+    unnamed.set("exec", "firefox", &locales);
+
+    let ids = split_list("gnome-only;new-private-window;unnamed;missing;");
+    let actions: Vec<ApplicationAction> = declared(vec![private, gnome, unnamed], Some(&ids))
+        .into_iter()
+        .filter_map(|action| action.into_action(true, None, &desktops))
+        .collect();
+
+    assert_eq!(actions.len(), 1);
+    assert_eq!(actions[0].name.as_deref(), Some("Neues privates Fenster"));
+    assert_eq!(
+        actions[0].exec.as_deref(),
+        Some("firefox --private-window %u")
+    );
+    assert!(actions[0].terminal);
+
+    assert!(shown_in(None, Some("KDE"), &desktops));
+    assert!(!shown_in(None, Some("KDE;sway"), &desktops));
+    assert!(!shown_in(Some("sway"), None, &[]));
+}
+
+#[test]
+fn test_locale_variants() {
+    assert_eq!(
+        locale_variants("sr_RS@latin"),
+        vec!["sr_rs@latin", "sr_rs", "sr@latin", "sr"]
+    );
+    assert_eq!(locale_variants("C"), vec!["c"]);
+
+    let locales = locale_variants("pt_BR.UTF-8");
+    assert_eq!(locale_rank("name[pt_br]", "name", &locales), Some(0));
+    assert_eq!(locale_rank("name[pt]", "name", &locales), Some(1));
+    assert_eq!(locale_rank("name[de]", "name", &locales), None);
+    assert_eq!(locale_rank("genericname[pt]", "name", &locales), None);
+}
+
 impl PathHelpers for Path {
     fn modtime(&self) -> Option<SystemTime> {
         self.metadata().ok().and_then(|m| m.modified().ok())
@@ -76,6 +76,7 @@ impl SherlockFlags {
             display_raw: check_flag_existence("--display-raw"),
             center_raw: check_flag_existence("--center"),
             cache: extract_path_value("--cache"),
+            socket: extract_path_value("--socket"),
             daemonize: check_flag_existence("--daemonize"),
             sub_menu: Self::extract_flag_value::<String>(&args, "--sub-menu", Some("-sm")),
             method: Self::extract_flag_value::<String>(&args, "--method", None),
@@ -83,7 +84,14 @@ impl SherlockFlags {
             multi: check_flag_existence("--multi"),
             photo_mode: check_flag_existence("--photo"),
             input: Self::extract_flag_value::<bool>(&args, "--input", None),
-            placeholder: Self::extract_flag_value::<String>(&args, "--placeholder", Some("-p")),
+            placeholder: Self::extract_flag_value::<String>(&args, "--placeholder", Some("-p"))
+                .or_else(|| Self::extract_flag_value::<String>(&args, "--prompt", None)),
+            keymap: check_flag_existence("keymap"),
+            dry_run: check_flag_existence("--dry-run"),
+            toggle: check_flag_existence("--toggle"),
+            dmenu: check_flag_existence("--dmenu"),
+            print_index: check_flag_existence("--index"),
+            debug_search: check_flag_existence("--debug-search"),
         })
     }
 }
@@ -101,6 +109,7 @@ pub fn flag_documentation() -> Result<(), SherlockError> {
         ("-v, --version", "Print the version of the application."),
         ("-h, --help", "Show this help message with allowed flags."),
         ("init", "Writes default configs into your config directory."),
+        ("keymap", "Print the active keybindings and exit."),
         ("\nFILES:", ""),
         ("--config", "Specify the configuration file to load."),
         ("--fallback", "Specify the fallback file to load."),
@@ -108,13 +117,14 @@ pub fn flag_documentation() -> Result<(), SherlockError> {
         ("--ignore", "Specify the Sherlock ignore file"),
         ("--alias", "Specify the Sherlock alias file (.json)."),
         ("--cache", "Specify the Sherlock cache file (.json)."),
+        ("--socket", "Specify the Sherlock socket file."),
         (
             "--config-dir",
             "Specify the directly Sherlock will look for its configuration in.",
         ),
         ("\nBEHAVIOR:", ""),
         (
-            "-p, --placeholder",
+            "-p, --placeholder, --prompt",
             "Overwrite the placeholder text of the search bar.",
         ),
         (
@@ -133,6 +143,27 @@ pub fn flag_documentation() -> Result<(), SherlockError> {
             "--photo",
             "Start Sherlock in \"photo mode\". This mode temporarily disables Sherlock from closing on focus loss.",
         ),
+        (
+            "--toggle",
+            "Close the window of the running instance instead if one is open.",
+        ),
+        (
+            "--dry-run",
+            "Print the requests of http actions instead of sending them.",
+        ),
+        (
+            "--debug-search",
+            "Write the score of every search match to the log file.",
+        ),
+        ("\nDMENU MODE:", ""),
+        (
+            "--dmenu",
+            "Pick one of the lines piped into stdin and print it instead of launching anything.",
+        ),
+        (
+            "--index",
+            "Print the line number of the picked line instead, starting at 0.",
+        ),
         ("\nPIPE MODE:", ""),
         (
             "--display-raw",
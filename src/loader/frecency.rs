@@ -0,0 +1,147 @@
+use std::{
+    collections::{BTreeSet, HashMap},
+    path::Path,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use crate::utils::{cache::BinaryCache, config::Sorting, errors::SherlockError};
+
+/// Launch times kept per exec key, older ones only count towards the launch count
+const MAX_LAUNCHES: usize = 10;
+const SECONDS_PER_DAY: f64 = 86_400.0;
+
+/// Unix timestamps of the most recent launches per exec key, oldest first. Stored next to the
+/// counts in `launches.bin`.
+pub type Launches = HashMap<String, Vec<u64>>;
+
+pub fn now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+}
+
+/// Appends a launch of `key` at `time`, dropping its oldest launches beyond [`MAX_LAUNCHES`]
+pub fn record(path: &Path, key: &str, time: u64) -> Result<(), SherlockError> {
+    let mut launches: Launches = BinaryCache::read(path).unwrap_or_default();
+    let times = launches.entry(key.to_string()).or_default();
+    times.push(time);
+    if times.len() > MAX_LAUNCHES {
+        times.drain(..times.len() - MAX_LAUNCHES);
+    }
+    BinaryCache::write(path, &launches)
+}
+
+/// Sum of the recorded launches of a key, each halving in weight every `half_life_days`
+pub fn frecency(times: &[u64], half_life_days: f32, now: u64) -> f64 {
+    let half_life = f64::from(half_life_days.max(f32::EPSILON)) * SECONDS_PER_DAY;
+    times
+        .iter()
+        .map(|&time| {
+            let age = now.saturating_sub(time) as f64;
+            0.5f64.powf(age / half_life)
+        })
+        .sum()
+}
+
+/// The usage ranks that [`parse_priority`](super::application_loader::parse_priority) turns
+/// into priorities: the higher the rank, the higher the entry shows up.
+///
+/// With [`Sorting::Frecency`], keys are ranked by [`frecency`] and ties, such as launches
+/// from before launch times were recorded, fall back to the counts.
+pub fn usage_ranks(
+    counts: &HashMap<String, u32>,
+    launches: &Launches,
+    sorting: Sorting,
+    half_life_days: f32,
+    now: u64,
+) -> HashMap<String, u32> {
+    match sorting {
+        Sorting::Counts => counts.clone(),
+        Sorting::Alphabetical => HashMap::new(),
+        Sorting::Frecency => {
+            let keys: BTreeSet<&String> = counts.keys().chain(launches.keys()).collect();
+            let mut scored: Vec<(&String, (f64, u32))> = keys
+                .into_iter()
+                .map(|key| {
+                    let score = launches
+                        .get(key)
+                        .map_or(0.0, |times| frecency(times, half_life_days, now));
+                    (key, (score, counts.get(key).copied().unwrap_or(0)))
+                })
+                .collect();
+            scored.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+
+            // dense ranks, unused keys keep rank 0
+            let mut ranks = HashMap::with_capacity(scored.len());
+            let mut rank = 0;
+            let mut previous = (0.0, 0);
+            for (key, score) in scored {
+                if score != previous {
+                    rank += 1;
+                    previous = score;
+                }
+                ranks.insert(key.clone(), rank);
+            }
+            ranks
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DAY: u64 = 86_400;
+    const NOW: u64 = 1_790_000_000;
+
+    #[test]
+    fn test_recent_launches_outrank_old_ones() {
+        // 50 launches last year, of which only the last 10 times are kept
+        let old: Vec<u64> = (0..MAX_LAUNCHES as u64)
+            .map(|i| NOW - 365 * DAY + i * 3600)
+            .collect();
+        // 5 launches this week
+        let recent: Vec<u64> = (1..=5).map(|i| NOW - i * DAY).collect();
+
+        let counts = HashMap::from([(String::from("old"), 50), (String::from("recent"), 5)]);
+        let launches =
+            Launches::from([(String::from("old"), old), (String::from("recent"), recent)]);
+
+        let ranks = usage_ranks(&counts, &launches, Sorting::Frecency, 7.0, NOW);
+        assert!(ranks["recent"] > ranks["old"]);
+        // the plain counts still prefer the old favorite
+        let ranks = usage_ranks(&counts, &launches, Sorting::Counts, 7.0, NOW);
+        assert!(ranks["recent"] < ranks["old"]);
+        assert!(usage_ranks(&counts, &launches, Sorting::Alphabetical, 7.0, NOW).is_empty());
+    }
+
+    #[test]
+    fn test_frecency_ties_fall_back_to_counts() {
+        let counts = HashMap::from([
+            (String::from("a"), 3),
+            (String::from("b"), 1),
+            (String::from("unused"), 0),
+        ]);
+        let ranks = usage_ranks(&counts, &Launches::new(), Sorting::Frecency, 7.0, NOW);
+        assert_eq!(ranks["unused"], 0);
+        assert!(ranks["a"] > ranks["b"]);
+
+        // a launch one half-life ago weighs half
+        let score = frecency(&[NOW - 7 * DAY, NOW], 7.0, NOW);
+        assert!((score - 1.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_record_keeps_the_latest_launches() {
+        let path =
+            std::env::temp_dir().join(format!("sherlock-launches-{}.bin", std::process::id()));
+        for i in 0..12 {
+            record(&path, "firefox", NOW + i).unwrap();
+        }
+        let launches: Launches = BinaryCache::read(&path).unwrap();
+        assert_eq!(launches["firefox"].len(), MAX_LAUNCHES);
+        assert_eq!(launches["firefox"][0], NOW + 2);
+        std::fs::remove_file(path).ok();
+    }
+}
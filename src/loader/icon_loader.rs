@@ -4,6 +4,7 @@ use crate::loader::assets::Assets;
 use crate::utils::errors::{SherlockError, SherlockErrorType};
 use crate::utils::files::home_dir;
 use crate::utils::paths::get_cache_dir;
+use crate::utils::stats;
 use crate::{ICONS, sherlock_error};
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -106,6 +107,14 @@ impl<'g> IconThemeGuard {
         Ok(())
     }
 
+    /// Drops all cached lookups, misses included, and scans `paths` again
+    pub fn reload<T: AsRef<Path>>(paths: &[T]) -> Result<(), SherlockError> {
+        let mut theme = CustomIconTheme::new();
+        paths.iter().for_each(|path| theme.add_path(path));
+        *Self::get_write()? = theme;
+        Ok(())
+    }
+
     pub fn lookup_icon(name: &str) -> Result<Option<Option<Arc<Path>>>, SherlockError> {
         let inner = Self::get_read()?;
         Ok(inner.lookup_icon(name))
@@ -124,8 +133,10 @@ impl<'g> IconThemeGuard {
 pub fn resolve_icon_path(name: &str) -> Option<Arc<Path>> {
     // 1. Check in-memory HashMap cache
     if let Ok(Some(icon)) = IconThemeGuard::lookup_icon(name) {
+        stats::record_icon_lookup(true);
         return icon;
     }
+    stats::record_icon_lookup(false);
 
     let mut result: Option<Arc<Path>> = None;
 
@@ -239,3 +250,26 @@ fn render_to_png_cache(key: &str, svg_data: &[u8]) -> Option<Arc<Path>> {
 
     Some(Arc::from(out.into_boxed_path()))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reload_resolves_new_icons() {
+        let _ = ICONS.set(RwLock::new(CustomIconTheme::new()));
+        let dir = std::env::temp_dir().join(format!("sherlock-icons-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let name = format!("sherlock-test-icon-{}", std::process::id());
+
+        // the miss is cached
+        assert_eq!(resolve_icon_path(&name), None);
+        let icon = dir.join(format!("{name}.png"));
+        std::fs::write(&icon, b"").unwrap();
+        assert_eq!(resolve_icon_path(&name), None);
+
+        IconThemeGuard::reload(&[&dir]).unwrap();
+        assert_eq!(resolve_icon_path(&name).as_deref(), Some(icon.as_path()));
+        std::fs::remove_dir_all(dir).ok();
+    }
+}
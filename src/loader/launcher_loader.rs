@@ -1,6 +1,11 @@
 use gpui::{App, Entity};
 use simd_json::prelude::ArrayTrait;
-use std::{collections::HashMap, fs::File, path::PathBuf, sync::Arc};
+use std::{
+    collections::HashMap,
+    fs::File,
+    path::{Path, PathBuf},
+    sync::{Arc, RwLock},
+};
 
 use crate::{
     launcher::{
@@ -8,11 +13,16 @@ use crate::{
         app_launcher::AppLauncher,
         audio_launcher::MusicPlayerLauncher,
         bookmark_launcher::BookmarkLauncher,
+        browser_history,
         calc_launcher::{CURRENCIES, CalculatorLauncher, Currency},
         category_launcher::CategoryLauncher,
         children::RenderableChild,
+        doc_launcher::DocLauncher,
+        ssh_launcher::SshLauncher,
         system_cmd_launcher::CommandLauncher,
+        url_launcher::UrlLauncher,
         weather_launcher::WeatherLauncher,
+        web_history::WebHistory,
         web_launcher::WebLauncher,
     },
     loader::utils::RawLauncher,
@@ -22,10 +32,12 @@ use crate::{
         cache::BinaryCache,
         config::{ConfigGuard, ConstantDefaults},
         errors::{SherlockError, SherlockErrorType},
+        first_run,
     },
 };
 
 use super::Loader;
+use super::frecency::{self, Launches};
 use super::utils::CounterReader;
 
 impl Loader {
@@ -33,19 +45,43 @@ impl Loader {
         cx: &mut App,
         data_handle: Entity<Arc<Vec<RenderableChild>>>,
     ) -> Result<Arc<[LauncherMode]>, SherlockError> {
+        let (renders, modes) = Self::read_launchers()?;
+        data_handle.update(cx, |items, cx| {
+            *items = Arc::new(renders);
+            cx.notify();
+        });
+        Ok(modes)
+    }
+
+    /// Reads the launchers and their children without touching the app, so the daemon can
+    /// reload them off the main thread
+    pub fn read_launchers() -> Result<(Vec<RenderableChild>, Arc<[LauncherMode]>), SherlockError> {
         // read config
         let config = ConfigGuard::read()?;
 
         // Read fallback data here:
-        let (raw_launchers, _n) = parse_launcher_configs(&config.files.fallback)?;
+        let (mut raw_launchers, mut non_breaking) = parse_launcher_configs(&config.files.fallback)?;
+        if config.runtime.first_run {
+            raw_launchers.extend(first_run::welcome_launcher(&config));
+        }
+        let config_file: Arc<Path> = Arc::from(config.files.fallback.as_path());
 
         // Read cached counter file
         let counter_reader = CounterReader::new()?;
         let counts: HashMap<String, u32> =
             BinaryCache::read(&counter_reader.path).unwrap_or_default();
+        let launches: Launches =
+            BinaryCache::read(&counter_reader.launches_path).unwrap_or_default();
+        let ranks = frecency::usage_ranks(
+            &counts,
+            &launches,
+            config.search.sorting,
+            config.search.frecency_half_life_days,
+            frecency::now(),
+        );
 
         // Construct max decimal count
-        let max_count = counts.values().max().cloned().unwrap_or(0);
+        let max_count = ranks.values().max().cloned().unwrap_or(0);
         let max_decimals = if max_count == 0 {
             0
         } else {
@@ -78,6 +114,9 @@ impl Loader {
                     "categories" => parse_category_launcher(&raw),
                     "command" => parse_command_launcher(&raw),
                     "debug" => parse_debug_launcher(&raw),
+                    "documentation" => parse_doc_launcher(&raw),
+                    "ssh" => parse_ssh_launcher(&raw),
+                    "url_launcher" => parse_url_launcher(&raw),
                     "weather" => parse_weather_launcher(&raw),
                     "web_launcher" => parse_web_launcher(&raw),
                     // "bulk_text" => parse_bulk_text_launcher(&raw),
@@ -90,6 +129,17 @@ impl Loader {
                     // "pomodoro" => parse_pomodoro(&raw),
                     _ => LauncherType::Empty,
                 };
+                if matches!(launcher_type, LauncherType::Empty) {
+                    non_breaking.push(sherlock_error!(
+                        SherlockErrorType::ConfigError(Some(format!(
+                            "Skipped launcher \"{}\" of type \"{}\"",
+                            raw.name.as_deref().unwrap_or_default(),
+                            raw.r#type
+                        ))),
+                        "The type is unknown or its arguments are invalid"
+                    ));
+                    return None;
+                }
 
                 let icon = raw
                     .args
@@ -98,7 +148,9 @@ impl Loader {
                     .map(|s| s.to_string());
 
                 let opts = Arc::clone(&raw.args);
-                let launcher = Arc::new(Launcher::from_raw(raw, method, launcher_type, icon));
+                let mut launcher = Launcher::from_raw(raw, method, launcher_type, icon);
+                launcher.config_file = Some(Arc::clone(&config_file));
+                let launcher = Arc::new(launcher);
 
                 Some((launcher, opts))
             })
@@ -120,15 +172,13 @@ impl Loader {
                 launcher.launcher_type.get_render_obj(
                     Arc::clone(&launcher),
                     opts,
-                    &counts,
+                    &ranks,
                     max_decimals,
                 )
             })
             .flatten()
             .collect();
 
-        // Get errors and launchers
-        let mut non_breaking = Vec::new();
         if counts.is_empty() {
             let counts: HashMap<String, u32> = renders
                 .iter()
@@ -140,12 +190,9 @@ impl Loader {
             };
         }
 
-        data_handle.update(cx, |items, cx| {
-            *items = Arc::new(renders);
-            cx.notify();
-        });
+        non_breaking.into_iter().for_each(SherlockError::report);
 
-        Ok(Arc::from(modes))
+        Ok((renders, Arc::from(modes)))
     }
 }
 
@@ -206,10 +253,24 @@ fn parse_bookmarks_launcher(
         .or_else(|| default_browser.cloned())
         .or_else(|| ConstantDefaults::browser().ok());
 
+    // recently visited pages are opt-in
+    let history = launcher
+        .args
+        .get("history")
+        .and_then(|history| history.as_bool())
+        .unwrap_or(false);
+    let history_limit = launcher
+        .args
+        .get("history_limit")
+        .and_then(|limit| limit.as_u64())
+        .map_or(browser_history::DEFAULT_LIMIT, |limit| limit as usize);
+
     // TODO parse bookmarks later
     if let Some(browser) = browser_target {
         return LauncherType::Bookmark(BookmarkLauncher {
             target_browser: browser,
+            history,
+            history_limit,
         });
     }
     LauncherType::Empty
@@ -240,6 +301,24 @@ fn parse_command_launcher(_raw: &RawLauncher) -> LauncherType {
 fn parse_debug_launcher(_: &RawLauncher) -> LauncherType {
     LauncherType::Command(CommandLauncher {})
 }
+fn parse_doc_launcher(raw: &RawLauncher) -> LauncherType {
+    match serde_json::from_value::<DocLauncher>(raw.args.as_ref().clone()) {
+        Ok(launcher) => LauncherType::Doc(launcher),
+        Err(_) => LauncherType::Empty,
+    }
+}
+fn parse_ssh_launcher(raw: &RawLauncher) -> LauncherType {
+    match serde_json::from_value::<SshLauncher>(raw.args.as_ref().clone()) {
+        Ok(launcher) => LauncherType::Ssh(launcher),
+        Err(_) => LauncherType::Empty,
+    }
+}
+fn parse_url_launcher(raw: &RawLauncher) -> LauncherType {
+    match serde_json::from_value::<UrlLauncher>(raw.args.as_ref().clone()) {
+        Ok(launcher) => LauncherType::Url(launcher),
+        Err(_) => LauncherType::Empty,
+    }
+}
 fn parse_weather_launcher(raw: &RawLauncher) -> LauncherType {
     match serde_json::from_value::<WeatherLauncher>(raw.args.as_ref().clone()) {
         Ok(launcher) => LauncherType::Weather(launcher),
@@ -249,7 +328,21 @@ fn parse_weather_launcher(raw: &RawLauncher) -> LauncherType {
 
 fn parse_web_launcher(raw: &RawLauncher) -> LauncherType {
     match serde_json::from_value::<WebLauncher>(raw.args.as_ref().clone()) {
-        Ok(launcher) => LauncherType::Web(launcher),
+        Ok(mut launcher) => {
+            // recent searches are opt-in
+            let history_size = raw
+                .args
+                .get("history_size")
+                .and_then(|size| size.as_u64())
+                .unwrap_or(0) as usize;
+            if history_size > 0 {
+                launcher.history = Some(Arc::new(RwLock::new(WebHistory::load(
+                    &launcher.engine,
+                    history_size,
+                ))));
+            }
+            LauncherType::Web(launcher)
+        }
         Err(_) => LauncherType::Empty,
     }
 }
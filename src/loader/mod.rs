@@ -1,9 +1,11 @@
 pub mod application_loader;
 pub mod assets;
 mod flag_loader;
+pub mod frecency;
 mod icon_loader;
 mod launcher_loader;
 pub mod utils;
+pub mod variable_history;
 
 pub struct Loader;
 pub use icon_loader::{CustomIconTheme, IconThemeGuard, resolve_icon_path};
@@ -4,21 +4,32 @@ use serde::{
     de::{MapAccess, Visitor},
 };
 use std::{
-    collections::{BTreeSet, HashMap, HashSet},
+    borrow::Cow,
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
     fmt::Debug,
     hash::{Hash, Hasher},
     path::{Path, PathBuf},
-    sync::Arc,
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
 };
 
+use regex::Regex;
+use unicode_normalization::UnicodeNormalization;
+
 use crate::{
-    launcher::{Launcher, LauncherType},
-    loader::resolve_icon_path,
+    launcher::{Launcher, LauncherType, children::NameHighlight, provenance::Transforms},
+    loader::{frecency, resolve_icon_path},
     sherlock_error,
     utils::{
+        accent::{TagColor, deserialize_tag_color},
         cache::BinaryCache,
+        command_launch::Substitution,
         config::HomeType,
         errors::{SherlockError, SherlockErrorType},
+        files::{expand_path, home_dir},
+        http_action::HttpMethod,
         paths,
     },
 };
@@ -31,6 +42,20 @@ pub struct ApplicationAction {
     pub method: String,
     #[serde(default = "default_true")]
     pub exit: bool,
+    /// Endpoint of `http` actions, see [`HttpRequest`](crate::utils::http_action::HttpRequest)
+    #[serde(default)]
+    pub url: Option<String>,
+    #[serde(default)]
+    pub http_method: HttpMethod,
+    /// JSON body template of `http` actions
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Headers of `http` actions. Values like `env:TOKEN` are read from the environment.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Runs `exec` in the terminal, set for desktop actions of `Terminal=true` apps
+    #[serde(default)]
+    pub terminal: bool,
 }
 impl ApplicationAction {
     pub fn new(method: &str) -> Self {
@@ -40,14 +65,13 @@ impl ApplicationAction {
             icon: None,
             method: method.to_string(),
             exit: true,
+            url: None,
+            http_method: HttpMethod::default(),
+            body: None,
+            headers: BTreeMap::new(),
+            terminal: false,
         }
     }
-    pub fn is_valid(&self) -> bool {
-        self.name.is_some() && self.exec.is_some()
-    }
-    pub fn is_full(&self) -> bool {
-        self.name.is_some() && self.exec.is_some() && self.icon.is_some()
-    }
 }
 
 #[derive(Clone, Debug, Deserialize, Serialize, PartialEq)]
@@ -55,10 +79,15 @@ pub struct AppData {
     #[serde(default)]
     pub name: Option<SharedString>,
     pub exec: Option<String>,
+    /// Normalized by [`normalize_search`] when the entry is loaded, e.g. with
+    /// [`construct_search`]. The filter compares it with the query as is.
     pub search_string: String,
     #[serde(default)]
     pub priority: Option<f32>,
     pub icon: Option<Arc<Path>>,
+    /// Name `icon` was resolved from, to resolve it again after the icons are reloaded
+    #[serde(default)]
+    pub icon_name: Option<String>,
     pub desktop_file: Option<PathBuf>,
     #[serde(default)]
     pub actions: Arc<[Arc<ApplicationAction>]>,
@@ -67,6 +96,22 @@ pub struct AppData {
     pub vars: Vec<ExecVariable>,
     #[serde(default)]
     pub terminal: bool,
+    #[serde(default)]
+    pub transforms: Transforms,
+    #[serde(default, rename = "match")]
+    pub match_policy: MatchPolicy,
+    /// Overrides the accent color of the launcher for this result
+    #[serde(default, deserialize_with = "deserialize_tag_color")]
+    pub accent: Option<TagColor>,
+    /// Shown below the name instead of the launcher name, e.g. the folder of a bookmark
+    #[serde(default)]
+    pub subtitle: Option<SharedString>,
+    /// Runs only once the exec key is pressed a second time, e.g. for a shutdown command
+    #[serde(default)]
+    pub confirm: bool,
+    /// The displayed name normalized for highlighting, see [`NameHighlight`]
+    #[serde(skip)]
+    pub name_highlight: NameHighlight,
 }
 impl Eq for AppData {}
 impl Hash for AppData {
@@ -84,10 +129,35 @@ impl AppData {
             search_string: String::new(),
             priority: None,
             icon: None,
+            icon_name: None,
             desktop_file: None,
             actions: Arc::new([]),
             vars: vec![],
             terminal: false,
+            transforms: Transforms::default(),
+            match_policy: MatchPolicy::default(),
+            accent: None,
+            subtitle: None,
+            confirm: false,
+            name_highlight: NameHighlight::default(),
+        }
+    }
+    pub fn set_icon(&mut self, name: &str) {
+        self.icon = resolve_icon_path(name);
+        self.icon_name = Some(name.to_string());
+    }
+    /// Uses the icon `name` if the entry has no icon, see
+    /// [`Launcher::fallback_icon`](crate::launcher::Launcher::fallback_icon)
+    pub fn apply_fallback_icon(&mut self, name: Option<&str>) {
+        if self.icon.is_none() {
+            self.icon = name.and_then(resolve_icon_path);
+        }
+    }
+    /// Normalizes a search string read from a cache, which may have been written under older
+    /// rules of [`normalize_search`]
+    pub fn normalize_cached_search(&mut self) {
+        if let Cow::Owned(normalized) = normalize_search(&self.search_string) {
+            self.search_string = normalized;
         }
     }
     pub fn apply_alias(
@@ -100,10 +170,12 @@ impl AppData {
         if let Some(alias) = alias {
             if let Some(alias_name) = alias.name.as_ref() {
                 self.name = Some(SharedString::from(alias_name));
+                self.transforms.insert(Transforms::ALIAS_NAME);
             }
 
-            if let Some(alias_icon) = alias.icon.as_ref().map(|i| resolve_icon_path(i)) {
-                self.icon = alias_icon;
+            if let Some(alias_icon) = alias.icon.as_ref() {
+                self.set_icon(alias_icon);
+                self.transforms.insert(Transforms::ALIAS_ICON);
             }
 
             let name: Option<&str> = self
@@ -113,14 +185,19 @@ impl AppData {
                 .or(launcher.display_name.as_ref().map(|s| s.as_str()));
             if let Some(alias_keywords) = alias.keywords.as_ref() {
                 self.search_string = construct_search(name, &alias_keywords, use_keywords);
+                self.transforms.insert(Transforms::ALIAS_KEYWORDS);
             } else {
                 self.search_string = construct_search(name, &self.search_string, use_keywords);
             }
 
             if let Some(alias_exec) = alias.exec.as_ref() {
                 self.exec = Some(alias_exec.to_string());
+                self.transforms.insert(Transforms::ALIAS_EXEC);
             }
 
+            if alias.actions.is_some() || alias.add_actions.is_some() {
+                self.transforms.insert(Transforms::ALIAS_ACTIONS);
+            }
             if let Some(add_actions) = alias.add_actions {
                 add_actions.into_iter().for_each(|mut a| {
                     if a.icon.is_none() {
@@ -146,6 +223,12 @@ impl AppData {
 
             if let Some(variables) = alias.variables {
                 self.vars.extend(variables);
+                self.transforms.insert(Transforms::ALIAS_VARIABLES);
+            }
+
+            if let Some(policy) = alias.match_policy {
+                self.match_policy = policy;
+                self.transforms.insert(Transforms::ALIAS_MATCH);
             }
         } else {
             let name: Option<&str> = self
@@ -160,9 +243,10 @@ impl AppData {
         match &launcher.launcher_type {
             LauncherType::Web(web) => Some(format!("websearch-{}", web.engine)),
 
-            LauncherType::App(_) | LauncherType::Command(_) | LauncherType::Category(_) => {
-                self.exec.clone()
-            }
+            LauncherType::App(_)
+            | LauncherType::Command(_)
+            | LauncherType::Category(_)
+            | LauncherType::Ssh(_) => self.exec.clone(),
 
             // None-Home Launchers
             LauncherType::Calc(_) => None,
@@ -181,27 +265,200 @@ pub struct SherlockAlias {
     pub actions: Option<Vec<ApplicationAction>>,
     pub add_actions: Option<Vec<ApplicationAction>>,
     pub variables: Option<Vec<ExecVariable>>,
+    #[serde(rename = "match")]
+    pub match_policy: Option<MatchPolicy>,
+}
+
+/// How a query has to match an entry's search string for the entry to show up
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MatchPolicy {
+    /// The query is a subsequence of the search string
+    #[default]
+    Fuzzy,
+    /// The query starts one of the `;` separated search elements
+    Prefix,
+    /// The query equals one of the search elements
+    Exact,
+    /// The query is contained in a search element at word boundaries
+    Word,
+}
+impl MatchPolicy {
+    /// Returns true if an entry that doesn't match a query can't match a longer query starting
+    /// with it either. `Exact` and `Word` matches can start to match as the query grows.
+    pub fn narrows(self) -> bool {
+        matches!(self, Self::Fuzzy | Self::Prefix)
+    }
 }
 
 #[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ExecVariable {
-    StringInput(SharedString),
-    PasswordInput(SharedString),
+    StringInput(VariableSpec),
+    PasswordInput(VariableSpec),
 }
 impl ExecVariable {
-    pub fn placeholder(&self) -> SharedString {
+    pub fn spec(&self) -> &VariableSpec {
         match self {
-            Self::StringInput(s) => s.clone(),
-            Self::PasswordInput(s) => s.clone(),
+            Self::StringInput(spec) | Self::PasswordInput(spec) => spec,
+        }
+    }
+    pub fn placeholder(&self) -> SharedString {
+        self.spec().placeholder.clone()
+    }
+    /// The values of a `choice` variable, picked with a selector instead of typed
+    pub fn choices(&self) -> Option<&[SharedString]> {
+        match &self.spec().kind {
+            VariableKind::Choice(choices) => Some(choices.as_slice()),
+            _ => None,
+        }
+    }
+}
+
+/// The input bar of a variable. Written as its placeholder alone, e.g. `{"string_input": "Port"}`,
+/// or as `{"string_input": {"placeholder": "Port", "kind": "int", "regex": "[0-9]{2,5}"}}`.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "RawVariableSpec")]
+pub struct VariableSpec {
+    pub placeholder: SharedString,
+    pub kind: VariableKind,
+    /// Filled in when no value was submitted before
+    pub default: Option<SharedString>,
+    /// Has to match the whole content
+    pub regex: Option<VariablePattern>,
+}
+impl VariableSpec {
+    /// Returns true if `content` may be passed to the command. Empty inputs always are, they
+    /// leave optional variables out, see `{prefix[..]:..}`.
+    pub fn validate(&self, content: &str) -> bool {
+        if content.is_empty() {
+            return true;
+        }
+        let valid = match &self.kind {
+            VariableKind::Text => true,
+            VariableKind::Int => content.parse::<i64>().is_ok(),
+            VariableKind::Float => content.parse::<f64>().is_ok_and(f64::is_finite),
+            VariableKind::Path => expand_home(content).exists(),
+            VariableKind::Choice(choices) => choices.iter().any(|choice| &**choice == content),
+        };
+        valid
+            && self
+                .regex
+                .as_ref()
+                .is_none_or(|regex| regex.0.is_match(content))
+    }
+    /// `content` as it is put into the exec string. Choices come from the config and are put in
+    /// as written, anything typed reaches the command as literal text.
+    pub fn substitute(&self, content: &str) -> Substitution {
+        match &self.kind {
+            VariableKind::Choice(_) => Substitution::Verbatim(content.to_string().into()),
+            VariableKind::Path if !content.is_empty() => {
+                Substitution::Literal(expand_home(content).to_string_lossy().into_owned().into())
+            }
+            _ => Substitution::Literal(content.to_string().into()),
         }
     }
 }
 
+fn expand_home(path: &str) -> PathBuf {
+    match home_dir() {
+        Ok(home) => expand_path(path, &home),
+        Err(_) => PathBuf::from(path),
+    }
+}
+
+/// What a variable input accepts
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VariableKind {
+    #[default]
+    Text,
+    Int,
+    Float,
+    /// A file or folder that exists, `~` is expanded
+    Path,
+    /// One of the listed values, written as `{"choice": ["a", "b"]}`
+    Choice(Vec<SharedString>),
+}
+
+/// The `regex` of a [`VariableSpec`], anchored to match the whole content
+#[derive(Clone, Debug)]
+pub struct VariablePattern(Regex, String);
+impl PartialEq for VariablePattern {
+    fn eq(&self, other: &Self) -> bool {
+        self.1 == other.1
+    }
+}
+impl Serialize for VariablePattern {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.1)
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum RawVariableSpec {
+    Placeholder(SharedString),
+    Full {
+        placeholder: SharedString,
+        #[serde(default)]
+        kind: VariableKind,
+        default: Option<SharedString>,
+        regex: Option<String>,
+    },
+}
+impl TryFrom<RawVariableSpec> for VariableSpec {
+    type Error = String;
+
+    fn try_from(raw: RawVariableSpec) -> Result<Self, Self::Error> {
+        let (placeholder, kind, default, regex) = match raw {
+            RawVariableSpec::Placeholder(placeholder) => {
+                (placeholder, VariableKind::Text, None, None)
+            }
+            RawVariableSpec::Full {
+                placeholder,
+                kind,
+                default,
+                regex,
+            } => (placeholder, kind, default, regex),
+        };
+        if let VariableKind::Choice(choices) = &kind {
+            if choices.is_empty() {
+                return Err(format!("variable \"{placeholder}\" has no choices"));
+            }
+            if default
+                .as_ref()
+                .is_some_and(|default| !choices.contains(default))
+            {
+                return Err(format!(
+                    "the default of variable \"{placeholder}\" is no choice"
+                ));
+            }
+        }
+        let regex = regex
+            .map(|source| {
+                Regex::new(&format!("^(?:{source})$"))
+                    .map(|regex| VariablePattern(regex, source))
+                    .map_err(|e| format!("invalid regex of variable \"{placeholder}\": {e}"))
+            })
+            .transpose()?;
+        Ok(Self {
+            placeholder,
+            kind,
+            default,
+            regex,
+        })
+    }
+}
+
 fn default_true() -> bool {
     true
 }
 
+fn default_async_timeout() -> u64 {
+    3000
+}
+
 #[derive(Deserialize, Debug, Serialize)]
 pub struct RawLauncher {
     pub name: Option<String>,
@@ -219,7 +476,11 @@ pub struct RawLauncher {
     #[serde(default = "default_true")]
     pub spawn_focus: bool,
     #[serde(default)]
+    pub prefer_raise: bool,
+    #[serde(default)]
     pub r#async: bool,
+    #[serde(default = "default_async_timeout")]
+    pub async_timeout_ms: u64,
     #[serde(default)]
     pub home: HomeType,
     #[serde(default)]
@@ -230,10 +491,16 @@ pub struct RawLauncher {
     pub add_actions: Option<Vec<ApplicationAction>>,
     #[serde(default)]
     pub variables: Option<Vec<ExecVariable>>,
+    #[serde(default, deserialize_with = "deserialize_tag_color")]
+    pub accent: Option<TagColor>,
 }
 
 pub struct CounterReader {
     pub path: PathBuf,
+    /// Recent launch times, see [`Launches`](super::frecency::Launches)
+    pub launches_path: PathBuf,
+    /// Submitted variable values, see [`VariableHistory`](super::variable_history::VariableHistory)
+    pub variables_path: PathBuf,
 }
 impl CounterReader {
     pub fn new() -> Result<Self, SherlockError> {
@@ -247,7 +514,11 @@ impl CounterReader {
                 )
             })?;
         }
-        Ok(CounterReader { path })
+        Ok(CounterReader {
+            launches_path: data_dir.join("launches.bin"),
+            variables_path: data_dir.join("variables.bin"),
+            path,
+        })
     }
     pub fn increment(&self, key: &str) -> Result<(), SherlockError> {
         let mut content: HashMap<String, u32> = BinaryCache::read(&self.path)?;
@@ -268,7 +539,7 @@ impl CounterReader {
 
         *content.entry(key.to_string()).or_insert(0) += 1;
         BinaryCache::write(&self.path, &content)?;
-        Ok(())
+        frecency::record(&self.launches_path, key, frecency::now())
     }
 }
 
@@ -298,8 +569,10 @@ where
     deserializer.deserialize_map(AppDataMapVisitor)
 }
 
+/// String the filter matches queries against, normalized by [`normalize_search`] like the
+/// query, so no child needs to be normalized again while filtering.
 pub fn construct_search(name: Option<&str>, search_str: &str, use_keywords: bool) -> String {
-    let mut s = if use_keywords {
+    let s = if use_keywords {
         let name_val = name.unwrap_or("");
         let mut s = String::with_capacity(name_val.len() + 1 + search_str.len());
         s.push_str(name_val);
@@ -310,6 +583,208 @@ pub fn construct_search(name: Option<&str>, search_str: &str, use_keywords: bool
         name.unwrap_or_default().to_string()
     };
 
-    s.make_ascii_lowercase();
-    s
+    normalize_search(&s).into_owned()
+}
+
+static FOLD_DIACRITICS: AtomicBool = AtomicBool::new(true);
+
+/// Sets whether [`normalize_search`] drops diacritics, see `search.fold_diacritics`. Children
+/// built before keep their search strings, so this is set before the launchers are loaded.
+pub fn set_fold_diacritics(enabled: bool) {
+    FOLD_DIACRITICS.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether [`normalize_search`] currently drops diacritics
+pub fn folds_diacritics() -> bool {
+    FOLD_DIACRITICS.load(Ordering::Relaxed)
+}
+
+/// Decomposes `text` (NFKD) and lowercases it, so that e.g. "Ö" matches "ö" and the ligature
+/// "ﬁ" matches "fi". Diacritics are dropped unless disabled with [`set_fold_diacritics`], so
+/// "cafe" matches "Café" as well. ASCII text is only lowercased and borrowed if it already is.
+pub fn normalize_search(text: &str) -> Cow<'_, str> {
+    if text.is_ascii() {
+        if text.bytes().any(|b| b.is_ascii_uppercase()) {
+            Cow::Owned(text.to_ascii_lowercase())
+        } else {
+            Cow::Borrowed(text)
+        }
+    } else {
+        Cow::Owned(decompose(text, folds_diacritics()))
+    }
+}
+
+fn decompose(text: &str, fold: bool) -> String {
+    // lowercasing can add marks, e.g. the dot of "İ", so they are dropped afterwards
+    text.nfkd()
+        .flat_map(char::to_lowercase)
+        .filter(|&c| !(fold && is_diacritic(c)))
+        .collect()
+}
+
+/// Combining marks of latin, greek and cyrillic letters like the accent of "é". Marks of other
+/// scripts, e.g. the dakuten of "が", tell words apart and are kept.
+fn is_diacritic(c: char) -> bool {
+    matches!(c, '\u{0300}'..='\u{036F}')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::children::SherlockSearch;
+
+    /// Whether `text` is already normalized by [`normalize_search`], the invariant of every
+    /// search string of a [`RenderableChild`](crate::launcher::children::RenderableChild)
+    fn is_normalized(text: &str) -> bool {
+        normalize_search(text) == text
+    }
+
+    #[test]
+    fn test_normalize_search() {
+        assert!(matches!(
+            normalize_search("firefox"),
+            Cow::Borrowed("firefox")
+        ));
+        assert_eq!(normalize_search("FireFox"), "firefox");
+        // composed and decomposed umlauts compare equal
+        assert_eq!(
+            normalize_search("Ölbild"),
+            normalize_search("o\u{308}lbild")
+        );
+        assert_eq!(normalize_search("ﬁle"), "file");
+
+        let cases = [
+            ("Ölbild Betrachter", "ölb", true),
+            ("Ölbild Betrachter", "ÖLBILD", true),
+            ("Straßenkarte", "straße", true),
+            ("Übersetzer", "ÜBER", true),
+            ("Текстовый редактор", "ТЕКСТ", true),
+            ("Текстовый редактор", "ред", true),
+            ("Текстовый редактор", "тя", false),
+            ("文本编辑器", "编辑", true),
+            ("文本编辑器", "本器", true),
+            ("文本编辑器", "辑本", false),
+        ];
+        for (name, query, expected) in cases {
+            let search = construct_search(Some(name), "", false);
+            assert_eq!(
+                search.fuzzy_match(&normalize_search(query), 16),
+                expected,
+                "{query:?} in {name:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_fold_diacritics() {
+        assert_eq!(decompose("Café", true), "cafe");
+        assert_eq!(decompose("Übersetzer", true), "ubersetzer");
+        assert_eq!(decompose("Ελληνικά", true), "ελληνικα");
+        assert_eq!(decompose("が", true), "か\u{3099}");
+        // exact matching keeps the marks, decomposed
+        assert_eq!(decompose("Café", false), "cafe\u{301}");
+        assert_eq!(decompose("ﬁlé", false), "file\u{301}");
+
+        let search = decompose("Café Crème", true);
+        assert!(search.fuzzy_match("cafe creme", 5));
+        assert!(search.fuzzy_match(&decompose("CAFÉ", true), 5));
+        assert!(!decompose("Café Crème", false).fuzzy_match("cafe creme", 1));
+        assert_eq!(decompose("İstanbul", true), "istanbul");
+    }
+
+    #[test]
+    fn test_normalize_search_is_stable() {
+        for text in [
+            "firefox",
+            "FireFox",
+            "Café",
+            "İstanbul",
+            "ﬁle",
+            "Straße",
+            "が",
+            "Ω",
+        ] {
+            let search = construct_search(Some(text), "Keyword;Ölbild", true);
+            assert!(is_normalized(&search), "{text:?} -> {search:?}");
+        }
+        assert!(!is_normalized("Firefox"));
+        assert!(!is_normalized("café"));
+
+        // written by a version that only lowercased
+        let mut cached = AppData::new();
+        cached.search_string = String::from("café;ölbild");
+        cached.normalize_cached_search();
+        assert_eq!(cached.search_string, "cafe;olbild");
+    }
+
+    #[test]
+    fn test_named_appdata_confirm() {
+        use serde::de::IntoDeserializer;
+
+        let commands = serde_json::json!({
+            "Shutdown": {
+                "exec": "systemctl poweroff",
+                "search_string": "shutdown;power off",
+                "confirm": true
+            },
+            "Lock Screen": {
+                "exec": "loginctl lock-session",
+                "search_string": "lock screen"
+            }
+        });
+        let apps = deserialize_named_appdata(commands.into_deserializer()).unwrap();
+        let confirm = |name: &str| {
+            apps.iter()
+                .find(|app| app.name.as_deref() == Some(name))
+                .is_some_and(|app| app.confirm)
+        };
+        assert!(confirm("Shutdown"));
+        assert!(!confirm("Lock Screen"));
+    }
+
+    #[test]
+    fn test_exec_variables() {
+        let vars: Vec<ExecVariable> = serde_json::from_value(serde_json::json!([
+            {"string_input": "Name"},
+            {"string_input": {"placeholder": "Port", "kind": "int", "regex": "[0-9]{2,5}"}},
+            {"string_input": {"placeholder": "Scale", "kind": "float"}},
+            {"password_input": {"placeholder": "Mode", "kind": {"choice": ["fast", "safe mode"]}, "default": "safe mode"}},
+        ]))
+        .unwrap();
+        let valid = |i: usize, content: &str| vars[i].spec().validate(content);
+
+        assert_eq!(&*vars[0].placeholder(), "Name");
+        assert!(valid(0, "anything; at all"));
+        assert!(valid(1, "8080"));
+        assert!(!valid(1, "8"));
+        assert!(!valid(1, "80a"));
+        assert!(!valid(1, "123456"));
+        assert!(valid(2, "1.5"));
+        assert!(!valid(2, "NaN"));
+        assert!(valid(3, "safe mode"));
+        assert!(!valid(3, "slow"));
+        // optional variables are left empty
+        assert!(vars.iter().all(|var| var.spec().validate("")));
+
+        assert_eq!(
+            vars[0].spec().substitute("a b"),
+            Substitution::Literal("a b".into())
+        );
+        assert_eq!(
+            vars[3].spec().substitute("safe mode"),
+            Substitution::Verbatim("safe mode".into())
+        );
+        assert_eq!(vars[3].choices().map(<[_]>::len), Some(2));
+        assert_eq!(vars[3].spec().default.as_deref(), Some("safe mode"));
+
+        let invalid = [
+            serde_json::json!({"string_input": {"placeholder": "X", "regex": "("}}),
+            serde_json::json!({"string_input": {"placeholder": "X", "kind": {"choice": []}}}),
+            serde_json::json!({"string_input": {"placeholder": "X", "kind": "number"}}),
+            serde_json::json!({"string_input": {"placeholder": "X", "kind": {"choice": ["a"]}, "default": "b"}}),
+        ];
+        for var in invalid {
+            assert!(serde_json::from_value::<ExecVariable>(var).is_err());
+        }
+    }
 }
@@ -0,0 +1,98 @@
+use std::{collections::HashMap, path::Path};
+
+use gpui::SharedString;
+
+use crate::{
+    loader::utils::ExecVariable,
+    utils::{cache::BinaryCache, errors::SherlockError},
+};
+
+/// Values kept per variable
+const MAX_VALUES: usize = 10;
+
+/// Values submitted to the variable inputs, most recent first, keyed by the launcher name and the
+/// placeholder of the variable. Stored next to the counts in `variables.bin`.
+pub type VariableHistory = HashMap<(String, String), Vec<String>>;
+
+pub fn read(path: &Path) -> VariableHistory {
+    BinaryCache::read(path).unwrap_or_default()
+}
+
+/// Moves `value` to the front of the values of `key`, dropping values beyond [`MAX_VALUES`]
+pub fn push(history: &mut VariableHistory, key: (String, String), value: String) {
+    let values = history.entry(key).or_default();
+    values.retain(|v| *v != value);
+    values.insert(0, value);
+    values.truncate(MAX_VALUES);
+}
+
+/// Adds the submitted values to the history at `path`
+pub fn record(
+    path: &Path,
+    submitted: impl IntoIterator<Item = ((String, String), String)>,
+) -> Result<(), SherlockError> {
+    let mut history = read(path);
+    for (key, value) in submitted {
+        push(&mut history, key, value);
+    }
+    BinaryCache::write(path, &history)
+}
+
+/// The values submitted to `variable` of `launcher` before that it still accepts, most recent
+/// first. Password inputs are never stored.
+pub fn recall(
+    history: &VariableHistory,
+    launcher: &str,
+    variable: &ExecVariable,
+) -> Vec<SharedString> {
+    let ExecVariable::StringInput(spec) = variable else {
+        return Vec::new();
+    };
+    history
+        .get(&(launcher.to_string(), spec.placeholder.to_string()))
+        .into_iter()
+        .flatten()
+        .filter(|value| spec.validate(value))
+        .map(|value| SharedString::from(value.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_recall() {
+        let key = |variable: &str| (String::from("VMs"), String::from(variable));
+        let mut history = VariableHistory::new();
+        for value in ["alpha", "beta", "alpha", "8080"] {
+            push(&mut history, key("Name"), value.to_string());
+        }
+        for i in 0..MAX_VALUES + 5 {
+            push(&mut history, key("Port"), i.to_string());
+        }
+        push(&mut history, key("Port"), String::from("not a port"));
+
+        let var =
+            |json: serde_json::Value| -> ExecVariable { serde_json::from_value(json).unwrap() };
+        let name = var(serde_json::json!({"string_input": "Name"}));
+        let port = var(serde_json::json!({"string_input": {"placeholder": "Port", "kind": "int"}}));
+        let password = var(serde_json::json!({"password_input": "Name"}));
+
+        let recall = |launcher: &str, variable: &ExecVariable| -> Vec<String> {
+            recall(&history, launcher, variable)
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        // the most recent value first, each value once
+        assert_eq!(recall("VMs", &name), ["8080", "alpha", "beta"]);
+        // values the variable doesn't accept anymore are skipped
+        let ports = recall("VMs", &port);
+        assert_eq!(ports.len(), MAX_VALUES - 1);
+        assert_eq!(ports[0], "14");
+        assert!(recall("VMs", &password).is_empty());
+        assert!(recall("Other", &name).is_empty());
+    }
+}
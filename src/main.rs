@@ -1,11 +1,18 @@
-use futures::future::join_all;
+use futures::{StreamExt, channel::mpsc::UnboundedSender};
 use once_cell::sync::OnceCell;
 use std::{
-    collections::HashMap,
+    collections::HashSet,
     io::Write,
-    sync::{Arc, OnceLock, RwLock},
+    sync::{
+        Arc, OnceLock, RwLock,
+        atomic::{AtomicU64, Ordering},
+    },
+    time::Duration,
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{UnixListener, UnixStream},
 };
-use tokio::net::UnixListener;
 
 use gpui::{
     layer_shell::{Layer, LayerShellOptions},
@@ -13,16 +20,25 @@ use gpui::{
 };
 
 use crate::{
-    launcher::children::{LauncherValues, RenderableChild},
-    loader::{CustomIconTheme, IconThemeGuard, Loader, assets::Assets},
-    ui::{
-        UIFunction,
-        main_window::{LauncherMode, NextVar, OpenContext, PrevVar},
-        search_bar::{EmptyBackspace, ShortcutAction},
+    launcher::{
+        app_launcher,
+        children::{AsyncRefresh, LauncherValues, RenderableChild},
+        dmenu_launcher::{self, DmenuLauncher},
+        provenance::Provenance,
+        utils::MprisData,
     },
+    loader::{CustomIconTheme, IconThemeGuard, Loader, assets::Assets, utils::set_fold_diacritics},
+    ui::{keymap::Keymap, main_window::LauncherMode, search_bar::EmptyBackspace},
     utils::{
-        config::{ConfigGuard, SherlockConfig},
+        accent::Accent,
+        config::{self, ConfigGuard, Reopen, ReopenAction, SherlockConfig},
         errors::SherlockErrorType,
+        first_run,
+        palette::Palette,
+        paths, sleep,
+        socket::{self, Instance, ModeEntry, SocketCommand},
+        soft_delete,
+        stats::Stats,
     },
 };
 
@@ -32,43 +48,74 @@ mod prelude;
 mod ui;
 mod utils;
 
-use ui::main_window::{Execute, FocusNext, FocusPrev, Quit, SherlockMainWindow};
-use ui::search_bar::{
-    Backspace, Copy, Cut, Delete, DeleteAll, End, Home, Left, Paste, Right, SelectAll, TextInput,
-};
+use ui::main_window::{ResultCache, SherlockMainWindow, max_height};
+use ui::search_bar::{EditHistory, TextInput};
 
 use utils::errors::SherlockError;
 
 static ICONS: OnceCell<RwLock<CustomIconTheme>> = OnceCell::new();
 static CONFIG: OnceCell<RwLock<SherlockConfig>> = OnceCell::new();
 
-static CONTEXT_MENU_BIND: OnceLock<String> = OnceLock::new();
+static KEYMAP: OnceLock<Keymap> = OnceLock::new();
+
+/// Interval in which a visible music tile refreshes its playback progress
+const PROGRESS_TICK: Duration = Duration::from_secs(1);
+/// How long a client of the socket may take to send its command
+const READ_TIMEOUT: Duration = Duration::from_millis(500);
 
 fn setup() -> Result<(), SherlockError> {
     let mut flags = Loader::load_flags()?;
+    config::remember_flags(&flags);
+
+    // a fresh install gets commented starter files instead of an empty launcher
+    let mut starter_files = Vec::new();
+    if flags.config.is_none() && flags.fallback.is_none() {
+        let dir = match flags.config_dir.clone() {
+            Some(dir) => dir,
+            None => paths::get_config_dir()?,
+        };
+        match first_run::write_starter_files(&dir) {
+            Ok(written) => starter_files = written,
+            Err(e) => e.report(),
+        }
+        for path in &starter_files {
+            println!("Created starter file {}", path.display());
+        }
+    }
 
-    let config = flags.to_config().map_or_else(
+    let mut config = flags.to_config().map_or_else(
         |e| {
-            eprintln!("{e}");
+            e.report();
             let defaults = SherlockConfig::default();
             SherlockConfig::apply_flags(&mut flags, defaults)
         },
         |(cfg, non_crit)| {
-            if !non_crit.is_empty() {
-                eprintln!("{:?}", non_crit);
-            }
+            non_crit.into_iter().for_each(SherlockError::report);
             cfg
         },
     );
+    config.runtime.first_run = !starter_files.is_empty();
 
     // Load custom icons
     let _ = ICONS.set(RwLock::new(CustomIconTheme::new()));
     config.appearance.icon_paths.iter().for_each(|path| {
         if let Err(e) = IconThemeGuard::add_path(path) {
-            eprintln!("{:?}", e);
+            e.report();
         }
     });
 
+    // Resolve key bindings
+    let (keymap, errors) = Keymap::from_config(&config);
+    errors.into_iter().for_each(SherlockError::report);
+    if flags.keymap {
+        keymap.print();
+        std::process::exit(0);
+    }
+    let _ = KEYMAP.set(keymap);
+
+    // Search strings of the launchers are normalized while loading
+    set_fold_diacritics(config.search.fold_diacritics);
+
     // Create global config
     CONFIG
         .set(RwLock::new(config.clone()))
@@ -79,69 +126,49 @@ fn setup() -> Result<(), SherlockError> {
 
 #[tokio::main]
 async fn main() {
-    // connect to existing socket
-    let socket_path = "/tmp/sherlock.sock";
-    if let Ok(mut stream) = std::os::unix::net::UnixStream::connect(socket_path) {
-        let _ = stream.write_all(b"open");
+    if let Err(e) = setup() {
+        eprintln!("{e}");
+    }
+
+    let runtime = ConfigGuard::read()
+        .map(|c| c.runtime.clone())
+        .unwrap_or_default();
+    // dmenu mode prints to its own stdout, so it never hands over to a running instance
+    if runtime.dmenu {
+        run_dmenu(runtime.print_index);
         return;
     }
 
-    if let Err(e) = setup() {
-        eprintln!("{e}");
+    // connect to existing socket, the primary instance binds the same path below
+    let socket_path = paths::get_socket_path();
+    match socket::probe_instance(&socket_path) {
+        Instance::Live(mut stream) => {
+            let command = match runtime.sub_menu {
+                _ if runtime.toggle => String::from("toggle"),
+                Some(mode) => format!("open {mode}"),
+                None => String::from("open"),
+            };
+            let _ = stream.write_all(command.as_bytes());
+            return;
+        }
+        // a second instance next to a hung one would leave two daemons on the system
+        Instance::Unresponsive => {
+            eprintln!(
+                "Sherlock is running but does not answer on {:?}",
+                socket_path
+            );
+            return;
+        }
+        Instance::Stale => {}
     }
 
     // start primary instance
     let app = Application::new().with_assets(Assets);
-    app.with_quit_mode(QuitMode::Explicit).run(|cx: &mut App| {
-        let mut final_bindings: HashMap<String, KeyBinding> = HashMap::new();
-
-        let mut add_binding = |key: &str, binding: KeyBinding| {
-            final_bindings.insert(key.to_string(), binding);
-        };
-
-        // default binds
-        add_binding("backspace", KeyBinding::new("backspace", Backspace, None));
-        add_binding("delete", KeyBinding::new("delete", Delete, None));
-        add_binding(
-            "ctrl-backspace",
-            KeyBinding::new("ctrl-backspace", DeleteAll, None),
-        );
-        add_binding("ctrl-a", KeyBinding::new("ctrl-a", SelectAll, None));
-        add_binding("ctrl-v", KeyBinding::new("ctrl-v", Paste, None));
-        add_binding("ctrl-c", KeyBinding::new("ctrl-c", Copy, None));
-        add_binding("ctrl-x", KeyBinding::new("ctrl-x", Cut, None));
-        add_binding("escape", KeyBinding::new("escape", Quit, None));
-
-        add_binding("home", KeyBinding::new("home", Home, None));
-        add_binding("end", KeyBinding::new("end", End, None));
-        add_binding("left", KeyBinding::new("left", Left, None));
-        add_binding("right", KeyBinding::new("right", Right, None));
-        add_binding("down", KeyBinding::new("down", FocusNext, None));
-        add_binding("up", KeyBinding::new("up", FocusPrev, None));
-        add_binding("enter", KeyBinding::new("enter", Execute, None));
-        add_binding("tab", KeyBinding::new("tab", NextVar, None));
-        add_binding("shift-tab", KeyBinding::new("shift-tab", PrevVar, None));
-        add_binding("ctrl-l", KeyBinding::new("ctrl-l", OpenContext, None));
-
-        if let Ok(config) = ConfigGuard::read() {
-            for (key, action_type) in &config.keybinds {
-                if *action_type == UIFunction::Shortcut && key.contains("<digit>") {
-                    for i in 0..=9 {
-                        let actual_key = key.replace("<digit>", &i.to_string());
-                        add_binding(
-                            &actual_key,
-                            KeyBinding::new(&actual_key, ShortcutAction { index: i }, None),
-                        );
-                    }
-                } else if let Some(binding) = action_type.into_bind(key) {
-                    add_binding(key, binding);
-                }
-            }
+    app.with_quit_mode(QuitMode::Explicit).run(move |cx: &mut App| {
+        if let Some(keymap) = KEYMAP.get() {
+            cx.bind_keys(keymap.key_bindings());
         }
 
-        cx.bind_keys(final_bindings.into_values().collect::<Vec<_>>());
-
-        let socket_path = "/tmp/sherlock.sock";
         let data: Entity<Arc<Vec<RenderableChild>>> = cx.new(|_| Arc::new(Vec::new()));
         let modes = match Loader::load_launchers(cx, data.clone()) {
             Ok(modes) => modes,
@@ -151,88 +178,251 @@ async fn main() {
             }
         };
 
+        // the socket refused connections above, so any existing socket file was left behind by
+        // a crashed instance
+        if socket_path.exists() {
+            if let Err(e) = std::fs::remove_file(&socket_path) {
+                eprintln!("Failed to remove stale socket {:?}: {e}", socket_path);
+            }
+        }
+
         // listen for open requests
-        let _ = std::fs::remove_file(socket_path);
-        let listener = UnixListener::bind(socket_path).unwrap();
+        let listener = match UnixListener::bind(&socket_path) {
+            Ok(listener) => listener,
+            Err(e) => {
+                eprintln!("Failed to bind socket {:?}: {e}", socket_path);
+                return;
+            }
+        };
+
+        // changes to the config and fallback files apply to the next window
+        let (reload_tx, mut reload_rx) = futures::channel::mpsc::unbounded::<bool>();
+        config::spawn_config_watcher(reload_tx);
+
+        // the wallpaper is decoded off the main thread; windows use the built-in palette until
+        // the accent is resolved
+        std::thread::spawn(Accent::refresh);
+        // the palette toggle starts from the configured state
+        Palette::set_high_contrast(ConfigGuard::read().is_ok_and(|c| c.appearance.high_contrast));
+
+        // refresh time-sensitive children after the system wakes up
+        let (resume_tx, mut resume_rx) = futures::channel::mpsc::unbounded::<()>();
+        sleep::spawn_sleep_watcher(resume_tx);
+
+        // every connection is read in a task of its own, so a silent client can't hold up the
+        // socket loop
+        let (command_tx, mut command_rx) = futures::channel::mpsc::unbounded();
+        // reloads read the launchers in the background and hand them back to the socket loop
+        let (reloaded_tx, mut reloaded_rx) = futures::channel::mpsc::unbounded::<Reloaded>();
 
         cx.spawn(|cx: &mut AsyncApp| {
             let cx = cx.clone();
             async move {
+                let mut modes = modes;
                 let mut win: Option<WindowHandle<SherlockMainWindow>> = None;
-                let mut current_generation: u64 = 0;
-                let mut active_update_task: Option<gpui::Task<()>> = None;
+                let generation = Arc::new(AtomicU64::new(0));
+                let mut active_update_tasks: Vec<gpui::Task<()>> = Vec::new();
+                // the latest reload that was started and the latest one that was applied, i.e.
+                // the version of `data`
+                let mut started_reload = 0;
+                let applied_reload = Arc::new(AtomicU64::new(0));
                 loop {
-                    if let Ok((_stream, _)) = listener.accept().await {
-                        // to prevent never read warning while also dropping previous task
-                        if let Some(task) = active_update_task.take() {
-                            drop(task)
+                    tokio::select! {
+                        accepted = listener.accept() => {
+                            let Ok((stream, _)) = accepted else {
+                                eprintln!("Broken UNIX Socket.");
+                                continue;
+                            };
+                            tokio::spawn(read_command(stream, command_tx.clone()));
                         }
-
-                        current_generation += 1;
-                        let this_generation = current_generation;
-
-                        // Create new window
-                        let new_win_handle = cx.update(|cx| {
-                            if let Some(old_win) = win.take() {
-                                let _ = old_win.update(cx, |_, win, _| {
-                                    win.remove_window();
+                        Some((command, stream)) = command_rx.next() => {
+                            let (mode, query) = match command {
+                                SocketCommand::Stats => {
+                                    let stats = data.read_with(&cx, |items, _| {
+                                        Stats::collect(
+                                            RenderableChild::count_launchers(items),
+                                            items.len(),
+                                        )
+                                    });
+                                    let json = stats.map(|s| simd_json::to_string(&s));
+                                    if let Ok(Ok(json)) = json {
+                                        reply(stream, json);
+                                    }
+                                    continue;
+                                }
+                                SocketCommand::Sources => {
+                                    let sources = data.read_with(&cx, |items, _| {
+                                        (0..items.len())
+                                            .filter_map(|i| Provenance::of(items, i))
+                                            .collect::<Vec<_>>()
+                                    });
+                                    let json = sources.map(|s| simd_json::to_string(&s));
+                                    if let Ok(Ok(json)) = json {
+                                        reply(stream, json);
+                                    }
+                                    continue;
+                                }
+                                SocketCommand::Ping => {
+                                    reply(stream, b"pong");
+                                    continue;
+                                }
+                                SocketCommand::Modes => {
+                                    let json = simd_json::to_string(&ModeEntry::list(&modes));
+                                    if let Ok(json) = json {
+                                        reply(stream, json);
+                                    }
+                                    continue;
+                                }
+                                SocketCommand::Accent => {
+                                    tokio::task::spawn_blocking(Accent::refresh);
+                                    continue;
+                                }
+                                SocketCommand::ReloadIcons => {
+                                    let (cx_inner, data, win) = (cx.clone(), data.clone(), win);
+                                    let version = Arc::clone(&applied_reload);
+                                    cx.spawn(move |_cx: &mut AsyncApp| async move {
+                                        reload_icons(&cx_inner, &data, win.as_ref(), &version)
+                                            .await;
+                                    })
+                                    .detach();
+                                    continue;
+                                }
+                                SocketCommand::Reload => {
+                                    // the client gets the outcome, which lets scripts wait for
+                                    // their launchers to show up
+                                    started_reload += 1;
+                                    let client = Some(stream);
+                                    spawn_reload(started_reload, false, client, &reloaded_tx);
+                                    continue;
+                                }
+                                SocketCommand::Close => {
+                                    if let Some(old_win) = win.take() {
+                                        active_update_tasks.clear();
+                                        let _ = cx.update(|cx| close_window(cx, old_win));
+                                    }
+                                    continue;
+                                }
+                                SocketCommand::Toggle | SocketCommand::Open { mode: None } => {
+                                    let reopen = if command == SocketCommand::Toggle {
+                                        Reopen::Toggle
+                                    } else {
+                                        ConfigGuard::read()
+                                            .map(|config| config.behavior.reopen)
+                                            .unwrap_or_default()
+                                    };
+                                    // the window may have closed itself meanwhile
+                                    let open_win = win.filter(|open_win| {
+                                        cx.update(|cx| is_open(cx, open_win)).unwrap_or(false)
+                                    });
+                                    match (reopen.decide(open_win.is_some()), open_win) {
+                                        (ReopenAction::Focus, Some(open_win)) => {
+                                            let _ = cx.update(|cx| focus_window(cx, &open_win));
+                                            continue;
+                                        }
+                                        (ReopenAction::Close, Some(open_win)) => {
+                                            win = None;
+                                            active_update_tasks.clear();
+                                            let _ = cx.update(|cx| close_window(cx, open_win));
+                                            continue;
+                                        }
+                                        _ => (None, None),
+                                    }
+                                }
+                                SocketCommand::Query(text) => {
+                                    let typed = win.as_ref().and_then(|open_win| {
+                                        cx.update(|cx| set_query(cx, open_win, &text)).ok()
+                                    });
+                                    if typed == Some(true) {
+                                        continue;
+                                    }
+                                    (None, Some(text))
+                                }
+                                SocketCommand::Open { mode } => (mode, None),
+                            };
+
+                            // drop update tasks of the previous window
+                            active_update_tasks.clear();
+
+                            let this_generation = generation.fetch_add(1, Ordering::SeqCst) + 1;
+
+                            // Create new window
+                            let new_win_handle = cx.update(|cx| {
+                                if let Some(old_win) = win.take() {
+                                    close_window(cx, old_win);
+                                }
+
+                                let mode = mode.as_deref().map_or(LauncherMode::Home, |alias| {
+                                    LauncherMode::from_alias(alias, &modes).unwrap_or_else(|| {
+                                        eprintln!("Unknown mode \"{alias}\"");
+                                        LauncherMode::Home
+                                    })
                                 });
+                                let new_win =
+                                    spawn_launcher(cx, data.clone(), Arc::clone(&modes), mode);
+                                if let Some(text) = query.as_deref() {
+                                    set_query(cx, &new_win, text);
+                                }
+                                win = Some(new_win.clone());
+                                new_win
+                            });
+
+                            if let Ok(new_win) = new_win_handle {
+                                active_update_tasks = spawn_async_updates(
+                                    &cx,
+                                    &data,
+                                    new_win,
+                                    &generation,
+                                    this_generation,
+                                );
+                            }
+                        }
+                        Some(icons_changed) = reload_rx.next() => {
+                            started_reload += 1;
+                            spawn_reload(started_reload, icons_changed, None, &reloaded_tx);
+                        }
+                        Some(reloaded) = reloaded_rx.next() => {
+                            let Reloaded { ticket, launchers, client } = reloaded;
+                            let outcome = match launchers {
+                                // a later reload finished first, its launchers are newer
+                                Ok(_) if ticket < applied_reload.load(Ordering::SeqCst) => Ok(()),
+                                Ok(launchers) => {
+                                    applied_reload.store(ticket, Ordering::SeqCst);
+                                    modes = apply_reload(
+                                        &cx,
+                                        &data,
+                                        win,
+                                        &generation,
+                                        launchers,
+                                        &mut active_update_tasks,
+                                    );
+                                    Ok(())
+                                }
+                                Err(e) => Err(e),
+                            };
+                            if let Some(client) = client {
+                                let message = match &outcome {
+                                    Ok(()) => String::from("ok"),
+                                    Err(e) => e.to_string(),
+                                };
+                                reply(client, message);
+                            }
+                            if let Err(e) = outcome {
+                                e.report();
+                            }
+                        }
+                        Some(()) = resume_rx.next() => {
+                            // refresh immediately if a window is currently open
+                            if let Some(open_win) = win.clone() {
+                                let this_generation = generation.load(Ordering::SeqCst);
+                                active_update_tasks = spawn_async_updates(
+                                    &cx,
+                                    &data,
+                                    open_win,
+                                    &generation,
+                                    this_generation,
+                                );
                             }
-
-                            let new_win = spawn_launcher(cx, data.clone(), Arc::clone(&modes));
-                            win = Some(new_win.clone());
-                            new_win
-                        });
-
-                        // update content async
-                        if let Ok(new_win) = new_win_handle {
-                            let cx_inner = cx.clone();
-                            let data_clone = data.clone();
-
-                            active_update_task =
-                                Some(cx.spawn(move |_cx: &mut AsyncApp| async move {
-                                    let data_items = data_clone
-                                        .read_with(&cx_inner, |this, _| this.clone())
-                                        .ok();
-
-                                    if let Some(items) = data_items {
-                                        let update_futures = items
-                                            .iter()
-                                            .enumerate()
-                                            .filter(|(_, item)| item.is_async())
-                                            .map(|(idx, item)| async move {
-                                                (idx, item.clone().update_async().await)
-                                            });
-
-                                        let updates = join_all(update_futures).await;
-
-                                        let _ = cx_inner.update(|cx| {
-                                            if current_generation != this_generation {
-                                                return;
-                                            }
-
-                                            if !updates.is_empty() {
-                                                data_clone.update(cx, |items_arc, _cx| {
-                                                    let items_vec = Arc::make_mut(items_arc);
-                                                    for (idx, update) in updates
-                                                        .into_iter()
-                                                        .filter_map(|(i, u)| u.map(|v| (i, v)))
-                                                    {
-                                                        items_vec[idx] = update;
-                                                    }
-                                                });
-
-                                                let _ = new_win.update(cx, |view, _, cx| {
-                                                    view.last_query = None; // forces update
-                                                    view.filter_and_sort(cx);
-                                                });
-                                            }
-                                        });
-                                    }
-                                }));
                         }
-                    } else {
-                        eprintln!("Broken UNIX Socket.");
                     }
                 }
             }
@@ -241,18 +431,461 @@ async fn main() {
     });
 }
 
+/// Launchers read by a reload, see [`spawn_reload`]
+struct Reloaded {
+    /// Reloads are numbered in the order they were started
+    ticket: u64,
+    launchers: Result<(Vec<RenderableChild>, Arc<[LauncherMode]>), SherlockError>,
+    /// The client of a `reload` command, which is answered once the reload is done
+    client: Option<UnixStream>,
+}
+
+/// Reads the launchers again off the main thread and hands them to the socket loop, which
+/// applies them with [`apply_reload`]. A launcher file that cannot be loaded keeps the current
+/// launchers.
+fn spawn_reload(
+    ticket: u64,
+    icons_changed: bool,
+    client: Option<UnixStream>,
+    reloaded: &UnboundedSender<Reloaded>,
+) {
+    let reloaded = reloaded.clone();
+    tokio::spawn(async move {
+        // launchers are resolved against the icons, so those go first
+        if icons_changed {
+            rescan_icons().await;
+        }
+        let launchers = tokio::task::spawn_blocking(|| {
+            soft_delete::persist_all();
+            Loader::read_launchers()
+        })
+        .await;
+        match launchers {
+            Ok(launchers) => {
+                let _ = reloaded.unbounded_send(Reloaded {
+                    ticket,
+                    launchers,
+                    client,
+                });
+            }
+            Err(e) => eprintln!("Failed to reload launchers: {e}"),
+        }
+    });
+}
+
+/// Replaces the children with reloaded `launchers` and refreshes an open window with them in
+/// place. Returns the modes of the new launchers.
+///
+/// Each reload starts a new generation, so updates that are still running for the old data are
+/// dropped instead of patching the new one.
+fn apply_reload(
+    cx: &AsyncApp,
+    data: &Entity<Arc<Vec<RenderableChild>>>,
+    win: Option<WindowHandle<SherlockMainWindow>>,
+    generation: &Arc<AtomicU64>,
+    (children, modes): (Vec<RenderableChild>, Arc<[LauncherMode]>),
+    active_update_tasks: &mut Vec<gpui::Task<()>>,
+) -> Arc<[LauncherMode]> {
+    let _ = cx.update(|cx| {
+        data.update(cx, |items, cx| {
+            *items = Arc::new(children);
+            cx.notify();
+        })
+    });
+    app_launcher::clear_hidden();
+
+    // the indices of running updates point into the old data
+    active_update_tasks.clear();
+    let this_generation = generation.fetch_add(1, Ordering::SeqCst) + 1;
+    if let Some(open_win) = win {
+        let modes = Arc::clone(&modes);
+        let _ = cx.update(|cx| open_win.update(cx, |view, _, cx| view.show_reloaded(modes, cx)));
+        *active_update_tasks =
+            spawn_async_updates(cx, data, open_win, generation, this_generation);
+    }
+    modes
+}
+
+/// Reads the command of a connection and hands it to the socket loop together with the
+/// connection, which some commands reply on. Clients that don't send anything in time are
+/// dropped.
+async fn read_command(
+    mut stream: UnixStream,
+    commands: UnboundedSender<(SocketCommand, UnixStream)>,
+) {
+    let mut buf = [0u8; 1024];
+    let Ok(Ok(n)) = tokio::time::timeout(READ_TIMEOUT, stream.read(&mut buf)).await else {
+        return;
+    };
+    let message = String::from_utf8_lossy(&buf[..n]);
+    let Some(command) = SocketCommand::parse(&message) else {
+        eprintln!("Unknown socket command {:?}", message.trim());
+        return;
+    };
+    let _ = commands.unbounded_send((command, stream));
+}
+
+/// Writes `message` in a task of its own, so a client that doesn't read can't hold up the
+/// socket loop
+fn reply(mut stream: UnixStream, message: impl AsRef<[u8]> + Send + 'static) {
+    tokio::spawn(async move {
+        let _ = stream.write_all(message.as_ref()).await;
+    });
+}
+
+/// Removes `win` and reports whether it was still open
+fn close_window(cx: &mut App, win: WindowHandle<SherlockMainWindow>) -> bool {
+    soft_delete::persist_all();
+    win.update(cx, |_, win, _| win.remove_window()).is_ok()
+}
+
+fn is_open(cx: &mut App, win: &WindowHandle<SherlockMainWindow>) -> bool {
+    win.update(cx, |_, _, _| ()).is_ok()
+}
+
+/// Moves the keyboard focus to the search bar of `win`, activating Sherlock as configured
+fn focus_window(cx: &mut App, win: &WindowHandle<SherlockMainWindow>) -> bool {
+    let activation = ConfigGuard::read()
+        .map(|config| config.behavior.activation)
+        .unwrap_or_default();
+    win.update(cx, |view, window, cx| {
+        window.activate_window();
+        window.focus(&view.text_input.focus_handle(cx));
+        if let Some(ignoring_other_apps) = activation.ignoring_other_apps() {
+            cx.activate(ignoring_other_apps);
+        }
+    })
+    .is_ok()
+}
+
+/// Types `text` into the search bar of `win` and reports whether the window was still open
+fn set_query(cx: &mut App, win: &WindowHandle<SherlockMainWindow>, text: &str) -> bool {
+    win.update(cx, |view, _, cx| {
+        view.text_input.update(cx, |input, cx| {
+            input.set_content(text.to_string());
+            cx.notify();
+        });
+    })
+    .is_ok()
+}
+
+/// Scans the configured icon paths again, e.g. after they changed in the config
+async fn rescan_icons() {
+    let icon_paths = ConfigGuard::read()
+        .map(|config| config.appearance.icon_paths.clone())
+        .unwrap_or_default();
+    // scanning renders svgs, which is too slow for the main thread
+    match tokio::task::spawn_blocking(move || IconThemeGuard::reload(&icon_paths)).await {
+        Ok(Ok(())) => {}
+        Ok(Err(e)) => e.report(),
+        Err(e) => eprintln!("Failed to reload icons: {e}"),
+    }
+}
+
+/// Rescans the custom icon paths and resolves the icons of all children again, so icons added
+/// while the daemon runs show up without a restart. The icons are dropped if a reload replaced
+/// the children meanwhile, see `applied_reload` of the socket loop.
+async fn reload_icons(
+    cx: &AsyncApp,
+    data: &Entity<Arc<Vec<RenderableChild>>>,
+    win: Option<&WindowHandle<SherlockMainWindow>>,
+    applied_reload: &AtomicU64,
+) {
+    // the icons are patched in by index
+    let version = applied_reload.load(Ordering::SeqCst);
+    let Ok(names) = data.read_with(cx, |items, _| {
+        items
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, item)| {
+                let name = item.icon_name()?.to_string();
+                Some((idx, name, item.launcher().fallback_icon()))
+            })
+            .collect::<Vec<_>>()
+    }) else {
+        return;
+    };
+    let icon_paths = ConfigGuard::read()
+        .map(|config| config.appearance.icon_paths.clone())
+        .unwrap_or_default();
+
+    // scanning renders svgs, which is too slow for the main thread
+    let resolved = tokio::task::spawn_blocking(move || {
+        IconThemeGuard::reload(&icon_paths)?;
+        Ok::<_, SherlockError>(
+            names
+                .into_iter()
+                .map(|(idx, name, fallback)| {
+                    let icon = loader::resolve_icon_path(&name)
+                        .or_else(|| loader::resolve_icon_path(fallback.as_deref()?));
+                    (idx, icon)
+                })
+                .collect::<Vec<_>>(),
+        )
+    })
+    .await;
+    let resolved = match resolved {
+        Ok(Ok(resolved)) => resolved,
+        Ok(Err(e)) => {
+            eprintln!("{e}");
+            return;
+        }
+        Err(e) => {
+            eprintln!("Failed to reload icons: {e}");
+            return;
+        }
+    };
+
+    let _ = cx.update(|cx| {
+        if applied_reload.load(Ordering::SeqCst) != version {
+            return;
+        }
+        data.update(cx, |items, cx| {
+            let items = Arc::make_mut(items);
+            for (idx, icon) in resolved {
+                if let Some(item) = items.get_mut(idx) {
+                    item.set_icon_path(icon);
+                }
+            }
+            cx.notify();
+        });
+        if let Some(win) = win {
+            let _ = win.update(cx, |_, _, cx| cx.notify());
+        }
+    });
+}
+
+/// Refreshes all async children independently so a slow source does not hold back the others.
+/// Results are discarded if `generation` moved on, i.e. the window was replaced meanwhile.
+fn spawn_async_updates(
+    cx: &AsyncApp,
+    data: &Entity<Arc<Vec<RenderableChild>>>,
+    win: WindowHandle<SherlockMainWindow>,
+    generation: &Arc<AtomicU64>,
+    this_generation: u64,
+) -> Vec<gpui::Task<()>> {
+    let Ok(items) = data.read_with(cx, |this, _| this.clone()) else {
+        return Vec::new();
+    };
+
+    items
+        .iter()
+        .enumerate()
+        .filter(|(_, item)| item.is_async())
+        .map(|(idx, item)| {
+            let item = item.clone();
+            let cx_inner = cx.clone();
+            let data_clone = data.clone();
+            let win = win.clone();
+            let generation = Arc::clone(generation);
+
+            cx.spawn(move |_cx: &mut AsyncApp| async move {
+                let is_music = matches!(item, RenderableChild::MusicLike { .. });
+                // covers that failed to load aren't fetched again on every tick
+                let mut failed_covers = HashSet::new();
+                let refresh = item.refresh_async().await;
+                let pending_cover = match &refresh {
+                    AsyncRefresh::Updated(child) => child.pending_cover().map(str::to_string),
+                    _ => None,
+                };
+
+                let _ = cx_inner.update(|cx| {
+                    // discard late arrivals from a previous window
+                    if generation.load(Ordering::SeqCst) != this_generation {
+                        return;
+                    }
+
+                    apply_async_refresh(cx, &data_clone, &win, idx, refresh);
+                });
+
+                // second phase: patch in remote cover art once it is downloaded
+                if let Some(art_url) = pending_cover {
+                    let loaded = patch_cover(
+                        &cx_inner,
+                        &data_clone,
+                        &win,
+                        idx,
+                        &art_url,
+                        &generation,
+                        this_generation,
+                    )
+                    .await;
+                    if !loaded {
+                        failed_covers.insert(art_url);
+                    }
+                }
+                if !is_music {
+                    return;
+                }
+
+                // third phase: keep the progress bar moving while the music tile is visible
+                loop {
+                    cx_inner.background_executor().timer(PROGRESS_TICK).await;
+
+                    // stops once the window is closed or replaced
+                    let current = cx_inner.update(|cx| {
+                        if generation.load(Ordering::SeqCst) != this_generation {
+                            return None;
+                        }
+                        let visible = win.read(cx).ok()?.filtered_indices.contains(&idx);
+                        Some(
+                            visible
+                                .then(|| data_clone.read(cx).get(idx).cloned())
+                                .flatten(),
+                        )
+                    });
+                    let Ok(Some(current)) = current else {
+                        return;
+                    };
+                    let Some(current) = current else {
+                        continue;
+                    };
+                    let AsyncRefresh::Updated(child) = current.refresh_async().await else {
+                        continue;
+                    };
+
+                    // replaces the tile in place, filtering again would reset the selection
+                    let pending_cover = child
+                        .pending_cover()
+                        .filter(|art_url| !failed_covers.contains(*art_url))
+                        .map(str::to_string);
+                    let _ = cx_inner.update(|cx| {
+                        if generation.load(Ordering::SeqCst) != this_generation {
+                            return;
+                        }
+                        data_clone.update(cx, |items_arc, cx| {
+                            if let Some(item) = Arc::make_mut(items_arc).get_mut(idx) {
+                                *item = child;
+                            }
+                            cx.notify();
+                        });
+                        let _ = win.update(cx, |_, _, cx| cx.notify());
+                    });
+                    if let Some(art_url) = pending_cover {
+                        let loaded = patch_cover(
+                            &cx_inner,
+                            &data_clone,
+                            &win,
+                            idx,
+                            &art_url,
+                            &generation,
+                            this_generation,
+                        )
+                        .await;
+                        if !loaded {
+                            failed_covers.insert(art_url);
+                        }
+                    }
+                }
+            })
+        })
+        .collect()
+}
+
+/// Downloads the cover art of a music tile and patches it into the tile at `idx`. Returns
+/// whether the cover could be loaded.
+async fn patch_cover(
+    cx: &AsyncApp,
+    data: &Entity<Arc<Vec<RenderableChild>>>,
+    win: &WindowHandle<SherlockMainWindow>,
+    idx: usize,
+    art_url: &str,
+    generation: &AtomicU64,
+    this_generation: u64,
+) -> bool {
+    let Some((image, _)) = MprisData::fetch_cover(art_url).await else {
+        return false;
+    };
+
+    let _ = cx.update(|cx| {
+        if generation.load(Ordering::SeqCst) != this_generation {
+            return;
+        }
+
+        let patched = data.update(cx, |items_arc, cx| {
+            let patched = Arc::make_mut(items_arc)
+                .get_mut(idx)
+                .is_some_and(|item| item.patch_cover(art_url, image));
+            if patched {
+                cx.notify();
+            }
+            patched
+        });
+        if patched {
+            let _ = win.update(cx, |_, _, cx| cx.notify());
+        }
+    });
+    true
+}
+
+fn apply_async_refresh(
+    cx: &mut App,
+    data: &Entity<Arc<Vec<RenderableChild>>>,
+    win: &WindowHandle<SherlockMainWindow>,
+    idx: usize,
+    refresh: AsyncRefresh<RenderableChild>,
+) {
+    match refresh {
+        AsyncRefresh::Updated(update) => {
+            data.update(cx, |items_arc, cx| {
+                let items_vec = Arc::make_mut(items_arc);
+                if let Some(item) = items_vec.get_mut(idx) {
+                    *item = update;
+                }
+                cx.notify();
+            });
+
+            let _ = win.update(cx, |view, _, cx| {
+                view.stale_indices.remove(&idx);
+                view.last_query = None; // forces update
+                view.filter_and_sort(cx);
+            });
+        }
+        AsyncRefresh::TimedOut => {
+            let _ = win.update(cx, |view, _, cx| {
+                view.stale_indices.insert(idx);
+                cx.notify();
+            });
+        }
+        AsyncRefresh::Unchanged => {}
+    }
+}
+
+/// Shows the lines of stdin in a window of its own, see [`DmenuLauncher`]
+fn run_dmenu(print_index: bool) {
+    let children = DmenuLauncher { print_index }.children(std::io::stdin().lock());
+
+    let app = Application::new().with_assets(Assets);
+    app.with_quit_mode(QuitMode::Explicit)
+        .run(move |cx: &mut App| {
+            if let Some(keymap) = KEYMAP.get() {
+                cx.bind_keys(keymap.key_bindings());
+            }
+            Palette::set_high_contrast(
+                ConfigGuard::read().is_ok_and(|c| c.appearance.high_contrast),
+            );
+
+            let data: Entity<Arc<Vec<RenderableChild>>> = cx.new(|_| Arc::new(children));
+            spawn_launcher(cx, data, Arc::from([]), LauncherMode::Home);
+            cx.on_window_closed(|_| dmenu_launcher::exit()).detach();
+        });
+}
+
 fn spawn_launcher(
     cx: &mut App,
     data: Entity<Arc<Vec<RenderableChild>>>,
     modes: Arc<[LauncherMode]>,
+    mode: LauncherMode,
 ) -> WindowHandle<SherlockMainWindow> {
     // For now load application here
     let window = cx
-        .open_window(get_window_options(), |_, cx| {
+        .open_window(get_window_options(), |window, cx| {
             let text_input = cx.new(|cx| TextInput {
                 focus_handle: cx.focus_handle(),
                 content: "".into(),
-                placeholder: "Search:".into(),
+                placeholder: ConfigGuard::read()
+                    .map(|c| SharedString::from(c.appearance.placeholder.clone()))
+                    .unwrap_or_else(|_| "Search:".into()),
                 variable: None,
                 selected_range: 0..0,
                 selection_reversed: false,
@@ -260,6 +893,7 @@ fn spawn_launcher(
                 last_layout: None,
                 last_bounds: None,
                 is_selecting: false,
+                history: EditHistory::default(),
             });
             cx.new(|cx| {
                 let data_len = data.read(cx).len();
@@ -270,6 +904,17 @@ fn spawn_launcher(
                         this.filter_and_sort(cx);
                     },
                 );
+                // cached results point into the data they were filtered from. Async refreshes
+                // patch children in place, which keeps their indices, and force a new filter if
+                // needed; reloads clear the cache through `show_reloaded`.
+                let mut cached_len = data_len;
+                let data_sub = cx.observe(&data, move |this: &mut SherlockMainWindow, data, cx| {
+                    let len = data.read(cx).len();
+                    if len != cached_len {
+                        cached_len = len;
+                        this.result_cache.clear();
+                    }
+                });
                 let backspace_sub =
                     cx.subscribe(&text_input, |this, _, _ev: &EmptyBackspace, cx| {
                         if this.mode != LauncherMode::Home {
@@ -288,22 +933,40 @@ fn spawn_launcher(
                     text_input,
                     focus_handle: cx.focus_handle(),
                     list_state,
-                    _subs: vec![sub, backspace_sub],
+                    _subs: vec![sub, data_sub, backspace_sub],
                     selected_index: 0,
+                    columns: 1,
                     // modes
-                    mode: LauncherMode::Home,
+                    mode,
                     modes,
                     // context menu
                     context_idx: None,
                     context_actions: Arc::new([]),
                     // variable inputs
                     variable_input: Vec::new(),
+                    variable_recall: Vec::new(),
                     active_bar: 0,
                     // Data model
                     data,
                     deferred_render_task: None,
+                    lookup_task: None,
                     last_query: None,
+                    results_for: None,
                     filtered_indices: (0..data_len).collect(),
+                    narrowing: None,
+                    result_cache: ResultCache::default(),
+                    stale_indices: HashSet::new(),
+                    run_command: None,
+                    expanded: None,
+                    held_exec: None,
+                    pending_confirm: None,
+                    error_page: None,
+                    max_height: max_height(),
+                    fitted_height: None,
+                    window: window.window_handle(),
+                    scrollbar_drag: None,
+                    awaiting_spawn_focus: true,
+                    hovered: None,
                 };
                 view.filter_and_sort(cx);
 
@@ -312,19 +975,20 @@ fn spawn_launcher(
         })
         .unwrap();
 
-    window
-        .update(cx, |view, window, cx| {
-            window.focus(&view.text_input.focus_handle(cx));
-            cx.activate(true);
-        })
-        .unwrap();
-
+    focus_window(cx, &window);
     window
 }
 
 fn get_window_options() -> WindowOptions {
+    // windows fitted to their results start out at their largest
     let (width, height) = ConfigGuard::read()
-        .map(|c| (c.appearance.width, c.appearance.height))
+        .map(|c| {
+            let appearance = &c.appearance;
+            (
+                appearance.width,
+                appearance.max_height.unwrap_or(appearance.height),
+            )
+        })
         .unwrap_or((900i32, 600i32));
 
     WindowOptions {
@@ -337,7 +1001,7 @@ fn get_window_options() -> WindowOptions {
             origin: point(px(0.), px(0.)),
             size: Size::new(px(width as f32), px(height as f32)),
         })),
-        window_background: WindowBackgroundAppearance::Blurred,
+        window_background: Palette::current().window_background(),
         ..Default::default()
     }
 }
@@ -0,0 +1,66 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+use gpui::{
+    Animation, AnimationExt, AnyElement, IntoElement, ParentElement, Pixels, Styled, div, hsla,
+};
+
+use crate::utils::{config::ConfigGuard, palette::Palette};
+
+/// Duration of the fade-in of a freshly swapped image
+const FADE_DURATION: Duration = Duration::from_millis(250);
+/// Images replaced longer ago than this are shown without fading
+const FRESH_FOR: Duration = Duration::from_secs(1);
+
+static NEXT_GENERATION: AtomicU64 = AtomicU64::new(1);
+
+/// Remembers when an image was last replaced by an async update, so that the next render can
+/// fade it in instead of popping it in
+#[derive(Clone, Debug, Default)]
+pub struct ImageGeneration {
+    generation: u64,
+    changed_at: Option<Instant>,
+}
+
+impl ImageGeneration {
+    pub fn bump(&mut self) {
+        self.generation = NEXT_GENERATION.fetch_add(1, Ordering::Relaxed);
+        self.changed_at = Some(Instant::now());
+    }
+    fn is_fresh(&self) -> bool {
+        self.changed_at
+            .is_some_and(|changed| changed.elapsed() < FRESH_FOR)
+    }
+}
+
+/// Fades `element` in if its image was replaced just now. The fade is skipped if animations
+/// are disabled in the config.
+pub fn fade_in(element: impl IntoElement, generation: &ImageGeneration) -> AnyElement {
+    let animate = ConfigGuard::read()
+        .map(|config| config.behavior.animate)
+        .unwrap_or(true);
+    if !animate || !generation.is_fresh() {
+        return element.into_any_element();
+    }
+
+    div()
+        .child(element)
+        .with_animation(
+            ("image-fade", generation.generation),
+            Animation::new(FADE_DURATION),
+            |this, delta| this.opacity(delta),
+        )
+        .into_any_element()
+}
+
+/// Neutral box reserving the space of an image that has not been loaded yet
+pub fn placeholder(size: Pixels) -> AnyElement {
+    div()
+        .flex_none()
+        .size(size)
+        .rounded_md()
+        .bg(Palette::current().bg(hsla(0., 0., 1., 0.06)))
+        .into_any_element()
+}
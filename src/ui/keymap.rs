@@ -0,0 +1,351 @@
+use std::collections::HashMap;
+
+use gpui::KeyBinding;
+
+use crate::{
+    sherlock_error,
+    ui::{UIFunction, search_bar::ShortcutAction},
+    utils::{
+        config::{KeyOverride, SherlockConfig},
+        errors::{SherlockError, SherlockErrorType},
+    },
+};
+
+const DEFAULT_BINDS: &[(UIFunction, &str)] = &[
+    (UIFunction::Backspace, "backspace"),
+    (UIFunction::Delete, "delete"),
+    (UIFunction::ClearBar, "ctrl-u"),
+    (UIFunction::SelectAll, "ctrl-a"),
+    (UIFunction::Paste, "ctrl-v"),
+    (UIFunction::Copy, "ctrl-c"),
+    (UIFunction::Cut, "ctrl-x"),
+    (UIFunction::Exit, "escape"),
+    (UIFunction::CloseContext, "escape"),
+    (UIFunction::Home, "home"),
+    (UIFunction::End, "end"),
+    (UIFunction::CursorLeft, "left"),
+    (UIFunction::CursorRight, "right"),
+    (UIFunction::WordLeft, "ctrl-left"),
+    (UIFunction::WordRight, "ctrl-right"),
+    (UIFunction::SelectWordLeft, "ctrl-shift-left"),
+    (UIFunction::SelectWordRight, "ctrl-shift-right"),
+    (UIFunction::ItemDown, "down"),
+    (UIFunction::ItemUp, "up"),
+    (UIFunction::Exec, "enter"),
+    (UIFunction::ArgNext, "tab"),
+    (UIFunction::ArgPrev, "shift-tab"),
+    (UIFunction::ToggleContext, "ctrl-l"),
+    (UIFunction::Undo, "ctrl-z"),
+    (UIFunction::Redo, "ctrl-shift-z"),
+    (UIFunction::Redo, "ctrl-y"),
+    (UIFunction::ErrorPage, "ctrl-e"),
+];
+
+/// Modifier of the default [`UIFunction::Shortcut`] binding for `appearance.shortcut_mod`,
+/// which also accepts the symbols of `mod_key_ascii`
+fn shortcut_modifier(value: &str) -> Option<&'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "ctrl" | "control" | "⌘" | "⌃" => Some("ctrl"),
+        "alt" | "⎇" | "⌥" => Some("alt"),
+        "super" | "✦" => Some("super"),
+        "shift" | "⇧" => Some("shift"),
+        _ => None,
+    }
+}
+
+/// The effective key bindings, keyed by the function they trigger
+#[derive(Debug, Clone, Default)]
+pub struct Keymap {
+    binds: HashMap<UIFunction, Vec<String>>,
+}
+
+impl Keymap {
+    pub fn from_config(config: &SherlockConfig) -> (Self, Vec<SherlockError>) {
+        let mut errors = Vec::new();
+        let shortcut_mod = match shortcut_modifier(&config.appearance.shortcut_mod) {
+            Some(modifier) => modifier,
+            None => {
+                errors.push(sherlock_error!(
+                    SherlockErrorType::ConfigError(Some(format!(
+                        "Unknown shortcut_mod \"{}\", expected ctrl, alt, super or shift",
+                        config.appearance.shortcut_mod
+                    ))),
+                    ""
+                ));
+                "ctrl"
+            }
+        };
+        let (keymap, conflicts) = Self::new(&config.keymap, &config.keybinds, shortcut_mod);
+        errors.extend(conflicts);
+        (keymap, errors)
+    }
+
+    /// Builds the keymap from the defaults, the `keymap` overrides and the additional
+    /// `keybinds`. Results are run by their digit together with `shortcut_mod` unless
+    /// [`UIFunction::Shortcut`] is overridden.
+    ///
+    /// Entries in `keybinds` shadow any default binding on the same key. Keys that end up bound
+    /// to more than one function are returned as errors.
+    pub fn new(
+        overrides: &HashMap<UIFunction, KeyOverride>,
+        keybinds: &HashMap<String, UIFunction>,
+        shortcut_mod: &str,
+    ) -> (Self, Vec<SherlockError>) {
+        let mut binds: HashMap<UIFunction, Vec<String>> = HashMap::new();
+        for (func, key) in DEFAULT_BINDS {
+            binds.entry(*func).or_default().push(key.to_string());
+        }
+        binds.insert(
+            UIFunction::Shortcut,
+            vec![format!("{shortcut_mod}-<digit>")],
+        );
+
+        for (func, value) in overrides {
+            if let Some(key) = value.key() {
+                binds.insert(*func, vec![key.to_string()]);
+            } else if value.is_unbound() {
+                binds.remove(func);
+            }
+        }
+
+        for (key, func) in keybinds {
+            for (other, keys) in binds.iter_mut() {
+                if other != func && !overrides.contains_key(other) {
+                    keys.retain(|k| k != key);
+                }
+            }
+            let keys = binds.entry(*func).or_default();
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+        binds.retain(|_, keys| !keys.is_empty());
+
+        let keymap = Self { binds };
+        let errors = keymap
+            .conflicts()
+            .into_iter()
+            .map(|(key, a, b)| {
+                sherlock_error!(
+                    SherlockErrorType::ConfigError(Some(format!(
+                        "Key \"{}\" is bound to both \"{}\" and \"{}\"",
+                        key,
+                        a.name(),
+                        b.name()
+                    ))),
+                    ""
+                )
+            })
+            .collect();
+
+        (keymap, errors)
+    }
+
+    /// Returns every key bound to more than one function of the same key context
+    pub fn conflicts(&self) -> Vec<(String, UIFunction, UIFunction)> {
+        let mut by_key: HashMap<(&str, Option<&str>), Vec<UIFunction>> = HashMap::new();
+        for (func, keys) in &self.binds {
+            for key in keys {
+                by_key
+                    .entry((key, func.key_context()))
+                    .or_default()
+                    .push(*func);
+            }
+        }
+
+        let mut conflicts: Vec<(String, UIFunction, UIFunction)> = by_key
+            .into_iter()
+            .filter(|(_, funcs)| funcs.len() > 1)
+            .flat_map(|((key, _), mut funcs)| {
+                funcs.sort_by_key(|f| f.name());
+                let first = funcs[0];
+                funcs
+                    .into_iter()
+                    .skip(1)
+                    .map(move |other| (key.to_string(), first, other))
+            })
+            .collect();
+        conflicts.sort_by(|a, b| a.0.cmp(&b.0));
+        conflicts
+    }
+
+    /// Returns the first key bound to `func`
+    pub fn key_for(&self, func: UIFunction) -> Option<&str> {
+        self.binds
+            .get(&func)
+            .and_then(|keys| keys.first())
+            .map(String::as_str)
+    }
+
+    /// Whether releasing `key`, a key name without modifiers, lets go of a binding of `func`
+    pub fn releases(&self, func: UIFunction, key: &str) -> bool {
+        self.binds.get(&func).is_some_and(|keys| {
+            keys.iter()
+                .any(|bound| bound.rsplit('-').next() == Some(key))
+        })
+    }
+
+    /// Returns the key that runs the `n`-th result, counting from 0 for the digit 1 up to 9
+    /// for the digit 0
+    pub fn shortcut_for(&self, n: usize) -> Option<String> {
+        let key = self.key_for(UIFunction::Shortcut)?;
+        (n < 10 && key.contains("<digit>"))
+            .then(|| key.replace("<digit>", &((n + 1) % 10).to_string()))
+    }
+
+    /// Returns all bindings as `(function name, key)`, sorted by function name
+    pub fn entries(&self) -> Vec<(&'static str, &str)> {
+        let mut entries: Vec<(&'static str, &str)> = self
+            .binds
+            .iter()
+            .flat_map(|(func, keys)| keys.iter().map(|key| (func.name(), key.as_str())))
+            .collect();
+        entries.sort();
+        entries
+    }
+
+    pub fn key_bindings(&self) -> Vec<KeyBinding> {
+        let mut bindings = Vec::new();
+        for (func, keys) in &self.binds {
+            for key in keys {
+                if *func == UIFunction::Shortcut && key.contains("<digit>") {
+                    for i in 0..=9 {
+                        let actual_key = key.replace("<digit>", &i.to_string());
+                        bindings.push(KeyBinding::new(
+                            &actual_key,
+                            ShortcutAction { index: i },
+                            None,
+                        ));
+                    }
+                } else if let Some(binding) = func.into_bind(key) {
+                    bindings.push(binding);
+                }
+            }
+        }
+        bindings
+    }
+
+    pub fn print(&self) {
+        let entries = self.entries();
+        let longest = entries
+            .iter()
+            .map(|(name, _)| name.len())
+            .max()
+            .unwrap_or(0);
+        for (name, key) in entries {
+            println!("{:<width$}  {}", name, key, width = longest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_overrides_rebind_and_unbind() {
+        let overrides = HashMap::from([
+            (UIFunction::ToggleContext, KeyOverride::Key("ctrl-k".into())),
+            (UIFunction::SelectAll, KeyOverride::Key("none".into())),
+            (UIFunction::Paste, KeyOverride::Enabled(false)),
+            (UIFunction::Copy, KeyOverride::Enabled(true)),
+        ]);
+        let (keymap, errors) = Keymap::new(&overrides, &HashMap::new(), "ctrl");
+
+        assert!(errors.is_empty());
+        assert_eq!(keymap.key_for(UIFunction::ToggleContext), Some("ctrl-k"));
+        assert_eq!(keymap.key_for(UIFunction::SelectAll), None);
+        assert_eq!(keymap.key_for(UIFunction::Paste), None);
+        assert_eq!(keymap.key_for(UIFunction::Copy), Some("ctrl-c"));
+        assert!(!keymap.entries().iter().any(|(_, key)| *key == "ctrl-l"));
+    }
+
+    #[test]
+    fn test_defaults() {
+        let (keymap, errors) = Keymap::new(&HashMap::new(), &HashMap::new(), "ctrl");
+
+        assert!(errors.is_empty());
+        assert_eq!(keymap.key_for(UIFunction::ClearBar), Some("ctrl-u"));
+        assert_eq!(keymap.key_for(UIFunction::ErrorPage), Some("ctrl-e"));
+        // escape closes an open context menu instead of the window
+        assert_eq!(keymap.key_for(UIFunction::Exit), Some("escape"));
+        assert_eq!(keymap.key_for(UIFunction::CloseContext), Some("escape"));
+        assert_eq!(keymap.shortcut_for(0).as_deref(), Some("ctrl-1"));
+        assert_eq!(keymap.shortcut_for(9).as_deref(), Some("ctrl-0"));
+        assert_eq!(keymap.shortcut_for(10), None);
+        assert!(keymap.releases(UIFunction::Exec, "enter"));
+        assert!(!keymap.releases(UIFunction::Exec, "shift"));
+    }
+
+    #[test]
+    fn test_shortcut_mod() {
+        assert_eq!(shortcut_modifier("⌘"), Some("ctrl"));
+        assert_eq!(shortcut_modifier("Alt"), Some("alt"));
+        assert_eq!(shortcut_modifier("hyper"), None);
+
+        let (keymap, errors) = Keymap::new(&HashMap::new(), &HashMap::new(), "alt");
+        assert!(errors.is_empty());
+        assert_eq!(keymap.shortcut_for(0).as_deref(), Some("alt-1"));
+
+        // an explicit binding wins over the modifier
+        let overrides = HashMap::from([(
+            UIFunction::Shortcut,
+            KeyOverride::Key("super-<digit>".into()),
+        )]);
+        let (keymap, _) = Keymap::new(&overrides, &HashMap::new(), "alt");
+        assert_eq!(keymap.shortcut_for(9).as_deref(), Some("super-0"));
+    }
+
+    #[test]
+    fn test_keybinds_shadow_defaults() {
+        let keybinds = HashMap::from([
+            (String::from("ctrl-l"), UIFunction::ItemDown),
+            (String::from("ctrl-j"), UIFunction::ItemDown),
+        ]);
+        let (keymap, errors) = Keymap::new(&HashMap::new(), &keybinds, "ctrl");
+
+        assert!(errors.is_empty());
+        assert_eq!(keymap.key_for(UIFunction::ToggleContext), None);
+        let down: Vec<&str> = keymap
+            .entries()
+            .into_iter()
+            .filter(|(name, _)| *name == "item_down")
+            .map(|(_, key)| key)
+            .collect();
+        assert_eq!(down, vec!["ctrl-j", "ctrl-l", "down"]);
+    }
+
+    #[test]
+    fn test_conflicts_are_reported() {
+        let overrides =
+            HashMap::from([(UIFunction::ToggleContext, KeyOverride::Key("ctrl-a".into()))]);
+        let (keymap, errors) = Keymap::new(&overrides, &HashMap::new(), "ctrl");
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            keymap.conflicts(),
+            vec![(
+                String::from("ctrl-a"),
+                UIFunction::SelectAll,
+                UIFunction::ToggleContext
+            )]
+        );
+    }
+
+    #[test]
+    fn test_keymap_from_toml() {
+        let config: SherlockConfig = toml::from_str(
+            r#"
+            [keymap]
+            toggle_context = "ctrl-k"
+            select_all = false
+            "#,
+        )
+        .unwrap();
+        let (keymap, errors) = Keymap::from_config(&config);
+
+        assert!(errors.is_empty());
+        assert_eq!(keymap.key_for(UIFunction::ToggleContext), Some("ctrl-k"));
+        assert_eq!(keymap.key_for(UIFunction::SelectAll), None);
+    }
+}
@@ -1,16 +1,46 @@
-use std::sync::Arc;
+use std::{
+    sync::Arc,
+    time::{Duration, Instant},
+};
 
-use gpui::{AppContext, ClipboardItem, Context, SharedString, Window, actions};
+use gpui::{
+    AppContext, AsyncApp, ClipboardItem, Context, KeyUpEvent, MouseDownEvent, MouseMoveEvent,
+    MouseUpEvent, ScrollWheelEvent, SharedString, WeakEntity, Window, actions, point, px,
+};
 use smallvec::SmallVec;
 
 use crate::{
+    KEYMAP,
     launcher::{
-        ExecMode,
+        ExecMode, LauncherType,
+        app_launcher::HideEntry,
+        audio_launcher::{MprisCommand, SEEK_STEP},
         children::{LauncherValues, RenderableChild, RenderableChildDelegate},
+        dmenu_launcher, web_history,
+        web_launcher::split_bang,
+    },
+    loader::{
+        utils::{CounterReader, ExecVariable},
+        variable_history,
+    },
+    ui::{
+        UIFunction,
+        main_window::{
+            CONFIRM_TIMEOUT, Expansion, HeldExec, PendingConfirm, SherlockMainWindow,
+            VariableRecall, column_step, row_step, scrollbar_offset, scrollbar_thumb,
+        },
+        search_bar::{Left, Right, ShortcutAction, TextInput, Undo},
+    },
+    utils::{
+        command_launch::{Substitution, open_path, spawn_desktop_exec, spawn_detached},
+        compositor,
+        config::ConfigGuard,
+        errors::SherlockError,
+        http_action::HttpRequest,
+        palette::Palette,
+        soft_delete::{self, GRACE_PERIOD},
+        websearch::websearch,
     },
-    loader::utils::{CounterReader, ExecVariable},
-    ui::{main_window::SherlockMainWindow, search_bar::TextInput},
-    utils::{command_launch::spawn_detached, errors::SherlockError, websearch::websearch},
 };
 
 actions!(
@@ -19,15 +49,21 @@ actions!(
         Quit,
         FocusNext,
         FocusPrev,
+        FocusLeft,
+        FocusRight,
         NextVar,
         PrevVar,
         Execute,
         OpenContext,
+        CloseContext,
         Backspace,
+        ToggleContrast,
+        ToggleErrors,
     ]
 );
 
 impl SherlockMainWindow {
+    /// Focuses the first child whose launcher has `spawn_focus` set, or the first child
     pub fn focus_first(&mut self, cx: &mut Context<Self>) {
         // early return if no indices
         if self.filtered_indices.is_empty() {
@@ -41,9 +77,7 @@ impl SherlockMainWindow {
                 .position(|idx| data_guard[*idx].spawn_focus())
         };
 
-        if let Some(n) = first_valid_index {
-            self.focus_nth(n, cx);
-        }
+        self.focus_nth(first_valid_index.unwrap_or(0), cx);
     }
     pub fn focus_nth(&mut self, n: usize, cx: &mut Context<Self>) {
         // early return on invalid index
@@ -51,8 +85,24 @@ impl SherlockMainWindow {
             return;
         }
 
+        // collapse the previously selected tile
+        if self.expanded.take().is_some() {
+            let prev = self.row_of(self.selected_index);
+            self.list_state.splice(prev..prev + 1, 1);
+        }
+
         self.selected_index = n;
-        self.list_state.scroll_to_reveal_item(n);
+        self.list_state.scroll_to_reveal_item(self.row_of(n));
+        self.cancel_held_exec(cx);
+        let selected = self.filtered_indices.get(n).copied();
+        if self
+            .pending_confirm
+            .is_some_and(|pending| !pending.kept_by(selected))
+        {
+            self.cancel_confirm(cx);
+        }
+        // the selection may have scrolled another result under the pointer
+        self.hovered = None;
 
         // Handle variable inputs
         self.update_vars(cx);
@@ -80,15 +130,16 @@ impl SherlockMainWindow {
                 self.context_idx = Some(idx + 1);
                 cx.notify();
             }
-        } else {
+        } else if self.active_bar > 0 {
+            // variable inputs step through their previous values instead
+            self.recall_variable(false, cx);
+        } else if let Some(n) = row_step(self.selected_index, count, self.columns, true) {
             // handle normal view
-            if self.selected_index < count - 1 {
-                self.focus_nth(self.selected_index + 1, cx);
-            }
+            self.focus_nth(n, cx);
         }
     }
     pub(super) fn focus_prev(&mut self, _: &FocusPrev, _: &mut Window, cx: &mut Context<Self>) {
-        let count = self.data.read(cx).len();
+        let count = self.filtered_indices.len();
         if count == 0 {
             return;
         }
@@ -99,11 +150,87 @@ impl SherlockMainWindow {
                 self.context_idx = Some(idx - 1);
                 cx.notify();
             }
-        } else {
+        } else if self.active_bar > 0 {
+            self.recall_variable(true, cx);
+        } else if let Some(n) = row_step(self.selected_index, count, self.columns, false) {
             // handle normal view
-            if self.selected_index > 0 {
-                self.focus_nth(self.selected_index - 1, cx);
-            }
+            self.focus_nth(n, cx);
+        }
+    }
+    /// Starts dragging the scrollbar thumb. Clicks on the track move the thumb there first.
+    pub(super) fn grab_scrollbar(
+        &mut self,
+        event: &MouseDownEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        cx.stop_propagation();
+        let bounds = self.list_state.viewport_bounds();
+        let (viewport, max_offset) = self.scroll_extent();
+        let offset = -f32::from(self.list_state.scroll_px_offset_for_scrollbar().y);
+        let Some((top, height)) = scrollbar_thumb(viewport, max_offset, offset) else {
+            return;
+        };
+        let y = f32::from(event.position.y - bounds.origin.y);
+        let grab = if (top..top + height).contains(&y) {
+            y - top
+        } else {
+            height / 2.
+        };
+        self.scrollbar_drag = Some(grab);
+        self.scroll_thumb_to(y - grab, cx);
+    }
+    pub(super) fn drag_scrollbar(
+        &mut self,
+        event: &MouseMoveEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        let Some(grab) = self.scrollbar_drag else {
+            return;
+        };
+        let bounds = self.list_state.viewport_bounds();
+        self.scroll_thumb_to(f32::from(event.position.y - bounds.origin.y) - grab, cx);
+    }
+    pub(super) fn release_scrollbar(
+        &mut self,
+        _: &MouseUpEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.scrollbar_drag.take().is_some() {
+            cx.notify();
+        }
+    }
+    /// Scrolls the list so the scrollbar thumb starts `top` pixels below the top of its track
+    fn scroll_thumb_to(&mut self, top: f32, cx: &mut Context<Self>) {
+        let (viewport, max_offset) = self.scroll_extent();
+        let offset = scrollbar_offset(viewport, max_offset, top);
+        self.list_state
+            .set_offset_from_scrollbar(point(px(0.), px(-offset)));
+        cx.notify();
+    }
+    /// Height of the visible part of the list and how far it can scroll
+    pub(super) fn scroll_extent(&self) -> (f32, f32) {
+        (
+            f32::from(self.list_state.viewport_bounds().size.height),
+            f32::from(self.list_state.max_offset_for_scrollbar().height),
+        )
+    }
+    pub(super) fn focus_left(&mut self, _: &FocusLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.focus_column(false, cx);
+    }
+    pub(super) fn focus_right(&mut self, _: &FocusRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.focus_column(true, cx);
+    }
+    /// Moves the selection to the neighbouring tile of a grid
+    fn focus_column(&mut self, right: bool, cx: &mut Context<Self>) {
+        if self.context_idx.is_some() {
+            return;
+        }
+        let count = self.filtered_indices.len();
+        if let Some(n) = column_step(self.selected_index, count, self.columns, right) {
+            self.focus_nth(n, cx);
         }
     }
     pub(super) fn next_var(&mut self, _: &NextVar, win: &mut Window, cx: &mut Context<Self>) {
@@ -143,18 +270,31 @@ impl SherlockMainWindow {
         &mut self,
         what: ExecMode,
         keyword: &str,
-        variables: &[(SharedString, SharedString)],
+        variables: &[(SharedString, Substitution)],
         cx: &mut Context<Self>,
     ) -> Result<bool, SherlockError> {
         match what {
-            ExecMode::App { exec, terminal } => {
-                let cmd = if terminal {
-                    format!(r#"{{terminal}} {exec}"#)
-                } else {
-                    exec.to_string()
-                };
-
-                spawn_detached(&cmd, keyword, variables)?;
+            ExecMode::App {
+                exec,
+                terminal,
+                raise,
+            } => {
+                match raise {
+                    // looking up the open windows waits on the compositor
+                    Some(app_id) => {
+                        let (exec, keyword, variables) =
+                            (exec.clone(), keyword.to_string(), variables.to_vec());
+                        std::thread::spawn(move || {
+                            if !compositor::raise_running(&app_id)
+                                && let Err(e) =
+                                    spawn_desktop_exec(&exec, terminal, &keyword, &variables)
+                            {
+                                e.report();
+                            }
+                        });
+                    }
+                    None => spawn_desktop_exec(&exec, terminal, keyword, variables)?,
+                }
                 increment(&exec);
             }
             ExecMode::Category { category } => {
@@ -170,28 +310,320 @@ impl SherlockMainWindow {
                 spawn_detached(&exec, keyword, variables)?;
                 increment(&exec);
             }
-            ExecMode::Copy { content } => {
+            ExecMode::Copy { content, record } => {
                 cx.write_to_clipboard(ClipboardItem::new_string(content.to_string()));
+                if let Some(record) = record {
+                    record.record();
+                }
+            }
+            ExecMode::Mpris { player, command } => {
+                command.run(&player)?;
+                // playback controls keep the launcher open
+                return Ok(command == MprisCommand::Raise);
+            }
+            ExecMode::Next => {
+                self.close_context(cx);
+                self.toggle_expansion(Expansion::Next, cx);
+                return Ok(false);
+            }
+            ExecMode::ShowSource => {
+                self.close_context(cx);
+                self.toggle_expansion(Expansion::Source, cx);
+                return Ok(false);
             }
             ExecMode::Web {
                 engine,
                 browser,
                 exec,
+                bangs,
             } => {
-                let engine = engine.as_deref().unwrap_or("plain");
                 let query = if let Some(query) = exec.as_deref() {
                     query
                 } else {
                     keyword
                 };
+                // a known bang picks the engine for this search only
+                let (engine, query) = split_bang(&bangs, query)
+                    .unwrap_or((engine.as_deref().unwrap_or("plain"), query));
                 websearch(engine, query, browser.as_deref(), variables)?;
             }
+            ExecMode::Open { path } => {
+                open_path(&path)?;
+            }
+            ExecMode::Hide { name } => {
+                if let Some(&index) = self.filtered_indices.get(self.selected_index) {
+                    soft_delete::stage(Box::new(HideEntry { name, index }));
+                    self.after_soft_delete(cx);
+                }
+                return Ok(false);
+            }
+            ExecMode::SoftDelete(change) => {
+                soft_delete::stage(change);
+                self.after_soft_delete(cx);
+                return Ok(false);
+            }
+            ExecMode::Print { output } => dmenu_launcher::print_selection(&output),
+            ExecMode::Http { action, name } => {
+                let request = HttpRequest::resolve(&action, keyword, &name)?;
+                if ConfigGuard::read()?.runtime.dry_run {
+                    println!("{request}");
+                } else {
+                    request.spawn(action.name.map(String::from).unwrap_or(name));
+                }
+            }
             _ => {}
         };
 
         Ok(true)
     }
+    fn toggle_expansion(&mut self, expansion: Expansion, cx: &mut Context<Self>) {
+        let current = self
+            .filtered_indices
+            .get(self.selected_index)
+            .map(|&idx| (idx, expansion));
+        self.expanded = if self.expanded == current {
+            None
+        } else {
+            current
+        };
+        // the tile changes its height
+        let selected = self.row_of(self.selected_index);
+        self.list_state.splice(selected..selected + 1, 1);
+        cx.notify();
+    }
+    /// Refreshes the results after a change was staged and persists it once its grace period
+    /// is over
+    fn after_soft_delete(&mut self, cx: &mut Context<Self>) {
+        self.close_context(cx);
+        self.last_query = None;
+        self.filter_and_sort(cx);
+
+        cx.spawn(
+            move |this: WeakEntity<SherlockMainWindow>, cx: &mut AsyncApp| {
+                let mut cx = cx.clone();
+                async move {
+                    cx.background_executor().timer(GRACE_PERIOD).await;
+                    soft_delete::persist_expired();
+                    // hides the footer message
+                    this.update(&mut cx, |_, cx| cx.notify()).ok();
+                }
+            },
+        )
+        .detach();
+    }
+    /// Undoes the latest staged change while it is shown in the footer, edits of the search
+    /// bar are undone after it
+    pub(super) fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if soft_delete::undo() {
+            cx.stop_propagation();
+            self.last_query = None;
+            self.filter_and_sort(cx);
+            cx.notify();
+        }
+    }
+    pub(super) fn toggle_contrast(
+        &mut self,
+        _: &ToggleContrast,
+        win: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        Palette::toggle_high_contrast();
+        win.set_background_appearance(Palette::current().window_background());
+        cx.notify();
+    }
+    /// Shows the errors reported since startup in place of the results, or hides them again
+    pub(super) fn toggle_errors(
+        &mut self,
+        _: &ToggleErrors,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.error_page.take().is_none() {
+            self.close_context(cx);
+            self.error_page = Some(SherlockError::reported().into());
+        }
+        self.fit_window(cx);
+        cx.notify();
+    }
+    /// The arrow keys move between the tiles of a grid once the caret of the search bar is at
+    /// its edge, seek the selected music tile or move the caret
+    pub(super) fn arrow_left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
+        if self.grid_takes_arrow(false, cx) {
+            cx.stop_propagation();
+            self.focus_column(false, cx);
+        } else if self.columns <= 1 {
+            self.seek(-SEEK_STEP, cx);
+        }
+    }
+    pub(super) fn arrow_right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
+        if self.grid_takes_arrow(true, cx) {
+            cx.stop_propagation();
+            self.focus_column(true, cx);
+        } else if self.columns <= 1 {
+            self.seek(SEEK_STEP, cx);
+        }
+    }
+    fn grid_takes_arrow(&self, forward: bool, cx: &Context<Self>) -> bool {
+        self.columns > 1 && self.active_bar == 0 && self.text_input.read(cx).caret_at_edge(forward)
+    }
+    /// Seeks the player of the selected music tile. Other tiles leave the arrow keys to the
+    /// search bar.
+    fn seek(&mut self, offset: i64, cx: &mut Context<Self>) {
+        if self.context_idx.is_some() {
+            return;
+        }
+        let player = self
+            .filtered_indices
+            .get(self.selected_index)
+            .and_then(|i| self.data.read(cx).get(*i))
+            .and_then(|child| match child {
+                RenderableChild::MusicLike { inner, .. } => inner.player.clone(),
+                _ => None,
+            });
+        let Some(player) = player else {
+            return;
+        };
+
+        cx.stop_propagation();
+        if let Err(e) = MprisCommand::Seek(offset).run(&player) {
+            eprintln!("{e}");
+        }
+    }
+    /// Runs the result the pressed digit is shown next to, 1 for the first and 0 for the tenth
+    pub(super) fn shortcut(
+        &mut self,
+        action: &ShortcutAction,
+        win: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.context_idx.is_some() || self.run_command.is_some() {
+            return;
+        }
+        let n = (action.index + 9) % 10;
+        let count = ConfigGuard::read()
+            .map(|config| config.appearance.num_shortcuts as usize)
+            .unwrap_or(5);
+        let enabled = n < count
+            && self
+                .filtered_indices
+                .get(n)
+                .and_then(|i| self.data.read(cx).get(*i))
+                .is_some_and(|child| child.launcher().shortcut);
+        if enabled {
+            // refocusing would drop the confirmation the first press asked for
+            if self.selected_index != n {
+                self.focus_nth(n, cx);
+            }
+            self.run_execute(false, win, cx);
+        }
+    }
     pub(super) fn execute(&mut self, _: &Execute, win: &mut Window, cx: &mut Context<Self>) {
+        // key repeat of a held exec key
+        if self.held_exec.is_some() {
+            return;
+        }
+
+        let threshold = ConfigGuard::read()
+            .ok()
+            .and_then(|config| config.behavior.hold_for_alternative)
+            .map(Duration::from_millis);
+        if let Some(threshold) = threshold
+            && self.context_idx.is_none()
+            && self.run_command.is_none()
+            && self.selected_alternative(cx).is_some()
+        {
+            self.hold_exec(threshold, win, cx);
+            return;
+        }
+
+        self.run_execute(false, win, cx);
+    }
+    /// Waits for the exec key to be released or `threshold` to pass before executing
+    fn hold_exec(&mut self, threshold: Duration, win: &mut Window, cx: &mut Context<Self>) {
+        let held = HeldExec {
+            started: Instant::now(),
+            threshold,
+        };
+        self.held_exec = Some(held);
+
+        cx.spawn_in(win, async move |this, cx| {
+            cx.background_executor().timer(threshold).await;
+            this.update_in(cx, |this, win, cx| {
+                // released, cancelled or replaced by another press in the meantime
+                if this.held_exec == Some(held) {
+                    this.held_exec = None;
+                    this.run_execute(true, win, cx);
+                }
+            })
+            .ok();
+        })
+        .detach();
+        cx.notify();
+    }
+    pub(super) fn release_exec(
+        &mut self,
+        event: &KeyUpEvent,
+        win: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        // e.g. a modifier let go of while the exec key is still held
+        let is_exec = KEYMAP
+            .get()
+            .is_none_or(|keymap| keymap.releases(UIFunction::Exec, &event.keystroke.key));
+        if !is_exec {
+            return;
+        }
+        if let Some(held) = self.held_exec.take() {
+            self.run_execute(held.is_due(), win, cx);
+        }
+    }
+    /// Drops a held exec key without executing anything, e.g. once the selection changes
+    pub(super) fn cancel_held_exec(&mut self, cx: &mut Context<Self>) {
+        if self.held_exec.take().is_some() {
+            cx.notify();
+        }
+    }
+    /// Asks to press the exec key again before the result at data index `idx` runs. Returns
+    /// true if it was already asked for, i.e. the result can run now.
+    fn confirmed(&mut self, idx: usize, cx: &mut Context<Self>) -> bool {
+        if PendingConfirm::confirm(&mut self.pending_confirm, idx) {
+            return true;
+        }
+
+        let pending = self.pending_confirm;
+        cx.spawn(
+            move |this: WeakEntity<SherlockMainWindow>, cx: &mut AsyncApp| {
+                let mut cx = cx.clone();
+                async move {
+                    cx.background_executor().timer(CONFIRM_TIMEOUT).await;
+                    this.update(&mut cx, |this, cx| {
+                        // confirmed, cancelled or asked again in the meantime
+                        if this.pending_confirm == pending {
+                            this.cancel_confirm(cx);
+                        }
+                    })
+                    .ok();
+                }
+            },
+        )
+        .detach();
+        cx.notify();
+        false
+    }
+    /// Drops a pending confirmation, e.g. once the selection changes
+    pub(super) fn cancel_confirm(&mut self, cx: &mut Context<Self>) {
+        if self.pending_confirm.take().is_some() {
+            cx.notify();
+        }
+    }
+    fn selected_alternative(&self, cx: &Context<Self>) -> Option<ExecMode> {
+        let selected = self
+            .data
+            .read(cx)
+            .get(*self.filtered_indices.get(self.selected_index)?)?;
+        selected.build_exec()?.alternative(selected.launcher())
+    }
+    fn run_execute(&mut self, alternative: bool, win: &mut Window, cx: &mut Context<Self>) {
         if let Some(idx) = self.context_idx {
             if let Some(action) = self.context_actions.get(idx) {
                 if let Some(selected) = self
@@ -200,41 +632,132 @@ impl SherlockMainWindow {
                     .get(self.filtered_indices[self.selected_index])
                 {
                     let what = selected.build_action_exec(action);
+                    let keyword = self.text_input.read(cx).content.clone();
 
-                    match self.execute_helper(what, "", &[], cx) {
+                    match self.execute_helper(what, keyword.as_ref(), &[], cx) {
                         Ok(exit) if exit => self.close_window(win, cx),
-                        Err(e) => eprintln!("{e}"),
+                        Err(e) => {
+                            e.report();
+                            cx.notify();
+                        }
                         _ => {}
                     }
                 }
             }
+        } else if let Some(run) = self.run_command.as_ref() {
+            match run.spawn() {
+                Ok(_) => self.close_window(win, cx),
+                Err(e) => {
+                    e.report();
+                    cx.notify();
+                }
+            }
         } else {
+            // the entry runs once all variables validate, the first that doesn't is focused
+            if let Some(invalid) = self
+                .variable_input
+                .iter()
+                .position(|input| !input.read(cx).is_valid())
+            {
+                self.active_bar = invalid + 1;
+                let handle = self.variable_input[invalid].read(cx).focus_handle.clone();
+                handle.focus(win);
+                cx.notify();
+                return;
+            }
+
             let keyword = self.text_input.read(cx).content.clone();
             // collect variables
-            let mut variables: SmallVec<[(SharedString, SharedString); 4]> = SmallVec::new();
+            let mut variables: SmallVec<[(SharedString, Substitution); 4]> = SmallVec::new();
             for s in &self.variable_input {
                 let guard = s.read(cx);
-                variables.push((guard.placeholder.clone(), guard.content.clone()));
+                variables.push((guard.placeholder.clone(), guard.substitution()));
             }
 
             let data = self.data.read(cx).clone();
-            if let Some(selected) = data.get(self.filtered_indices[self.selected_index]) {
-                if let Some(what) = selected.build_exec() {
+            let idx = self.filtered_indices[self.selected_index];
+            if let Some(selected) = data.get(idx) {
+                if selected.confirm() && !self.confirmed(idx, cx) {
+                    return;
+                }
+                let what = if alternative {
+                    selected
+                        .build_exec()
+                        .and_then(|what| what.alternative(selected.launcher()))
+                } else {
+                    selected.build_exec()
+                };
+                if let Some(what) = what {
                     match self.execute_helper(what, keyword.as_ref(), &variables, cx) {
-                        Ok(exit) if exit => {
-                            self.close_window(win, cx);
-                            return;
+                        Ok(exit) => {
+                            self.record_variables(selected, cx);
+                            if exit {
+                                self.record_search(selected, keyword.as_ref(), cx);
+                                self.close_window(win, cx);
+                                return;
+                            }
                         }
                         Err(e) => {
-                            eprintln!("{e}");
+                            // shown in the footer, the window stays open
+                            e.report();
+                            cx.notify();
                             return;
                         }
-                        _ => {}
                     }
                 }
             }
         }
     }
+    /// Adds a web search to the history of its launcher and updates the recent search
+    /// children to match it
+    fn record_search(&mut self, selected: &RenderableChild, keyword: &str, cx: &mut Context<Self>) {
+        let RenderableChild::AppLike { launcher, inner } = selected else {
+            return;
+        };
+        let LauncherType::Web(web) = &launcher.launcher_type else {
+            return;
+        };
+        let Some(history) = web.history.as_ref() else {
+            return;
+        };
+
+        let entries: Vec<String> = {
+            let Ok(mut history) = history.write() else {
+                return;
+            };
+            history.push(inner.exec.as_deref().unwrap_or(keyword));
+            if let Err(e) = history.save() {
+                eprintln!("{e}");
+            }
+            history.entries().map(String::from).collect()
+        };
+
+        self.data.update(cx, |items, cx| {
+            let items = Arc::make_mut(items);
+            // existing children are reused in place, so that the indices of others stay valid
+            let slots: Vec<usize> = items
+                .iter()
+                .enumerate()
+                .filter(|(_, child)| match child {
+                    RenderableChild::AppLike {
+                        launcher: other,
+                        inner,
+                    } => Arc::ptr_eq(other, launcher) && inner.is_recent_search(other),
+                    _ => false,
+                })
+                .map(|(i, _)| i)
+                .collect();
+
+            for (slot, query) in entries.iter().enumerate() {
+                let child = web_history::recent_search(launcher, query, slot, inner);
+                match slots.get(slot) {
+                    Some(&i) => items[i] = child,
+                    None => items.push(child),
+                }
+            }
+            cx.notify();
+        });
+    }
     pub(super) fn open_context(
         &mut self,
         _: &OpenContext,
@@ -244,6 +767,7 @@ impl SherlockMainWindow {
         if self.context_actions.is_empty() {
             return;
         }
+        self.cancel_confirm(cx);
 
         // toggle logic
         if self.context_idx.take().is_none() {
@@ -252,6 +776,58 @@ impl SherlockMainWindow {
 
         cx.notify();
     }
+    /// Highlights the `n`-th result while the pointer moves over it
+    pub(super) fn hover_item(&mut self, n: usize, cx: &mut Context<Self>) {
+        if self.hovered != Some(n) {
+            self.hovered = Some(n);
+            cx.notify();
+        }
+    }
+    pub(super) fn unhover_item(&mut self, n: usize, cx: &mut Context<Self>) {
+        if self.hovered == Some(n) {
+            self.hovered = None;
+            cx.notify();
+        }
+    }
+    /// The list scrolls by itself, the result that ends up under the pointer is only
+    /// highlighted once the pointer moves
+    pub(super) fn scroll_results(
+        &mut self,
+        _: &ScrollWheelEvent,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        if self.hovered.take().is_some() {
+            cx.notify();
+        }
+    }
+    pub(super) fn click_item(&mut self, n: usize, win: &mut Window, cx: &mut Context<Self>) {
+        self.close_context(cx);
+        if self.selected_index == n {
+            self.run_execute(false, win, cx);
+        } else {
+            self.focus_nth(n, cx);
+        }
+    }
+    /// Selects the `n`-th result and opens its context menu
+    pub(super) fn open_context_at(&mut self, n: usize, cx: &mut Context<Self>) {
+        if self.selected_index != n {
+            self.close_context(cx);
+            self.focus_nth(n, cx);
+        }
+        if !self.context_actions.is_empty() {
+            self.context_idx = Some(0);
+            cx.notify();
+        }
+    }
+    pub(super) fn dismiss_context(
+        &mut self,
+        _: &CloseContext,
+        _: &mut Window,
+        cx: &mut Context<Self>,
+    ) {
+        self.close_context(cx);
+    }
     pub(super) fn close_context(&mut self, cx: &mut Context<Self>) {
         if let Some(_) = self.context_idx.take() {
             cx.notify();
@@ -260,14 +836,27 @@ impl SherlockMainWindow {
     pub(super) fn quit(&mut self, _: &Quit, win: &mut Window, cx: &mut Context<Self>) {
         if self.context_idx.is_some() {
             self.close_context(cx);
+        } else if self.pending_confirm.is_some() {
+            self.cancel_confirm(cx);
+        } else if self.error_page.take().is_some() {
+            self.fit_window(cx);
+            cx.notify();
         } else {
             self.close_window(win, cx);
         }
     }
     pub(super) fn close_window(&mut self, win: &mut Window, cx: &mut Context<Self>) {
+        // Staged changes can no longer be undone
+        soft_delete::persist_all();
+
         // Cleanup
-        self.variable_input.clear();
+        self.clear_variable_inputs();
+        self.held_exec = None;
+        self.pending_confirm = None;
         self.filtered_indices = Arc::new([]);
+        self.run_command = None;
+        self.expanded = None;
+        self.error_page = None;
         if let Some(task) = self.deferred_render_task.take() {
             drop(task)
         }
@@ -291,26 +880,95 @@ impl SherlockMainWindow {
         };
 
         if let Some(vars_to_create) = needed_vars {
-            self.variable_input = vars_to_create
-                .into_iter()
-                .map(|var| {
-                    cx.new(|cx| TextInput {
-                        focus_handle: cx.focus_handle(),
-                        content: "".into(),
-                        placeholder: var.placeholder(),
-                        variable: Some(var),
-                        // Initialize your other fields here...
-                        selected_range: 0..0,
-                        selection_reversed: false,
-                        marked_range: None,
-                        last_layout: None,
-                        last_bounds: None,
-                        is_selecting: false,
-                    })
-                })
-                .collect();
+            self.create_variable_inputs(idx, vars_to_create, cx);
         } else {
-            self.variable_input.clear();
+            self.clear_variable_inputs();
+        }
+    }
+    /// Creates the inputs for the variables of the child at data index `idx`, filled with the
+    /// values submitted to them last
+    pub(super) fn create_variable_inputs(
+        &mut self,
+        idx: usize,
+        vars: Vec<ExecVariable>,
+        cx: &mut Context<Self>,
+    ) {
+        let launcher = self
+            .data
+            .read(cx)
+            .get(idx)
+            .and_then(|child| child.launcher().name.clone());
+        let history = match &launcher {
+            Some(_) => CounterReader::new()
+                .map(|reader| variable_history::read(&reader.variables_path))
+                .unwrap_or_default(),
+            None => Default::default(),
+        };
+
+        (self.variable_input, self.variable_recall) = vars
+            .into_iter()
+            .map(|var| {
+                let recall = VariableRecall::new(
+                    launcher
+                        .as_deref()
+                        .map(|launcher| variable_history::recall(&history, launcher, &var))
+                        .unwrap_or_default(),
+                );
+                let input = cx.new(|cx| TextInput::for_variable(var, recall.first(), cx));
+                (input, recall)
+            })
+            .unzip();
+    }
+    pub(super) fn clear_variable_inputs(&mut self) {
+        self.variable_input.clear();
+        self.variable_recall.clear();
+    }
+    /// Fills the focused variable input with the next older or newer value submitted to it
+    fn recall_variable(&mut self, older: bool, cx: &mut Context<Self>) {
+        let Some(bar) = self.active_bar.checked_sub(1) else {
+            return;
+        };
+        let (Some(input), Some(recall)) = (
+            self.variable_input.get(bar),
+            self.variable_recall.get_mut(bar),
+        ) else {
+            return;
+        };
+        if let Some(value) = recall.step(older) {
+            input.update(cx, |input, cx| {
+                input.set_content(value);
+                cx.notify();
+            });
+        }
+    }
+    /// Remembers the values submitted to the variable inputs of `selected`, see
+    /// [`variable_history`]
+    fn record_variables(&self, selected: &RenderableChild, cx: &Context<Self>) {
+        let Some(launcher) = selected.launcher().name.as_ref() else {
+            return;
+        };
+        let submitted: Vec<((String, String), String)> = self
+            .variable_input
+            .iter()
+            .filter_map(|input| {
+                let input = input.read(cx);
+                // passwords are never stored
+                let ExecVariable::StringInput(spec) = input.variable.as_ref()? else {
+                    return None;
+                };
+                (!input.content.is_empty()).then(|| {
+                    (
+                        (launcher.clone(), spec.placeholder.to_string()),
+                        input.content.to_string(),
+                    )
+                })
+            })
+            .collect();
+        if submitted.is_empty() {
+            return;
+        }
+        if let Ok(reader) = CounterReader::new() {
+            let _ = variable_history::record(&reader.variables_path, submitted);
         }
     }
 }
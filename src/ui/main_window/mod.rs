@@ -1,14 +1,24 @@
-use std::sync::Arc;
+use std::collections::{HashSet, VecDeque};
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 
-use crate::launcher::LauncherType;
+use crate::launcher::app_launcher;
 use crate::launcher::children::{LauncherValues, RenderableChild};
 use crate::launcher::children::{RenderableChildDelegate, SherlockSearch};
-use crate::loader::utils::{ApplicationAction, ExecVariable};
-use crate::utils::config::HomeType;
-use gpui::{App, Context, Entity, FocusHandle, Focusable, ListState, SharedString, Subscription};
+use crate::launcher::doc_launcher::DocLookup;
+use crate::loader::utils::{ApplicationAction, ExecVariable, MatchPolicy, normalize_search};
+use crate::sher_log;
+use crate::utils::command_launch::RunCommand;
+use crate::utils::config::{ConfigGuard, ConfigSearch, HomeType, Sorting};
+use crate::utils::errors::SherlockError;
+use crate::utils::stats;
+use gpui::{
+    AnyWindowHandle, App, Context, Entity, FocusHandle, Focusable, ListState, SharedString, Size,
+    Subscription, px,
+};
 use gpui::{AppContext, WeakEntity};
 use gpui::{AsyncApp, Task};
-use rayon::iter::{IntoParallelIterator, ParallelIterator};
+use rayon::iter::{IntoParallelIterator, IntoParallelRefIterator, ParallelIterator};
 use simd_json::prelude::Indexed;
 
 use crate::ui::search_bar::TextInput;
@@ -16,14 +26,24 @@ use crate::ui::search_bar::TextInput;
 pub mod actions;
 pub mod render;
 
-pub use actions::{Execute, FocusNext, FocusPrev, NextVar, OpenContext, PrevVar, Quit};
+pub use actions::{
+    CloseContext, Execute, FocusLeft, FocusNext, FocusPrev, FocusRight, NextVar, OpenContext,
+    PrevVar, Quit, ToggleContrast, ToggleErrors,
+};
+
+/// Key context while the context menu is open, its bindings shadow the global ones
+pub const CONTEXT_MENU: &str = "ContextMenu";
 
 pub struct SherlockMainWindow {
     pub text_input: Entity<TextInput>,
     pub focus_handle: FocusHandle,
+    /// Holds one row per `columns` results
     pub list_state: ListState,
     pub _subs: Vec<Subscription>,
+    /// Position of the selected result in `filtered_indices`, not its row
     pub selected_index: usize,
+    /// Tiles per row of the current results, 1 for the list
+    pub columns: usize,
 
     // mode
     pub mode: LauncherMode,
@@ -35,13 +55,154 @@ pub struct SherlockMainWindow {
 
     // variable input fields
     pub variable_input: Vec<Entity<TextInput>>,
+    /// Values submitted before to each of `variable_input`
+    pub variable_recall: Vec<VariableRecall>,
     pub active_bar: usize,
 
     // Model
     pub deferred_render_task: Option<Task<Option<()>>>,
+    /// Pending documentation lookup, replaced by the lookup of the next query
+    pub lookup_task: Option<Task<()>>,
     pub data: Entity<Arc<Vec<RenderableChild>>>,
     pub filtered_indices: Arc<[usize]>,
     pub last_query: Option<String>,
+    /// Mode and query the shown results were filtered for. Unlike `last_query` it is not
+    /// cleared to force a refresh, so refreshes can keep the selection.
+    pub results_for: Option<(LauncherMode, String)>,
+    /// Children the last query could show, see [`Narrowing`]
+    pub narrowing: Option<Narrowing>,
+    /// Results of recent queries, shown again without filtering
+    pub result_cache: ResultCache,
+    /// Data indices of async children whose last refresh timed out
+    pub stale_indices: HashSet<usize>,
+    /// Shell command entered through the run prefix, replaces the results while set
+    pub run_command: Option<RunCommand>,
+    /// Data index of the expanded tile and what it shows below its content
+    pub expanded: Option<(usize, Expansion)>,
+    /// Exec key that is held down to run the alternative action
+    pub held_exec: Option<HeldExec>,
+    /// Result that runs once the exec key is pressed again
+    pub pending_confirm: Option<PendingConfirm>,
+    /// Reported errors while the error page replaces the results
+    pub error_page: Option<Arc<[SherlockError]>>,
+    /// `appearance.max_height`, the window is fitted to the results up to it if set
+    pub max_height: Option<f32>,
+    /// Height the window was last fitted to, see [`fit_window`](Self::fit_window)
+    pub fitted_height: Option<f32>,
+    pub window: AnyWindowHandle,
+    /// Distance from the top of the scrollbar thumb to the pointer while it is dragged
+    pub scrollbar_drag: Option<f32>,
+    /// Set until the first results after opening the window are shown. Only those select the
+    /// first child with `spawn_focus`, later queries start at the top.
+    pub awaiting_spawn_focus: bool,
+    /// Result under the pointer, highlighted without selecting it. Keyboard navigation and
+    /// scrolling clear it until the pointer moves again.
+    pub hovered: Option<usize>,
+}
+
+/// Armed by [`Execute`] when the selected entry has an alternative action and
+/// `behavior.hold_for_alternative` is set. Releasing the key before `threshold` runs the
+/// normal action.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct HeldExec {
+    pub started: Instant,
+    pub threshold: Duration,
+}
+
+impl HeldExec {
+    /// Fraction of the threshold that has passed, between 0 and 1
+    pub fn progress(&self) -> f32 {
+        (self.started.elapsed().as_secs_f32() / self.threshold.as_secs_f32()).min(1.0)
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.started.elapsed() >= self.threshold
+    }
+}
+
+/// How long a result that asks for confirmation waits for the exec key to be pressed again
+pub const CONFIRM_TIMEOUT: Duration = Duration::from_secs(4);
+
+/// Armed by [`Execute`] for results that ask for confirmation, see
+/// [`RenderableChildImpl::confirm`](crate::launcher::children::RenderableChildImpl::confirm).
+/// It is dropped once the selection changes or [`CONFIRM_TIMEOUT`] passes.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct PendingConfirm {
+    /// Data index of the result
+    pub idx: usize,
+    pub started: Instant,
+}
+
+impl PendingConfirm {
+    /// Asks for confirmation of the result at data index `idx`, or returns true if it was
+    /// already asked for, i.e. the result can run now
+    pub fn confirm(pending: &mut Option<Self>, idx: usize) -> bool {
+        if pending.take().is_some_and(|pending| pending.idx == idx) {
+            return true;
+        }
+        *pending = Some(Self {
+            idx,
+            started: Instant::now(),
+        });
+        false
+    }
+
+    /// Whether the confirmation is kept once the result at data index `idx` is selected. Only
+    /// selecting the same result again keeps it, e.g. by pressing its shortcut a second time.
+    pub fn kept_by(&self, idx: Option<usize>) -> bool {
+        idx == Some(self.idx)
+    }
+
+    /// Fraction of [`CONFIRM_TIMEOUT`] that is left, between 0 and 1
+    pub fn remaining(&self) -> f32 {
+        1.0 - (self.started.elapsed().as_secs_f32() / CONFIRM_TIMEOUT.as_secs_f32()).min(1.0)
+    }
+}
+
+/// Values submitted to a variable input before, most recent first, see
+/// [`variable_history`](crate::loader::variable_history). Up and down step through them while
+/// the input is focused.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct VariableRecall {
+    values: Vec<SharedString>,
+    /// Position of the shown value in `values`
+    index: Option<usize>,
+}
+
+impl VariableRecall {
+    /// The input starts with the most recent value, if there is one
+    pub fn new(values: Vec<SharedString>) -> Self {
+        let index = (!values.is_empty()).then_some(0);
+        Self { values, index }
+    }
+
+    pub fn first(&self) -> Option<SharedString> {
+        self.values.first().cloned()
+    }
+
+    /// The next older or newer value, wrapping around at either end
+    pub fn step(&mut self, older: bool) -> Option<SharedString> {
+        let len = self.values.len();
+        if len == 0 {
+            return None;
+        }
+        let index = match (self.index, older) {
+            (Some(i), true) => (i + 1) % len,
+            (Some(i), false) => (i + len - 1) % len,
+            (None, true) => 0,
+            (None, false) => len - 1,
+        };
+        self.index = Some(index);
+        Some(self.values[index].clone())
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Expansion {
+    /// The `next_content` of the launcher, see `method = "next"`
+    Next,
+    /// Where the entry comes from, see [`Provenance`](crate::launcher::provenance::Provenance)
+    Source,
 }
 
 impl Focusable for SherlockMainWindow {
@@ -53,9 +214,19 @@ impl Focusable for SherlockMainWindow {
 impl SherlockMainWindow {
     pub fn apply_results(&mut self, results: Arc<[usize]>, query: String, cx: &mut Context<Self>) {
         let old_count = self.list_state.item_count();
-        let new_count = results.len();
+        self.columns = self.grid_columns(&results, cx);
+        let new_count = results.len().div_ceil(self.columns);
 
-        if let Some(&first_idx) = results.first() {
+        // refreshes of the data keep the selected entry, new results start at the top
+        let previous = self.filtered_indices.get(self.selected_index).copied();
+        let results_for = (self.mode.clone(), query.clone());
+        let query_changed = self.results_for.as_ref() != Some(&results_for);
+        let restored = restored_selection(previous, &results, query_changed);
+        self.results_for = Some(results_for);
+
+        if let Some(&first_idx) = results.first()
+            && restored.is_none()
+        {
             let needed_vars: Option<Vec<ExecVariable>> = {
                 let data_guard = self.data.read(cx);
                 data_guard
@@ -66,45 +237,117 @@ impl SherlockMainWindow {
             if let Some(vars_to_create) = needed_vars {
                 let current_top_idx = self.filtered_indices.get(self.selected_index).copied();
                 if current_top_idx != Some(first_idx) {
-                    self.variable_input = vars_to_create
-                        .into_iter()
-                        .map(|var| {
-                            cx.new(|cx| TextInput {
-                                focus_handle: cx.focus_handle(),
-                                content: "".into(),
-                                placeholder: var.placeholder(),
-                                variable: Some(var),
-                                selected_range: 0..0,
-                                selection_reversed: false,
-                                marked_range: None,
-                                last_layout: None,
-                                last_bounds: None,
-                                is_selecting: false,
-                            })
-                        })
-                        .collect();
+                    self.create_variable_inputs(first_idx, vars_to_create, cx);
                 }
             } else {
-                self.variable_input.clear();
+                self.clear_variable_inputs();
             }
         }
 
-        self.active_bar = 0;
         self.filtered_indices = results;
         self.last_query = Some(query);
+        self.hovered = None;
 
         self.list_state.splice(0..old_count, new_count);
 
-        self.focus_first(cx);
+        if let Some(n) = restored {
+            // the expanded tile and the variable inputs still belong to the selected entry
+            self.selected_index = n;
+            self.list_state.scroll_to_reveal_item(self.row_of(n));
+        } else {
+            self.active_bar = 0;
+            self.expanded = None;
+            self.selected_index = 0;
+            if self.awaiting_spawn_focus && !self.filtered_indices.is_empty() {
+                self.awaiting_spawn_focus = false;
+                self.focus_first(cx);
+            } else {
+                self.focus_nth(0, cx);
+            }
+        }
 
+        self.fit_window(cx);
         cx.notify();
     }
+    /// Resizes the window to fit the shown results if [`max_height`](Self::max_height) is set.
+    /// Called whenever the rows, the error page or the run command change.
+    pub fn fit_window(&mut self, cx: &mut Context<Self>) {
+        let Some(max_height) = self.max_height else {
+            return;
+        };
+        let height = if self.error_page.is_some() {
+            max_height
+        } else if self.run_command.is_some() {
+            fitted_height(1, 1, max_height)
+        } else {
+            fitted_height(self.list_state.item_count(), self.columns, max_height)
+        };
+        if self.fitted_height == Some(height) {
+            return;
+        }
+        self.fitted_height = Some(height);
+
+        // the window can't be updated while it renders or dispatches to this view
+        let window = self.window;
+        cx.defer(move |cx| {
+            let _ = window.update(cx, |_, window, _| {
+                let size = window.bounds().size;
+                window.resize(Size::new(size.width, px(height)));
+            });
+        });
+    }
+    /// Filters the launchers again after they were reloaded. Everything that points into the
+    /// old data is dropped, so the selection starts at the top.
+    pub fn show_reloaded(&mut self, modes: Arc<[LauncherMode]>, cx: &mut Context<Self>) {
+        self.modes = modes;
+        self.max_height = max_height();
+        self.filtered_indices = Arc::new([]);
+        self.selected_index = 0;
+        self.results_for = None;
+        self.narrowing = None;
+        self.result_cache.clear();
+        self.pending_confirm = None;
+        self.expanded = None;
+        self.stale_indices.clear();
+        self.hovered = None;
+        self.context_idx = None;
+        self.context_actions = Arc::new([]);
+        self.last_query = None;
+        self.filter_and_sort(cx);
+    }
+    /// The configured grid columns if every result is an application shown in an alias mode,
+    /// otherwise 1
+    fn grid_columns(&self, results: &[usize], cx: &Context<Self>) -> usize {
+        let columns = ConfigGuard::read()
+            .map(|config| config.appearance.grid_columns)
+            .unwrap_or(1)
+            .max(1);
+        if columns == 1 || results.is_empty() || !matches!(self.mode, LauncherMode::Alias { .. }) {
+            return 1;
+        }
+        let data = self.data.read(cx);
+        let all_tiles = results
+            .iter()
+            .all(|i| data.get(*i).is_some_and(RenderableChild::is_tile));
+        if all_tiles { columns } else { 1 }
+    }
+    /// The row of the list that shows the `n`-th result
+    pub fn row_of(&self, n: usize) -> usize {
+        n / self.columns.max(1)
+    }
     pub fn filter_and_sort(&mut self, cx: &mut Context<Self>) {
-        let mut query = self.text_input.read(cx).content.to_lowercase();
+        let content = self.text_input.read(cx).content.clone();
+        let mut query = content.to_lowercase();
+        // paths keep their case, see `RenderableChild::based_show`
+        let mut typed = content.to_string();
 
         if Some(&query) == self.last_query.as_ref() {
             return;
         }
+        // a forced refresh may change the results of any query
+        if self.last_query.is_none() {
+            self.result_cache.clear();
+        }
 
         if let Some(task) = self.deferred_render_task.take() {
             drop(task);
@@ -116,89 +359,200 @@ impl SherlockMainWindow {
                 this.reset();
             });
             query = "".into();
+            typed.clear();
+        }
+
+        // run prefix: show a single entry for the command instead of searching
+        self.run_command = match self.mode {
+            LauncherMode::Alias { .. } => None,
+            _ => ConfigGuard::read()
+                .ok()
+                .and_then(|config| RunCommand::parse(&content, &config.behavior.run_prefix)),
+        };
+        if self.run_command.is_some() {
+            self.apply_results(Arc::new([]), query, cx);
+            return;
+        }
+
+        self.spawn_lookups(&query, cx);
+
+        // the query as typed, see `based_show`
+        let cache_key = (self.mode.clone(), typed.clone());
+        if let Some(results) = self.result_cache.get(&cache_key.0, &cache_key.1) {
+            self.apply_results(results, query, cx);
+            return;
         }
 
         let data_arc = self.data.read(cx).clone();
+        let cache_generation = self.result_cache.generation();
+        let hidden = app_launcher::hidden_entries();
         let mode = self.mode.clone();
+        // forced refreshes check every child again
+        let narrowing = self.last_query.as_ref().and(self.narrowing.clone());
+        let (dedup, candidate_limit, params, sorting) = ConfigGuard::read()
+            .map(|config| {
+                (
+                    config.search.dedup,
+                    config.search.candidate_limit.filter(|&limit| limit > 0),
+                    ScoringParams {
+                        debug: debug_search(
+                            config.debug.search,
+                            std::env::var("DEBUG_SEARCH").ok().as_deref(),
+                        ),
+                        ..ScoringParams::from(&config.search)
+                    },
+                    config.search.sorting,
+                )
+            })
+            .unwrap_or_else(|_| (false, None, ScoringParams::default(), Sorting::default()));
         self.deferred_render_task = Some(cx.spawn(
-            |this: WeakEntity<SherlockMainWindow>, cx: &mut AsyncApp| {
+            move |this: WeakEntity<SherlockMainWindow>, cx: &mut AsyncApp| {
                 let mut cx = cx.clone();
                 async move {
+                    let started = Instant::now();
                     let mode = mode.as_str();
                     let is_home = query.is_empty() && mode == "all";
+                    // children hold normalized search strings, see `construct_search`
+                    let search_query = normalize_search(cap_query(&query));
+                    let search_query = search_query.as_ref();
 
-                    // collects Vec<(index, priority)>
-                    let mut results: Vec<(usize, f32)> = (0..data_arc.len())
-                        .into_par_iter()
-                        .map(|i| (i, &data_arc[i]))
-                        .filter(|(_, data)| {
-                            let home = data.home();
-                            // [Rule 1]
-                            // Case 1: Early return if mode applies but item is not assigned to that mode
-                            // Case 2: Early return if current mode is not required mode for item
-                            if Some(mode) != data.alias() {
-                                if mode != "all" || data.priority() < 1.0 {
-                                    return false;
-                                }
-                            }
+                    // a longer query only matches children the previous one could, so only
+                    // those are checked again
+                    let pool = narrowing
+                        .as_ref()
+                        .and_then(|narrowing| narrowing.pool_for(search_query, mode, &data_arc));
 
-                            // [Rule 2]
-                            // Early return if item should always show (websearch for example)
-                            if home == HomeType::Persist {
-                                return true;
-                            }
+                    // index and how the child was checked. Children that can't show for a
+                    // longer query either are left out.
+                    let check = |i: usize| -> Option<(usize, Checked)> {
+                        let data = &data_arc[i];
+                        // entries hidden through the context menu
+                        if hidden.contains(&i) {
+                            return None;
+                        }
 
-                            // [Rule 3]
-                            // Early return if based show (calc for example) applies
-                            if let Some(based) = data.based_show(&query) {
-                                return based;
+                        let home = data.home();
+                        // [Rule 1]
+                        // Case 1: Early return if mode applies but item is not assigned to that mode
+                        // Case 2: Early return if current mode is not required mode for item
+                        if Some(mode) != data.alias() {
+                            if mode != "all" || data.priority() < 1.0 {
+                                return None;
                             }
+                        }
 
-                            // [Rule 4]
-                            // Early return if not home but item is assigned to only show on home
-                            if !is_home && home == HomeType::OnlyHome {
-                                return false;
-                            }
+                        // [Rule 2]
+                        // Early return if item should always show (websearch for example)
+                        if home == HomeType::Persist {
+                            return Some((i, Checked::Pinned));
+                        }
 
-                            // [Rule 5]
-                            // Early return if item should only show on search but mode is home
-                            if is_home && home == HomeType::Search {
-                                return false;
-                            }
+                        // [Rule 3]
+                        // Early return if based show (calc for example) applies. It is
+                        // decided again for every query.
+                        if let Some(based) = data.based_show(&query, &typed, mode) {
+                            let checked = if based {
+                                Checked::Pinned
+                            } else {
+                                Checked::Unmatched
+                            };
+                            return Some((i, checked));
+                        }
 
-                            // [Rule 6]
-                            // Check if query matches
-                            data.search().fuzzy_match(&query)
-                        })
-                        .map(|(i, data)| {
-                            let mut match_in = data.search();
-                            if let LauncherType::App(app) = data.launcher_type() {
-                                if !app.use_keywords {
-                                    match_in = data.name().unwrap_or_default()
-                                }
-                            }
+                        // [Rule 4]
+                        // Early return if not home but item is assigned to only show on home
+                        if !is_home && home == HomeType::OnlyHome {
+                            return None;
+                        }
 
-                            let prio = make_prio(data.priority(), &query, match_in);
-                            (i, prio)
-                        })
+                        // [Rule 5]
+                        // Early return if item should only show on search but mode is home
+                        if is_home && home == HomeType::Search {
+                            return None;
+                        }
+
+                        // [Rule 6]
+                        // Check if query matches
+                        check_query(&data_arc, i, search_query, candidate_limit, &params)
+                    };
+                    let checked: Vec<(usize, Checked)> = match pool {
+                        Some(pool) => pool.par_iter().filter_map(|&i| check(i)).collect(),
+                        None => (0..data_arc.len())
+                            .into_par_iter()
+                            .filter_map(check)
+                            .collect(),
+                    };
+                    let checked = resolve_deferred(
+                        &data_arc,
+                        checked,
+                        search_query,
+                        candidate_limit,
+                        &params,
+                    );
+                    // the home page shows other children than any query
+                    let narrowing = (!search_query.is_empty()).then(|| Narrowing {
+                        query: search_query.to_string(),
+                        mode: mode.to_string(),
+                        data: Arc::downgrade(&data_arc),
+                        pool: checked.iter().map(|&(i, _)| i).collect(),
+                    });
+
+                    // collects Vec<(index, pinned)>
+                    let candidates: Vec<(usize, bool)> = checked
+                        .into_iter()
+                        .filter_map(|(i, checked)| Some((i, checked.pinned()?)))
                         .collect();
+                    // children shown through `based_show` hold state of this query, e.g. the
+                    // calculator result, which a later query replaces
+                    let cacheable = !candidates
+                        .iter()
+                        .any(|&(i, pinned)| pinned && data_arc[i].home() != HomeType::Persist);
 
-                    // drop here to release lock faster
-                    drop(data_arc);
+                    // collects Vec<(index, priority)>
+                    let mut results = score_candidates(
+                        &data_arc,
+                        candidates,
+                        search_query,
+                        candidate_limit,
+                        &params,
+                    );
 
-                    // sort based on priority
+                    // sort based on priority, ties keep the data order, e.g. of dmenu input
                     results.sort_unstable_by(|a, b| {
-                        a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal)
+                        let order = a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal);
+                        match sorting {
+                            Sorting::Alphabetical => order.then_with(|| {
+                                sort_name(&data_arc[a.0]).cmp(sort_name(&data_arc[b.0]))
+                            }),
+                            _ => order,
+                        }
+                        .then(a.0.cmp(&b.0))
                     });
 
-                    // strip the priority from results
-                    let results_arc: Arc<[usize]> = results
-                        .into_iter()
-                        .map(|(i, _)| i)
-                        .collect::<Vec<_>>()
-                        .into();
+                    // strip the priority from results and remove duplicate targets across
+                    // launchers
+                    let indices = results.into_iter().map(|(i, _)| i);
+                    let results_arc: Arc<[usize]> = if dedup {
+                        dedup_results(indices.collect(), &data_arc).into()
+                    } else {
+                        indices.collect()
+                    };
+
+                    // drop here to release lock faster
+                    drop(data_arc);
+                    stats::record_filter_duration(started.elapsed());
 
                     this.update(&mut cx, |this, cx| {
+                        this.narrowing = narrowing;
+                        if cacheable {
+                            let (mode, typed) = cache_key;
+                            this.result_cache.insert(
+                                cache_generation,
+                                mode,
+                                typed,
+                                Arc::clone(&results_arc),
+                            );
+                        }
                         this.apply_results(results_arc, query, cx);
                     })
                     .ok();
@@ -208,8 +562,53 @@ impl SherlockMainWindow {
             },
         ));
     }
+    /// Starts the lookups of the documentation launchers of the current mode. The results are
+    /// filtered in once they arrive; the next query cancels a lookup that is still waiting
+    /// for typing to pause or running.
+    fn spawn_lookups(&mut self, query: &str, cx: &mut Context<Self>) {
+        let lookups: Vec<Arc<DocLookup>> = {
+            let mode = self.mode.as_str();
+            self.data
+                .read(cx)
+                .iter()
+                .filter(|child| child.alias() == Some(mode))
+                .filter_map(|child| match child {
+                    RenderableChild::DocLike { inner, .. } => inner.lookup().cloned(),
+                    _ => None,
+                })
+                .filter(|lookup| lookup.request(query))
+                .collect()
+        };
+        if lookups.is_empty() {
+            return;
+        }
+
+        let query = query.to_string();
+        self.lookup_task = Some(cx.spawn(
+            move |this: WeakEntity<SherlockMainWindow>, cx: &mut AsyncApp| {
+                let mut cx = cx.clone();
+                async move {
+                    cx.background_executor().timer(LOOKUP_DEBOUNCE).await;
+                    let mut changed = false;
+                    for lookup in lookups {
+                        changed |= lookup.run(query.clone()).await;
+                    }
+                    if changed {
+                        this.update(&mut cx, |this, cx| {
+                            this.last_query = None; // forces update
+                            this.filter_and_sort(cx);
+                        })
+                        .ok();
+                    }
+                }
+            },
+        ));
+    }
 }
 
+/// Time typing has to pause before a documentation lookup runs
+const LOOKUP_DEBOUNCE: Duration = Duration::from_millis(150);
+
 #[derive(PartialEq, Eq, Clone)]
 pub enum LauncherMode {
     Home,
@@ -235,22 +634,24 @@ impl LauncherMode {
             Self::Alias { name, .. } => name.clone(),
         }
     }
+    /// The mode with the alias `short`
+    pub fn from_alias(short: &str, modes: &[Self]) -> Option<Self> {
+        modes
+            .iter()
+            .find(|mode| match mode {
+                Self::Alias { short: alias, .. } => alias.eq_ignore_ascii_case(short),
+                _ => false,
+            })
+            .cloned()
+    }
     pub fn transition_for_query(&mut self, query: &str, modes: &[Self]) -> bool {
         match (self, query.is_empty()) {
             (m @ Self::Search, true) => *m = Self::Home,
             (m @ Self::Home, false) => *m = Self::Search,
             (m @ Self::Search, false) | (m @ Self::Alias { .. }, false) => {
                 if let Some(alias_input) = query.strip_suffix(' ') {
-                    let found_mode = modes.iter().find(|mode| {
-                        if let Self::Alias { short, .. } = mode {
-                            short.eq_ignore_ascii_case(alias_input)
-                        } else {
-                            false
-                        }
-                    });
-
-                    if let Some(new_mode) = found_mode {
-                        *m = new_mode.clone();
+                    if let Some(new_mode) = Self::from_alias(alias_input, modes) {
+                        *m = new_mode;
                         // should clear search bar
                         return true;
                     }
@@ -264,7 +665,345 @@ impl LauncherMode {
     }
 }
 
-fn search_score(query: &str, match_in: &str) -> f32 {
+/// Position of the previously selected data index `previous` in the new `results`. `None` if
+/// the selection should start over, i.e. the query changed or the entry is gone.
+fn restored_selection(
+    previous: Option<usize>,
+    results: &[usize],
+    query_changed: bool,
+) -> Option<usize> {
+    if query_changed {
+        return None;
+    }
+    results.iter().position(|&idx| Some(idx) == previous)
+}
+
+/// The result one row below (or above) the `n`-th of `count` results in rows of `columns`.
+/// Moving down from the row above a shorter last row lands on its last result.
+fn row_step(n: usize, count: usize, columns: usize, down: bool) -> Option<usize> {
+    if down {
+        let last = count.checked_sub(1)?;
+        (n / columns < last / columns).then(|| (n + columns).min(last))
+    } else {
+        n.checked_sub(columns)
+    }
+}
+
+/// The result next to the `n`-th of `count` results within its row of `columns`
+fn column_step(n: usize, count: usize, columns: usize, right: bool) -> Option<usize> {
+    let column = n % columns;
+    if right {
+        (column + 1 < columns && n + 1 < count).then_some(n + 1)
+    } else {
+        (column > 0).then(|| n - 1)
+    }
+}
+
+/// Estimated height of a result in the list, including its margin
+const ROW_HEIGHT: f32 = 53.;
+/// Estimated height of a row of application tiles, see
+/// [`grid_columns`](SherlockMainWindow::grid_columns)
+const TILE_ROW_HEIGHT: f32 = 100.;
+/// The search bar, the mode chip, the status bar and the window border
+const CHROME_HEIGHT: f32 = 110.;
+
+/// `appearance.max_height` of the current config
+pub fn max_height() -> Option<f32> {
+    ConfigGuard::read()
+        .ok()
+        .and_then(|config| config.appearance.max_height)
+        .map(|height| height as f32)
+}
+
+/// Height of a window showing `rows` rows of results in `columns`, at most `max_height`
+fn fitted_height(rows: usize, columns: usize, max_height: f32) -> f32 {
+    let row_height = if columns > 1 {
+        TILE_ROW_HEIGHT
+    } else {
+        ROW_HEIGHT
+    };
+    (CHROME_HEIGHT + rows as f32 * row_height).min(max_height)
+}
+
+/// Shortest scrollbar thumb, so long lists keep something to grab
+const MIN_THUMB: f32 = 24.;
+
+/// Top and height of the scrollbar thumb for a list scrolled `offset` of `max_offset` pixels
+/// in a `viewport` high track. `None` if all items fit.
+fn scrollbar_thumb(viewport: f32, max_offset: f32, offset: f32) -> Option<(f32, f32)> {
+    if viewport <= 0. || max_offset <= 0. {
+        return None;
+    }
+    let height = (viewport * viewport / (viewport + max_offset)).max(MIN_THUMB.min(viewport));
+    let top = (viewport - height) * (offset / max_offset).clamp(0., 1.);
+    Some((top, height))
+}
+
+/// The scroll offset that moves the thumb of [`scrollbar_thumb`] to `top`
+fn scrollbar_offset(viewport: f32, max_offset: f32, top: f32) -> f32 {
+    let Some((_, height)) = scrollbar_thumb(viewport, max_offset, 0.) else {
+        return 0.;
+    };
+    let track = viewport - height;
+    if track <= 0. {
+        return 0.;
+    }
+    (top / track).clamp(0., 1.) * max_offset
+}
+
+/// How a child fared against the query in
+/// [`filter_and_sort`](SherlockMainWindow::filter_and_sort)
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Checked {
+    /// Shown regardless of the query, e.g. the web search or a calculator result
+    Pinned,
+    Matched,
+    /// Not shown now, but a longer query could show it
+    Unmatched,
+    /// A fuzzy match at best, only checked if there are too few other candidates
+    Deferred,
+}
+
+impl Checked {
+    /// Whether the child is pinned, or `None` if it isn't shown
+    fn pinned(self) -> Option<bool> {
+        match self {
+            Self::Pinned => Some(true),
+            Self::Matched => Some(false),
+            Self::Unmatched | Self::Deferred => None,
+        }
+    }
+}
+
+/// Children a query could show, collected by the last run of
+/// [`filter_and_sort`](SherlockMainWindow::filter_and_sort). A query that extends `query` only
+/// matches a subset of them, so typing another character doesn't scan all of the data again.
+///
+/// The pool holds every child that matched, as well as those decided anew for every query like
+/// the calculator or entries with an `exact` or `word` match policy, and fuzzy candidates that
+/// were never checked because enough others contained the query.
+#[derive(Clone)]
+pub struct Narrowing {
+    /// Normalized query the pool was collected for, never empty
+    query: String,
+    mode: String,
+    data: Weak<Vec<RenderableChild>>,
+    pool: Arc<[usize]>,
+}
+
+impl Narrowing {
+    /// The children to check for `query`, or `None` if every child has to be checked because the
+    /// query shrank or changed, or the mode or data are different
+    fn pool_for(
+        &self,
+        query: &str,
+        mode: &str,
+        data: &Arc<Vec<RenderableChild>>,
+    ) -> Option<Arc<[usize]>> {
+        (query.starts_with(&self.query)
+            && mode == self.mode
+            && std::ptr::eq(self.data.as_ptr(), Arc::as_ptr(data)))
+        .then(|| Arc::clone(&self.pool))
+    }
+}
+
+/// Queries whose results are kept by [`ResultCache`]
+const RESULT_CACHE_SIZE: usize = 16;
+
+/// Results of the most recently filtered queries, keyed by the mode and the query as typed.
+/// Backspacing or retyping a query shows its results again without filtering. It is cleared
+/// whenever the data changes or a refresh is forced.
+#[derive(Default)]
+pub struct ResultCache {
+    /// Least recently used first
+    entries: VecDeque<(LauncherMode, String, Arc<[usize]>)>,
+    /// Counts the clears, results filtered before one are not inserted anymore
+    generation: u64,
+}
+
+impl ResultCache {
+    fn get(&mut self, mode: &LauncherMode, query: &str) -> Option<Arc<[usize]>> {
+        let pos = self
+            .entries
+            .iter()
+            .position(|(m, q, _)| m == mode && q == query)?;
+        let entry = self.entries.remove(pos)?;
+        let results = Arc::clone(&entry.2);
+        self.entries.push_back(entry);
+        Some(results)
+    }
+
+    /// Adds the results of a filter that started in `generation`, see [`Self::generation`]
+    fn insert(
+        &mut self,
+        generation: u64,
+        mode: LauncherMode,
+        query: String,
+        results: Arc<[usize]>,
+    ) {
+        // the cache was cleared while filtering, the results may point into old data
+        if generation != self.generation {
+            return;
+        }
+        self.entries.retain(|(m, q, _)| *m != mode || *q != query);
+        if self.entries.len() >= RESULT_CACHE_SIZE {
+            self.entries.pop_front();
+        }
+        self.entries.push_back((mode, query, results));
+    }
+
+    fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.generation += 1;
+    }
+}
+
+/// Removes results pointing to the same exec/url as a higher ranked result.
+/// Expects `indices` to already be sorted by priority.
+fn dedup_results(indices: Vec<usize>, data: &[RenderableChild]) -> Vec<usize> {
+    let mut seen: HashSet<&str> = HashSet::with_capacity(indices.len());
+    indices
+        .into_iter()
+        .filter(
+            |&i| match data.get(i).and_then(RenderableChild::dedup_key) {
+                Some(key) => seen.insert(key),
+                None => true,
+            },
+        )
+        .collect()
+}
+
+/// Queries longer than this are matched as plain substrings, without fuzzy matching or
+/// levenshtein scoring
+const LONG_QUERY_LEN: usize = 64;
+/// Only this many bytes of a query are considered while searching, e.g. after an accidental
+/// large paste
+const MAX_QUERY_LEN: usize = 256;
+
+/// The normalized name of a child, i.e. the primary field of its search string
+fn sort_name(child: &RenderableChild) -> &str {
+    let search = child.search();
+    search.split_once(';').map_or(search, |(name, _)| name)
+}
+
+/// Truncates the query to [`MAX_QUERY_LEN`] bytes at a char boundary
+fn cap_query(query: &str) -> &str {
+    if query.len() <= MAX_QUERY_LEN {
+        return query;
+    }
+    let mut end = MAX_QUERY_LEN;
+    while !query.is_char_boundary(end) {
+        end -= 1;
+    }
+    &query[..end]
+}
+
+/// Tunables of the scoring functions, read from the `[search]` config once per
+/// [`filter_and_sort`](SherlockMainWindow::filter_and_sort) run
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ScoringParams {
+    prefix_bonus: f32,
+    fuzzy_window: usize,
+    levenshtein_max_len_diff: usize,
+    count_weight: f32,
+    /// Logs every score, see [`debug_search`]
+    debug: bool,
+}
+
+impl From<&ConfigSearch> for ScoringParams {
+    fn from(search: &ConfigSearch) -> Self {
+        Self {
+            prefix_bonus: search.prefix_bonus.clamp(0.0, 0.2),
+            fuzzy_window: search.fuzzy_window.max(1),
+            levenshtein_max_len_diff: search.levenshtein_max_len_diff,
+            count_weight: search.count_weight.max(0.0),
+            debug: false,
+        }
+    }
+}
+
+/// Whether scores are logged. `debug.search` in the config or `--debug-search` turn it on, the
+/// `DEBUG_SEARCH` environment variable overrides both if it is set to a boolean.
+fn debug_search(config: bool, env: Option<&str>) -> bool {
+    match env.map(str::trim) {
+        Some("true" | "1") => true,
+        Some("false" | "0") => false,
+        _ => config,
+    }
+}
+
+impl Default for ScoringParams {
+    fn default() -> Self {
+        Self::from(&ConfigSearch::default())
+    }
+}
+
+fn matches_query(search: &str, query: &str, params: &ScoringParams) -> bool {
+    if query.len() > LONG_QUERY_LEN {
+        memchr::memmem::find(search.as_bytes(), query.as_bytes()).is_some()
+    } else {
+        search.fuzzy_match(query, params.fuzzy_window)
+    }
+}
+
+/// Like [`matches_query`], but only accepts the matches allowed by `policy`. Every policy
+/// matches an empty query.
+fn matches_policy(search: &str, query: &str, policy: MatchPolicy, params: &ScoringParams) -> bool {
+    if query.is_empty() {
+        return true;
+    }
+    let mut elements = search.split(';');
+    match policy {
+        MatchPolicy::Fuzzy => matches_query(search, query, params),
+        MatchPolicy::Prefix => elements.any(|element| element.starts_with(query)),
+        MatchPolicy::Exact => elements.any(|element| element == query),
+        MatchPolicy::Word => elements.any(|element| contains_word(element, query)),
+    }
+}
+
+/// Returns true if `query` occurs in `element` without letters or digits directly around it
+fn contains_word(element: &str, query: &str) -> bool {
+    element.match_indices(query).any(|(i, _)| {
+        let before = element[..i].chars().next_back();
+        let after = element[i + query.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// Added to the score of matches outside of the primary field, so that e.g. a match in an
+/// app's comment ranks below the same match in another app's name
+const SECONDARY_PENALTY: f32 = 0.3;
+
+/// A part of a search string. Lower penalties rank higher.
+struct SearchField<'a> {
+    text: &'a str,
+    penalty: f32,
+}
+
+impl<'a> SearchField<'a> {
+    /// Splits a search string into its primary field (the name, up to the first `;`) and the
+    /// secondary fields such as keywords, comments or urls
+    fn split(match_in: &'a str) -> [Self; 2] {
+        let (primary, secondary) = match_in.split_once(';').unwrap_or((match_in, ""));
+        [
+            Self {
+                text: primary,
+                penalty: 0.0,
+            },
+            Self {
+                text: secondary,
+                penalty: SECONDARY_PENALTY,
+            },
+        ]
+    }
+}
+
+/// Scores the best element of `match_in` that `policy` accepts
+fn search_score(query: &str, match_in: &str, policy: MatchPolicy, params: &ScoringParams) -> f32 {
     if query.is_empty() {
         return 0.8;
     }
@@ -272,55 +1011,843 @@ fn search_score(query: &str, match_in: &str) -> f32 {
         return 1.0;
     }
 
-    let mut best_score = 1.0;
+    let mut best_score: f32 = 1.0;
 
-    for element in match_in.split(';') {
-        // skip emtpy elements
-        if element.is_empty() {
-            continue;
+    for field in SearchField::split(match_in) {
+        for element in field.text.split(';') {
+            // skip emtpy elements
+            if element.is_empty() {
+                continue;
+            }
+            if let Some(score) = element_score(query, element, policy, params) {
+                best_score = best_score.min((score + field.penalty).min(1.0));
+            }
         }
 
-        // early return on perfect match
-        if element == query {
-            return 0.0;
+        // nothing in the secondary fields can beat a perfect match
+        if best_score == 0.0 {
+            break;
         }
+    }
+    best_score
+}
+
+fn element_score(
+    query: &str,
+    element: &str,
+    policy: MatchPolicy,
+    params: &ScoringParams,
+) -> Option<f32> {
+    // elements the policy rejects don't count towards the score
+    let accepted = match policy {
+        MatchPolicy::Fuzzy => true,
+        MatchPolicy::Prefix => element.starts_with(query),
+        MatchPolicy::Exact => element == query,
+        MatchPolicy::Word => contains_word(element, query),
+    };
+    if !accepted {
+        return None;
+    }
+
+    // perfect match
+    if element == query {
+        return Some(0.0);
+    }
+
+    // prefix match
+    if element.starts_with(query) {
+        // bonus for coverage, e.g. 4 out of 5 chars match
+        let coverage = query.len() as f32 / element.len() as f32;
+        return Some(0.2 - params.prefix_bonus * coverage);
+    }
+
+    // prefix of a single word in longer text such as comments
+    if element
+        .split_whitespace()
+        .skip(1)
+        .any(|word| word.starts_with(query))
+    {
+        return Some(0.25);
+    }
+
+    // levenshtein matching, only fuzzy matches may be inexact
+    if policy == MatchPolicy::Fuzzy
+        && query.len() <= LONG_QUERY_LEN
+        && element.len().abs_diff(query.len()) <= params.levenshtein_max_len_diff
+    {
+        let dist = levenshtein::levenshtein(query, element);
+        return Some((dist as f32 / element.len() as f32).clamp(0.2, 1.0));
+    }
+
+    // whole words in the middle of an element, e.g. "rm -rf" in "sudo rm -rf build"
+    (policy == MatchPolicy::Word).then_some(0.25)
+}
+
+/// Scores the matches of a query. `candidates` holds the data index of every match and
+/// whether it is pinned, i.e. shown regardless of the query like web search or calculator
+/// entries.
+///
+/// With a `limit`, only that many candidates are scored. They are chosen by [`prefilter`],
+/// which is a lot cheaper than [`make_prio`] for large data sets.
+fn score_candidates(
+    data: &[RenderableChild],
+    mut candidates: Vec<(usize, bool)>,
+    query: &str,
+    limit: Option<usize>,
+    params: &ScoringParams,
+) -> Vec<(usize, f32)> {
+    if let Some(limit) = limit {
+        prefilter(data, &mut candidates, query, limit);
+    }
+
+    candidates
+        .into_par_iter()
+        .map(|(i, _)| {
+            let data = &data[i];
+            (
+                i,
+                make_prio(
+                    data.priority(),
+                    query,
+                    data.search(),
+                    data.match_policy(),
+                    params,
+                ),
+            )
+        })
+        .collect()
+}
 
-        // prefix match
-        if element.starts_with(query) {
-            // bonus for coverage, e.g. 4 out of 5 chars match
-            let coverage = query.len() as f32 / element.len() as f32;
-            let score = 0.1 + (0.1 * (1.0 - coverage));
-            if score < best_score {
-                best_score = score
+/// Keeps the `limit` most promising candidates. Pinned candidates come first, followed by
+/// prefix matches, substring matches and the remaining fuzzy matches. Ties are broken by the
+/// launcher priority, then by the data order.
+fn prefilter(
+    data: &[RenderableChild],
+    candidates: &mut Vec<(usize, bool)>,
+    query: &str,
+    limit: usize,
+) {
+    if limit == 0 || candidates.len() <= limit {
+        return;
+    }
+
+    let key = |&(i, pinned): &(usize, bool)| {
+        (
+            prefilter_tier(data[i].search(), query, pinned),
+            data[i].priority(),
+        )
+    };
+    let cmp = |a: &(u8, f32), b: &(u8, f32)| {
+        a.0.cmp(&b.0)
+            .then(a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+    };
+
+    let mut keyed: Vec<((u8, f32), (usize, bool))> = candidates
+        .par_iter()
+        .map(|candidate| (key(candidate), *candidate))
+        .collect();
+    keyed.select_nth_unstable_by(limit - 1, |a, b| cmp(&a.0, &b.0).then(a.1.0.cmp(&b.1.0)));
+    keyed.truncate(limit);
+
+    candidates.clear();
+    candidates.extend(keyed.into_iter().map(|(_, candidate)| candidate));
+}
+
+/// Checks whether the child at `i` matches `query` with its match policy. With a candidate
+/// limit, fuzzy matching is put off for children that don't contain the query, see
+/// [`resolve_deferred`].
+fn check_query(
+    data: &[RenderableChild],
+    i: usize,
+    query: &str,
+    candidate_limit: Option<usize>,
+    params: &ScoringParams,
+) -> Option<(usize, Checked)> {
+    let child = &data[i];
+    let policy = child.match_policy();
+    if candidate_limit.is_some()
+        && policy == MatchPolicy::Fuzzy
+        && prefilter_tier(child.search(), query, false) == FUZZY_TIER
+    {
+        Some((i, Checked::Deferred))
+    } else if matches_policy(child.search(), query, policy, params) {
+        Some((i, Checked::Matched))
+    } else {
+        policy.narrows().then_some((i, Checked::Unmatched))
+    }
+}
+
+/// Fuzzy matches the deferred children if fewer than `candidate_limit` others are shown.
+/// Otherwise [`prefilter`] would drop them anyway, and they stay deferred.
+fn resolve_deferred(
+    data: &[RenderableChild],
+    checked: Vec<(usize, Checked)>,
+    query: &str,
+    candidate_limit: Option<usize>,
+    params: &ScoringParams,
+) -> Vec<(usize, Checked)> {
+    let shown = checked
+        .iter()
+        .filter(|(_, checked)| checked.pinned().is_some())
+        .count();
+    if candidate_limit.is_none_or(|limit| shown >= limit) {
+        return checked;
+    }
+    checked
+        .into_par_iter()
+        .filter_map(|(i, checked)| match checked {
+            Checked::Deferred => check_query(data, i, query, None, params),
+            checked => Some((i, checked)),
+        })
+        .collect()
+}
+
+/// Tier of candidates that neither are pinned nor contain the query
+const FUZZY_TIER: u8 = 2;
+
+/// Ranks a candidate for [`prefilter`]: pinned candidates and prefix matches come first,
+/// followed by substring matches and [`FUZZY_TIER`]
+fn prefilter_tier(search: &str, query: &str, pinned: bool) -> u8 {
+    if pinned || search.starts_with(query) {
+        0
+    } else if memchr::memmem::find(search.as_bytes(), query.as_bytes()).is_some() {
+        1
+    } else {
+        FUZZY_TIER
+    }
+}
+
+fn make_prio(
+    prio: f32,
+    query: &str,
+    match_in: &str,
+    policy: MatchPolicy,
+    params: &ScoringParams,
+) -> f32 {
+    let score = search_score(query, match_in, policy, params);
+    // shift counts 3 to right; 1.34 → 1.0034 to make room for levenshtein (2 spaces for
+    // max .99)
+    let counters = prio.fract() / 100.0 * params.count_weight;
+    let result = prio.trunc() + (counters + score).min(0.99);
+    if params.debug {
+        let _ = sher_log!(format!(
+            "search \"{query}\" in \"{match_in}\": base prio {prio}, score {score}, result {result}"
+        ));
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::launcher::Launcher;
+    use crate::launcher::children::NameHighlight;
+    use crate::launcher::provenance::Transforms;
+    use crate::loader::utils::{AppData, construct_search};
+
+    /// Counts allocations for `bench_staged_filter`. Only built with the `alloc-count` feature,
+    /// so other test runs keep the system allocator.
+    #[cfg(feature = "alloc-count")]
+    mod alloc_count {
+        use std::alloc::{GlobalAlloc, Layout, System};
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        pub static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+        struct CountingAlloc;
+
+        unsafe impl GlobalAlloc for CountingAlloc {
+            unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+                ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+                unsafe { System.alloc(layout) }
+            }
+
+            unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+                unsafe { System.dealloc(ptr, layout) }
             }
-            continue;
         }
 
-        // levenshtein matching
-        if (element.len() as isize - query.len() as isize).abs() < 4 {
-            let dist = levenshtein::levenshtein(query, element);
-            let normed = (dist as f32 / element.len() as f32).clamp(0.2, 1.0);
-            if normed < best_score {
-                best_score = normed
+        #[global_allocator]
+        static GLOBAL: CountingAlloc = CountingAlloc;
+    }
+
+    /// Allocations made so far by the whole test binary
+    #[cfg(feature = "alloc-count")]
+    fn allocations() -> Option<usize> {
+        Some(alloc_count::ALLOCATIONS.load(std::sync::atomic::Ordering::Relaxed))
+    }
+
+    #[cfg(not(feature = "alloc-count"))]
+    fn allocations() -> Option<usize> {
+        None
+    }
+
+    fn app(launcher: &Arc<Launcher>, name: &str, exec: &str) -> RenderableChild {
+        RenderableChild::AppLike {
+            launcher: Arc::clone(launcher),
+            inner: AppData {
+                name: Some(SharedString::from(name.to_string())),
+                exec: Some(exec.to_string()),
+                search_string: construct_search(Some(name), "", false),
+                priority: None,
+                icon: None,
+                icon_name: None,
+                desktop_file: None,
+                actions: Arc::new([]),
+                vars: Vec::new(),
+                terminal: false,
+                transforms: Transforms::default(),
+                match_policy: MatchPolicy::default(),
+                accent: None,
+                subtitle: None,
+                confirm: false,
+                name_highlight: NameHighlight::default(),
+            },
+        }
+    }
+
+    #[test]
+    fn test_fitted_height() {
+        // a bare search bar without results
+        assert_eq!(fitted_height(0, 1, 600.), CHROME_HEIGHT);
+        assert_eq!(fitted_height(3, 1, 600.), CHROME_HEIGHT + 3. * ROW_HEIGHT);
+        assert_eq!(
+            fitted_height(2, 4, 600.),
+            CHROME_HEIGHT + 2. * TILE_ROW_HEIGHT
+        );
+        // long lists scroll within the largest window
+        assert_eq!(fitted_height(50, 1, 600.), 600.);
+    }
+
+    #[test]
+    fn test_scrollbar_thumb() {
+        // everything fits
+        assert_eq!(scrollbar_thumb(400., 0., 0.), None);
+        assert_eq!(scrollbar_thumb(0., 100., 0.), None);
+
+        // twice the viewport: half high thumb, moving through the other half
+        assert_eq!(scrollbar_thumb(400., 400., 0.), Some((0., 200.)));
+        assert_eq!(scrollbar_thumb(400., 400., 200.), Some((100., 200.)));
+        assert_eq!(scrollbar_thumb(400., 400., 400.), Some((200., 200.)));
+        assert_eq!(scrollbar_thumb(400., 400., 900.), Some((200., 200.)));
+
+        // very long lists keep a thumb that can be grabbed
+        let (_, height) = scrollbar_thumb(400., 100_000., 0.).unwrap();
+        assert_eq!(height, MIN_THUMB);
+
+        assert_eq!(scrollbar_offset(400., 400., 100.), 200.);
+        assert_eq!(scrollbar_offset(400., 400., -50.), 0.);
+        assert_eq!(scrollbar_offset(400., 400., 300.), 400.);
+        assert_eq!(scrollbar_offset(400., 0., 100.), 0.);
+    }
+
+    #[test]
+    fn test_grid_steps() {
+        // 7 results in rows of 3: [0 1 2] [3 4 5] [6]
+        assert_eq!(row_step(1, 7, 3, true), Some(4));
+        assert_eq!(row_step(5, 7, 3, true), Some(6));
+        assert_eq!(row_step(6, 7, 3, true), None);
+        assert_eq!(row_step(6, 7, 3, false), Some(3));
+        assert_eq!(row_step(2, 7, 3, false), None);
+
+        assert_eq!(column_step(3, 7, 3, true), Some(4));
+        assert_eq!(column_step(5, 7, 3, true), None);
+        assert_eq!(column_step(6, 7, 3, true), None);
+        assert_eq!(column_step(3, 7, 3, false), None);
+        assert_eq!(column_step(4, 7, 3, false), Some(3));
+
+        // a single column behaves like the list
+        assert_eq!(row_step(0, 2, 1, true), Some(1));
+        assert_eq!(row_step(1, 2, 1, true), None);
+        assert_eq!(row_step(1, 2, 1, false), Some(0));
+        assert_eq!(column_step(0, 2, 1, true), None);
+        assert_eq!(row_step(0, 0, 1, true), None);
+    }
+
+    #[test]
+    fn test_selection_survives_refresh() {
+        // the selected entry moved after a refresh
+        assert_eq!(restored_selection(Some(7), &[3, 5, 7, 9], false), Some(2));
+        assert_eq!(restored_selection(Some(7), &[7, 3, 5], false), Some(0));
+
+        // the selected entry was removed
+        assert_eq!(restored_selection(Some(7), &[3, 5, 9], false), None);
+        assert_eq!(restored_selection(None, &[3, 5], false), None);
+
+        // typing starts over even if the entry is still there
+        assert_eq!(restored_selection(Some(7), &[3, 5, 7], true), None);
+    }
+
+    #[test]
+    fn test_dedup_keeps_highest_priority() {
+        let apps = Arc::new(Launcher {
+            name: Some("App Launcher".into()),
+            priority: 1,
+            ..Default::default()
+        });
+        let categories = Arc::new(Launcher {
+            name: Some("Categories".into()),
+            priority: 3,
+            ..Default::default()
+        });
+
+        let data = vec![
+            app(&categories, "Firefox", "firefox %u"),
+            app(&apps, "Firefox", "firefox %u"),
+            app(&apps, "Files", "nautilus"),
+            app(&categories, "Files", "thunar"),
+        ];
+
+        // sorted by priority: app launcher entries first
+        let indices = dedup_results(vec![1, 2, 0, 3], &data);
+        assert_eq!(indices, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_huge_query_is_capped() {
+        let query = "lorem ipsum dolor sit amet ".repeat(300);
+        let capped = cap_query(&query);
+        assert_eq!(capped.len(), MAX_QUERY_LEN);
+        assert!(query.starts_with(capped));
+
+        let item = "application 1;keyword;a much longer description 1";
+        assert!(!matches_query(item, capped, &ScoringParams::default()));
+        assert_eq!(
+            search_score(capped, item, MatchPolicy::Fuzzy, &ScoringParams::default()),
+            1.0
+        );
+        assert!(matches_query(
+            &query,
+            cap_query(&query),
+            &ScoringParams::default()
+        ));
+        let umlauts = "ä".repeat(200);
+        let capped = cap_query(&umlauts);
+        assert!(!capped.is_empty() && capped.len() <= MAX_QUERY_LEN);
+    }
+
+    #[test]
+    fn test_name_match_outranks_secondary_match() {
+        let by_name = "web;internet;";
+        let by_keyword = "firefox;web;browser;";
+        let by_comment = "firefox;browser;browse the web";
+
+        let name = search_score(
+            "web",
+            by_name,
+            MatchPolicy::Fuzzy,
+            &ScoringParams::default(),
+        );
+        let keyword = search_score(
+            "web",
+            by_keyword,
+            MatchPolicy::Fuzzy,
+            &ScoringParams::default(),
+        );
+        let comment = search_score(
+            "web",
+            by_comment,
+            MatchPolicy::Fuzzy,
+            &ScoringParams::default(),
+        );
+        assert_eq!(name, 0.0);
+        assert!(name < keyword);
+        assert!(keyword < comment);
+        assert!(comment < 1.0);
+
+        // a prefix of the name still beats an exact keyword
+        assert!(
+            search_score(
+                "fire",
+                "firefox;browser",
+                MatchPolicy::Fuzzy,
+                &ScoringParams::default()
+            ) < search_score(
+                "fire",
+                "alarm;fire",
+                MatchPolicy::Fuzzy,
+                &ScoringParams::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_dedup_keeps_items_without_exec() {
+        let launcher = Arc::new(Launcher::default());
+        let mut first = app(&launcher, "Weather", "");
+        let mut second = app(&launcher, "Weather", "");
+        for child in [&mut first, &mut second] {
+            if let RenderableChild::AppLike { inner, .. } = child {
+                inner.exec = None;
             }
         }
+
+        let indices = dedup_results(vec![0, 1], &[first, second]);
+        assert_eq!(indices, vec![0, 1]);
     }
-    best_score
-}
 
-fn make_prio(prio: f32, query: &str, match_in: &str) -> f32 {
-    let score = search_score(query, match_in);
-    // shift counts 3 to right; 1.34 → 1.0034 to make room for levenshtein (2 spaces for
-    // max .99)
-    let counters = prio.fract() / 100.0;
-    if let Ok(var) = std::env::var("DEBUG_SEARCH") {
-        if var == "true" {
-            println!("Base Prio: {}", prio);
+    fn ranked(data: &[RenderableChild], query: &str, limit: Option<usize>) -> Vec<usize> {
+        rank(data, query, limit, true)
+    }
+
+    /// Ranks like `filter_and_sort`, or fuzzy matches every child with `defer` set to false
+    fn rank(
+        data: &[RenderableChild],
+        query: &str,
+        limit: Option<usize>,
+        defer: bool,
+    ) -> Vec<usize> {
+        let params = ScoringParams::default();
+        let defer_limit = limit.filter(|_| defer);
+        let checked = (0..data.len())
+            .filter_map(|i| check_query(data, i, query, defer_limit, &params))
+            .collect();
+        let candidates = resolve_deferred(data, checked, query, defer_limit, &params)
+            .into_iter()
+            .filter_map(|(i, checked)| Some((i, checked.pinned()?)))
+            .collect();
+        let mut results = score_candidates(data, candidates, query, limit, &params);
+        results.sort_unstable_by(|a, b| a.1.total_cmp(&b.1).then(a.0.cmp(&b.0)));
+        results.into_iter().map(|(i, _)| i).collect()
+    }
+
+    #[test]
+    fn test_prefilter_keeps_best_candidates() {
+        let launcher = Arc::new(Launcher {
+            priority: 1,
+            ..Default::default()
+        });
+        let data = vec![
+            app(&launcher, "Gnome Terminal", "gnome-terminal"),
+            app(&launcher, "Terminal", "kgx"),
+            app(&launcher, "Thunderbird Mail", "thunderbird"),
+            app(&launcher, "Termius", "termius"),
+        ];
+
+        let full = ranked(&data, "term", None);
+        assert_eq!(full.len(), 3);
+        assert_eq!(ranked(&data, "term", Some(2)), full[..2]);
+
+        // fuzzy matches are checked once there are too few substring matches
+        let fuzzy = ranked(&data, "gntrm", Some(2));
+        assert_eq!(fuzzy, [0]);
+        assert_eq!(fuzzy, ranked(&data, "gntrm", None));
+
+        // pinned candidates survive even without matching the query
+        let mut candidates = vec![(0, false), (1, false), (2, true), (3, false)];
+        prefilter(&data, &mut candidates, "term", 2);
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.contains(&(2, true)));
+    }
+
+    /// Compares the staged filter with fuzzy matching and scoring every child, and checks that
+    /// it shows the same results. Run with
+    /// `cargo test --features alloc-count bench_staged_filter -- --ignored --nocapture`
+    /// to count allocations as well, the staged filter must not allocate per child.
+    #[test]
+    #[ignore]
+    fn bench_staged_filter() {
+        let launcher = Arc::new(Launcher {
+            priority: 1,
+            ..Default::default()
+        });
+        let data: Vec<RenderableChild> = (0..200_000)
+            .map(|i| app(&launcher, &format!("application {i} entry"), "true"))
+            .collect();
+
+        // difference of the allocation counts, if they are counted
+        let since = |before: Option<usize>| Some(allocations()? - before?);
+        for query in ["a", "app", "ent", "n 1", "n21"] {
+            let allocated = allocations();
+            let started = Instant::now();
+            let full = ranked(&data, query, None);
+            let full_time = started.elapsed();
+            let full_allocations = since(allocated);
+
+            let allocated = allocations();
+            let started = Instant::now();
+            let staged = ranked(&data, query, Some(500));
+            let staged_time = started.elapsed();
+            let staged_allocations = since(allocated);
+
             println!(
-                "Resulting Prio: {}\n",
-                prio.trunc() + (counters + score).min(0.99)
+                "{query:>4}: full {full_time:?} ({} results, {full_allocations:?} allocations), \
+                 staged {staged_time:?} ({} results, {staged_allocations:?} allocations)",
+                full.len(),
+                staged.len()
+            );
+            assert_eq!(staged.len(), full.len().min(500), "{query:?}");
+            if let Some(staged_allocations) = staged_allocations {
+                assert!(staged_allocations < data.len() / 100, "{query:?}");
+            }
+            assert_eq!(staged, rank(&data, query, Some(500), false), "{query:?}");
+        }
+    }
+
+    #[test]
+    fn test_match_policies() {
+        use MatchPolicy::*;
+        let search = "rm -rf build dirs;cleanup;sudo rm -rf target";
+
+        // query, fuzzy, prefix, exact, word
+        let matrix = [
+            ("rfb", true, false, false, false),
+            ("rm", true, true, false, true),
+            ("rm -rf", true, true, false, true),
+            ("clean", true, true, false, false),
+            ("cleanup", true, true, true, true),
+            ("build", true, false, false, true),
+            ("uild", true, false, false, false),
+            ("rm -rf build dirs", true, true, true, true),
+            ("", true, true, true, true),
+        ];
+        for (query, fuzzy, prefix, exact, word) in matrix {
+            for (policy, expected) in [
+                (Fuzzy, fuzzy),
+                (Prefix, prefix),
+                (Exact, exact),
+                (Word, word),
+            ] {
+                assert_eq!(
+                    matches_policy(search, query, policy, &ScoringParams::default()),
+                    expected,
+                    "{query:?} with {policy:?}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_policy_scores() {
+        // no levenshtein leniency outside of fuzzy matching
+        assert!(
+            search_score(
+                "cleanip",
+                "cleanup",
+                MatchPolicy::Fuzzy,
+                &ScoringParams::default()
+            ) < 1.0
+        );
+        for policy in [MatchPolicy::Prefix, MatchPolicy::Exact, MatchPolicy::Word] {
+            assert_eq!(
+                search_score("cleanip", "cleanup", policy, &ScoringParams::default()),
+                1.0
             );
         }
+
+        assert_eq!(
+            search_score(
+                "cleanup",
+                "rm;cleanup",
+                MatchPolicy::Exact,
+                &ScoringParams::default()
+            ),
+            SECONDARY_PENALTY
+        );
+        assert!(
+            search_score(
+                "rm -rf",
+                "sudo rm -rf target",
+                MatchPolicy::Word,
+                &ScoringParams::default()
+            ) < 1.0
+        );
+        assert_eq!(
+            search_score(
+                "clean",
+                "cleanup",
+                MatchPolicy::Prefix,
+                &ScoringParams::default()
+            ),
+            search_score(
+                "clean",
+                "cleanup",
+                MatchPolicy::Fuzzy,
+                &ScoringParams::default()
+            )
+        );
+    }
+
+    #[test]
+    fn test_scoring_params() {
+        let defaults = ScoringParams::default();
+        let params = |f: fn(&mut ScoringParams)| {
+            let mut params = defaults;
+            f(&mut params);
+            params
+        };
+
+        // prefix bonus scales with coverage
+        let score =
+            |params: &ScoringParams| search_score("clean", "cleanup", MatchPolicy::Fuzzy, params);
+        assert!(score(&defaults) < 0.2);
+        assert_eq!(score(&params(|p| p.prefix_bonus = 0.0)), 0.2);
+
+        // gaps in fuzzy matches
+        assert!(matches_query("firefox", "fx", &defaults));
+        assert!(!matches_query(
+            "firefox",
+            "fx",
+            &params(|p| p.fuzzy_window = 1)
+        ));
+
+        // edit distance only for similar lengths
+        let typo = |params: &ScoringParams| {
+            search_score("cleanip", "cleanups", MatchPolicy::Fuzzy, params)
+        };
+        assert!(typo(&defaults) < 1.0);
+        assert_eq!(typo(&params(|p| p.levenshtein_max_len_diff = 0)), 1.0);
+
+        // launch counts
+        let ignore_counts = params(|p| p.count_weight = 0.0);
+        let prio = |prio, params| make_prio(prio, "fire", "firefox", MatchPolicy::Fuzzy, params);
+        assert!(prio(1.34, &defaults) > prio(1.0, &defaults));
+        assert_eq!(prio(1.34, &ignore_counts), prio(1.0, &ignore_counts));
+    }
+
+    #[test]
+    fn test_debug_search_override() {
+        assert!(!debug_search(false, None));
+        assert!(debug_search(true, None));
+        assert!(debug_search(false, Some("true")));
+        assert!(!debug_search(true, Some("0")));
+        // anything else leaves the config in charge
+        assert!(debug_search(true, Some("")));
+    }
+
+    #[test]
+    fn test_narrowing_pool() {
+        let launcher = Arc::new(Launcher::default());
+        let data = Arc::new(vec![app(&launcher, "Firefox", "firefox %u")]);
+        let narrowing = Narrowing {
+            query: String::from("fi"),
+            mode: String::from("all"),
+            data: Arc::downgrade(&data),
+            pool: Arc::new([0]),
+        };
+        assert!(narrowing.pool_for("fir", "all", &data).is_some());
+        assert!(narrowing.pool_for("fi", "all", &data).is_some());
+
+        // shorter or different queries, other modes and reloaded data need a full scan
+        assert!(narrowing.pool_for("f", "all", &data).is_none());
+        assert!(narrowing.pool_for("fo", "all", &data).is_none());
+        assert!(narrowing.pool_for("fir", "app", &data).is_none());
+        let reloaded = Arc::new(data.as_ref().clone());
+        assert!(narrowing.pool_for("fir", "all", &reloaded).is_none());
+    }
+
+    #[test]
+    fn test_variable_recall() {
+        let mut recall = VariableRecall::new(vec!["new".into(), "mid".into(), "old".into()]);
+        assert_eq!(recall.first().as_deref(), Some("new"));
+        // the input starts with the newest value, up goes back in time
+        let older: Vec<_> = (0..3)
+            .filter_map(|_| Some(recall.step(true)?.to_string()))
+            .collect();
+        assert_eq!(older, ["mid", "old", "new"]);
+        assert_eq!(recall.step(false).as_deref(), Some("old"));
+
+        let mut empty = VariableRecall::new(Vec::new());
+        assert_eq!(empty.first(), None);
+        assert_eq!(empty.step(true), None);
+    }
+
+    #[test]
+    fn test_confirm_by_shortcut() {
+        let mut pending = None;
+        // the first press of the shortcut asks for confirmation
+        assert!(!PendingConfirm::confirm(&mut pending, 3));
+        // the second one selects the same result, which keeps it, and runs the result
+        assert!(pending.is_some_and(|pending| pending.kept_by(Some(3))));
+        assert!(PendingConfirm::confirm(&mut pending, 3));
+        assert_eq!(pending, None);
+
+        // selecting another result drops it
+        assert!(!PendingConfirm::confirm(&mut pending, 3));
+        assert!(pending.is_some_and(|pending| !pending.kept_by(Some(4))));
+        assert!(!PendingConfirm::confirm(&mut pending, 4));
+    }
+
+    #[test]
+    fn test_result_cache() {
+        let mut cache = ResultCache::default();
+        let results = |i: usize| -> Arc<[usize]> { Arc::new([i]) };
+        for i in 0..RESULT_CACHE_SIZE {
+            cache.insert(0, LauncherMode::Home, i.to_string(), results(i));
+        }
+        // modes are kept apart
+        assert!(cache.get(&LauncherMode::Home, "0").is_some());
+        let alias = LauncherMode::Alias {
+            short: "app".into(),
+            name: "Apps".into(),
+        };
+        assert!(cache.get(&alias, "0").is_none());
+
+        // "0" was used last, so "1" is evicted first
+        cache.insert(0, alias.clone(), String::from("0"), results(100));
+        assert!(cache.get(&LauncherMode::Home, "1").is_none());
+        assert_eq!(cache.get(&LauncherMode::Home, "0"), Some(results(0)));
+        assert_eq!(cache.get(&alias, "0"), Some(results(100)));
+
+        // queries are replaced rather than added twice
+        cache.insert(0, alias.clone(), String::from("0"), results(101));
+        assert_eq!(cache.get(&alias, "0"), Some(results(101)));
+        assert_eq!(cache.entries.len(), RESULT_CACHE_SIZE);
+
+        cache.clear();
+        assert!(cache.get(&alias, "0").is_none());
+
+        // filters that started before the clear don't fill it again
+        cache.insert(0, alias.clone(), String::from("0"), results(100));
+        assert!(cache.get(&alias, "0").is_none());
+        cache.insert(
+            cache.generation(),
+            alias.clone(),
+            String::from("0"),
+            results(102),
+        );
+        assert_eq!(cache.get(&alias, "0"), Some(results(102)));
+    }
+
+    #[test]
+    fn test_narrowing_policies() {
+        let params = ScoringParams::default();
+        let searches = [
+            "firefox;web browser",
+            "gnome terminal",
+            "sudo rm -rf build",
+            "visual studio code;editor",
+        ];
+        let queries = [
+            "f", "fi", "fir", "fx", "rm", "rm -", "rm -rf", "te", "term", "code", "ed",
+        ];
+        let policies = [
+            MatchPolicy::Fuzzy,
+            MatchPolicy::Prefix,
+            MatchPolicy::Exact,
+            MatchPolicy::Word,
+        ];
+        // what these policies reject for a query stays rejected for longer queries
+        for search in searches {
+            for policy in policies.into_iter().filter(|policy| policy.narrows()) {
+                for query in queries {
+                    if matches_policy(search, query, policy, &params) {
+                        continue;
+                    }
+                    for longer in queries.iter().filter(|q| q.starts_with(query)) {
+                        assert!(
+                            !matches_policy(search, longer, policy, &params),
+                            "{policy:?}: {longer:?} in {search:?}"
+                        );
+                    }
+                }
+            }
+        }
+
+        // the others can start to match as the query grows
+        let word = |query| matches_policy("sudo rm -rf build", query, MatchPolicy::Word, &params);
+        assert!(!word("rm -") && word("rm -rf"));
+        let exact = |query| matches_policy("code", query, MatchPolicy::Exact, &params);
+        assert!(!exact("cod") && exact("code"));
     }
-    prio.trunc() + (counters + score).min(0.99)
 }
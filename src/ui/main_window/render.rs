@@ -1,40 +1,90 @@
 use std::sync::Arc;
 
 use gpui::{
-    AnyElement, Context, Element, Focusable, FontWeight, Image, ImageSource, InteractiveElement,
-    IntoElement, ParentElement, Render, SharedString, StatefulInteractiveElement, Styled, Window,
-    div, hsla, img, list, px, relative, rgb,
+    AnyElement, App, Context, Div, Element, Focusable, FontWeight, Hsla, Image, ImageSource,
+    InteractiveElement, IntoElement, MouseButton, ParentElement, Render, SharedString, Stateful,
+    StatefulInteractiveElement, Styled, WeakEntity, Window, div, hsla, img, list,
+    prelude::FluentBuilder, px, relative, rgb,
 };
 
 use crate::{
-    CONTEXT_MENU_BIND,
-    launcher::children::{RenderableChild, RenderableChildDelegate},
-    ui::{UIFunction, main_window::SherlockMainWindow},
-    utils::config::ConfigGuard,
+    KEYMAP,
+    launcher::{
+        children::{Highlight, RenderableChild, RenderableChildDelegate},
+        provenance::Provenance,
+    },
+    ui::{
+        UIFunction,
+        main_window::{CONTEXT_MENU, Expansion, SherlockMainWindow, cap_query, scrollbar_thumb},
+    },
+    utils::{
+        accent::Accent,
+        command_launch::RunCommand,
+        config::{ConfigGuard, SearchDefaults},
+        errors::SherlockError,
+        palette::Palette,
+        soft_delete,
+    },
 };
 
 impl Render for SherlockMainWindow {
-    fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+    fn render(&mut self, window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        if self.held_exec.is_some() {
+            if window.is_window_active() {
+                // animates the hold indicator of the selected row
+                window.request_animation_frame();
+            } else {
+                self.held_exec = None;
+            }
+        }
+        if self.pending_confirm.is_some() && window.is_window_active() {
+            // animates the time left to confirm
+            window.request_animation_frame();
+        }
         let weak_self = cx.entity().downgrade();
+        let accent = Accent::current();
+        let palette = Palette::current();
+        // matches are highlighted with the window the search used
+        let fuzzy_window = ConfigGuard::read()
+            .map(|config| config.search.fuzzy_window.max(1))
+            .unwrap_or_else(|_| SearchDefaults::fuzzy_window());
         div()
             .id("sherlock")
             .track_focus(&self.focus_handle(cx))
+            .when(self.context_idx.is_some(), |this| {
+                this.key_context(CONTEXT_MENU)
+            })
             .flex()
             .flex_col()
             .size_full()
-            .bg(rgb(0x0F0F0F))
+            .bg(palette.bg(rgb(0x0F0F0F)))
             .border_2()
-            .border_color(hsla(0., 0., 0.1882, 1.0))
+            .border_color(palette.fg(hsla(0., 0., 0.1882, 1.0)))
             .rounded(px(5.))
             .shadow_xl()
             .overflow_hidden()
             .on_action(cx.listener(Self::focus_next))
             .on_action(cx.listener(Self::focus_prev))
+            .on_action(cx.listener(Self::focus_left))
+            .on_action(cx.listener(Self::focus_right))
             .on_action(cx.listener(Self::next_var))
             .on_action(cx.listener(Self::prev_var))
             .on_action(cx.listener(Self::execute))
+            .on_action(cx.listener(Self::shortcut))
             .on_action(cx.listener(Self::quit))
             .on_action(cx.listener(Self::open_context))
+            .on_action(cx.listener(Self::dismiss_context))
+            .on_action(cx.listener(Self::toggle_contrast))
+            .on_action(cx.listener(Self::toggle_errors))
+            .on_key_up(cx.listener(Self::release_exec))
+            // the thumb follows the pointer outside of the scrollbar as well
+            .on_mouse_move(cx.listener(Self::drag_scrollbar))
+            .on_mouse_up(MouseButton::Left, cx.listener(Self::release_scrollbar))
+            .on_mouse_up_out(MouseButton::Left, cx.listener(Self::release_scrollbar))
+            // runs before the search bar moves its cursor
+            .capture_action(cx.listener(Self::undo))
+            .capture_action(cx.listener(Self::arrow_left))
+            .capture_action(cx.listener(Self::arrow_right))
             .child(
                 // search bar
                 div()
@@ -45,19 +95,19 @@ impl Render for SherlockMainWindow {
                     .px_4()
                     .py(px(4.))
                     .gap_3()
-                    .child(div().text_color(rgb(0x888888)).child(""))
+                    .child(div().text_color(palette.fg(rgb(0x888888))).child(""))
                     .child(div().w_auto().child(self.text_input.clone()))
                     .children(self.variable_input.iter().cloned())
                     .border_b_2()
-                    .border_color(hsla(0., 0., 0.1882, 1.0)),
+                    .border_color(palette.fg(hsla(0., 0., 0.1882, 1.0))),
             )
             .child(
                 div()
                     .px(px(14.))
                     .py(px(4.))
-                    .text_size(px(14.))
+                    .text_size(palette.text_size(px(14.)))
                     .font_weight(FontWeight::BOLD)
-                    .text_color(rgb(0x2e2e2e))
+                    .text_color(accent.mode_chip)
                     .child(self.mode.display_str()),
             )
             .child(
@@ -66,7 +116,13 @@ impl Render for SherlockMainWindow {
                     .flex_1()
                     .min_h_0()
                     .px(px(10.))
-                    .child(
+                    .relative()
+                    .on_scroll_wheel(cx.listener(Self::scroll_results))
+                    .child(if let Some(errors) = self.error_page.as_deref() {
+                        render_error_page(errors)
+                    } else if let Some(run) = self.run_command.as_ref() {
+                        render_run_item(run)
+                    } else {
                         list(self.list_state.clone(), move |idx, _win, cx| {
                             // 1. Upgrade and Read
                             let entity = weak_self.upgrade();
@@ -74,6 +130,14 @@ impl Render for SherlockMainWindow {
                                 return div().into_any_element();
                             }
                             let state = entity.unwrap().read(cx);
+                            let highlight = Highlight {
+                                query: cap_query(state.last_query.as_deref().unwrap_or_default()),
+                                window: fuzzy_window,
+                            };
+                            // rows of a grid hold several results
+                            if state.columns > 1 {
+                                return state.render_grid_row(&weak_self, idx, highlight, cx);
+                            }
 
                             // 2. Bounds Check - If this fails, we return an empty div to satisfy AnyElement
                             let data_idx = match state.filtered_indices.get(idx) {
@@ -87,16 +151,31 @@ impl Render for SherlockMainWindow {
                                 None => return div().into_any_element(),
                             };
 
-                            state.render_list_item(&child, idx)
+                            let is_stale = state.stale_indices.contains(&data_idx);
+                            let expansion = match state.expanded {
+                                Some((i, Expansion::Next)) if i == data_idx => child.render_next(),
+                                Some((i, Expansion::Source)) if i == data_idx => {
+                                    Provenance::of(&data_guard, data_idx).map(render_source)
+                                }
+                                _ => None,
+                            };
+                            state.render_list_item(
+                                &weak_self, &child, idx, is_stale, expansion, highlight,
+                            )
                         })
-                        .size_full(),
+                        .size_full()
+                        .into_any_element()
+                    })
+                    .when(
+                        self.error_page.is_none() && self.run_command.is_none(),
+                        |this| this.children(self.render_scrollbar(cx)),
                     )
                     .child(if let Some(active) = self.context_idx {
                         div().inset_0().absolute().child(
                             div()
                                 .p(px(7.))
-                                .bg(rgb(0x0F0F0F))
-                                .border_color(hsla(0., 0., 0.1882, 1.0))
+                                .bg(palette.bg(rgb(0x0F0F0F)))
+                                .border_color(palette.fg(hsla(0., 0., 0.1882, 1.0)))
                                 .border(px(1.))
                                 .rounded_md()
                                 .absolute()
@@ -117,16 +196,22 @@ impl Render for SherlockMainWindow {
                                             .gap(px(10.))
                                             .p(px(10.))
                                             .cursor_pointer()
-                                            .text_color(if is_selected {
+                                            .text_color(palette.fg(if is_selected {
                                                 hsla(0.0, 0.0, 0.8, 1.0)
                                             } else {
                                                 hsla(0.6, 0.0217, 0.3608, 1.0)
-                                            })
-                                            .text_size(px(13.))
+                                            }))
+                                            .text_size(palette.text_size(px(13.)))
                                             .line_height(relative(1.0))
                                             .items_center()
                                             .bg(if is_selected {
-                                                hsla(0., 0., 0.149, 1.0)
+                                                accent.selection
+                                            } else {
+                                                hsla(0., 0., 0., 0.)
+                                            })
+                                            .border(palette.selection_border())
+                                            .border_color(if is_selected {
+                                                palette.fg(rgb(0xffffff))
                                             } else {
                                                 hsla(0., 0., 0., 0.)
                                             })
@@ -134,7 +219,7 @@ impl Render for SherlockMainWindow {
                                                 if is_selected && self.context_idx.is_some() {
                                                     s
                                                 } else {
-                                                    s.bg(hsla(0., 0., 0.12, 1.0))
+                                                    s.bg(palette.bg(hsla(0., 0., 0.12, 1.0)))
                                                 }
                                             })
                                             .child(if let Some(icon) = child.icon.as_ref() {
@@ -161,14 +246,18 @@ impl Render for SherlockMainWindow {
                     .line_height(px(30.))
                     .w_full()
                     .flex()
-                    .bg(hsla(0., 0., 0.098, 1.0))
+                    .bg(palette.bg(hsla(0., 0., 0.098, 1.0)))
                     .border_t_1()
-                    .border_color(hsla(0., 0., 0.1882, 1.0))
+                    .border_color(palette.fg(hsla(0., 0., 0.1882, 1.0)))
                     .px_5()
-                    .text_size(px(13.))
+                    .text_size(palette.text_size(px(13.)))
                     .items_center()
-                    .text_color(hsla(0.6, 0.0217, 0.3608, 1.0))
-                    .child(String::from("Sherlock"))
+                    .text_color(accent.footer)
+                    .child(
+                        undo_hint()
+                            .or_else(error_hint)
+                            .unwrap_or_else(|| String::from("Sherlock")),
+                    )
                     .child(div().flex_1())
                     .child({
                         let guard = self.data.read(cx);
@@ -179,14 +268,18 @@ impl Render for SherlockMainWindow {
                             .and_then(RenderableChild::actions)
                             .map(|a| !a.is_empty())
                         {
+                            // the open menu is navigated with the item binds and left with its
+                            // own close bind
+                            let (label, func) = match self.context_idx {
+                                Some(_) => ("Close Actions", UIFunction::CloseContext),
+                                None => ("Additional Actions", UIFunction::ToggleContext),
+                            };
                             div()
                                 .flex()
                                 .items_center()
                                 .gap(px(5.))
-                                .child(div().mr_1().child(SharedString::from("Additional Actions")))
-                                .children(
-                                    get_context_key_parts().into_iter().map(|p| keybind_box(p)),
-                                )
+                                .child(div().mr_1().child(SharedString::from(label)))
+                                .children(get_key_parts(func).into_iter().map(|p| keybind_box(p)))
                         } else {
                             div()
                         }
@@ -196,25 +289,252 @@ impl Render for SherlockMainWindow {
 }
 
 fn keybind_box(text: String) -> impl Element {
+    let palette = Palette::current();
     div()
         .flex_none()
         .p(px(5.))
-        .bg(rgb(0x262626))
+        .bg(palette.bg(rgb(0x262626)))
         .rounded_sm()
-        .text_size(px(11.))
+        .text_size(palette.text_size(px(11.)))
         .line_height(relative(1.0))
         .child(text)
 }
 
+fn render_run_item(run: &RunCommand) -> AnyElement {
+    let hint = if run.terminal {
+        "Run in terminal"
+    } else {
+        "Run command"
+    };
+    let palette = Palette::current();
+    div()
+        .rounded_md()
+        .mb(px(5.0))
+        .w_full()
+        .px_4()
+        .py_2()
+        .flex()
+        .flex_col()
+        .bg(Accent::current().selection)
+        .border(palette.selection_border())
+        .border_color(palette.fg(rgb(0xffffff)))
+        .child(
+            div()
+                .text_size(palette.text_size(px(14.)))
+                .text_color(palette.fg(rgb(0xffffff)))
+                .overflow_hidden()
+                .text_ellipsis()
+                .whitespace_nowrap()
+                .child(format!("Run: {}", run.command)),
+        )
+        .child(
+            div()
+                .text_size(palette.text_size(px(12.)))
+                .text_color(palette.fg(rgb(0x999999)))
+                .child(hint),
+        )
+        .into_any_element()
+}
+
+/// Lists the reported errors in place of the results
+fn render_error_page(errors: &[SherlockError]) -> AnyElement {
+    let palette = Palette::current();
+    if errors.is_empty() {
+        return div()
+            .px_4()
+            .py_2()
+            .text_size(palette.text_size(px(13.)))
+            .text_color(palette.fg(rgb(0x999999)))
+            .child("No errors reported")
+            .into_any_element();
+    }
+
+    div()
+        .id("error-page")
+        .size_full()
+        .overflow_y_scroll()
+        .flex()
+        .flex_col()
+        .gap_2()
+        .py_2()
+        .children(errors.iter().map(|error| {
+            let (title, message) = error.error.get_message();
+            div()
+                .px_4()
+                .py_2()
+                .rounded_md()
+                .bg(palette.bg(rgb(0x1a1a1a)))
+                .flex()
+                .flex_col()
+                .gap_1()
+                .child(
+                    div()
+                        .text_size(palette.text_size(px(14.)))
+                        .font_weight(FontWeight::BOLD)
+                        .text_color(palette.fg(rgb(0xe06c75)))
+                        .child(title),
+                )
+                .child(
+                    div()
+                        .text_size(palette.text_size(px(13.)))
+                        .text_color(palette.fg(rgb(0xcccccc)))
+                        .child(message),
+                )
+                .child(
+                    div()
+                        .text_size(palette.text_size(px(12.)))
+                        .text_color(palette.fg(rgb(0x666666)))
+                        .child(error.traceback.clone()),
+                )
+        }))
+        .into_any_element()
+}
+
 impl SherlockMainWindow {
-    fn render_list_item(&self, ad: &RenderableChild, idx: usize) -> AnyElement {
-        let is_selected = self.selected_index == idx;
+    /// Thin scrollbar on the right edge of the results, hidden while all of them fit
+    fn render_scrollbar(&self, cx: &Context<Self>) -> Option<AnyElement> {
+        let (viewport, max_offset) = self.scroll_extent();
+        let offset = -f32::from(self.list_state.scroll_px_offset_for_scrollbar().y);
+        let (top, height) = scrollbar_thumb(viewport, max_offset, offset)?;
+        let lightness = if self.scrollbar_drag.is_some() {
+            0.5
+        } else {
+            0.3
+        };
+        let palette = Palette::current();
+        Some(
+            div()
+                .id("scrollbar")
+                .absolute()
+                .top_0()
+                .bottom_0()
+                .right(px(2.))
+                .w(px(6.))
+                .on_mouse_down(MouseButton::Left, cx.listener(Self::grab_scrollbar))
+                .child(
+                    div()
+                        .absolute()
+                        .top(px(top))
+                        .h(px(height))
+                        .w_full()
+                        .rounded_full()
+                        .bg(palette.fg(hsla(0., 0., lightness, 1.0))),
+                )
+                .into_any_element(),
+        )
+    }
+    /// Background of the `n`-th result. Hovered results are only highlighted while the context
+    /// menu is closed, so the pointer cannot be mistaken for the selection.
+    fn item_bg(&self, n: usize) -> Hsla {
+        if self.selected_index == n {
+            Accent::current().selection
+        } else if self.hovered == Some(n) && self.context_idx.is_none() {
+            Palette::current().bg(hsla(0., 0., 0.12, 1.0))
+        } else {
+            hsla(0., 0., 0., 0.)
+        }
+    }
+    /// Covers the `n`-th result while it waits for the exec key to be pressed again
+    fn confirm_overlay(&self, n: usize) -> Option<Div> {
+        let pending = self
+            .pending_confirm
+            .filter(|pending| self.filtered_indices.get(n) == Some(&pending.idx))?;
+        let palette = Palette::current();
+        let keys = |func| get_key_parts(func).into_iter().map(keybind_box);
+        Some(
+            div()
+                .absolute()
+                .inset_0()
+                .rounded_md()
+                .overflow_hidden()
+                .px_4()
+                .flex()
+                .items_center()
+                .gap(px(5.))
+                .bg(palette.bg(hsla(0., 0., 0.098, 1.0)))
+                .text_size(palette.text_size(px(13.)))
+                .text_color(palette.fg(rgb(0xcccccc)))
+                .child("Press")
+                .children(keys(UIFunction::Exec))
+                .child("again to confirm,")
+                .children(keys(UIFunction::Exit))
+                .child("to cancel")
+                .child(
+                    // shrinks until the confirmation times out
+                    div()
+                        .absolute()
+                        .bottom_0()
+                        .left_0()
+                        .h(px(2.))
+                        .w(relative(pending.remaining()))
+                        .bg(palette.fg(rgb(0xcccccc))),
+                ),
+        )
+    }
+    /// The results shown in the `row`-th row of the grid
+    fn render_grid_row(
+        &self,
+        this: &WeakEntity<Self>,
+        row: usize,
+        highlight: Highlight,
+        cx: &App,
+    ) -> AnyElement {
+        let data = self.data.read(cx);
+        let first = row * self.columns;
         div()
-            .id(("keystroke", idx))
             .w_full()
-            .on_click(move |_, _, _| {
-                println!("Clicked item {}", idx);
+            .flex()
+            .gap(px(5.))
+            .mb(px(5.))
+            .children((first..first + self.columns).map(|n| {
+                let tile = self
+                    .filtered_indices
+                    .get(n)
+                    .and_then(|i| data.get(*i))
+                    .map(|child| self.render_grid_item(this, child, n, highlight));
+                // empty cells keep the tiles of a shorter last row in their columns
+                div().flex_1().min_w_0().children(tile)
+            }))
+            .into_any_element()
+    }
+    fn render_grid_item(
+        &self,
+        this: &WeakEntity<Self>,
+        ad: &RenderableChild,
+        n: usize,
+        highlight: Highlight,
+    ) -> AnyElement {
+        let is_selected = self.selected_index == n;
+        let palette = Palette::current();
+        item_handlers(div().id(("keystroke", n)), this, n)
+            .rounded_md()
+            .cursor_pointer()
+            .bg(self.item_bg(n))
+            .border(palette.selection_border())
+            .border_color(if is_selected {
+                palette.fg(rgb(0xffffff))
+            } else {
+                hsla(0., 0., 0., 0.)
             })
+            .relative()
+            .child(ad.render_tile(is_selected, highlight))
+            .children(shortcut_hint(ad, n).map(|hint| hint.top(px(5.)).right(px(5.))))
+            .children(self.confirm_overlay(n))
+            .into_any_element()
+    }
+    fn render_list_item(
+        &self,
+        this: &WeakEntity<Self>,
+        ad: &RenderableChild,
+        idx: usize,
+        is_stale: bool,
+        expansion: Option<AnyElement>,
+        highlight: Highlight,
+    ) -> AnyElement {
+        let is_selected = self.selected_index == idx;
+        let palette = Palette::current();
+        item_handlers(div().id(("keystroke", idx)), this, idx)
+            .w_full()
             .child(
                 div()
                     .group("")
@@ -223,46 +543,194 @@ impl SherlockMainWindow {
                     .mb(px(5.0))
                     .w_full()
                     .cursor_pointer()
-                    .bg(if is_selected {
-                        hsla(0., 0., 0.149, 1.0)
+                    .bg(self.item_bg(idx))
+                    .border(palette.selection_border())
+                    .border_color(if is_selected {
+                        palette.fg(rgb(0xffffff))
                     } else {
                         hsla(0., 0., 0., 0.)
                     })
-                    .hover(|s| {
-                        if is_selected || self.context_idx.is_some() {
-                            s
-                        } else {
-                            s.bg(hsla(0., 0., 0.12, 1.0))
-                        }
+                    .child(ad.render(is_selected, highlight))
+                    .children(
+                        shortcut_hint(ad, idx).map(|hint| {
+                            hint.top_0().bottom_0().right(px(10.)).flex().items_center()
+                        }),
+                    )
+                    .children(expansion)
+                    .when_some(ad.accent(), |this, accent| {
+                        // tag of the launcher or the result itself
+                        this.child(
+                            div()
+                                .absolute()
+                                .top_0()
+                                .bottom_0()
+                                .left_0()
+                                .w(px(3.))
+                                .rounded_l_md()
+                                .bg(palette.fg(accent.color)),
+                        )
                     })
-                    .child(ad.render(is_selected)),
+                    .when_some(self.held_exec.filter(|_| is_selected), |this, held| {
+                        // fills up while the exec key is held
+                        this.child(
+                            div()
+                                .absolute()
+                                .bottom_0()
+                                .left_0()
+                                .h(px(2.))
+                                .w(relative(held.progress()))
+                                .bg(palette.fg(rgb(0xcccccc))),
+                        )
+                    })
+                    .children(self.confirm_overlay(idx))
+                    .when(is_stale, |this| {
+                        // subtle marker for async content that failed to refresh in time
+                        this.child(
+                            div()
+                                .absolute()
+                                .top(px(6.))
+                                .right(px(6.))
+                                .size(px(5.))
+                                .rounded_full()
+                                .bg(palette.fg(hsla(0.1, 0.6, 0.5, 0.6))),
+                        )
+                    }),
             )
             .into_any_element()
     }
 }
 
-fn get_context_key_parts() -> Vec<String> {
-    CONTEXT_MENU_BIND
-        .get_or_init(|| {
-            ConfigGuard::read()
-                .ok()
-                .and_then(|config| {
-                    config
-                        .keybinds
-                        .iter()
-                        .find(|(_, func)| **func == UIFunction::ToggleContext)
-                        .map(|(name, _)| name.clone())
-                })
-                .unwrap_or_else(|| "ctrl-l".to_string())
-        })
-        .split('-')
+/// Left clicks select the `n`-th result or run it if it already is selected, right clicks open
+/// its context menu. Moving the pointer over it highlights it.
+fn item_handlers(
+    item: Stateful<Div>,
+    this: &WeakEntity<SherlockMainWindow>,
+    n: usize,
+) -> Stateful<Div> {
+    let (primary, secondary) = (this.clone(), this.clone());
+    let (enter, leave) = (this.clone(), this.clone());
+    item.on_click(move |_, win, cx| {
+        let _ = primary.update(cx, |view, cx| view.click_item(n, win, cx));
+    })
+    .on_mouse_down(MouseButton::Right, move |_, _, cx| {
+        let _ = secondary.update(cx, |view, cx| view.open_context_at(n, cx));
+    })
+    .on_mouse_move(move |_, _, cx| {
+        let _ = enter.update(cx, |view, cx| view.hover_item(n, cx));
+    })
+    .on_hover(move |hovered, _, cx| {
+        if !hovered {
+            let _ = leave.update(cx, |view, cx| view.unhover_item(n, cx));
+        }
+    })
+}
+
+/// The "Source" section of an expanded tile
+fn render_source(provenance: Provenance) -> AnyElement {
+    let palette = Palette::current();
+    div()
+        .px_4()
+        .pb_2()
+        .flex()
+        .flex_col()
+        .gap_1()
+        .text_size(palette.text_size(px(12.)))
+        .child(
+            div()
+                .text_color(palette.fg(hsla(0., 0., 0.8, 1.0)))
+                .font_weight(FontWeight::BOLD)
+                .child("Source"),
+        )
+        .children(provenance.rows().into_iter().map(|(label, value)| {
+            div()
+                .flex()
+                .gap_2()
+                .child(
+                    div()
+                        .w(px(70.))
+                        .flex_none()
+                        .text_color(palette.fg(hsla(0.6, 0.0217, 0.3608, 1.0)))
+                        .child(label),
+                )
+                .child(
+                    div()
+                        .flex_1()
+                        .overflow_hidden()
+                        .text_ellipsis()
+                        .whitespace_nowrap()
+                        .text_color(palette.fg(hsla(0., 0., 0.8, 1.0)))
+                        .child(value),
+                )
+        }))
+        .into_any_element()
+}
+
+/// Footer message while a staged change can still be undone
+fn undo_hint() -> Option<String> {
+    let message = soft_delete::latest()?;
+    match KEYMAP
+        .get()
+        .and_then(|keymap| keymap.key_for(UIFunction::Undo))
+    {
+        Some(key) => Some(format!("{message} — undo ({key})")),
+        None => Some(message),
+    }
+}
+
+/// Footer message while errors were reported, pointing to the error page
+fn error_hint() -> Option<String> {
+    let count = SherlockError::reported_count();
+    let message = match count {
+        0 => return None,
+        1 => String::from("1 error"),
+        n => format!("{n} errors"),
+    };
+    match KEYMAP
+        .get()
+        .and_then(|keymap| keymap.key_for(UIFunction::ErrorPage))
+    {
+        Some(key) => Some(format!("{message} — show ({key})")),
+        None => Some(message),
+    }
+}
+
+fn get_key_parts(func: UIFunction) -> Vec<String> {
+    KEYMAP
+        .get()
+        .and_then(|keymap| keymap.key_for(func))
+        .map(key_parts)
+        .unwrap_or_default()
+}
+
+/// The modifiers of `key` as symbols, followed by the key itself
+fn key_parts(key: &str) -> Vec<String> {
+    key.split('-')
         .map(|part| match part {
             "ctrl" => "⌃".to_string(),
             "cmd" => "⌘".to_string(),
             "shift" => "⇧".to_string(),
             "alt" => "⌥".to_string(),
+            "super" => "✦".to_string(),
             other if other.len() == 1 => other.to_uppercase(),
             other => other.to_string(),
         })
         .collect()
 }
+
+/// Hint for the shortcut that runs the `n`-th result, shown on the first
+/// `appearance.num_shortcuts` results of launchers with `shortcut` enabled
+fn shortcut_hint(ad: &RenderableChild, n: usize) -> Option<Div> {
+    let count = ConfigGuard::read()
+        .map(|config| config.appearance.num_shortcuts as usize)
+        .unwrap_or(5);
+    if n >= count || !ad.launcher().shortcut {
+        return None;
+    }
+    let key = KEYMAP.get()?.shortcut_for(n)?;
+    Some(
+        div()
+            .absolute()
+            .text_color(Palette::current().fg(rgb(0x666666)))
+            .child(keybind_box(key_parts(&key).concat())),
+    )
+}
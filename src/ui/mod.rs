@@ -1,10 +1,19 @@
+pub mod image;
+pub mod keymap;
 pub mod main_window;
 pub mod search_bar;
 
 use gpui::KeyBinding;
 use serde::{Deserialize, Serialize};
 
-use crate::ui::main_window::{Execute, FocusNext, FocusPrev, NextVar, OpenContext, PrevVar, Quit};
+use crate::ui::main_window::{
+    CONTEXT_MENU, CloseContext, Execute, FocusLeft, FocusNext, FocusPrev, FocusRight, NextVar,
+    OpenContext, PrevVar, Quit, ToggleContrast, ToggleErrors,
+};
+use crate::ui::search_bar::{
+    Backspace, Copy, Cut, Delete, DeleteAll, End, Home, Left, Paste, Redo, Right, SelectAll,
+    SelectWordLeft, SelectWordRight, Undo, WordLeft, WordRight,
+};
 
 #[derive(Deserialize, Serialize, Hash, Debug, Clone, Copy, Eq, PartialEq)]
 #[serde(rename_all = "snake_case")]
@@ -29,10 +38,30 @@ pub enum UIFunction {
 
     ClearBar,
     Backspace,
+    Delete,
+
+    SelectAll,
+    Copy,
+    Cut,
+    Paste,
+
+    Home,
+    End,
+    CursorLeft,
+    CursorRight,
+    WordLeft,
+    WordRight,
+    SelectWordLeft,
+    SelectWordRight,
 
     ErrorPage,
 
     Shortcut,
+
+    Undo,
+    Redo,
+
+    ToggleContrast,
 }
 impl UIFunction {
     pub fn into_bind(&self, key: &str) -> Option<KeyBinding> {
@@ -40,11 +69,77 @@ impl UIFunction {
             Self::Exit => Some(KeyBinding::new(key, Quit, None)),
             Self::ItemDown => Some(KeyBinding::new(key, FocusNext, None)),
             Self::ItemUp => Some(KeyBinding::new(key, FocusPrev, None)),
+            Self::ItemLeft => Some(KeyBinding::new(key, FocusLeft, None)),
+            Self::ItemRight => Some(KeyBinding::new(key, FocusRight, None)),
             Self::Exec => Some(KeyBinding::new(key, Execute, None)),
             Self::ArgNext => Some(KeyBinding::new(key, NextVar, None)),
             Self::ArgPrev => Some(KeyBinding::new(key, PrevVar, None)),
             Self::ToggleContext => Some(KeyBinding::new(key, OpenContext, None)),
+            Self::CloseContext => Some(KeyBinding::new(key, CloseContext, Some(CONTEXT_MENU))),
+            Self::ClearBar => Some(KeyBinding::new(key, DeleteAll, None)),
+            Self::Backspace => Some(KeyBinding::new(key, Backspace, None)),
+            Self::Delete => Some(KeyBinding::new(key, Delete, None)),
+            Self::SelectAll => Some(KeyBinding::new(key, SelectAll, None)),
+            Self::Copy => Some(KeyBinding::new(key, Copy, None)),
+            Self::Cut => Some(KeyBinding::new(key, Cut, None)),
+            Self::Paste => Some(KeyBinding::new(key, Paste, None)),
+            Self::Home => Some(KeyBinding::new(key, Home, None)),
+            Self::End => Some(KeyBinding::new(key, End, None)),
+            Self::CursorLeft => Some(KeyBinding::new(key, Left, None)),
+            Self::CursorRight => Some(KeyBinding::new(key, Right, None)),
+            Self::WordLeft => Some(KeyBinding::new(key, WordLeft, None)),
+            Self::WordRight => Some(KeyBinding::new(key, WordRight, None)),
+            Self::SelectWordLeft => Some(KeyBinding::new(key, SelectWordLeft, None)),
+            Self::SelectWordRight => Some(KeyBinding::new(key, SelectWordRight, None)),
+            Self::ErrorPage => Some(KeyBinding::new(key, ToggleErrors, None)),
+            Self::Undo => Some(KeyBinding::new(key, Undo, None)),
+            Self::Redo => Some(KeyBinding::new(key, Redo, None)),
+            Self::ToggleContrast => Some(KeyBinding::new(key, ToggleContrast, None)),
             _ => None,
         }
     }
+    /// Key context the binding is limited to, bindings without one apply everywhere
+    pub fn key_context(&self) -> Option<&'static str> {
+        match self {
+            Self::CloseContext => Some(CONTEXT_MENU),
+            _ => None,
+        }
+    }
+    /// The name used for this function in the config
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Exit => "exit",
+            Self::ItemDown => "item_down",
+            Self::ItemUp => "item_up",
+            Self::ItemLeft => "item_left",
+            Self::ItemRight => "item_right",
+            Self::ArgNext => "arg_next",
+            Self::ArgPrev => "arg_prev",
+            Self::Exec => "exec",
+            Self::ExecInplace => "exec_inplace",
+            Self::MultiSelect => "multi_select",
+            Self::ToggleContext => "toggle_context",
+            Self::CloseContext => "close_context",
+            Self::ClearBar => "clear_bar",
+            Self::Backspace => "backspace",
+            Self::Delete => "delete",
+            Self::SelectAll => "select_all",
+            Self::Copy => "copy",
+            Self::Cut => "cut",
+            Self::Paste => "paste",
+            Self::Home => "home",
+            Self::End => "end",
+            Self::CursorLeft => "cursor_left",
+            Self::CursorRight => "cursor_right",
+            Self::WordLeft => "word_left",
+            Self::WordRight => "word_right",
+            Self::SelectWordLeft => "select_word_left",
+            Self::SelectWordRight => "select_word_right",
+            Self::ErrorPage => "error_page",
+            Self::Shortcut => "shortcut",
+            Self::Undo => "undo",
+            Self::Redo => "redo",
+            Self::ToggleContrast => "toggle_contrast",
+        }
+    }
 }
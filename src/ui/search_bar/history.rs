@@ -0,0 +1,128 @@
+use std::{
+    collections::VecDeque,
+    ops::Range,
+    time::{Duration, Instant},
+};
+
+use gpui::SharedString;
+
+/// Undo steps kept per input, older ones are dropped
+const MAX_STEPS: usize = 100;
+
+/// Edits that follow each other within this time are undone together, so typing a word is one
+/// step instead of one per key
+const MERGE_WINDOW: Duration = Duration::from_millis(500);
+
+/// Content and selection of an input, restored by undo and redo
+#[derive(Clone, Debug, PartialEq)]
+pub struct Snapshot {
+    pub content: SharedString,
+    pub selection: Range<usize>,
+}
+
+/// Undo and redo steps of a [`TextInput`](super::TextInput)
+#[derive(Debug, Default)]
+pub struct EditHistory {
+    undo: VecDeque<Snapshot>,
+    redo: Vec<Snapshot>,
+    last_edit: Option<Instant>,
+}
+
+impl EditHistory {
+    /// Records `before`, the state ahead of an edit made at `now`. An edit right after the
+    /// previous one extends its step instead.
+    pub fn record(&mut self, before: Snapshot, now: Instant) {
+        let merge = self
+            .last_edit
+            .is_some_and(|last| now.duration_since(last) < MERGE_WINDOW);
+        self.last_edit = Some(now);
+        self.redo.clear();
+        if !merge {
+            self.push_undo(before);
+        }
+    }
+
+    /// The state before the latest step, `current` becomes redoable
+    pub fn undo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let previous = self.undo.pop_back()?;
+        self.redo.push(current);
+        self.last_edit = None;
+        Some(previous)
+    }
+
+    /// The state the latest undo went back from, `current` becomes undoable again
+    pub fn redo(&mut self, current: Snapshot) -> Option<Snapshot> {
+        let next = self.redo.pop()?;
+        self.push_undo(current);
+        self.last_edit = None;
+        Some(next)
+    }
+
+    pub fn clear(&mut self) {
+        *self = Self::default();
+    }
+
+    fn push_undo(&mut self, snapshot: Snapshot) {
+        if self.undo.len() == MAX_STEPS {
+            self.undo.pop_front();
+        }
+        self.undo.push_back(snapshot);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(content: &'static str) -> Snapshot {
+        Snapshot {
+            content: content.into(),
+            selection: content.len()..content.len(),
+        }
+    }
+
+    #[test]
+    fn test_quick_edits_are_one_step() {
+        let start = Instant::now();
+        let mut history = EditHistory::default();
+        history.record(snapshot(""), start);
+        history.record(snapshot("f"), start + Duration::from_millis(100));
+        history.record(snapshot("fi"), start + Duration::from_millis(200));
+        // a pause starts the next step
+        history.record(snapshot("fir"), start + Duration::from_secs(2));
+
+        assert_eq!(history.undo(snapshot("fire")), Some(snapshot("fir")));
+        assert_eq!(history.undo(snapshot("fir")), Some(snapshot("")));
+        assert_eq!(history.undo(snapshot("")), None);
+
+        assert_eq!(history.redo(snapshot("")), Some(snapshot("fir")));
+        assert_eq!(history.redo(snapshot("fir")), Some(snapshot("fire")));
+        assert_eq!(history.redo(snapshot("fire")), None);
+    }
+
+    #[test]
+    fn test_edits_drop_redo_steps() {
+        let start = Instant::now();
+        let mut history = EditHistory::default();
+        history.record(snapshot(""), start);
+        assert_eq!(history.undo(snapshot("a")), Some(snapshot("")));
+
+        history.record(snapshot(""), start + Duration::from_secs(1));
+        assert_eq!(history.redo(snapshot("b")), None);
+        assert_eq!(history.undo(snapshot("b")), Some(snapshot("")));
+    }
+
+    #[test]
+    fn test_steps_are_bounded() {
+        let start = Instant::now();
+        let mut history = EditHistory::default();
+        for i in 0..MAX_STEPS + 5 {
+            history.record(snapshot(""), start + MERGE_WINDOW * 2 * i as u32);
+        }
+        let mut steps = 0;
+        while history.undo(snapshot("")).is_some() {
+            steps += 1;
+        }
+        assert_eq!(steps, MAX_STEPS);
+    }
+}
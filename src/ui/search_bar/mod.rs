@@ -1,4 +1,6 @@
-use std::ops::Range;
+mod history;
+
+use std::{ops::Range, time::Instant};
 
 use gpui::{
     AbsoluteLength, Action, App, Bounds, ClipboardItem, Context, CursorStyle, Element, ElementId,
@@ -11,7 +13,14 @@ use gpui::{
 use serde::Deserialize;
 use unicode_segmentation::UnicodeSegmentation;
 
+pub use history::EditHistory;
+use history::Snapshot;
+
 use crate::loader::utils::ExecVariable;
+use crate::utils::bidi::BidiText;
+use crate::utils::command_launch::Substitution;
+use crate::utils::config::{CaretMovement, ConfigGuard};
+use crate::utils::palette::Palette;
 
 actions!(
     text_input,
@@ -23,6 +32,10 @@ actions!(
         Right,
         SelectLeft,
         SelectRight,
+        WordLeft,
+        WordRight,
+        SelectWordLeft,
+        SelectWordRight,
         SelectAll,
         Home,
         End,
@@ -30,6 +43,8 @@ actions!(
         Paste,
         Cut,
         Copy,
+        Undo,
+        Redo,
     ]
 );
 
@@ -83,31 +98,163 @@ pub struct TextInput {
     pub last_bounds: Option<Bounds<Pixels>>,
     pub is_selecting: bool,
     pub variable: Option<ExecVariable>,
+    pub history: EditHistory,
 }
 
 impl TextInput {
+    /// The input bar of a variable, filled with `recalled`, the value submitted to it last, or
+    /// its default. Choices start at their first value otherwise. A recalled value is selected,
+    /// so that typing replaces it.
+    pub fn for_variable(
+        variable: ExecVariable,
+        recalled: Option<SharedString>,
+        cx: &mut Context<Self>,
+    ) -> Self {
+        let selected = recalled.is_some();
+        let content = recalled
+            .or_else(|| variable.spec().default.clone())
+            .or_else(|| {
+                variable
+                    .choices()
+                    .and_then(|choices| choices.first().cloned())
+            })
+            .unwrap_or_default();
+        let selected_range = if selected {
+            0..content.len()
+        } else {
+            content.len()..content.len()
+        };
+        Self {
+            focus_handle: cx.focus_handle(),
+            content,
+            placeholder: variable.placeholder(),
+            variable: Some(variable),
+            selected_range,
+            selection_reversed: false,
+            marked_range: None,
+            last_layout: None,
+            last_bounds: None,
+            is_selecting: false,
+            history: EditHistory::default(),
+        }
+    }
+
+    /// Returns false if the content of a variable input doesn't validate, the entry can't run
+    /// until it does
+    pub fn is_valid(&self) -> bool {
+        self.variable
+            .as_ref()
+            .is_none_or(|variable| variable.spec().validate(&self.content))
+    }
+
+    /// The content as it is put into the exec string, see
+    /// [`VariableSpec::substitute`](crate::loader::utils::VariableSpec::substitute)
+    pub fn substitution(&self) -> Substitution {
+        match &self.variable {
+            Some(variable) => variable.spec().substitute(&self.content),
+            None => Substitution::Literal(self.content.clone()),
+        }
+    }
+
+    /// Whether the caret sits at the start or, with `forward`, the end of the content without a
+    /// selection, so the arrow key in that direction has nothing left to move
+    pub fn caret_at_edge(&self, forward: bool) -> bool {
+        let edge = if forward { self.content.len() } else { 0 };
+        self.selected_range == (edge..edge)
+    }
+
+    fn choices(&self) -> Option<&[SharedString]> {
+        self.variable.as_ref()?.choices()
+    }
+
+    /// Moves a choice input to the next or previous value, wrapping around at either end
+    fn cycle_choice(&mut self, forward: bool, cx: &mut Context<Self>) {
+        let Some(choices) = self.choices() else {
+            return;
+        };
+        let current = choices.iter().position(|choice| *choice == self.content);
+        let next = match (current, forward) {
+            (Some(i), true) => (i + 1) % choices.len(),
+            (Some(i), false) => (i + choices.len() - 1) % choices.len(),
+            (None, _) => 0,
+        };
+        self.content = choices[next].clone();
+        self.selected_range = 0..0;
+        cx.notify();
+    }
+
     fn left(&mut self, _: &Left, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.move_to(self.previous_boundary(self.cursor_offset()), cx);
+        if self.choices().is_some() {
+            self.cycle_choice(false, cx);
+        } else if self.selected_range.is_empty() {
+            self.move_to(self.step(self.cursor_offset(), false), cx);
         } else {
             self.move_to(self.selected_range.start, cx)
         }
     }
 
     fn right(&mut self, _: &Right, _: &mut Window, cx: &mut Context<Self>) {
-        if self.selected_range.is_empty() {
-            self.move_to(self.next_boundary(self.selected_range.end), cx);
+        if self.choices().is_some() {
+            self.cycle_choice(true, cx);
+        } else if self.selected_range.is_empty() {
+            self.move_to(self.step(self.selected_range.end, true), cx);
         } else {
             self.move_to(self.selected_range.end, cx)
         }
     }
 
     fn select_left(&mut self, _: &SelectLeft, _: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.previous_boundary(self.cursor_offset()), cx);
+        self.select_to(self.step(self.cursor_offset(), false), cx);
     }
 
     fn select_right(&mut self, _: &SelectRight, _: &mut Window, cx: &mut Context<Self>) {
-        self.select_to(self.next_boundary(self.cursor_offset()), cx);
+        self.select_to(self.step(self.cursor_offset(), true), cx);
+    }
+
+    fn word_left(&mut self, _: &WordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.word_step(self.cursor_offset(), false), cx);
+    }
+
+    fn word_right(&mut self, _: &WordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.move_to(self.word_step(self.cursor_offset(), true), cx);
+    }
+
+    fn select_word_left(&mut self, _: &SelectWordLeft, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.word_step(self.cursor_offset(), false), cx);
+    }
+
+    fn select_word_right(&mut self, _: &SelectWordRight, _: &mut Window, cx: &mut Context<Self>) {
+        self.select_to(self.word_step(self.cursor_offset(), true), cx);
+    }
+
+    /// The start of the word left of `offset` or the end of the word right of it. Password
+    /// fields jump to either end, so the caret doesn't give away where the words are.
+    fn word_step(&self, offset: usize, right: bool) -> usize {
+        match (self.is_masked(), right) {
+            (true, true) => self.content.len(),
+            (true, false) => 0,
+            (false, true) => next_word_boundary(&self.content, offset),
+            (false, false) => previous_word_boundary(&self.content, offset),
+        }
+    }
+
+    /// The caret position one step to the right or left of `offset`. Moves through
+    /// right-to-left runs visually unless `behavior.caret_movement` is set to `logical`.
+    fn step(&self, offset: usize, right: bool) -> usize {
+        let visual = ConfigGuard::read()
+            .map(|config| config.behavior.caret_movement == CaretMovement::Visual)
+            .unwrap_or(true);
+        if visual && !self.is_masked() {
+            let bidi = BidiText::new(&self.content);
+            if bidi.is_reordered() {
+                return bidi.move_visually(offset, right);
+            }
+        }
+        if right {
+            self.next_boundary(offset)
+        } else {
+            self.previous_boundary(offset)
+        }
     }
 
     fn select_all(&mut self, _: &SelectAll, _: &mut Window, cx: &mut Context<Self>) {
@@ -142,16 +289,53 @@ impl TextInput {
         self.replace_text_in_range(None, "", window, cx)
     }
     fn delete_all(&mut self, _: &DeleteAll, _window: &mut Window, cx: &mut Context<Self>) {
+        if self.choices().is_some() {
+            return;
+        }
+        if !self.content.is_empty() {
+            self.history.record(self.snapshot(), Instant::now());
+        }
         self.reset();
         cx.notify();
     }
 
+    fn undo(&mut self, _: &Undo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(previous) = self.history.undo(self.snapshot()) {
+            self.restore(previous, cx);
+        }
+    }
+
+    fn redo(&mut self, _: &Redo, _: &mut Window, cx: &mut Context<Self>) {
+        if let Some(next) = self.history.redo(self.snapshot()) {
+            self.restore(next, cx);
+        }
+    }
+
+    fn snapshot(&self) -> Snapshot {
+        Snapshot {
+            content: self.content.clone(),
+            selection: self.selected_range.clone(),
+        }
+    }
+
+    fn restore(&mut self, snapshot: Snapshot, cx: &mut Context<Self>) {
+        self.content = snapshot.content;
+        self.selected_range = snapshot.selection;
+        self.selection_reversed = false;
+        self.marked_range = None;
+        cx.notify();
+    }
+
     fn on_mouse_down(
         &mut self,
         event: &MouseDownEvent,
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.choices().is_some() {
+            self.cycle_choice(!event.modifiers.shift, cx);
+            return;
+        }
         self.is_selecting = true;
 
         if event.modifiers.shift {
@@ -230,7 +414,51 @@ impl TextInput {
         if position.y > bounds.bottom() {
             return self.content.len();
         }
-        line.closest_index_for_x(position.x - bounds.left())
+        self.offset_for_x(line, position.x - bounds.left())
+    }
+
+    /// The caret position closest to `x` on the shaped line
+    fn offset_for_x(&self, line: &ShapedLine, x: Pixels) -> usize {
+        let display = self.display_text();
+        let bidi = BidiText::new(&display);
+        if !bidi.is_reordered() {
+            return self.offset_from_display(line.closest_index_for_x(x));
+        }
+        bidi.caret_stops()
+            .min_by_key(|&(_, visual)| (line.x_for_index(visual) - x).abs())
+            .map_or(0, |(offset, _)| self.offset_from_display(offset))
+    }
+
+    /// `content` as it is shaped, one bullet per grapheme for password fields
+    fn display_text(&self) -> SharedString {
+        if self.is_masked() {
+            "•".repeat(self.content.graphemes(true).count()).into()
+        } else {
+            self.content.clone()
+        }
+    }
+
+    fn is_masked(&self) -> bool {
+        matches!(self.variable, Some(ExecVariable::PasswordInput(_)))
+    }
+
+    /// Maps an offset into the shaped line back to `content`. Password fields show one bullet
+    /// per grapheme, the shaped line can also split a cluster the caret must not enter.
+    fn offset_from_display(&self, offset: usize) -> usize {
+        if self.is_masked() {
+            unmask_offset(&self.content, offset)
+        } else {
+            snap_to_boundary(&self.content, offset)
+        }
+    }
+
+    /// Maps an offset into `content` to the shaped line
+    fn offset_to_display(&self, offset: usize) -> usize {
+        if self.is_masked() {
+            mask_offset(&self.content, offset)
+        } else {
+            offset
+        }
     }
 
     fn select_to(&mut self, offset: usize, cx: &mut Context<Self>) {
@@ -247,33 +475,11 @@ impl TextInput {
     }
 
     fn offset_from_utf16(&self, offset: usize) -> usize {
-        let mut utf8_offset = 0;
-        let mut utf16_count = 0;
-
-        for ch in self.content.chars() {
-            if utf16_count >= offset {
-                break;
-            }
-            utf16_count += ch.len_utf16();
-            utf8_offset += ch.len_utf8();
-        }
-
-        utf8_offset
+        utf16_to_utf8(&self.content, offset)
     }
 
     fn offset_to_utf16(&self, offset: usize) -> usize {
-        let mut utf16_offset = 0;
-        let mut utf8_count = 0;
-
-        for ch in self.content.chars() {
-            if utf8_count >= offset {
-                break;
-            }
-            utf8_count += ch.len_utf8();
-            utf16_offset += ch.len_utf16();
-        }
-
-        utf16_offset
+        utf8_to_utf16(&self.content, offset)
     }
 
     fn range_to_utf16(&self, range: &Range<usize>) -> Range<usize> {
@@ -285,18 +491,11 @@ impl TextInput {
     }
 
     fn previous_boundary(&self, offset: usize) -> usize {
-        self.content
-            .grapheme_indices(true)
-            .rev()
-            .find_map(|(idx, _)| (idx < offset).then_some(idx))
-            .unwrap_or(0)
+        previous_boundary(&self.content, offset)
     }
 
     fn next_boundary(&self, offset: usize) -> usize {
-        self.content
-            .grapheme_indices(true)
-            .find_map(|(idx, _)| (idx > offset).then_some(idx))
-            .unwrap_or(self.content.len())
+        next_boundary(&self.content, offset)
     }
 
     pub fn reset(&mut self) {
@@ -308,6 +507,12 @@ impl TextInput {
         self.last_bounds = None;
         self.is_selecting = false;
     }
+    /// Replaces the content and moves the cursor to its end
+    pub fn set_content(&mut self, content: impl Into<SharedString>) {
+        self.reset();
+        self.content = content.into();
+        self.selected_range = self.content.len()..self.content.len();
+    }
 }
 
 impl EntityInputHandler for TextInput {
@@ -356,12 +561,20 @@ impl EntityInputHandler for TextInput {
         _: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        // choices are picked, not typed
+        if self.choices().is_some() {
+            return;
+        }
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
             .or(self.marked_range.clone())
             .unwrap_or(self.selected_range.clone());
 
+        // a composition was recorded when it started
+        if self.marked_range.is_none() && !(range.is_empty() && new_text.is_empty()) {
+            self.history.record(self.snapshot(), Instant::now());
+        }
         self.content =
             (self.content[0..range.start].to_owned() + new_text + &self.content[range.end..])
                 .into();
@@ -378,12 +591,18 @@ impl EntityInputHandler for TextInput {
         _window: &mut Window,
         cx: &mut Context<Self>,
     ) {
+        if self.choices().is_some() {
+            return;
+        }
         let range = range_utf16
             .as_ref()
             .map(|range_utf16| self.range_from_utf16(range_utf16))
             .or(self.marked_range.clone())
             .unwrap_or(self.selected_range.clone());
 
+        if self.marked_range.is_none() {
+            self.history.record(self.snapshot(), Instant::now());
+        }
         self.content =
             (self.content[0..range.start].to_owned() + new_text + &self.content[range.end..])
                 .into();
@@ -392,10 +611,13 @@ impl EntityInputHandler for TextInput {
         } else {
             self.marked_range = None;
         }
+        // the new selection is relative to the inserted text
         self.selected_range = new_selected_range_utf16
             .as_ref()
-            .map(|range_utf16| self.range_from_utf16(range_utf16))
-            .map(|new_range| new_range.start + range.start..new_range.end + range.end)
+            .map(|new_range| {
+                range.start + utf16_to_utf8(new_text, new_range.start)
+                    ..range.start + utf16_to_utf8(new_text, new_range.end)
+            })
             .unwrap_or_else(|| range.start + new_text.len()..range.start + new_text.len());
 
         cx.notify();
@@ -410,15 +632,14 @@ impl EntityInputHandler for TextInput {
     ) -> Option<Bounds<Pixels>> {
         let last_layout = self.last_layout.as_ref()?;
         let range = self.range_from_utf16(&range_utf16);
+        let display = self.display_text();
+        let bidi = BidiText::new(&display);
+        let x =
+            |offset| last_layout.x_for_index(bidi.visual_offset(self.offset_to_display(offset)));
+        let (start, end) = (x(range.start), x(range.end));
         Some(Bounds::from_corners(
-            point(
-                bounds.left() + last_layout.x_for_index(range.start),
-                bounds.top(),
-            ),
-            point(
-                bounds.left() + last_layout.x_for_index(range.end),
-                bounds.bottom(),
-            ),
+            point(bounds.left() + start.min(end), bounds.top()),
+            point(bounds.left() + start.max(end), bounds.bottom()),
         ))
     }
 
@@ -431,9 +652,7 @@ impl EntityInputHandler for TextInput {
         let line_point = self.last_bounds?.localize(&point)?;
         let last_layout = self.last_layout.as_ref()?;
 
-        assert_eq!(last_layout.text, self.content);
-        let utf8_index = last_layout.index_for_x(point.x - line_point.x)?;
-        Some(self.offset_to_utf16(utf8_index))
+        Some(self.offset_to_utf16(self.offset_for_x(last_layout, line_point.x)))
     }
 }
 
@@ -444,7 +663,8 @@ struct TextElement {
 struct PrepaintState {
     line: Option<ShapedLine>,
     cursor: Option<PaintQuad>,
-    selection: Option<PaintQuad>,
+    /// One quad per direction run the selection covers
+    selection: Vec<PaintQuad>,
 }
 
 struct TextElementRequestLayoutState {
@@ -479,19 +699,24 @@ impl Element for TextElement {
         cx: &mut App,
     ) -> (LayoutId, Self::RequestLayoutState) {
         let input = self.input.read(cx);
-        let content: SharedString = match &input.variable {
-            Some(ExecVariable::PasswordInput(_)) => {
-                "•".repeat(input.content.chars().count()).into()
-            }
-            _ => input.content.clone().into(),
-        };
+        let content = input.display_text();
         let style = window.text_style();
 
-        let (display_text, text_color) = if content.is_empty() {
-            (input.placeholder.clone(), hsla(1., 1., 1., 0.2))
+        let (logical_text, text_color) = if content.is_empty() {
+            (
+                input.placeholder.clone(),
+                Palette::current().fg(hsla(1., 1., 1., 0.2)),
+            )
         } else {
             (content, style.color)
         };
+        // the shaper does not reorder right-to-left runs itself
+        let bidi = BidiText::new(&logical_text);
+        let display_text: SharedString = if bidi.is_reordered() {
+            bidi.as_str().to_string().into()
+        } else {
+            logical_text.clone()
+        };
 
         let run = TextRun {
             len: display_text.len(),
@@ -502,28 +727,35 @@ impl Element for TextElement {
             strikethrough: None,
         };
         let runs = if let Some(marked_range) = input.marked_range.as_ref() {
-            vec![
-                TextRun {
-                    len: marked_range.start,
-                    ..run.clone()
-                },
-                TextRun {
-                    len: marked_range.end - marked_range.start,
-                    underline: Some(UnderlineStyle {
-                        color: Some(run.color),
-                        thickness: px(1.0),
-                        wavy: false,
-                    }),
+            let marked = input.offset_to_display(marked_range.start)
+                ..input.offset_to_display(marked_range.end);
+            let underlined = TextRun {
+                underline: Some(UnderlineStyle {
+                    color: Some(run.color),
+                    thickness: px(1.0),
+                    wavy: false,
+                }),
+                ..run.clone()
+            };
+            let mut runs = Vec::new();
+            let mut end = 0;
+            for range in bidi.visual_ranges(std::iter::once(marked)) {
+                runs.push(TextRun {
+                    len: range.start - end,
                     ..run.clone()
-                },
-                TextRun {
-                    len: display_text.len() - marked_range.end,
-                    ..run
-                },
-            ]
-            .into_iter()
-            .filter(|run| run.len > 0)
-            .collect()
+                });
+                runs.push(TextRun {
+                    len: range.len(),
+                    ..underlined.clone()
+                });
+                end = range.end;
+            }
+            runs.push(TextRun {
+                len: display_text.len() - end,
+                ..run
+            });
+            runs.retain(|run| run.len > 0);
+            runs
         } else {
             vec![run]
         };
@@ -556,17 +788,12 @@ impl Element for TextElement {
         cx: &mut App,
     ) -> Self::PrepaintState {
         let input = self.input.read(cx);
-        let mut selected_range = input.selected_range.clone();
-        let mut cursor = input.cursor_offset();
-
-        // handle password fields
-        if let Some(ExecVariable::PasswordInput(_)) = &input.variable {
-            cursor = input.content[..cursor].chars().count() * "•".len();
-
-            let start = input.content[..selected_range.start].chars().count() * "•".len();
-            let end = input.content[..selected_range.end].chars().count() * "•".len();
-            selected_range = start..end;
-        }
+        // handle password fields and right-to-left runs
+        let display = input.display_text();
+        let bidi = BidiText::new(&display);
+        let cursor = bidi.visual_offset(input.offset_to_display(input.cursor_offset()));
+        let selected_range = input.offset_to_display(input.selected_range.start)
+            ..input.offset_to_display(input.selected_range.end);
 
         // Cached from request layout
         let line = &request_layout.l;
@@ -574,7 +801,7 @@ impl Element for TextElement {
         let cursor_pos = line.x_for_index(cursor);
         let (selection, cursor) = if selected_range.is_empty() {
             (
-                None,
+                Vec::new(),
                 Some(fill(
                     Bounds::new(
                         point(bounds.left() + cursor_pos, bounds.top()),
@@ -583,24 +810,23 @@ impl Element for TextElement {
                             height: bounds.bottom() - bounds.top(),
                         },
                     ),
-                    rgb(0xcccccc),
+                    Palette::current().fg(rgb(0xcccccc)),
                 )),
             )
         } else {
             (
-                Some(fill(
-                    Bounds::from_corners(
-                        point(
-                            bounds.left() + line.x_for_index(selected_range.start),
-                            bounds.top(),
-                        ),
-                        point(
-                            bounds.left() + line.x_for_index(selected_range.end),
-                            bounds.bottom(),
-                        ),
-                    ),
-                    rgba(0x3311ff30),
-                )),
+                bidi.visual_ranges(std::iter::once(selected_range))
+                    .into_iter()
+                    .map(|range| {
+                        fill(
+                            Bounds::from_corners(
+                                point(bounds.left() + line.x_for_index(range.start), bounds.top()),
+                                point(bounds.left() + line.x_for_index(range.end), bounds.bottom()),
+                            ),
+                            rgba(0x3311ff30),
+                        )
+                    })
+                    .collect(),
                 None,
             )
         };
@@ -627,7 +853,7 @@ impl Element for TextElement {
             ElementInputHandler::new(bounds, self.input.clone()),
             cx,
         );
-        if let Some(selection) = prepaint.selection.take() {
+        for selection in prepaint.selection.drain(..) {
             window.paint_quad(selection)
         }
         let line = prepaint.line.take().unwrap();
@@ -649,11 +875,21 @@ impl Element for TextElement {
 
 impl Render for TextInput {
     fn render(&mut self, _window: &mut Window, cx: &mut Context<Self>) -> impl IntoElement {
+        let palette = Palette::current();
+        let border = if self.is_valid() {
+            hsla(0., 0., 0.1882, 1.0)
+        } else {
+            rgb(0xe06c75).into()
+        };
         div()
             .flex()
             .key_context("TextInput")
             .track_focus(&self.focus_handle(cx))
-            .cursor(CursorStyle::IBeam)
+            .cursor(if self.choices().is_some() {
+                CursorStyle::PointingHand
+            } else {
+                CursorStyle::IBeam
+            })
             .on_action(cx.listener(Self::backspace))
             .on_action(cx.listener(Self::delete))
             .on_action(cx.listener(Self::delete_all))
@@ -661,6 +897,10 @@ impl Render for TextInput {
             .on_action(cx.listener(Self::right))
             .on_action(cx.listener(Self::select_left))
             .on_action(cx.listener(Self::select_right))
+            .on_action(cx.listener(Self::word_left))
+            .on_action(cx.listener(Self::word_right))
+            .on_action(cx.listener(Self::select_word_left))
+            .on_action(cx.listener(Self::select_word_right))
             .on_action(cx.listener(Self::select_all))
             .on_action(cx.listener(Self::home))
             .on_action(cx.listener(Self::end))
@@ -668,16 +908,18 @@ impl Render for TextInput {
             .on_action(cx.listener(Self::paste))
             .on_action(cx.listener(Self::cut))
             .on_action(cx.listener(Self::copy))
+            .on_action(cx.listener(Self::undo))
+            .on_action(cx.listener(Self::redo))
             .on_mouse_down(MouseButton::Left, cx.listener(Self::on_mouse_down))
             .on_mouse_up(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_up_out(MouseButton::Left, cx.listener(Self::on_mouse_up))
             .on_mouse_move(cx.listener(Self::on_mouse_move))
-            .text_color(rgb(0xcccccc))
+            .text_color(palette.fg(rgb(0xcccccc)))
             .w_auto()
             .child(if self.variable.is_some() {
-                div()
-                    .line_height(px(12.))
-                    .text_size(px(12.))
+                let bar = div()
+                    .line_height(palette.text_size(px(12.)))
+                    .text_size(palette.text_size(px(12.)))
                     .h(px(20. + 4. * 2.)) // 38px
                     .p(px(4.))
                     .px(px(7.))
@@ -686,10 +928,21 @@ impl Render for TextInput {
                     .flex_none()
                     .items_center()
                     .border(px(1.))
-                    .border_color(hsla(0., 0., 0.1882, 1.0))
+                    .border_color(palette.fg(border))
                     .rounded_md()
-                    .min_w(px(20.))
-                    .child(TextElement { input: cx.entity() })
+                    .min_w(px(20.));
+                // choices show their value between arrows, left and right cycle through them
+                if self.choices().is_some() {
+                    let arrow = |arrow: &'static str| {
+                        div().text_color(palette.fg(rgb(0x666666))).child(arrow)
+                    };
+                    bar.gap_2()
+                        .child(arrow("‹"))
+                        .child(self.content.clone())
+                        .child(arrow("›"))
+                } else {
+                    bar.child(TextElement { input: cx.entity() })
+                }
             } else {
                 div()
                     .line_height(px(16.))
@@ -711,3 +964,209 @@ impl Focusable for TextInput {
         self.focus_handle.clone()
     }
 }
+
+/// Start of the grapheme cluster before `offset`
+fn previous_boundary(text: &str, offset: usize) -> usize {
+    text.grapheme_indices(true)
+        .rev()
+        .find_map(|(idx, _)| (idx < offset).then_some(idx))
+        .unwrap_or(0)
+}
+
+/// Start of the grapheme cluster after `offset`, or the end of `text`
+fn next_boundary(text: &str, offset: usize) -> usize {
+    text.grapheme_indices(true)
+        .find_map(|(idx, _)| (idx > offset).then_some(idx))
+        .unwrap_or(text.len())
+}
+
+/// Words are runs of alphanumeric grapheme clusters, e.g. letters with combining accents
+fn is_word(grapheme: &str) -> bool {
+    grapheme.chars().next().is_some_and(char::is_alphanumeric)
+}
+
+/// Start of the word before `offset`, skipping anything between it and `offset`
+fn previous_word_boundary(text: &str, offset: usize) -> usize {
+    let mut in_word = false;
+    for (idx, grapheme) in text[..offset].grapheme_indices(true).rev() {
+        if is_word(grapheme) {
+            in_word = true;
+        } else if in_word {
+            return idx + grapheme.len();
+        }
+    }
+    0
+}
+
+/// End of the word after `offset`, skipping anything between `offset` and it
+fn next_word_boundary(text: &str, offset: usize) -> usize {
+    let mut in_word = false;
+    for (idx, grapheme) in text[offset..].grapheme_indices(true) {
+        if is_word(grapheme) {
+            in_word = true;
+        } else if in_word {
+            return offset + idx;
+        }
+    }
+    text.len()
+}
+
+/// Moves `offset` back to the start of the grapheme cluster it points into
+fn snap_to_boundary(text: &str, offset: usize) -> usize {
+    if offset >= text.len() {
+        return text.len();
+    }
+    text.grapheme_indices(true)
+        .take_while(|(idx, _)| *idx <= offset)
+        .last()
+        .map_or(0, |(idx, _)| idx)
+}
+
+/// Offset into a password field's bullets, one per grapheme cluster of `text`
+fn mask_offset(text: &str, offset: usize) -> usize {
+    text.grapheme_indices(true)
+        .take_while(|(idx, _)| *idx < offset)
+        .count()
+        * "•".len()
+}
+
+/// Offset into `text` for an offset into its bullets, see [`mask_offset`]
+fn unmask_offset(text: &str, offset: usize) -> usize {
+    text.grapheme_indices(true)
+        .nth(offset / "•".len())
+        .map_or(text.len(), |(idx, _)| idx)
+}
+
+/// Converts a UTF-16 offset, as used by the platform input handler, to a byte offset. Offsets
+/// in the middle of a surrogate pair are moved to the end of the character.
+fn utf16_to_utf8(text: &str, offset: usize) -> usize {
+    let mut utf8_offset = 0;
+    let mut utf16_count = 0;
+
+    for ch in text.chars() {
+        if utf16_count >= offset {
+            break;
+        }
+        utf16_count += ch.len_utf16();
+        utf8_offset += ch.len_utf8();
+    }
+
+    utf8_offset
+}
+
+fn utf8_to_utf16(text: &str, offset: usize) -> usize {
+    let mut utf16_offset = 0;
+    let mut utf8_count = 0;
+
+    for ch in text.chars() {
+        if utf8_count >= offset {
+            break;
+        }
+        utf8_count += ch.len_utf8();
+        utf16_offset += ch.len_utf16();
+    }
+
+    utf16_offset
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FAMILY: &str = "👨\u{200d}👩\u{200d}👧";
+    const FLAG: &str = "🇩🇪";
+
+    #[test]
+    fn test_caret_skips_grapheme_clusters() {
+        // "e" followed by a combining acute accent, a family emoji and a flag
+        let text = format!("ae\u{301}{FAMILY}{FLAG}!");
+        let family = 1 + "e\u{301}".len();
+        let flag = family + FAMILY.len();
+
+        let mut offset = 0;
+        let mut stops = vec![offset];
+        while offset < text.len() {
+            offset = next_boundary(&text, offset);
+            stops.push(offset);
+        }
+        assert_eq!(
+            stops,
+            vec![0, 1, family, flag, flag + FLAG.len(), text.len()]
+        );
+
+        let mut backwards = vec![offset];
+        while offset > 0 {
+            offset = previous_boundary(&text, offset);
+            backwards.push(offset);
+        }
+        stops.reverse();
+        assert_eq!(backwards, stops);
+
+        for stop in &stops {
+            assert!(text.is_char_boundary(*stop));
+        }
+    }
+
+    #[test]
+    fn test_word_boundaries() {
+        let text = "open  fire-fox\u{301} ";
+        let fox = "open  fire-".len();
+
+        let mut offset = 0;
+        let mut stops = Vec::new();
+        while offset < text.len() {
+            offset = next_word_boundary(text, offset);
+            stops.push(offset);
+        }
+        assert_eq!(stops, vec![4, fox - 1, text.len() - 1, text.len()]);
+
+        let mut backwards = Vec::new();
+        while offset > 0 {
+            offset = previous_word_boundary(text, offset);
+            backwards.push(offset);
+        }
+        assert_eq!(backwards, vec![fox, 6, 0]);
+
+        assert_eq!(previous_word_boundary("  ", 2), 0);
+        assert_eq!(next_word_boundary("", 0), 0);
+    }
+
+    #[test]
+    fn test_snap_to_boundary() {
+        let text = format!("x{FAMILY}y");
+        for offset in 1..=FAMILY.len() {
+            assert_eq!(snap_to_boundary(&text, offset), 1);
+        }
+        assert_eq!(snap_to_boundary(&text, FAMILY.len() + 1), FAMILY.len() + 1);
+        assert_eq!(snap_to_boundary(&text, 100), text.len());
+        assert_eq!(snap_to_boundary("", 3), 0);
+    }
+
+    #[test]
+    fn test_password_offsets() {
+        let text = format!("a{FLAG}e\u{301}");
+        let flag_end = 1 + FLAG.len();
+
+        assert_eq!(mask_offset(&text, 0), 0);
+        assert_eq!(mask_offset(&text, 1), "•".len());
+        assert_eq!(mask_offset(&text, flag_end), 2 * "•".len());
+        assert_eq!(mask_offset(&text, text.len()), 3 * "•".len());
+
+        assert_eq!(unmask_offset(&text, 2 * "•".len()), flag_end);
+        assert_eq!(unmask_offset(&text, 3 * "•".len()), text.len());
+        for offset in 0..=text.len() {
+            let roundtrip = unmask_offset(&text, mask_offset(&text, offset));
+            assert!(text.is_char_boundary(roundtrip));
+        }
+    }
+
+    #[test]
+    fn test_utf16_offsets() {
+        // the flag is two surrogate pairs, four UTF-16 units and eight bytes
+        let text = format!("a{FLAG}b");
+        assert_eq!(utf8_to_utf16(&text, 1 + FLAG.len()), 5);
+        assert_eq!(utf16_to_utf8(&text, 5), 1 + FLAG.len());
+        assert_eq!(utf16_to_utf8(&text, 2), 5);
+        assert_eq!(utf16_to_utf8(&text, 100), text.len());
+    }
+}
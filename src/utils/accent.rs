@@ -0,0 +1,307 @@
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+    sync::RwLock,
+};
+
+use gpui::{Hsla, Rgba, hsla, rgb};
+use image::DynamicImage;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::utils::{
+    config::{ConfigAppearance, ConfigGuard},
+    intent::colors::ColorConverter,
+    palette::Palette,
+};
+
+/// Window background the accent has to stay readable against
+const BACKGROUND: (f32, f32, f32) = (15.0, 15.0, 15.0);
+/// Text drawn on top of the selection background
+const SELECTED_TEXT: (f32, f32, f32) = (255.0, 255.0, 255.0);
+
+static ACCENT: RwLock<Option<Accent>> = RwLock::new(None);
+
+/// Colors derived from the configured or extracted accent color
+#[derive(Clone, Copy, Debug)]
+pub struct Accent {
+    pub selection: Hsla,
+    pub mode_chip: Hsla,
+    pub footer: Hsla,
+}
+
+impl Default for Accent {
+    fn default() -> Self {
+        Self {
+            selection: hsla(0., 0., 0.149, 1.0),
+            mode_chip: rgb(0x2e2e2e).into(),
+            footer: hsla(0.6, 0.0217, 0.3608, 1.0),
+        }
+    }
+}
+
+impl Accent {
+    /// Returns the accent currently in use, with the [`Palette`] overlay applied
+    pub fn current() -> Self {
+        let accent = ACCENT
+            .read()
+            .ok()
+            .and_then(|accent| *accent)
+            .unwrap_or_default();
+        let palette = Palette::current();
+        Self {
+            selection: palette.bg(accent.selection),
+            mode_chip: palette.fg(accent.mode_chip),
+            footer: palette.fg(accent.footer),
+        }
+    }
+
+    /// Resolves `appearance.accent` again. Blocks while the wallpaper is decoded, so it should
+    /// not be called from the UI thread.
+    ///
+    /// Any failure silently falls back to the built-in palette.
+    pub fn refresh() {
+        let accent = ConfigGuard::read()
+            .ok()
+            .and_then(|config| Self::resolve(&config.appearance));
+        if let Ok(mut current) = ACCENT.write() {
+            *current = accent;
+        }
+    }
+
+    fn resolve(appearance: &ConfigAppearance) -> Option<Self> {
+        let color = match appearance.accent.trim() {
+            "" => return None,
+            "auto" => {
+                let path = wallpaper_path(appearance)?;
+                dominant_color(&image::open(path).ok()?)?
+            }
+            hex => ColorConverter::hex_to_rgb(hex)?,
+        };
+        Some(Self::from_rgb(color))
+    }
+
+    /// Derives the palette from a single color, adjusting its lightness so that text stays
+    /// readable
+    fn from_rgb(color: (f32, f32, f32)) -> Self {
+        let (h, s, _) = ColorConverter::rgb_to_hsl(color.0, color.1, color.2);
+
+        // dark enough for white text, but still distinguishable from the background
+        let selection = (5..=40)
+            .rev()
+            .map(|l| ColorConverter::hsl_to_rgb(h, s.min(60.0), l as f32))
+            .find(|c| contrast(*c, SELECTED_TEXT) >= 7.0 && contrast(*c, BACKGROUND) >= 1.3)
+            .unwrap_or(ColorConverter::hsl_to_rgb(h, s.min(60.0), 20.0));
+
+        // text accents need to be bright enough on the background
+        let text = (30..=90)
+            .map(|l| ColorConverter::hsl_to_rgb(h, s, l as f32))
+            .find(|c| contrast(*c, BACKGROUND) >= 4.5)
+            .unwrap_or(SELECTED_TEXT);
+
+        Self {
+            selection: to_hsla(selection, 1.0),
+            mode_chip: to_hsla(text, 1.0),
+            footer: to_hsla(text, 0.8),
+        }
+    }
+}
+
+/// Color a launcher or a single result is tagged with, e.g. red for destructive commands.
+/// Written as a hex string like `#e06c75`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct TagColor {
+    hex: String,
+    pub color: Hsla,
+}
+
+impl TagColor {
+    pub fn parse(hex: &str) -> Option<Self> {
+        let color = ColorConverter::hex_to_rgb(hex.trim())?;
+        Some(Self {
+            hex: hex.trim().to_string(),
+            color: to_hsla(color, 1.0),
+        })
+    }
+}
+
+impl Serialize for TagColor {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.hex)
+    }
+}
+
+/// Reads an optional [`TagColor`]. Invalid colors are reported and ignored, so that a typo
+/// does not drop the whole launcher.
+pub fn deserialize_tag_color<'de, D>(deserializer: D) -> Result<Option<TagColor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(hex) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+    let tag = TagColor::parse(&hex);
+    if tag.is_none() {
+        eprintln!("Ignoring invalid accent color \"{hex}\", expected a hex color like #e06c75");
+    }
+    Ok(tag)
+}
+
+fn to_hsla(color: (f32, f32, f32), alpha: f32) -> Hsla {
+    Rgba {
+        r: color.0 / 255.0,
+        g: color.1 / 255.0,
+        b: color.2 / 255.0,
+        a: alpha,
+    }
+    .into()
+}
+
+/// Resolves the wallpaper from `appearance.wallpaper_command`, falling back to
+/// `appearance.wallpaper`
+fn wallpaper_path(appearance: &ConfigAppearance) -> Option<PathBuf> {
+    let from_command = appearance.wallpaper_command.as_ref().and_then(|cmd| {
+        let output = Command::new("sh").arg("-c").arg(cmd).output().ok()?;
+        parse_wallpaper_output(&String::from_utf8_lossy(&output.stdout))
+    });
+    from_command
+        .or_else(|| appearance.wallpaper.clone())
+        .filter(|path| path.is_file())
+}
+
+/// Accepts either a plain path or the output of `swww query`, which looks like
+/// `eDP-1: 1920x1080, scale: 1, currently displaying: image: /path/to/wallpaper.png`
+fn parse_wallpaper_output(output: &str) -> Option<PathBuf> {
+    let line = output.lines().map(str::trim).find(|l| !l.is_empty())?;
+    let path = line
+        .rsplit_once("image: ")
+        .map_or(line, |(_, path)| path.trim());
+    Some(Path::new(path).to_path_buf())
+}
+
+/// Picks the most common vibrant color of the image. Colors are quantized to 4 bits per
+/// channel and weighted by their saturation, so that large dull areas do not win over a
+/// smaller but colorful subject.
+fn dominant_color(image: &DynamicImage) -> Option<(f32, f32, f32)> {
+    let thumbnail = image.thumbnail(64, 64).to_rgb8();
+
+    // per bucket: (weight, summed r, g, b, pixel count)
+    let mut buckets = vec![(0.0f32, 0u32, 0u32, 0u32, 0u32); 4096];
+    for pixel in thumbnail.pixels() {
+        let [r, g, b] = pixel.0;
+        let (_, s, l) = ColorConverter::rgb_to_hsl(r as f32, g as f32, b as f32);
+        let idx = (r as usize >> 4) << 8 | (g as usize >> 4) << 4 | (b as usize >> 4);
+        let bucket = &mut buckets[idx];
+
+        // near black and near white pixels only count when nothing else is found
+        let weight = if (10.0..=90.0).contains(&l) {
+            0.1 + s / 100.0
+        } else {
+            0.001
+        };
+        bucket.0 += weight;
+        bucket.1 += r as u32;
+        bucket.2 += g as u32;
+        bucket.3 += b as u32;
+        bucket.4 += 1;
+    }
+
+    let best = buckets
+        .into_iter()
+        .filter(|b| b.4 > 0)
+        .max_by(|a, b| a.0.total_cmp(&b.0))?;
+    let n = best.4 as f32;
+    Some((best.1 as f32 / n, best.2 as f32 / n, best.3 as f32 / n))
+}
+
+/// WCAG contrast ratio between two colors
+fn contrast(a: (f32, f32, f32), b: (f32, f32, f32)) -> f32 {
+    let (la, lb) = (luminance(a), luminance(b));
+    (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+}
+
+/// WCAG relative luminance of an RGB color with channels from 0 to 255
+pub fn luminance(color: (f32, f32, f32)) -> f32 {
+    let channel = |c: f32| {
+        let c = c / 255.0;
+        if c <= 0.03928 {
+            c / 12.92
+        } else {
+            ((c + 0.055) / 1.055).powf(2.4)
+        }
+    };
+    0.2126 * channel(color.0) + 0.7152 * channel(color.1) + 0.0722 * channel(color.2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use image::{Rgb, RgbImage};
+
+    #[test]
+    fn test_dominant_color_prefers_vibrant() {
+        // mostly gray with a smaller orange area
+        let image = RgbImage::from_fn(100, 100, |x, _| {
+            if x < 70 {
+                Rgb([120, 120, 120])
+            } else {
+                Rgb([240, 130, 20])
+            }
+        });
+        let (r, g, b) = dominant_color(&DynamicImage::ImageRgb8(image)).unwrap();
+        assert!(r > 200.0 && g > 100.0 && b < 60.0);
+    }
+
+    #[test]
+    fn test_accent_stays_readable() {
+        for color in [(255.0, 255.0, 0.0), (10.0, 10.0, 80.0), (128.0, 0.0, 255.0)] {
+            let accent = Accent::from_rgb(color);
+            let selection: Rgba = accent.selection.into();
+            let selection = (
+                selection.r * 255.0,
+                selection.g * 255.0,
+                selection.b * 255.0,
+            );
+            let chip: Rgba = accent.mode_chip.into();
+            let chip = (chip.r * 255.0, chip.g * 255.0, chip.b * 255.0);
+
+            assert!(contrast(selection, SELECTED_TEXT) >= 4.5);
+            assert!(contrast(chip, BACKGROUND) >= 4.5);
+        }
+    }
+
+    #[test]
+    fn test_tag_color() {
+        #[derive(Deserialize, Serialize)]
+        struct Tagged {
+            #[serde(default, deserialize_with = "deserialize_tag_color")]
+            accent: Option<TagColor>,
+        }
+
+        let tagged: Tagged = serde_json::from_str(r##"{"accent": "#f00"}"##).unwrap();
+        let red = tagged.accent.as_ref().unwrap();
+        assert_eq!(red.color, to_hsla((255.0, 0.0, 0.0), 1.0));
+        assert_eq!(
+            serde_json::to_string(&tagged).unwrap(),
+            r##"{"accent":"#f00"}"##
+        );
+
+        let invalid: Tagged = serde_json::from_str(r#"{"accent": "reddish"}"#).unwrap();
+        assert!(invalid.accent.is_none());
+        let missing: Tagged = serde_json::from_str("{}").unwrap();
+        assert!(missing.accent.is_none());
+    }
+
+    #[test]
+    fn test_parse_wallpaper_output() {
+        let swww = "eDP-1: 1920x1080, scale: 1, currently displaying: image: /home/u/wall.png\n";
+        assert_eq!(
+            parse_wallpaper_output(swww),
+            Some(PathBuf::from("/home/u/wall.png"))
+        );
+        assert_eq!(
+            parse_wallpaper_output("/home/u/wall.jpg\n"),
+            Some(PathBuf::from("/home/u/wall.jpg"))
+        );
+        assert_eq!(parse_wallpaper_output(""), None);
+    }
+}
@@ -0,0 +1,279 @@
+use std::ops::Range;
+
+use smallvec::SmallVec;
+use unicode_bidi::{BidiClass, BidiInfo, bidi_class};
+use unicode_segmentation::UnicodeSegmentation;
+
+/// A grapheme cluster of the logical text and the place it was moved to for display
+#[derive(Clone, Debug)]
+struct Cluster {
+    logical: Range<usize>,
+    visual: Range<usize>,
+    rtl: bool,
+}
+
+/// Text reordered for display following the Unicode bidirectional algorithm (UAX #9).
+///
+/// The shaper lays glyphs out strictly left to right, so right-to-left runs are reversed before
+/// shaping and byte offsets into the logical text have to be mapped into the reordered one.
+/// Text without right-to-left characters is left untouched.
+#[derive(Clone, Debug)]
+pub struct BidiText<'a> {
+    text: &'a str,
+    visual: String,
+    /// Sorted by their logical position, empty if nothing was reordered
+    clusters: Vec<Cluster>,
+    rtl: bool,
+}
+
+impl<'a> BidiText<'a> {
+    /// Reorders `text`, the base direction is taken from its first strong character
+    pub fn new(text: &'a str) -> Self {
+        let mut bidi = Self {
+            text,
+            visual: String::new(),
+            clusters: Vec::new(),
+            rtl: false,
+        };
+        if text.is_ascii() || !text.chars().any(is_rtl) {
+            return bidi;
+        }
+
+        let info = BidiInfo::new(text, None);
+        bidi.rtl = info
+            .paragraphs
+            .first()
+            .is_some_and(|para| para.level.is_rtl());
+        bidi.visual.reserve(text.len());
+        for para in &info.paragraphs {
+            let (levels, runs) = info.visual_runs(para, para.range.clone());
+            for run in runs {
+                let rtl = levels[run.start].is_rtl();
+                let graphemes = text[run.clone()].grapheme_indices(true);
+                let mut push = |(idx, grapheme): (usize, &str)| {
+                    let start = bidi.visual.len();
+                    match grapheme.chars().next().and_then(mirrored) {
+                        Some(mirror) if rtl && grapheme.len() == mirror.len_utf8() => {
+                            bidi.visual.push(mirror)
+                        }
+                        _ => bidi.visual.push_str(grapheme),
+                    }
+                    bidi.clusters.push(Cluster {
+                        logical: run.start + idx..run.start + idx + grapheme.len(),
+                        visual: start..bidi.visual.len(),
+                        rtl,
+                    });
+                };
+                if rtl {
+                    graphemes.rev().for_each(&mut push);
+                } else {
+                    graphemes.for_each(&mut push);
+                }
+            }
+        }
+        bidi.clusters.sort_by_key(|cluster| cluster.logical.start);
+        bidi
+    }
+
+    /// The text in display order
+    pub fn as_str(&self) -> &str {
+        if self.is_reordered() {
+            &self.visual
+        } else {
+            self.text
+        }
+    }
+
+    /// Returns true if the displayed text differs from the logical one
+    pub fn is_reordered(&self) -> bool {
+        !self.clusters.is_empty()
+    }
+
+    /// Returns true if the base direction of the text is right to left
+    pub fn is_rtl(&self) -> bool {
+        self.rtl
+    }
+
+    /// Position of the caret at the logical `offset` in the displayed text. The caret sits at
+    /// the leading edge of the cluster after it, which is its right edge in a right-to-left
+    /// run. At the end of the text it sits at the trailing edge of the last cluster.
+    pub fn visual_offset(&self, offset: usize) -> usize {
+        if !self.is_reordered() {
+            return offset;
+        }
+        let idx = self
+            .clusters
+            .partition_point(|cluster| cluster.logical.end <= offset);
+        match self.clusters.get(idx) {
+            Some(cluster) if cluster.rtl => cluster.visual.end,
+            Some(cluster) => cluster.visual.start,
+            None => self.clusters.last().map_or(0, |cluster| {
+                if cluster.rtl {
+                    cluster.visual.start
+                } else {
+                    cluster.visual.end
+                }
+            }),
+        }
+    }
+
+    /// Ranges of the displayed text covering the logical `ranges`, sorted and merged. A single
+    /// logical range can be split up if it crosses a change of direction.
+    pub fn visual_ranges(
+        &self,
+        ranges: impl IntoIterator<Item = Range<usize>>,
+    ) -> SmallVec<[Range<usize>; 4]> {
+        if !self.is_reordered() {
+            return ranges.into_iter().filter(|r| !r.is_empty()).collect();
+        }
+        let mut visual: SmallVec<[Range<usize>; 4]> = SmallVec::new();
+        for range in ranges {
+            let first = self
+                .clusters
+                .partition_point(|cluster| cluster.logical.start < range.start);
+            visual.extend(
+                self.clusters[first..]
+                    .iter()
+                    .take_while(|cluster| cluster.logical.end <= range.end)
+                    .map(|cluster| cluster.visual.clone()),
+            );
+        }
+        visual.sort_by_key(|range| range.start);
+
+        let mut merged: SmallVec<[Range<usize>; 4]> = SmallVec::new();
+        for range in visual {
+            match merged.last_mut() {
+                Some(last) if last.end == range.start => last.end = range.end,
+                _ => merged.push(range),
+            }
+        }
+        merged
+    }
+
+    /// Every logical caret position together with its position in the displayed text
+    pub fn caret_stops(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.clusters
+            .iter()
+            .map(|cluster| cluster.logical.start)
+            .chain(std::iter::once(self.text.len()))
+            .map(|offset| (offset, self.visual_offset(offset)))
+    }
+
+    /// The logical caret position next to `offset` on screen, to the right or the left. Stays
+    /// at `offset` if the caret is at that edge already.
+    pub fn move_visually(&self, offset: usize, right: bool) -> usize {
+        let current = self.visual_offset(offset);
+        let stops = self.caret_stops().filter(|&(_, visual)| {
+            if right {
+                visual > current
+            } else {
+                visual < current
+            }
+        });
+        let next = if right {
+            stops.min_by_key(|&(logical, visual)| (visual, logical.abs_diff(offset)))
+        } else {
+            stops.min_by_key(|&(logical, visual)| (current - visual, logical.abs_diff(offset)))
+        };
+        next.map_or(offset, |(logical, _)| logical)
+    }
+}
+
+fn is_rtl(c: char) -> bool {
+    matches!(
+        bidi_class(c),
+        BidiClass::R | BidiClass::AL | BidiClass::RLE | BidiClass::RLO | BidiClass::RLI
+    )
+}
+
+/// Mirror image of paired punctuation, shown in right-to-left runs
+fn mirrored(c: char) -> Option<char> {
+    Some(match c {
+        '(' => ')',
+        ')' => '(',
+        '[' => ']',
+        ']' => '[',
+        '{' => '}',
+        '}' => '{',
+        '<' => '>',
+        '>' => '<',
+        '«' => '»',
+        '»' => '«',
+        _ => return None,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::iter::once;
+
+    use super::*;
+
+    #[test]
+    fn test_ltr_is_untouched() {
+        let bidi = BidiText::new("Firefox 2.0");
+        assert!(!bidi.is_reordered());
+        assert!(!bidi.is_rtl());
+        assert_eq!(bidi.as_str(), "Firefox 2.0");
+        assert_eq!(bidi.visual_offset(3), 3);
+        assert_eq!(bidi.visual_ranges([1..3, 4..4]).into_vec(), vec![1..3]);
+    }
+
+    #[test]
+    fn test_rtl_name_with_version() {
+        // four hebrew letters of two bytes each, a space and a version number
+        let bidi = BidiText::new("שלום 2.0");
+        assert!(bidi.is_reordered());
+        assert!(bidi.is_rtl());
+        assert_eq!(bidi.as_str(), "2.0 םולש");
+
+        // start of the word is at its right edge, the number keeps its direction
+        assert_eq!(bidi.visual_offset(0), 12);
+        assert_eq!(bidi.visual_offset(8), 4);
+        assert_eq!(bidi.visual_offset(9), 0);
+        assert_eq!(bidi.visual_offset(12), 3);
+
+        // the word, and a selection across the change of direction
+        assert_eq!(bidi.visual_ranges(once(0..8)).into_vec(), vec![4..12]);
+        assert_eq!(bidi.visual_ranges(once(6..10)).into_vec(), vec![0..1, 3..6]);
+    }
+
+    #[test]
+    fn test_visual_movement() {
+        let bidi = BidiText::new("שלום 2.0");
+        let mut offset = 0;
+        let mut stops = vec![offset];
+        loop {
+            let next = bidi.move_visually(offset, false);
+            if next == offset {
+                break;
+            }
+            offset = next;
+            stops.push(offset);
+        }
+        assert_eq!(stops, vec![0, 2, 4, 6, 8, 12, 11, 10, 9]);
+
+        let mut back = vec![offset];
+        while bidi.move_visually(offset, true) != offset {
+            offset = bidi.move_visually(offset, true);
+            back.push(offset);
+        }
+        back.reverse();
+        assert_eq!(back, stops);
+    }
+
+    #[test]
+    fn test_rtl_run_in_ltr_text() {
+        let bidi = BidiText::new("abc שלום");
+        assert!(!bidi.is_rtl());
+        assert_eq!(bidi.as_str(), "abc םולש");
+        assert_eq!(bidi.visual_offset(3), 3);
+        assert_eq!(bidi.visual_offset(4), 12);
+    }
+
+    #[test]
+    fn test_brackets_are_mirrored() {
+        let bidi = BidiText::new("(שלום)");
+        assert_eq!(bidi.as_str(), "(םולש)");
+    }
+}
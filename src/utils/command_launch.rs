@@ -1,5 +1,6 @@
 use std::{
     os::unix::process::CommandExt,
+    path::Path,
     process::{Command, Stdio},
 };
 
@@ -18,7 +19,9 @@ use crate::{
 ///
 /// This function uses a "double-fork" strategy to ensure that the spawned process is adopted by
 /// the system init process (PID 1). This prevents empty "zombie" process from cluttering the
-/// process table and ensures the child survives even if the daemon exits.
+/// process table and ensures the child survives even if the daemon exits. With
+/// `behavior.use_systemd_scope`, the command is started through `systemd-run` instead, see
+/// [`in_systemd_scope`].
 ///
 /// # Safety
 /// This function uses `unsafe` and `pre_exec`. `pre_exec` runs in a restricted environment between
@@ -31,17 +34,180 @@ use crate::{
 pub fn spawn_detached(
     cmd: &str,
     keyword: &str,
-    variables: &[(SharedString, SharedString)],
+    variables: &[(SharedString, Substitution)],
 ) -> Result<(), SherlockError> {
-    let config = ConfigGuard::read().unwrap();
+    let config = ConfigGuard::read()?;
     let cmd = parse_variables(cmd, keyword, variables, &config);
 
     drop(config);
 
-    let parts = split_as_command(&cmd);
+    // exec lines copied from desktop entries still work, codes within arguments are kept
+    let mut parts = split_as_command(&cmd);
+    parts.retain(|part| !is_field_code(part));
+    spawn_parts(&cmd, parts)
+}
+
+/// Like [`spawn_detached`] for the `Exec` line of a desktop entry. Its field codes are
+/// removed, see [`strip_field_codes`], and `terminal` runs it inside the configured terminal
+/// as `Terminal=true` asks for.
+pub fn spawn_desktop_exec(
+    exec: &str,
+    terminal: bool,
+    keyword: &str,
+    variables: &[(SharedString, Substitution)],
+) -> Result<(), SherlockError> {
+    let config = ConfigGuard::read()?;
+    let exec = parse_variables(exec, keyword, variables, &config);
+    let mut parts = Vec::new();
+    if terminal {
+        parts.extend(split_as_command(&terminal_prefix(
+            &config.default_apps.terminal,
+        )?));
+    }
+    drop(config);
+
+    parts.extend(strip_field_codes(split_as_command(&exec)));
+    spawn_parts(&exec, parts)
+}
+
+/// The configured terminal followed by the flag it expects before the command to run, e.g.
+/// `alacritty -e`. Terminals that take the command as their trailing arguments get no flag.
+fn terminal_prefix(terminal: &str) -> Result<String, SherlockError> {
+    let program = split_as_command(terminal)
+        .into_iter()
+        .next()
+        .ok_or_else(|| {
+            sherlock_error!(
+                SherlockErrorType::ConfigError(Some(String::from("No terminal found"))),
+                "Set default_apps.terminal to run applications with Terminal=true"
+            )
+        })?;
+    let name = Path::new(&program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .unwrap_or(&program);
+    Ok(match name {
+        "kitty" | "foot" => terminal.to_string(),
+        // `-e` is deprecated or takes a single string in these
+        "gnome-terminal" | "kgx" | "ptyxis" => format!("{terminal} --"),
+        _ => format!("{terminal} -e"),
+    })
+}
+
+/// Codes of the desktop entry spec that expand to nothing when no files or URLs are passed
+const FIELD_CODES: &[char] = &[
+    'f', 'F', 'u', 'U', 'i', 'c', 'k', // deprecated
+    'd', 'D', 'n', 'N', 'v', 'm',
+];
+
+/// Returns true if `part` is a field code on its own, e.g. `%U`
+fn is_field_code(part: &str) -> bool {
+    part.strip_prefix('%')
+        .is_some_and(|code| code.len() == 1 && code.starts_with(FIELD_CODES))
+}
+
+/// Removes the field codes from the arguments of an `Exec` line. Sherlock starts entries without
+/// files or URLs, so `%f`, `%u` and their list variants are dropped, as are `%i`, `%c`, `%k` and
+/// the deprecated codes. Arguments made up of a code alone disappear, `%%` is a literal `%`.
+pub fn strip_field_codes(parts: Vec<String>) -> Vec<String> {
+    parts
+        .into_iter()
+        .filter_map(|part| {
+            let mut arg = String::with_capacity(part.len());
+            let mut stripped = false;
+            let mut chars = part.chars();
+            while let Some(c) = chars.next() {
+                if c != '%' {
+                    arg.push(c);
+                    continue;
+                }
+                match chars.next() {
+                    Some('%') => arg.push('%'),
+                    Some(code) if FIELD_CODES.contains(&code) => stripped = true,
+                    // not a valid code, kept as written
+                    Some(other) => {
+                        arg.push('%');
+                        arg.push(other);
+                    }
+                    None => arg.push('%'),
+                }
+            }
+            (!stripped || !arg.is_empty()).then_some(arg)
+        })
+        .collect()
+}
+
+/// A shell command entered through the run prefix (e.g. `> make install`)
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunCommand {
+    pub command: String,
+    /// Set if the prefix was doubled (e.g. `>> htop`) to run the command inside the terminal
+    pub terminal: bool,
+}
+
+impl RunCommand {
+    /// Parses `query` as a run command if it starts with `prefix`.
+    ///
+    /// The prefix has to be the very first thing in the query and an empty prefix disables the
+    /// feature altogether. Repeating the prefix requests the command to be run inside the
+    /// configured terminal.
+    pub fn parse(query: &str, prefix: &str) -> Option<Self> {
+        if prefix.is_empty() {
+            return None;
+        }
+        let rest = query.strip_prefix(prefix)?;
+        let (rest, terminal) = match rest.strip_prefix(prefix) {
+            Some(rest) => (rest, true),
+            None => (rest, false),
+        };
+
+        Some(Self {
+            command: rest.trim().to_string(),
+            terminal,
+        })
+    }
+
+    /// Runs the command through `sh -c`, detached from Sherlock.
+    ///
+    /// The command is handed to the shell as a single argument, so neither Sherlock's variable
+    /// substitution nor its argument splitting touch it.
+    pub fn spawn(&self) -> Result<(), SherlockError> {
+        if self.command.is_empty() {
+            return Ok(());
+        }
+
+        let mut parts = Vec::new();
+        if self.terminal {
+            let config = ConfigGuard::read()?;
+            parts.extend(split_as_command(&terminal_prefix(
+                &config.default_apps.terminal,
+            )?));
+        }
+        parts.extend([String::from("sh"), String::from("-c"), self.command.clone()]);
+
+        spawn_parts(&self.command, parts)
+    }
+}
+
+/// Opens a file or folder with its default application through `xdg-open`
+pub fn open_path(path: &Path) -> Result<(), SherlockError> {
+    let parts = vec![
+        String::from("xdg-open"),
+        path.to_string_lossy().into_owned(),
+    ];
+    spawn_parts("xdg-open", parts)
+}
+
+/// Starts `parts` detached, see [`spawn_detached`]. The grandchild inherits the pipe through
+/// which the standard library reports a failed `exec`, so a missing program is still returned
+/// as an error.
+fn spawn_parts(cmd: &str, mut parts: Vec<String>) -> Result<(), SherlockError> {
     if parts.is_empty() {
         return Ok(());
     }
+    if ConfigGuard::read()?.behavior.use_systemd_scope {
+        parts = in_systemd_scope(parts);
+    }
 
     let program = &parts[0];
     let args = &parts[1..];
@@ -84,59 +250,215 @@ pub fn spawn_detached(
     Ok(())
 }
 
+/// Wraps a command in a transient systemd scope within `app.slice`, like desktop environments
+/// start their apps. The scope takes over the process, so it stays detached.
+fn in_systemd_scope(parts: Vec<String>) -> Vec<String> {
+    [
+        "systemd-run",
+        "--user",
+        "--scope",
+        "--slice=app.slice",
+        "--quiet",
+        "--",
+    ]
+    .into_iter()
+    .map(String::from)
+    .chain(parts)
+    .collect()
+}
+
 pub fn split_as_command(cmd: &str) -> Vec<String> {
-    let mut parts = Vec::new();
-    let mut current = String::new();
-    let mut double_quoting = false;
-    let mut single_quoting = false;
-    let mut escaped = false;
-
-    let mut it = cmd.chars().peekable();
-
-    while let Some(c) = it.next() {
-        if escaped {
-            current.push(c);
-            escaped = false;
-            continue;
-        }
+    let mut splitter = Splitter::default();
+    splitter.feed(cmd);
 
-        match c {
-            '\\' if !single_quoting => {
-                escaped = true;
-            }
-            '"' if !single_quoting => {
-                double_quoting = !double_quoting;
-            }
-            '\'' if !double_quoting => {
-                single_quoting = !single_quoting;
+    let Splitter {
+        mut parts, current, ..
+    } = splitter;
+    if !current.is_empty() {
+        parts.push(current);
+    }
+    parts
+}
+
+/// State of [`split_as_command`] after reading a part of an exec string
+#[derive(Default)]
+struct Splitter {
+    parts: Vec<String>,
+    current: String,
+    double_quoting: bool,
+    single_quoting: bool,
+    escaped: bool,
+}
+
+impl Splitter {
+    fn feed(&mut self, cmd: &str) {
+        for c in cmd.chars() {
+            if self.escaped {
+                self.current.push(c);
+                self.escaped = false;
+                continue;
             }
-            c if c.is_whitespace() && !double_quoting && !single_quoting => {
-                if !current.is_empty() {
-                    parts.push(current.split_off(0));
+
+            match c {
+                '\\' if !self.single_quoting => {
+                    self.escaped = true;
+                }
+                '"' if !self.single_quoting => {
+                    self.double_quoting = !self.double_quoting;
+                }
+                '\'' if !self.double_quoting => {
+                    self.single_quoting = !self.single_quoting;
+                }
+                c if c.is_whitespace() && !self.double_quoting && !self.single_quoting => {
+                    if !self.current.is_empty() {
+                        self.parts.push(self.current.split_off(0));
+                    }
+                }
+                c => {
+                    self.current.push(c);
                 }
-            }
-            c => {
-                current.push(c);
             }
         }
     }
 
-    if !current.is_empty() {
-        parts.push(current);
+    /// The start of the script if the argument read last is the one a shell runs, e.g. the
+    /// last one of `sh -c "echo`
+    fn shell_script(&self) -> Option<&str> {
+        let [.., program, flags] = self.parts.as_slice() else {
+            return None;
+        };
+        let program = Path::new(program).file_name()?.to_str()?;
+        // combined flags like `bash -lc` end with the `c`
+        let runs_script =
+            flags.starts_with('-') && !flags.starts_with("--") && flags.ends_with('c');
+        (runs_script && SHELLS.contains(&program)).then_some(&self.current)
     }
+}
 
-    parts.retain(|s| !s.starts_with('%'));
-    parts
+/// Shells whose quoting [`quote_for_shell`] follows
+const SHELLS: &[&str] = &["sh", "ash", "bash", "dash", "ksh", "zsh"];
+
+/// Characters [`split_as_command`] or a shell would read as syntax
+const SHELL_METACHARACTERS: &[char] = &[
+    '\\', '\'', '"', '`', '$', ';', '&', '|', '<', '>', '(', ')', '*', '?', '[', ']', '{', '}',
+    '#', '~', '!',
+];
+
+/// Escapes whitespace and shell metacharacters in `value` with backslashes, so that it stays a
+/// single literal argument once the exec string it is put into is split
+fn escape_argument(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if c.is_whitespace() || SHELL_METACHARACTERS.contains(&c) {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+/// Quotes `value` so that the command receives it as literal text when it is put into an exec
+/// string right after `before`. Within the script of `sh -c`, it is quoted for the shell as
+/// well.
+fn quote_literal(before: &str, value: &str) -> String {
+    let mut splitter = Splitter::default();
+    splitter.feed(before);
+    let value = match splitter.shell_script() {
+        Some(script) => quote_for_shell(script, value),
+        None => value.to_string(),
+    };
+
+    // single quotes can't escape anything, so they are closed around an escaped quote
+    if splitter.single_quoting {
+        value.replace('\'', r"'\''")
+    } else {
+        escape_argument(&value)
+    }
+}
+
+/// Quotes `value` for a POSIX shell, depending on the quotes `script` leaves open
+fn quote_for_shell(script: &str, value: &str) -> String {
+    let (mut single_quoting, mut double_quoting, mut escaped) = (false, false, false);
+    for c in script.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\'' if !double_quoting => single_quoting = !single_quoting,
+            '"' if !single_quoting => double_quoting = !double_quoting,
+            '\\' if !single_quoting => escaped = true,
+            _ => {}
+        }
+    }
+
+    if single_quoting {
+        value.replace('\'', r"'\''")
+    } else if double_quoting {
+        let mut quoted = String::with_capacity(value.len());
+        for c in value.chars() {
+            if matches!(c, '\\' | '$' | '`' | '"') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        quoted
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// The value of a variable input as it is put into an exec string, see [`parse_variables`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Substitution {
+    /// Typed into the input, the command receives it as literal text wherever it is put
+    Literal(SharedString),
+    /// Taken from the config, e.g. a choice, and put in as written
+    Verbatim(SharedString),
+}
+
+impl Substitution {
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::Literal(value) | Self::Verbatim(value) => value,
+        }
+    }
+    /// The text that replaces the placeholder of the variable, which follows `before`
+    fn quoted(&self, before: &str) -> String {
+        match self {
+            Self::Literal(value) => quote_literal(before, value),
+            Self::Verbatim(value) => value.to_string(),
+        }
+    }
 }
 
 pub fn parse_variables<'a>(
     exec_input: &'a str,
     keyword: &str,
-    variables: &[(SharedString, SharedString)],
+    variables: &[(SharedString, Substitution)],
     config: &SherlockConfig,
 ) -> String {
     let mut exec = exec_input.to_string();
 
+    // Handle prefixes, before the values are put in so that a value can't form one
+    let prefix_pattern = r#"\{prefix\[(.*?)\]:(.*?)\}"#;
+    let re_prefix = Regex::new(prefix_pattern).unwrap();
+
+    exec = re_prefix
+        .replace_all(&exec, |caps: &Captures| {
+            let prefix_for = &caps[1];
+            let prefix = &caps[2];
+
+            let has_value = variables
+                .iter()
+                .find(|v| v.0.as_ref() == prefix_for)
+                .map_or(false, |v| !v.1.as_str().is_empty());
+
+            if has_value {
+                prefix.to_string()
+            } else {
+                "".to_string()
+            }
+        })
+        .into_owned();
+
     // Handle standard variables
     let pattern = r#"\{([a-zA-Z_]+)(?::(.*?))?\}"#;
     let re = Regex::new(pattern).unwrap();
@@ -145,41 +467,169 @@ pub fn parse_variables<'a>(
         .replace_all(&exec, |caps: &Captures| {
             let key = &caps[1];
             let value = caps.get(2).map(|m| m.as_str());
+            // values are quoted for the place they are put in
+            let before = &exec[..caps.get(0).map_or(0, |m| m.start())];
 
             match key {
-                "terminal" => format!("{} -e", config.default_apps.terminal),
+                "terminal" => terminal_prefix(&config.default_apps.terminal).unwrap_or_default(),
                 "keyword" => keyword.to_string(),
                 "variable" => variables
                     .iter()
                     .find(|v| Some(v.0.as_ref()) == value)
-                    .map(|v| v.1.to_string())
+                    .map(|v| v.1.quoted(before))
                     .unwrap_or_else(|| caps[0].to_string()),
                 _ => caps[0].to_string(),
             }
         })
         .into_owned();
 
-    // Handle prefixes
-    let prefix_pattern = r#"\{prefix\[(.*?)\]:(.*?)\}"#;
-    let re_prefix = Regex::new(prefix_pattern).unwrap();
+    exec
+}
 
-    exec = re_prefix
-        .replace_all(&exec, |caps: &Captures| {
-            let prefix_for = &caps[1];
-            let prefix = &caps[2];
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::config::OtherDefaults;
 
-            let has_value = variables
-                .iter()
-                .find(|v| v.0.as_ref() == prefix_for)
-                .map_or(false, |v| !v.1.is_empty());
+    #[test]
+    fn test_run_prefix_detection() {
+        assert_eq!(
+            RunCommand::parse("$ ls -la", "$"),
+            Some(RunCommand {
+                command: String::from("ls -la"),
+                terminal: false,
+            })
+        );
+        assert_eq!(
+            RunCommand::parse("$$htop", "$"),
+            Some(RunCommand {
+                command: String::from("htop"),
+                terminal: true,
+            })
+        );
+        assert_eq!(
+            RunCommand::parse("run: echo 'a b'", "run:"),
+            Some(RunCommand {
+                command: String::from("echo 'a b'"),
+                terminal: false,
+            })
+        );
+    }
 
-            if has_value {
-                prefix.to_string()
-            } else {
-                "".to_string()
-            }
-        })
-        .into_owned();
+    #[test]
+    fn test_escape_argument() {
+        for value in [
+            "plain",
+            "two words",
+            r#"it's "quoted""#,
+            r"back\slash",
+            "$(rm -rf ~); echo `id` | cat > /tmp/x & {a,b} [c]* #!",
+        ] {
+            let exec = format!("printf %s {}", escape_argument(value));
+            assert_eq!(split_as_command(&exec), vec!["printf", "%s", value]);
+            let exec = format!(r#"printf "%s" "--value={}""#, escape_argument(value));
+            assert_eq!(split_as_command(&exec)[2], format!("--value={value}"));
+        }
+        assert_eq!(escape_argument("a b;c"), r"a\ b\;c");
+    }
 
-    exec
+    #[test]
+    fn test_variables_in_shell_scripts() {
+        let config = SherlockConfig::default();
+        let value = "$(echo a); `id` | \"b\" 'c' \\ d & {e} *";
+        let variables = [(SharedString::from("x"), Substitution::Literal(value.into()))];
+
+        for template in [
+            "printf %s {variable:x}",
+            "printf %s '{variable:x}'",
+            r#"sh -c "printf %s {variable:x}""#,
+            r#"sh -c "printf %s \"{variable:x}\"""#,
+            r#"sh -c "printf %s '{variable:x}'""#,
+            "sh -c 'printf %s {variable:x}'",
+            r#"bash -lc "printf %s {variable:x} >&1""#,
+        ] {
+            let parts = split_as_command(&parse_variables(template, "", &variables, &config));
+            let output = Command::new(&parts[0]).args(&parts[1..]).output().unwrap();
+            assert_eq!(String::from_utf8_lossy(&output.stdout), value, "{template}");
+        }
+
+        // choices are put in as written
+        let variables = [(
+            SharedString::from("x"),
+            Substitution::Verbatim("--fast --quiet".into()),
+        )];
+        let exec = parse_variables("app {variable:x}", "", &variables, &config);
+        assert_eq!(split_as_command(&exec), vec!["app", "--fast", "--quiet"]);
+    }
+
+    #[test]
+    fn test_desktop_exec_lines() {
+        let exec = |line: &str| strip_field_codes(split_as_command(line));
+
+        assert_eq!(
+            exec(r#""/opt/My App/app" --new-window %U"#),
+            vec!["/opt/My App/app", "--new-window"]
+        );
+        assert_eq!(
+            exec(r"/opt/my\ app/bin\ x %f --name=%c"),
+            vec!["/opt/my app/bin x", "--name="]
+        );
+        assert_eq!(
+            exec(r#"sh -c "echo \"100%%\" %i""#),
+            vec!["sh", "-c", r#"echo "100%" "#]
+        );
+        assert_eq!(
+            exec(r#"env FOO="a b" app %k %% %x"#),
+            vec!["env", "FOO=a b", "app", "%", "%x"]
+        );
+
+        // plain commands keep codes within their arguments
+        assert!(is_field_code("%U"));
+        assert!(!is_field_code("+%d"));
+        assert!(!is_field_code("%s"));
+        assert_eq!(split_as_command("date +%d"), vec!["date", "+%d"]);
+    }
+
+    #[test]
+    fn test_terminal_prefix() {
+        assert_eq!(terminal_prefix("alacritty").unwrap(), "alacritty -e");
+        assert_eq!(terminal_prefix("/usr/bin/foot").unwrap(), "/usr/bin/foot");
+        assert_eq!(
+            terminal_prefix("gnome-terminal --wait").unwrap(),
+            "gnome-terminal --wait --"
+        );
+        assert!(terminal_prefix("").is_err());
+    }
+
+    #[test]
+    fn test_systemd_scope() {
+        let parts = in_systemd_scope(split_as_command(r#"app --title "a b""#));
+        assert_eq!(parts[0], "systemd-run");
+        assert_eq!(
+            parts[parts.len() - 4..],
+            ["--", "app", "--title", "a b"].map(String::from)
+        );
+    }
+
+    #[test]
+    fn test_run_prefix_must_be_explicit() {
+        assert_eq!(RunCommand::parse("ls $ -la", "$"), None);
+        assert_eq!(RunCommand::parse(" $ ls", "$"), None);
+        assert_eq!(RunCommand::parse("firefox", "$"), None);
+        assert_eq!(RunCommand::parse("$ ls", ""), None);
+    }
+
+    #[test]
+    fn test_default_run_prefix() {
+        let prefix = OtherDefaults::run_prefix();
+        // currency conversions have to reach the calculator
+        assert_eq!(RunCommand::parse("$100 to eur", &prefix), None);
+        assert_eq!(
+            RunCommand::parse(">> htop", &prefix),
+            Some(RunCommand {
+                command: String::from("htop"),
+                terminal: true,
+            })
+        );
+    }
 }
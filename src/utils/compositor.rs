@@ -0,0 +1,172 @@
+use std::process::Command;
+
+use serde_json::Value;
+
+use crate::{
+    sher_log, sherlock_error,
+    utils::errors::{SherlockError, SherlockErrorType},
+};
+
+/// Compositors whose windows can be looked up and focused, detected from the environment of
+/// the session
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compositor {
+    Sway,
+    Hyprland,
+}
+
+impl Compositor {
+    pub fn detect() -> Option<Self> {
+        if std::env::var_os("HYPRLAND_INSTANCE_SIGNATURE").is_some() {
+            Some(Self::Hyprland)
+        } else if std::env::var_os("SWAYSOCK").is_some() {
+            Some(Self::Sway)
+        } else {
+            None
+        }
+    }
+
+    /// Focuses a window of `app_id`. Returns false if none is open.
+    pub fn raise(self, app_id: &str) -> Result<bool, SherlockError> {
+        match self {
+            Self::Sway => {
+                let tree = query("swaymsg", &["-t", "get_tree", "--raw"])?;
+                let Some(id) = sway_window(&tree, app_id) else {
+                    return Ok(false);
+                };
+                run("swaymsg", &[&format!("[con_id={id}] focus")])?;
+            }
+            Self::Hyprland => {
+                let clients = query("hyprctl", &["clients", "-j"])?;
+                let Some(address) = hyprland_window(&clients, app_id) else {
+                    return Ok(false);
+                };
+                run(
+                    "hyprctl",
+                    &["dispatch", "focuswindow", &format!("address:{address}")],
+                )?;
+            }
+        }
+        Ok(true)
+    }
+}
+
+/// Focuses an open window of `app_id` instead of starting the app again. Returns false if there
+/// is none, or if the windows cannot be looked up, in which case the app should be launched.
+/// Blocks until the compositor answers.
+pub fn raise_running(app_id: &str) -> bool {
+    let Some(compositor) = Compositor::detect() else {
+        return false;
+    };
+    compositor.raise(app_id).unwrap_or_else(|e| {
+        let _ = sher_log!(format!("Cannot raise {app_id}: {e}"));
+        false
+    })
+}
+
+/// Returns true if a window reporting `id` as its app id or class belongs to `app_id`. Desktop
+/// files are often named after the app id, but not always with the same case.
+fn is_app(id: Option<&str>, app_id: &str) -> bool {
+    id.is_some_and(|id| !id.is_empty() && id.eq_ignore_ascii_case(app_id))
+}
+
+/// The container id of the first window of `app_id` in the tree printed by `swaymsg -t
+/// get_tree`. Native windows carry an `app_id`, Xwayland ones a class.
+fn sway_window(node: &Value, app_id: &str) -> Option<u64> {
+    let class = node
+        .get("window_properties")
+        .and_then(|props| props.get("class"))
+        .and_then(Value::as_str);
+    if is_app(node.get("app_id").and_then(Value::as_str), app_id) || is_app(class, app_id) {
+        return node.get("id").and_then(Value::as_u64);
+    }
+    ["nodes", "floating_nodes"]
+        .into_iter()
+        .filter_map(|key| node.get(key).and_then(Value::as_array))
+        .flatten()
+        .find_map(|child| sway_window(child, app_id))
+}
+
+/// The address of the first window of `app_id` in the list printed by `hyprctl clients -j`
+fn hyprland_window(clients: &Value, app_id: &str) -> Option<String> {
+    clients
+        .as_array()?
+        .iter()
+        .find(|client| {
+            ["class", "initialClass"]
+                .into_iter()
+                .any(|key| is_app(client.get(key).and_then(Value::as_str), app_id))
+        })
+        .and_then(|client| client.get("address"))
+        .and_then(Value::as_str)
+        .map(String::from)
+}
+
+fn command_error(program: &str, message: String) -> SherlockError {
+    sherlock_error!(
+        SherlockErrorType::CommandExecutionError(program.to_string()),
+        message
+    )
+}
+
+fn run(program: &str, args: &[&str]) -> Result<Vec<u8>, SherlockError> {
+    let output = Command::new(program)
+        .args(args)
+        .output()
+        .map_err(|e| command_error(program, e.to_string()))?;
+    if !output.status.success() {
+        return Err(command_error(
+            program,
+            String::from_utf8_lossy(&output.stderr).into_owned(),
+        ));
+    }
+    Ok(output.stdout)
+}
+
+fn query(program: &str, args: &[&str]) -> Result<Value, SherlockError> {
+    let stdout = run(program, args)?;
+    serde_json::from_slice(&stdout).map_err(|e| command_error(program, e.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_sway_window() {
+        let tree = json!({
+            "id": 1,
+            "nodes": [{
+                "id": 4,
+                "nodes": [
+                    { "id": 7, "app_id": "foot", "nodes": [] },
+                    { "id": 9, "app_id": null, "window_properties": { "class": "Steam" } },
+                ],
+                "floating_nodes": [{ "id": 12, "app_id": "org.gnome.Nautilus" }],
+            }],
+        });
+
+        assert_eq!(sway_window(&tree, "foot"), Some(7));
+        assert_eq!(sway_window(&tree, "steam"), Some(9));
+        assert_eq!(sway_window(&tree, "org.gnome.Nautilus"), Some(12));
+        assert_eq!(sway_window(&tree, "firefox"), None);
+    }
+
+    #[test]
+    fn test_hyprland_window() {
+        let clients = json!([
+            { "address": "0x55f1", "class": "kitty", "initialClass": "kitty" },
+            { "address": "0x55f2", "class": "", "initialClass": "Code" },
+        ]);
+
+        assert_eq!(
+            hyprland_window(&clients, "kitty").as_deref(),
+            Some("0x55f1")
+        );
+        assert_eq!(hyprland_window(&clients, "code").as_deref(), Some("0x55f2"));
+        assert_eq!(hyprland_window(&clients, ""), None);
+        assert_eq!(hyprland_window(&json!({}), "kitty"), None);
+    }
+}
@@ -156,6 +156,9 @@ impl SherlockConfig {
                 .unwrap_or(&config.caching.cache),
             &home,
         );
+        if let Some(socket) = sherlock_flags.socket.take() {
+            config.files.socket = Some(expand_path(&socket, &home));
+        }
         config.runtime.sub_menu = sherlock_flags.sub_menu.take();
         config.runtime.method = sherlock_flags.method.take();
         config.runtime.input = sherlock_flags.input.take();
@@ -165,6 +168,11 @@ impl SherlockConfig {
         config.runtime.photo_mode = sherlock_flags.photo_mode;
         config.runtime.field = sherlock_flags.field.take();
         config.runtime.daemonize = sherlock_flags.daemonize;
+        config.runtime.dry_run = sherlock_flags.dry_run;
+        config.runtime.toggle = sherlock_flags.toggle;
+        config.runtime.dmenu = sherlock_flags.dmenu;
+        config.runtime.print_index = sherlock_flags.print_index;
+        config.debug.search |= sherlock_flags.debug_search;
 
         if let Some(placeholder) = sherlock_flags.placeholder.take() {
             config.appearance.placeholder = placeholder;
@@ -215,6 +215,25 @@ impl FileDefaults {
     }
 }
 
+pub struct SearchDefaults {}
+impl SearchDefaults {
+    pub fn prefix_bonus() -> f32 {
+        0.1
+    }
+    pub fn fuzzy_window() -> usize {
+        5
+    }
+    pub fn levenshtein_max_len_diff() -> usize {
+        3
+    }
+    pub fn count_weight() -> f32 {
+        1.0
+    }
+    pub fn frecency_half_life_days() -> f32 {
+        7.0
+    }
+}
+
 pub struct OtherDefaults {}
 impl OtherDefaults {
     pub fn bool_true() -> bool {
@@ -226,6 +245,9 @@ impl OtherDefaults {
     pub fn five() -> u8 {
         5
     }
+    pub fn grid_columns() -> usize {
+        1
+    }
     pub fn backdrop_opacity() -> f64 {
         0.6
     }
@@ -244,4 +266,8 @@ impl OtherDefaults {
     pub fn placeholder() -> String {
         String::from("Search:")
     }
+    /// `$` would swallow currency conversions like `$100 to eur`
+    pub fn run_prefix() -> String {
+        String::from(">")
+    }
 }
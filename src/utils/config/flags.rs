@@ -20,6 +20,7 @@ pub struct SherlockFlags {
     pub display_raw: bool,
     pub center_raw: bool,
     pub cache: Option<PathBuf>,
+    pub socket: Option<PathBuf>,
     pub daemonize: bool,
     pub method: Option<String>,
     pub field: Option<String>,
@@ -28,6 +29,12 @@ pub struct SherlockFlags {
     pub photo_mode: bool,
     pub input: Option<bool>,
     pub placeholder: Option<String>,
+    pub keymap: bool,
+    pub dry_run: bool,
+    pub toggle: bool,
+    pub dmenu: bool,
+    pub print_index: bool,
+    pub debug_search: bool,
 }
 
 impl SherlockFlags {
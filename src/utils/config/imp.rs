@@ -1,13 +1,14 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     path::{Path, PathBuf},
 };
 
 use crate::utils::{
     config::{
-        ConfigAppearance, ConfigBackdrop, ConfigBehavior, ConfigBinds, ConfigCaching, ConfigDebug,
-        ConfigDefaultApps, ConfigExpand, ConfigFiles, ConfigUnits, SearchBarIcon, StatusBar,
-        defaults::{BindDefaults, ConstantDefaults, FileDefaults, OtherDefaults},
+        Activation, CaretMovement, ConfigAppearance, ConfigBackdrop, ConfigBehavior, ConfigBinds,
+        ConfigCaching, ConfigDebug, ConfigDefaultApps, ConfigExpand, ConfigFiles, ConfigSearch,
+        ConfigUnits, Reopen, SearchBarIcon, Sorting, StatusBar,
+        defaults::{BindDefaults, ConstantDefaults, FileDefaults, OtherDefaults, SearchDefaults},
     },
     files::home_dir,
 };
@@ -42,6 +43,7 @@ impl Default for ConfigDebug {
             try_suppress_errors: false,
             try_suppress_warnings: false,
             app_paths: HashSet::new(),
+            search: false,
         }
     }
 }
@@ -51,6 +53,7 @@ impl Default for ConfigAppearance {
         Self {
             width: 900,
             height: 593, // 617 with, 593 without notification bar
+            max_height: None,
             margins: (0, 0, 0, 0),
             anchor: String::from(""),
             gsk_renderer: String::from("cairo"),
@@ -63,6 +66,12 @@ impl Default for ConfigAppearance {
             shortcut_mod: BindDefaults::shortcut_mod(),
             num_shortcuts: 5,
             placeholder: OtherDefaults::placeholder(),
+            accent: String::new(),
+            wallpaper: None,
+            wallpaper_command: None,
+            high_contrast: false,
+            fallback_icons: HashMap::new(),
+            grid_columns: OtherDefaults::grid_columns(),
         }
     }
 }
@@ -77,6 +86,12 @@ impl Default for ConfigBehavior {
             use_lr_nav: false,
             remember_query: false,
             n_clicks: Some(2),
+            run_prefix: OtherDefaults::run_prefix(),
+            activation: Activation::default(),
+            reopen: Reopen::default(),
+            caret_movement: CaretMovement::default(),
+            hold_for_alternative: None,
+            use_systemd_scope: false,
         }
     }
 }
@@ -90,6 +105,7 @@ impl Default for ConfigFiles {
             alias: FileDefaults::alias(),
             ignore: FileDefaults::ignore(),
             actions: FileDefaults::actions(),
+            socket: None,
         }
     }
 }
@@ -154,6 +170,22 @@ impl Default for StatusBar {
     }
 }
 
+impl Default for ConfigSearch {
+    fn default() -> Self {
+        Self {
+            dedup: false,
+            candidate_limit: None,
+            prefix_bonus: SearchDefaults::prefix_bonus(),
+            fuzzy_window: SearchDefaults::fuzzy_window(),
+            levenshtein_max_len_diff: SearchDefaults::levenshtein_max_len_diff(),
+            count_weight: SearchDefaults::count_weight(),
+            sorting: Sorting::default(),
+            frecency_half_life_days: SearchDefaults::frecency_half_life_days(),
+            fold_diacritics: true,
+        }
+    }
+}
+
 // With Root Implementations
 pub trait WithRoot {
     fn with_root(root: &PathBuf) -> Self;
@@ -207,6 +239,7 @@ impl WithRoot for ConfigFiles {
             alias: use_root(&root, FileDefaults::alias()),
             ignore: use_root(&root, FileDefaults::ignore()),
             actions: use_root(&root, FileDefaults::actions()),
+            socket: None,
         }
     }
 }
@@ -12,10 +12,12 @@ mod defaults;
 mod flags;
 mod guard;
 mod imp;
+mod reload;
 
-pub use defaults::{BindDefaults, ConstantDefaults, OtherDefaults};
+pub use defaults::{BindDefaults, ConstantDefaults, OtherDefaults, SearchDefaults};
 pub use flags::SherlockFlags;
 pub use guard::ConfigGuard;
+pub use reload::{reload_config, remember_flags, spawn_config_watcher};
 
 #[derive(Deserialize, Serialize, Debug, Clone, Default)]
 pub struct SherlockConfig {
@@ -47,6 +49,10 @@ pub struct SherlockConfig {
     #[serde(default)]
     pub keybinds: HashMap<String, UIFunction>,
 
+    /// Rebinds or removes the default key of a function
+    #[serde(default)]
+    pub keymap: HashMap<UIFunction, KeyOverride>,
+
     /// User-specified overrides for default config file paths
     #[serde(default)]
     pub files: ConfigFiles,
@@ -74,6 +80,10 @@ pub struct SherlockConfig {
     /// Configures search bar icons
     #[serde(default)]
     pub search_bar_icon: SearchBarIcon,
+
+    /// Configures how search results are filtered
+    #[serde(default)]
+    pub search: ConfigSearch,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -111,6 +121,9 @@ pub struct ConfigDebug {
     pub try_suppress_warnings: bool,
     #[serde(default)]
     pub app_paths: HashSet<String>,
+    /// Writes the score of every match to the log file
+    #[serde(default)]
+    pub search: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -119,6 +132,10 @@ pub struct ConfigAppearance {
     pub width: i32,
     #[serde(default)]
     pub height: i32,
+    /// Shrinks the window to fit the results, growing it up to this height. Unset keeps the
+    /// window at `height`.
+    #[serde(default)]
+    pub max_height: Option<i32>,
     #[serde(default)]
     pub margins: (i32, i32, i32, i32),
     #[serde(default)]
@@ -137,12 +154,33 @@ pub struct ConfigAppearance {
     pub opacity: f64,
     #[serde(default = "BindDefaults::modkey_ascii")]
     pub mod_key_ascii: Vec<String>,
+    /// Modifier held with the digit of a result to run it: ctrl, alt, super, shift or one of
+    /// the symbols of `mod_key_ascii`
     #[serde(default = "BindDefaults::shortcut_mod")]
     pub shortcut_mod: String,
+    /// Number of results showing the key of their `shortcut` binding, at most 10
     #[serde(default = "OtherDefaults::five")]
     pub num_shortcuts: u8,
     #[serde(default = "OtherDefaults::placeholder")]
     pub placeholder: String,
+    /// Accent color: empty for the built-in palette, a hex color, or `"auto"` to extract it
+    /// from the wallpaper
+    #[serde(default)]
+    pub accent: String,
+    #[serde(default)]
+    pub wallpaper: Option<PathBuf>,
+    /// Command printing the current wallpaper path, e.g. `swww query`
+    #[serde(default)]
+    pub wallpaper_command: Option<String>,
+    /// Starts with the high contrast overlay enabled, see [`Palette`](crate::utils::palette::Palette)
+    #[serde(default)]
+    pub high_contrast: bool,
+    /// Icon names by launcher type (e.g. `web_launcher`) for results without an icon
+    #[serde(default)]
+    pub fallback_icons: HashMap<String, String>,
+    /// Tiles per row in modes that only show applications, 1 keeps the list
+    #[serde(default = "OtherDefaults::grid_columns")]
+    pub grid_columns: usize,
 }
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ConfigBehavior {
@@ -160,6 +198,48 @@ pub struct ConfigBehavior {
     pub n_clicks: Option<u8>,
     #[serde(default)]
     pub remember_query: bool,
+    #[serde(default = "OtherDefaults::run_prefix")]
+    pub run_prefix: String,
+    #[serde(default)]
+    pub activation: Activation,
+    /// Holding the exec key for this many milliseconds runs the alternative action of the
+    /// selected entry instead, e.g. an app in a terminal. Unset to disable.
+    #[serde(default)]
+    pub hold_for_alternative: Option<u64>,
+    /// What `open` does while a window is still open
+    #[serde(default)]
+    pub reopen: Reopen,
+    /// How the arrow keys move the caret through mixed-direction text
+    #[serde(default)]
+    pub caret_movement: CaretMovement,
+    /// Starts apps and commands through `systemd-run --user --scope`, so that each of them gets
+    /// its own cgroup in `app.slice` instead of sharing the one of sherlock
+    #[serde(default)]
+    pub use_systemd_scope: bool,
+}
+
+/// A key mapped to a function in the `keymap` section. Either a key (e.g. `"ctrl-k"`) or
+/// `"none"`/`false` to remove the binding.
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq)]
+#[serde(untagged)]
+pub enum KeyOverride {
+    Enabled(bool),
+    Key(String),
+}
+impl KeyOverride {
+    /// Returns true if the function should not be bound at all
+    pub fn is_unbound(&self) -> bool {
+        match self {
+            Self::Enabled(enabled) => !enabled,
+            Self::Key(key) => key.eq_ignore_ascii_case("none"),
+        }
+    }
+    pub fn key(&self) -> Option<&str> {
+        match self {
+            Self::Key(key) if !self.is_unbound() => Some(key),
+            _ => None,
+        }
+    }
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -176,6 +256,8 @@ pub struct ConfigFiles {
     pub ignore: PathBuf,
     #[serde(default = "FileDefaults::actions")]
     pub actions: PathBuf,
+    #[serde(default)]
+    pub socket: Option<PathBuf>,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -216,6 +298,22 @@ pub struct Runtime {
     pub daemonize: bool,
     #[serde(default)]
     pub field: Option<String>,
+    /// Print requests of `http` actions instead of sending them
+    #[serde(default)]
+    pub dry_run: bool,
+    /// Ask a running instance to close its window if one is open
+    #[serde(default)]
+    pub toggle: bool,
+    /// Set if the starter files were written on this start, shows the welcome entries
+    #[serde(default)]
+    pub first_run: bool,
+    /// Pick a line from stdin instead of launching, see
+    /// [`DmenuLauncher`](crate::launcher::dmenu_launcher::DmenuLauncher)
+    #[serde(default)]
+    pub dmenu: bool,
+    /// Print the line number of the picked line in dmenu mode
+    #[serde(default)]
+    pub print_index: bool,
 }
 
 #[derive(Deserialize, Serialize, Debug, Clone)]
@@ -267,6 +365,52 @@ pub struct StatusBar {
     pub enable: bool,
 }
 
+#[derive(Deserialize, Serialize, Debug, Clone)]
+pub struct ConfigSearch {
+    #[serde(default)]
+    pub dedup: bool,
+    /// Maximum number of matches that are scored per keystroke. Matches beyond it are dropped
+    /// by a cheap prefix/substring prefilter first. Unset or `0` scores every match.
+    #[serde(default)]
+    pub candidate_limit: Option<usize>,
+    /// Bonus of prefix matches covering the whole element. Prefix matches score between
+    /// `0.2 - prefix_bonus` and `0.2`, lower scores rank higher.
+    #[serde(default = "SearchDefaults::prefix_bonus")]
+    pub prefix_bonus: f32,
+    /// Largest gap between consecutive query characters in a fuzzy match
+    #[serde(default = "SearchDefaults::fuzzy_window")]
+    pub fuzzy_window: usize,
+    /// Largest length difference between query and element that is still scored by edit
+    /// distance
+    #[serde(default = "SearchDefaults::levenshtein_max_len_diff")]
+    pub levenshtein_max_len_diff: usize,
+    /// Weight of launch counts relative to the match score
+    #[serde(default = "SearchDefaults::count_weight")]
+    pub count_weight: f32,
+    /// How launches order the results, most visible on the home view
+    #[serde(default)]
+    pub sorting: Sorting,
+    /// Days after which a launch only weighs half as much, see [`Sorting::Frecency`]
+    #[serde(default = "SearchDefaults::frecency_half_life_days")]
+    pub frecency_half_life_days: f32,
+    /// Drops diacritics of queries and results, so "cafe" matches "Café"
+    #[serde(default = "OtherDefaults::bool_true")]
+    pub fold_diacritics: bool,
+}
+
+/// Order of results that match equally well
+#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Sorting {
+    /// Most launched first
+    #[default]
+    Counts,
+    /// Most launched first, with recent launches weighing more than old ones
+    Frecency,
+    /// By name, ignoring launches
+    Alphabetical,
+}
+
 #[derive(Deserialize, Serialize, Debug, Clone)]
 pub struct ConfigSourceFiles {
     pub source: Vec<ConfigSource>,
@@ -284,3 +428,110 @@ pub enum HomeType {
     Home,
     Persist,
 }
+
+/// How the launcher claims focus when its window opens
+#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Activation {
+    /// Activate Sherlock even if another application is focused
+    #[default]
+    Force,
+    /// Ask for activation, the compositor may decline while another application is focused
+    Request,
+    /// Never activate, the window only focuses its search bar
+    Never,
+}
+impl Activation {
+    /// The `ignoring_other_apps` argument of [`gpui::App::activate`], or `None` if Sherlock
+    /// should not be activated at all
+    pub fn ignoring_other_apps(self) -> Option<bool> {
+        match self {
+            Self::Force => Some(true),
+            Self::Request => Some(false),
+            Self::Never => None,
+        }
+    }
+}
+
+/// How an `open` request treats a window that is still open
+#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum Reopen {
+    /// Replace it with a new window, dropping its query
+    #[default]
+    Fresh,
+    /// Focus it again and keep its query
+    Focus,
+    /// Close it
+    Toggle,
+}
+
+/// Where Left and Right move the caret in text mixing left-to-right and right-to-left runs
+#[derive(Debug, Copy, Clone, Deserialize, PartialEq, Serialize, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum CaretMovement {
+    /// To the character on screen next to it
+    #[default]
+    Visual,
+    /// To the previous or next character of the text, regardless of its direction
+    Logical,
+}
+
+/// What happens to the window in response to an `open` or `toggle` request
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ReopenAction {
+    /// Open a new window, replacing the current one
+    Spawn,
+    Focus,
+    Close,
+}
+
+impl Reopen {
+    pub fn decide(self, window_open: bool) -> ReopenAction {
+        match (self, window_open) {
+            (_, false) | (Self::Fresh, true) => ReopenAction::Spawn,
+            (Self::Focus, true) => ReopenAction::Focus,
+            (Self::Toggle, true) => ReopenAction::Close,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reopen_decision() {
+        let behavior: ConfigBehavior = serde_json::from_str("{}").unwrap();
+        assert_eq!(behavior.reopen, Reopen::Fresh);
+
+        let cases = [
+            (Reopen::Fresh, false, ReopenAction::Spawn),
+            (Reopen::Fresh, true, ReopenAction::Spawn),
+            (Reopen::Focus, false, ReopenAction::Spawn),
+            (Reopen::Focus, true, ReopenAction::Focus),
+            (Reopen::Toggle, false, ReopenAction::Spawn),
+            (Reopen::Toggle, true, ReopenAction::Close),
+        ];
+        for (reopen, window_open, expected) in cases {
+            assert_eq!(reopen.decide(window_open), expected, "{reopen:?}");
+        }
+
+        let behavior: ConfigBehavior = serde_json::from_str(r#"{"reopen": "focus"}"#).unwrap();
+        assert_eq!(behavior.reopen, Reopen::Focus);
+    }
+
+    #[test]
+    fn test_activation_decision() {
+        let behavior: ConfigBehavior = serde_json::from_str("{}").unwrap();
+        assert_eq!(behavior.activation, Activation::Force);
+        assert_eq!(behavior.activation.ignoring_other_apps(), Some(true));
+
+        let behavior: ConfigBehavior =
+            serde_json::from_str(r#"{"activation": "request"}"#).unwrap();
+        assert_eq!(behavior.activation.ignoring_other_apps(), Some(false));
+
+        let behavior: ConfigBehavior = serde_json::from_str(r#"{"activation": "never"}"#).unwrap();
+        assert_eq!(behavior.activation.ignoring_other_apps(), None);
+    }
+}
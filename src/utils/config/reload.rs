@@ -0,0 +1,169 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::{OnceLock, mpsc},
+    time::Duration,
+};
+
+use futures::channel::mpsc::UnboundedSender;
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+
+use crate::{
+    loader::utils::set_fold_diacritics,
+    sherlock_error,
+    utils::{
+        config::{ConfigGuard, SherlockFlags},
+        errors::{SherlockError, SherlockErrorType},
+    },
+};
+
+/// Flags sherlock was started with, applied to every reloaded config
+static FLAGS: OnceLock<SherlockFlags> = OnceLock::new();
+
+/// Editors write files in several steps, so changes are collected until the file was left
+/// alone for this long
+const SETTLE: Duration = Duration::from_millis(200);
+
+/// Keeps the startup flags around for [`reload_config`]. Has to be called before the flags
+/// are consumed by [`SherlockFlags::to_config`].
+pub fn remember_flags(flags: &SherlockFlags) {
+    let _ = FLAGS.set(flags.clone());
+}
+
+/// Reads the config file again and replaces the global config. A file that cannot be read or
+/// parsed keeps the current config, its errors are returned. Open windows keep their size,
+/// the next window uses the new appearance. Key bindings are bound once at startup and stay.
+///
+/// Returns true if the icon paths changed and have to be scanned again.
+pub fn reload_config() -> Result<bool, Vec<SherlockError>> {
+    let mut flags = FLAGS.get().cloned().unwrap_or_default();
+    let (mut config, errors) = flags.to_config().map_err(|e| vec![e])?;
+    if !errors.is_empty() {
+        return Err(errors);
+    }
+
+    let mut icons_changed = false;
+    ConfigGuard::write_key(|current| {
+        // runtime state was derived at startup, e.g. whether the starter files were written
+        config.runtime = current.runtime.clone();
+        icons_changed = config.appearance.icon_paths != current.appearance.icon_paths;
+        // the launchers are loaded again after a reload
+        set_fold_diacritics(config.search.fold_diacritics);
+        *current = config;
+    })
+    .map_err(|e| vec![e])?;
+    Ok(icons_changed)
+}
+
+/// Watches the config and fallback files from a background thread and reloads the config
+/// whenever one of them changes. Each successful reload is sent to `on_reload`, along with
+/// whether the icon paths changed, so the launchers can be loaded again.
+pub fn spawn_config_watcher(on_reload: UnboundedSender<bool>) {
+    let Ok(paths) = ConfigGuard::read()
+        .map(|config| [config.files.config.clone(), config.files.fallback.clone()])
+    else {
+        return;
+    };
+    std::thread::spawn(move || {
+        if let Err(e) = watch_config(&paths, &on_reload) {
+            e.report();
+        }
+    });
+}
+
+fn watch_config(paths: &[PathBuf], on_reload: &UnboundedSender<bool>) -> Result<(), SherlockError> {
+    let watch_error = |path: &Path, e: notify::Error| {
+        sherlock_error!(
+            SherlockErrorType::ConfigError(Some(format!("Cannot watch {}", path.display()))),
+            e.to_string()
+        )
+    };
+
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).map_err(|e| watch_error(&paths[0], e))?;
+    let mut dirs: Vec<&Path> = paths
+        .iter()
+        .map(|path| {
+            path.parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(Path::new("."))
+        })
+        .collect();
+    dirs.dedup();
+    for dir in dirs {
+        // editors tend to replace the file instead of writing to it, which would end a watch
+        // on the file itself
+        watcher
+            .watch(dir, RecursiveMode::NonRecursive)
+            .map_err(|e| watch_error(dir, e))?;
+    }
+
+    let changed = |event: &Event| paths.iter().any(|path| is_config_change(event, path));
+    while let Ok(event) = rx.recv() {
+        if !event.is_ok_and(|event| changed(&event)) {
+            continue;
+        }
+        while rx.recv_timeout(SETTLE).is_ok() {}
+
+        match reload_config() {
+            Ok(icons_changed) => {
+                if on_reload.unbounded_send(icons_changed).is_err() {
+                    break;
+                }
+            }
+            Err(errors) => errors.into_iter().for_each(SherlockError::report),
+        }
+    }
+    Ok(())
+}
+
+/// Returns true if `event` modified the config at `path`. A json config is looked up next to a
+/// missing toml config and the other way around, so both count.
+fn is_config_change(event: &Event, path: &Path) -> bool {
+    let is_config = |changed: &PathBuf| {
+        changed.parent() == path.parent()
+            && changed.file_stem() == path.file_stem()
+            && changed
+                .extension()
+                .is_some_and(|ext| ext == "toml" || ext == "json")
+    };
+    matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_))
+        && event.paths.iter().any(is_config)
+}
+
+#[cfg(test)]
+mod tests {
+    use notify::event::{AccessKind, CreateKind, ModifyKind};
+
+    use super::*;
+
+    #[test]
+    fn test_config_changes() {
+        let config = Path::new("/home/user/.config/sherlock/config.toml");
+        let event = |kind: EventKind, path: &str| Event::new(kind).add_path(PathBuf::from(path));
+
+        let write = event(
+            EventKind::Modify(ModifyKind::Any),
+            "/home/user/.config/sherlock/config.toml",
+        );
+        assert!(is_config_change(&write, config));
+
+        // the json variant is read if the toml config is missing
+        let json = event(
+            EventKind::Create(CreateKind::File),
+            "/home/user/.config/sherlock/config.json",
+        );
+        assert!(is_config_change(&json, config));
+
+        let fallback = event(
+            EventKind::Modify(ModifyKind::Any),
+            "/home/user/.config/sherlock/fallback.json",
+        );
+        assert!(!is_config_change(&fallback, config));
+
+        let read = event(
+            EventKind::Access(AccessKind::Any),
+            "/home/user/.config/sherlock/config.toml",
+        );
+        assert!(!is_config_change(&read, config));
+    }
+}
@@ -1,4 +1,5 @@
 use std::fmt::Display;
+use std::sync::Mutex;
 use std::{fmt::Debug, path::PathBuf};
 
 use serde::{Deserialize, Serialize};
@@ -11,6 +12,9 @@ macro_rules! sherlock_error {
         $crate::SherlockError::new($errtype, $source, file!(), line!())
     };
 }
+/// Non-critical errors collected since startup, listed on the error page
+static REPORTED: Mutex<Vec<SherlockError>> = Mutex::new(Vec::new());
+
 #[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct SherlockError {
     pub error: SherlockErrorType,
@@ -34,6 +38,24 @@ impl SherlockError {
             ),
         }
     }
+    /// Prints the error and keeps it for the error page. An error that was already reported,
+    /// e.g. by a previous reload, is only kept once.
+    pub fn report(self) {
+        eprintln!("{self}");
+        if let Ok(mut reported) = REPORTED.lock() {
+            let message = self.to_string();
+            if !reported.iter().any(|e| e.to_string() == message) {
+                reported.push(self);
+            }
+        }
+    }
+    /// All errors reported so far, oldest first
+    pub fn reported() -> Vec<SherlockError> {
+        REPORTED.lock().map(|r| r.clone()).unwrap_or_default()
+    }
+    pub fn reported_count() -> usize {
+        REPORTED.lock().map(|r| r.len()).unwrap_or_default()
+    }
 }
 
 #[allow(dead_code)]
@@ -0,0 +1,145 @@
+use std::{
+    fs::OpenOptions,
+    io::Write,
+    path::{Path, PathBuf},
+};
+
+use rust_embed::RustEmbed;
+use serde_json::json;
+
+use crate::{
+    loader::utils::RawLauncher,
+    sherlock_error,
+    utils::{
+        config::SherlockConfig,
+        errors::{SherlockError, SherlockErrorType},
+    },
+};
+
+/// Starter files written on the first run, versioned with the binary. The launchers file is
+/// plain JSON and therefore explained by the comments of the config file.
+#[derive(RustEmbed)]
+#[folder = "assets/starter"]
+struct Starter;
+
+const CONFIG_FILE: &str = "config.toml";
+const LAUNCHERS_FILE: &str = "fallback.json";
+const DOCUMENTATION: &str = "https://github.com/Skxxtz/sherlock/tree/documentation/docs";
+
+/// Writes the starter config and launchers to `dir` if it holds neither a config nor a
+/// launchers file. Existing files are never overwritten.
+///
+/// Returns the written files, so an empty list means this is not the first run.
+pub fn write_starter_files(dir: &Path) -> Result<Vec<PathBuf>, SherlockError> {
+    let existing = [CONFIG_FILE, "config.json", LAUNCHERS_FILE]
+        .iter()
+        .any(|name| dir.join(name).exists());
+    if existing {
+        return Ok(Vec::new());
+    }
+
+    let mut written = Vec::new();
+    for name in [CONFIG_FILE, LAUNCHERS_FILE] {
+        let Some(content) = Starter::get(name) else {
+            continue;
+        };
+        let path = dir.join(name);
+        let write_error = |e: std::io::Error| {
+            sherlock_error!(
+                SherlockErrorType::FileWriteError(path.clone()),
+                e.to_string()
+            )
+        };
+        // `create_new` keeps a file that appeared in the meantime
+        let mut file = match OpenOptions::new().write(true).create_new(true).open(&path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => continue,
+            Err(e) => return Err(write_error(e)),
+        };
+        file.write_all(&content.data).map_err(write_error)?;
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// Launcher of the welcome entries shown on the home view of the first run
+pub fn welcome_launcher(config: &SherlockConfig) -> Option<RawLauncher> {
+    let edit = format!(
+        r#"{{terminal}} sh -c '"${{EDITOR:-vi}}" "$@"' sherlock {} {}"#,
+        quote(&config.files.config),
+        quote(&config.files.fallback)
+    );
+    let raw = json!({
+        "name": "Welcome",
+        "display_name": "Welcome to Sherlock",
+        "type": "command",
+        "priority": 0,
+        "home": "OnlyHome",
+        "args": {
+            "commands": {
+                "Edit configuration": {
+                    "icon": "document-edit",
+                    "exec": edit,
+                    "search_string": "edit configuration;settings"
+                },
+                "Open documentation": {
+                    "icon": "help-browser",
+                    "exec": format!("xdg-open {DOCUMENTATION}"),
+                    "search_string": "open documentation;help"
+                }
+            }
+        }
+    });
+    serde_json::from_value(raw).ok()
+}
+
+/// Double quotes `path` for [`split_as_command`](crate::utils::command_launch::split_as_command)
+fn quote(path: &Path) -> String {
+    let path = path.to_string_lossy();
+    format!("\"{}\"", path.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::command_launch::split_as_command;
+
+    #[test]
+    fn test_starter_files() {
+        let dir = std::env::temp_dir().join(format!("sherlock-first-run-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        let written = write_starter_files(&dir).unwrap();
+        assert_eq!(written, [dir.join(CONFIG_FILE), dir.join(LAUNCHERS_FILE)]);
+        let config = std::fs::read_to_string(dir.join(CONFIG_FILE)).unwrap();
+        toml::de::from_str::<SherlockConfig>(&config).unwrap();
+        let launchers = std::fs::read(dir.join(LAUNCHERS_FILE)).unwrap();
+        let launchers: Vec<RawLauncher> = serde_json::from_slice(&launchers).unwrap();
+        assert_eq!(launchers.len(), 4);
+
+        // a second run keeps the edited files
+        std::fs::write(dir.join(CONFIG_FILE), "# edited").unwrap();
+        assert!(write_starter_files(&dir).unwrap().is_empty());
+        std::fs::remove_file(dir.join(LAUNCHERS_FILE)).unwrap();
+        assert!(write_starter_files(&dir).unwrap().is_empty());
+        assert_eq!(
+            std::fs::read_to_string(dir.join(CONFIG_FILE)).unwrap(),
+            "# edited"
+        );
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_welcome_launcher() {
+        let mut config = SherlockConfig::default();
+        config.files.config = PathBuf::from("/home/me/my \"conf\"/config.toml");
+        let launcher = welcome_launcher(&config).unwrap();
+        let commands = launcher.args["commands"].as_object().unwrap();
+
+        let edit = commands["Edit configuration"]["exec"].as_str().unwrap();
+        let parts = split_as_command(edit);
+        assert_eq!(parts[0], "{terminal}");
+        assert_eq!(parts[3], r#""${EDITOR:-vi}" "$@""#);
+        assert_eq!(parts[5], "/home/me/my \"conf\"/config.toml");
+    }
+}
@@ -0,0 +1,267 @@
+use std::{collections::HashMap, fmt, time::Duration};
+
+use regex::{Captures, Regex};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    loader::utils::ApplicationAction,
+    sherlock_error,
+    utils::errors::{SherlockError, SherlockErrorType},
+};
+
+/// Requests that take longer are cancelled and reported as failed
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// Prefix of header values that are read from the environment, e.g. `env:DEPLOY_TOKEN`
+const ENV_PREFIX: &str = "env:";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    #[serde(alias = "get")]
+    Get,
+    #[serde(alias = "post")]
+    Post,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+struct Header {
+    name: String,
+    value: String,
+    /// Read from the environment and therefore never printed
+    secret: bool,
+}
+
+/// Request of an action with `method = "http"`, with its placeholders and secrets resolved
+#[derive(Clone, Debug, PartialEq)]
+pub struct HttpRequest {
+    method: HttpMethod,
+    url: String,
+    headers: Vec<Header>,
+    body: Option<String>,
+}
+
+impl HttpRequest {
+    /// Fills `{query}` and `{name}` into the url and body of `action`. They are percent-encoded
+    /// in the url and escaped as JSON strings in the body.
+    pub fn resolve(
+        action: &ApplicationAction,
+        query: &str,
+        name: &str,
+    ) -> Result<Self, SherlockError> {
+        let url = action.url.as_deref().ok_or_else(|| {
+            sherlock_error!(
+                SherlockErrorType::InvalidAction,
+                "http actions require a url"
+            )
+        })?;
+        let headers = action
+            .headers
+            .iter()
+            .map(|(name, value)| {
+                let header = match value.strip_prefix(ENV_PREFIX) {
+                    Some(var) => Header {
+                        name: name.clone(),
+                        value: std::env::var(var).map_err(|e| {
+                            sherlock_error!(
+                                SherlockErrorType::EnvVarNotFoundError(var.to_string()),
+                                e.to_string()
+                            )
+                        })?,
+                        secret: true,
+                    },
+                    None => Header {
+                        name: name.clone(),
+                        value: value.clone(),
+                        secret: false,
+                    },
+                };
+                Ok(header)
+            })
+            .collect::<Result<_, SherlockError>>()?;
+
+        Ok(Self {
+            method: action.http_method,
+            url: fill(url, query, name, percent_encode),
+            headers,
+            body: action
+                .body
+                .as_deref()
+                .map(|body| fill(body, query, name, json_escape)),
+        })
+    }
+
+    /// Sends the request in the background and reports the outcome as a desktop notification,
+    /// as the window is usually closed by then
+    pub fn spawn(self, title: String) {
+        tokio::spawn(async move {
+            let message = match self.send().await {
+                Ok(status) => format!("{} {}: {status}", self.method_str(), self.url),
+                Err(e) => {
+                    let (_, message) = e.error.get_message();
+                    format!("{} {} failed: {message}", self.method_str(), self.url)
+                }
+            };
+            if let Err(e) = notify(&title, &message).await {
+                eprintln!("{message} ({e})");
+            }
+        });
+    }
+
+    async fn send(&self) -> Result<reqwest::StatusCode, SherlockError> {
+        let request_error = |e: reqwest::Error| {
+            sherlock_error!(
+                SherlockErrorType::HttpRequestError(self.url.clone()),
+                e.to_string()
+            )
+        };
+
+        let client = reqwest::Client::builder()
+            .timeout(TIMEOUT)
+            .build()
+            .map_err(request_error)?;
+        let mut request = match self.method {
+            HttpMethod::Get => client.get(&self.url),
+            HttpMethod::Post => client.post(&self.url),
+        };
+        for header in &self.headers {
+            request = request.header(&header.name, &header.value);
+        }
+        if let Some(body) = &self.body {
+            request = request
+                .header(reqwest::header::CONTENT_TYPE, "application/json")
+                .body(body.clone());
+        }
+
+        let response = request.send().await.map_err(request_error)?;
+        response
+            .error_for_status()
+            .map(|response| response.status())
+            .map_err(request_error)
+    }
+
+    fn method_str(&self) -> &'static str {
+        match self.method {
+            HttpMethod::Get => "GET",
+            HttpMethod::Post => "POST",
+        }
+    }
+}
+
+/// The request as printed in dry-run mode. Secret header values are masked.
+impl fmt::Display for HttpRequest {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.method_str(), self.url)?;
+        for header in &self.headers {
+            let value = if header.secret { "***" } else { &header.value };
+            write!(f, "\n{}: {value}", header.name)?;
+        }
+        if let Some(body) = &self.body {
+            write!(f, "\n\n{body}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Puts the escaped `query` and `name` in for their placeholders. Both are replaced in a single
+/// pass, so a query that contains `{name}` is sent as typed.
+fn fill(template: &str, query: &str, name: &str, escape: fn(&str) -> String) -> String {
+    let placeholders = Regex::new(r"\{(query|name)\}").unwrap();
+    placeholders
+        .replace_all(template, |caps: &Captures| match &caps[1] {
+            "query" => escape(query),
+            _ => escape(name),
+        })
+        .into_owned()
+}
+
+fn percent_encode(value: &str) -> String {
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => {
+                encoded.push(byte as char)
+            }
+            _ => encoded.push_str(&format!("%{byte:02X}")),
+        }
+    }
+    encoded
+}
+
+fn json_escape(value: &str) -> String {
+    let quoted = serde_json::to_string(value).unwrap_or_default();
+    quoted[1..quoted.len() - 1].to_string()
+}
+
+async fn notify(summary: &str, body: &str) -> zbus::Result<()> {
+    let connection = zbus::Connection::session().await?;
+    connection
+        .call_method(
+            Some("org.freedesktop.Notifications"),
+            "/org/freedesktop/Notifications",
+            Some("org.freedesktop.Notifications"),
+            "Notify",
+            &(
+                "Sherlock",
+                0u32,
+                "",
+                summary,
+                body,
+                Vec::<&str>::new(),
+                HashMap::<&str, zbus::zvariant::Value>::new(),
+                -1i32,
+            ),
+        )
+        .await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_request() {
+        let mut action = ApplicationAction::new("http");
+        action.url = Some(String::from("https://example.com/hook?q={query}"));
+        action.http_method = HttpMethod::Post;
+        action.body = Some(String::from(r#"{"text": "{query}", "target": "{name}"}"#));
+        action.headers = [
+            (String::from("X-Source"), String::from("sherlock")),
+            (String::from("Authorization"), String::from("env:PATH")),
+        ]
+        .into();
+
+        let request = HttpRequest::resolve(&action, r#"say "hi" & go"#, "Deploy").unwrap();
+        assert_eq!(
+            request.url,
+            "https://example.com/hook?q=say%20%22hi%22%20%26%20go"
+        );
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["text"], r#"say "hi" & go"#);
+        assert_eq!(body["target"], "Deploy");
+
+        // placeholders within the query are left as typed
+        let request = HttpRequest::resolve(&action, "{name} {query}", "Deploy").unwrap();
+        let body: serde_json::Value =
+            serde_json::from_str(request.body.as_deref().unwrap()).unwrap();
+        assert_eq!(body["text"], "{name} {query}");
+
+        // secrets are resolved but never printed
+        let printed = request.to_string();
+        assert!(printed.starts_with("POST https://example.com/hook"));
+        assert!(printed.contains("Authorization: ***"));
+        assert!(printed.contains("X-Source: sherlock"));
+
+        action.headers = [(
+            String::from("Authorization"),
+            String::from("env:SHERLOCK_SURELY_UNSET_TOKEN"),
+        )]
+        .into();
+        assert!(HttpRequest::resolve(&action, "", "").is_err());
+        action.url = None;
+        action.headers.clear();
+        assert!(HttpRequest::resolve(&action, "", "").is_err());
+    }
+}
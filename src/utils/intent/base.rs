@@ -0,0 +1,221 @@
+/// Numeral system of a base conversion
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Base {
+    Bin,
+    Oct,
+    Dec,
+    Hex,
+}
+
+impl Base {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "bin" | "binary" => Some(Self::Bin),
+            "oct" | "octal" => Some(Self::Oct),
+            "dec" | "decimal" => Some(Self::Dec),
+            "hex" | "hexadecimal" => Some(Self::Hex),
+            _ => None,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Bin => "bin",
+            Self::Oct => "oct",
+            Self::Dec => "dec",
+            Self::Hex => "hex",
+        }
+    }
+
+    pub fn format(&self, value: u64) -> String {
+        match self {
+            Self::Bin => format!("{:#b}", value),
+            Self::Oct => format!("{:#o}", value),
+            Self::Dec => value.to_string(),
+            Self::Hex => format!("{:#x}", value),
+        }
+    }
+}
+
+/// Returns true if `expr` can only be evaluated as an integer expression, i.e. it contains a
+/// prefixed literal or a bitwise operator.
+///
+/// `^` is left out on purpose: on its own, `2^10` is a power for meval.
+pub fn needs_integer_path(expr: &str) -> bool {
+    let lower = expr.to_lowercase();
+    lower.contains(['&', '|', '~'])
+        || lower.contains("<<")
+        || lower.contains(">>")
+        || ["0x", "0b", "0o"].iter().any(|prefix| {
+            lower.match_indices(prefix).any(|(i, _)| {
+                // the prefix has to start a literal, not end one like in `10x`
+                !lower[..i].ends_with(|c: char| c.is_ascii_alphanumeric())
+            })
+        })
+}
+
+/// Evaluates an unsigned integer expression
+///
+/// Supports decimal, `0x`, `0o` and `0b` literals, parentheses, `~` and the binary operators
+/// `* / % + - << >> & ^ |` with C precedence. Overflowing or otherwise invalid expressions
+/// produce `None` instead of a wrapped result.
+pub fn eval(expr: &str) -> Option<u64> {
+    let mut parser = Parser {
+        input: expr.as_bytes(),
+        pos: 0,
+    };
+    let value = parser.expr(0)?;
+    parser.skip_whitespace();
+    (parser.pos == parser.input.len()).then_some(value)
+}
+
+/// Binary operators, from lowest to highest precedence
+const LEVELS: &[&[&str]] = &[
+    &["|"],
+    &["^"],
+    &["&"],
+    &["<<", ">>"],
+    &["+", "-"],
+    &["*", "/", "%"],
+];
+
+struct Parser<'a> {
+    input: &'a [u8],
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn skip_whitespace(&mut self) {
+        while self
+            .input
+            .get(self.pos)
+            .is_some_and(u8::is_ascii_whitespace)
+        {
+            self.pos += 1;
+        }
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        if self.input[self.pos..].starts_with(token.as_bytes()) {
+            self.pos += token.len();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn expr(&mut self, level: usize) -> Option<u64> {
+        let Some(ops) = LEVELS.get(level) else {
+            return self.unary();
+        };
+
+        let mut lhs = self.expr(level + 1)?;
+        'outer: loop {
+            for op in *ops {
+                if self.eat(op) {
+                    let rhs = self.expr(level + 1)?;
+                    lhs = apply(op, lhs, rhs)?;
+                    continue 'outer;
+                }
+            }
+            return Some(lhs);
+        }
+    }
+
+    fn unary(&mut self) -> Option<u64> {
+        if self.eat("~") {
+            return self.unary().map(|v| !v);
+        }
+        if self.eat("(") {
+            let value = self.expr(0)?;
+            return self.eat(")").then_some(value);
+        }
+        self.literal()
+    }
+
+    fn literal(&mut self) -> Option<u64> {
+        self.skip_whitespace();
+        let rest = &self.input[self.pos..];
+        let (radix, prefix) = match rest {
+            [b'0', b'x' | b'X', ..] => (16, 2),
+            [b'0', b'o' | b'O', ..] => (8, 2),
+            [b'0', b'b' | b'B', ..] => (2, 2),
+            _ => (10, 0),
+        };
+
+        let digits = rest[prefix..]
+            .iter()
+            .take_while(|b| b.is_ascii_alphanumeric() || **b == b'_')
+            .count();
+        let text = std::str::from_utf8(&rest[prefix..prefix + digits]).ok()?;
+        let value = u64::from_str_radix(&text.replace('_', ""), radix).ok()?;
+
+        self.pos += prefix + digits;
+        Some(value)
+    }
+}
+
+fn apply(op: &str, lhs: u64, rhs: u64) -> Option<u64> {
+    match op {
+        "|" => Some(lhs | rhs),
+        "^" => Some(lhs ^ rhs),
+        "&" => Some(lhs & rhs),
+        // reject shifts that would drop set bits
+        "<<" => (rhs < 64 && lhs.leading_zeros() as u64 >= rhs).then(|| lhs << rhs),
+        ">>" => lhs.checked_shr(rhs.try_into().ok()?),
+        "+" => lhs.checked_add(rhs),
+        "-" => lhs.checked_sub(rhs),
+        "*" => lhs.checked_mul(rhs),
+        "/" => lhs.checked_div(rhs),
+        "%" => lhs.checked_rem(rhs),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_eval() {
+        assert_eq!(eval("0xff"), Some(255));
+        assert_eq!(eval("0b1010"), Some(10));
+        assert_eq!(eval("1 << 12"), Some(4096));
+        assert_eq!(eval("8 | 1 ^ 3 & 2"), Some(11));
+        assert_eq!(eval("(1 | 2) << 1 + 1"), Some(12));
+        assert_eq!(eval("~0"), Some(u64::MAX));
+        assert_eq!(eval("0xff & ~0x0f"), Some(0xf0));
+        assert_eq!(eval("1_000 >> 3"), Some(125));
+
+        // overflow produces no result
+        assert_eq!(eval("1 << 64"), None);
+        assert_eq!(eval("0xffff_ffff_ffff_ffff << 1"), None);
+        assert_eq!(eval("0xffffffffffffffff + 1"), None);
+        assert_eq!(eval("0 - 1"), None);
+        assert_eq!(eval("0x1_0000_0000_0000_0000"), None);
+
+        // malformed
+        assert_eq!(eval("1 <<"), None);
+        assert_eq!(eval("(1 | 2"), None);
+        assert_eq!(eval("0xfg"), None);
+    }
+
+    #[test]
+    fn test_format() {
+        assert_eq!(Base::Hex.format(255), "0xff");
+        assert_eq!(Base::Bin.format(10), "0b1010");
+        assert_eq!(Base::Oct.format(8), "0o10");
+        assert_eq!(Base::Dec.format(4096), "4096");
+    }
+
+    #[test]
+    fn test_needs_integer_path() {
+        assert!(needs_integer_path("0xff"));
+        assert!(needs_integer_path("1 << 12"));
+        assert!(needs_integer_path("~5"));
+        assert!(!needs_integer_path("2^10"));
+        assert!(!needs_integer_path("3 * 4"));
+        assert!(!needs_integer_path("10x"));
+    }
+}
@@ -5,9 +5,13 @@ impl ColorConverter {
         let rgb = match from {
             "rgb" | "rgba" if values.len() >= 3 => Some((values[0], values[1], values[2])),
             "hex" => Some((values[0], values[1], values[2])),
+            "name" if values.len() >= 3 => Some((values[0], values[1], values[2])),
             "hsl" if values.len() >= 3 => Some(Self::hsl_to_rgb(values[0], values[1], values[2])),
             "hsv" if values.len() >= 3 => Some(Self::hsv_to_rgb(values[0], values[1], values[2])),
             "lab" if values.len() >= 3 => Some(Self::lab_to_rgb(values[0], values[1], values[2])),
+            "cmyk" if values.len() >= 4 => Some(Self::cmyk_to_rgb(
+                values[0], values[1], values[2], values[3],
+            )),
             _ => None,
         }?;
 
@@ -37,6 +41,10 @@ impl ColorConverter {
                 let (l, a, b) = Self::rgb_to_lab(rgb.0, rgb.1, rgb.2);
                 Some(format!("lab({:.1}, {:.1}, {:.1})", l, a, b))
             }
+            "cmyk" => {
+                let (c, m, y, k) = Self::rgb_to_cmyk(rgb.0, rgb.1, rgb.2);
+                Some(format!("cmyk({:.0}%, {:.0}%, {:.0}%, {:.0}%)", c, m, y, k))
+            }
             _ => None,
         }
     }
@@ -64,9 +72,177 @@ impl ColorConverter {
     }
 }
 
+// --- Named colors
+impl ColorConverter {
+    /// Resolves a CSS color name such as `rebeccapurple` to rgb
+    pub fn from_name(name: &str) -> Option<(f32, f32, f32)> {
+        let (_, hex) = NAMED_COLORS
+            .iter()
+            .find(|(color, _)| color.eq_ignore_ascii_case(name))?;
+
+        Some((
+            ((hex >> 16) & 0xFF) as f32,
+            ((hex >> 8) & 0xFF) as f32,
+            (hex & 0xFF) as f32,
+        ))
+    }
+}
+
+/// CSS named colors, sorted by name
+const NAMED_COLORS: [(&str, u32); 148] = [
+    ("aliceblue", 0xF0F8FF),
+    ("antiquewhite", 0xFAEBD7),
+    ("aqua", 0x00FFFF),
+    ("aquamarine", 0x7FFFD4),
+    ("azure", 0xF0FFFF),
+    ("beige", 0xF5F5DC),
+    ("bisque", 0xFFE4C4),
+    ("black", 0x000000),
+    ("blanchedalmond", 0xFFEBCD),
+    ("blue", 0x0000FF),
+    ("blueviolet", 0x8A2BE2),
+    ("brown", 0xA52A2A),
+    ("burlywood", 0xDEB887),
+    ("cadetblue", 0x5F9EA0),
+    ("chartreuse", 0x7FFF00),
+    ("chocolate", 0xD2691E),
+    ("coral", 0xFF7F50),
+    ("cornflowerblue", 0x6495ED),
+    ("cornsilk", 0xFFF8DC),
+    ("crimson", 0xDC143C),
+    ("cyan", 0x00FFFF),
+    ("darkblue", 0x00008B),
+    ("darkcyan", 0x008B8B),
+    ("darkgoldenrod", 0xB8860B),
+    ("darkgray", 0xA9A9A9),
+    ("darkgreen", 0x006400),
+    ("darkgrey", 0xA9A9A9),
+    ("darkkhaki", 0xBDB76B),
+    ("darkmagenta", 0x8B008B),
+    ("darkolivegreen", 0x556B2F),
+    ("darkorange", 0xFF8C00),
+    ("darkorchid", 0x9932CC),
+    ("darkred", 0x8B0000),
+    ("darksalmon", 0xE9967A),
+    ("darkseagreen", 0x8FBC8F),
+    ("darkslateblue", 0x483D8B),
+    ("darkslategray", 0x2F4F4F),
+    ("darkslategrey", 0x2F4F4F),
+    ("darkturquoise", 0x00CED1),
+    ("darkviolet", 0x9400D3),
+    ("deeppink", 0xFF1493),
+    ("deepskyblue", 0x00BFFF),
+    ("dimgray", 0x696969),
+    ("dimgrey", 0x696969),
+    ("dodgerblue", 0x1E90FF),
+    ("firebrick", 0xB22222),
+    ("floralwhite", 0xFFFAF0),
+    ("forestgreen", 0x228B22),
+    ("fuchsia", 0xFF00FF),
+    ("gainsboro", 0xDCDCDC),
+    ("ghostwhite", 0xF8F8FF),
+    ("gold", 0xFFD700),
+    ("goldenrod", 0xDAA520),
+    ("gray", 0x808080),
+    ("green", 0x008000),
+    ("greenyellow", 0xADFF2F),
+    ("grey", 0x808080),
+    ("honeydew", 0xF0FFF0),
+    ("hotpink", 0xFF69B4),
+    ("indianred", 0xCD5C5C),
+    ("indigo", 0x4B0082),
+    ("ivory", 0xFFFFF0),
+    ("khaki", 0xF0E68C),
+    ("lavender", 0xE6E6FA),
+    ("lavenderblush", 0xFFF0F5),
+    ("lawngreen", 0x7CFC00),
+    ("lemonchiffon", 0xFFFACD),
+    ("lightblue", 0xADD8E6),
+    ("lightcoral", 0xF08080),
+    ("lightcyan", 0xE0FFFF),
+    ("lightgoldenrodyellow", 0xFAFAD2),
+    ("lightgray", 0xD3D3D3),
+    ("lightgreen", 0x90EE90),
+    ("lightgrey", 0xD3D3D3),
+    ("lightpink", 0xFFB6C1),
+    ("lightsalmon", 0xFFA07A),
+    ("lightseagreen", 0x20B2AA),
+    ("lightskyblue", 0x87CEFA),
+    ("lightslategray", 0x778899),
+    ("lightslategrey", 0x778899),
+    ("lightsteelblue", 0xB0C4DE),
+    ("lightyellow", 0xFFFFE0),
+    ("lime", 0x00FF00),
+    ("limegreen", 0x32CD32),
+    ("linen", 0xFAF0E6),
+    ("magenta", 0xFF00FF),
+    ("maroon", 0x800000),
+    ("mediumaquamarine", 0x66CDAA),
+    ("mediumblue", 0x0000CD),
+    ("mediumorchid", 0xBA55D3),
+    ("mediumpurple", 0x9370DB),
+    ("mediumseagreen", 0x3CB371),
+    ("mediumslateblue", 0x7B68EE),
+    ("mediumspringgreen", 0x00FA9A),
+    ("mediumturquoise", 0x48D1CC),
+    ("mediumvioletred", 0xC71585),
+    ("midnightblue", 0x191970),
+    ("mintcream", 0xF5FFFA),
+    ("mistyrose", 0xFFE4E1),
+    ("moccasin", 0xFFE4B5),
+    ("navajowhite", 0xFFDEAD),
+    ("navy", 0x000080),
+    ("oldlace", 0xFDF5E6),
+    ("olive", 0x808000),
+    ("olivedrab", 0x6B8E23),
+    ("orange", 0xFFA500),
+    ("orangered", 0xFF4500),
+    ("orchid", 0xDA70D6),
+    ("palegoldenrod", 0xEEE8AA),
+    ("palegreen", 0x98FB98),
+    ("paleturquoise", 0xAFEEEE),
+    ("palevioletred", 0xDB7093),
+    ("papayawhip", 0xFFEFD5),
+    ("peachpuff", 0xFFDAB9),
+    ("peru", 0xCD853F),
+    ("pink", 0xFFC0CB),
+    ("plum", 0xDDA0DD),
+    ("powderblue", 0xB0E0E6),
+    ("purple", 0x800080),
+    ("rebeccapurple", 0x663399),
+    ("red", 0xFF0000),
+    ("rosybrown", 0xBC8F8F),
+    ("royalblue", 0x4169E1),
+    ("saddlebrown", 0x8B4513),
+    ("salmon", 0xFA8072),
+    ("sandybrown", 0xF4A460),
+    ("seagreen", 0x2E8B57),
+    ("seashell", 0xFFF5EE),
+    ("sienna", 0xA0522D),
+    ("silver", 0xC0C0C0),
+    ("skyblue", 0x87CEEB),
+    ("slateblue", 0x6A5ACD),
+    ("slategray", 0x708090),
+    ("slategrey", 0x708090),
+    ("snow", 0xFFFAFA),
+    ("springgreen", 0x00FF7F),
+    ("steelblue", 0x4682B4),
+    ("tan", 0xD2B48C),
+    ("teal", 0x008080),
+    ("thistle", 0xD8BFD8),
+    ("tomato", 0xFF6347),
+    ("turquoise", 0x40E0D0),
+    ("violet", 0xEE82EE),
+    ("wheat", 0xF5DEB3),
+    ("white", 0xFFFFFF),
+    ("whitesmoke", 0xF5F5F5),
+    ("yellow", 0xFFFF00),
+    ("yellowgreen", 0x9ACD32),
+];
+
 // --- Hsl conversions
 impl ColorConverter {
-    fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
+    pub fn hsl_to_rgb(h: f32, s: f32, l: f32) -> (f32, f32, f32) {
         let s = s / 100.0;
         let l = l / 100.0;
         let c = (1.0 - (2.0 * l - 1.0).abs()) * s;
@@ -89,7 +265,7 @@ impl ColorConverter {
         )
     }
 
-    fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
+    pub fn rgb_to_hsl(r: f32, g: f32, b: f32) -> (f32, f32, f32) {
         let r = r / 255.0;
         let g = g / 255.0;
         let b = b / 255.0;
@@ -237,6 +413,39 @@ impl ColorConverter {
     }
 }
 
+// --- Cmyk conversions ---
+impl ColorConverter {
+    fn cmyk_to_rgb(c: f32, m: f32, y: f32, k: f32) -> (f32, f32, f32) {
+        let c = (c / 100.0).clamp(0.0, 1.0);
+        let m = (m / 100.0).clamp(0.0, 1.0);
+        let y = (y / 100.0).clamp(0.0, 1.0);
+        let k = (k / 100.0).clamp(0.0, 1.0);
+
+        (
+            255.0 * (1.0 - c) * (1.0 - k),
+            255.0 * (1.0 - m) * (1.0 - k),
+            255.0 * (1.0 - y) * (1.0 - k),
+        )
+    }
+
+    fn rgb_to_cmyk(r: f32, g: f32, b: f32) -> (f32, f32, f32, f32) {
+        let r = (r / 255.0).clamp(0.0, 1.0);
+        let g = (g / 255.0).clamp(0.0, 1.0);
+        let b = (b / 255.0).clamp(0.0, 1.0);
+
+        let k = 1.0 - r.max(g).max(b);
+        // pure black: avoid division by zero
+        if k >= 1.0 {
+            return (0.0, 0.0, 0.0, 100.0);
+        }
+
+        let c = (1.0 - r - k) / (1.0 - k);
+        let m = (1.0 - g - k) / (1.0 - k);
+        let y = (1.0 - b - k) / (1.0 - k);
+        (c * 100.0, m * 100.0, y * 100.0, k * 100.0)
+    }
+}
+
 #[cfg(test)]
 mod color_tests {
     use super::*;
@@ -297,6 +506,45 @@ mod color_tests {
         assert_near(back_to_rgb.2, 255.0, 1.0);
     }
 
+    #[test]
+    fn test_cmyk_roundtrip() {
+        let colors = [
+            (255.0, 0.0, 0.0),
+            (0.0, 255.0, 0.0),
+            (0.0, 0.0, 255.0),
+            (0.0, 0.0, 0.0),
+        ];
+
+        for (r, g, b) in colors {
+            let (c, m, y, k) = ColorConverter::rgb_to_cmyk(r, g, b);
+            let back_to_rgb = ColorConverter::cmyk_to_rgb(c, m, y, k);
+            assert_near(back_to_rgb.0, r, 0.5);
+            assert_near(back_to_rgb.1, g, 0.5);
+            assert_near(back_to_rgb.2, b, 0.5);
+        }
+
+        // Pure red should be 0% cyan, 100% magenta and yellow, 0% key
+        let res = ColorConverter::convert("rgb", &[255.0, 0.0, 0.0], "cmyk");
+        assert_eq!(res, Some("cmyk(0%, 100%, 100%, 0%)".to_string()));
+    }
+
+    #[test]
+    fn test_named_colors() {
+        assert_eq!(
+            ColorConverter::from_name("rebeccapurple"),
+            Some((102.0, 51.0, 153.0))
+        );
+        assert_eq!(
+            ColorConverter::from_name("Tomato"),
+            Some((255.0, 99.0, 71.0))
+        );
+        assert_eq!(ColorConverter::from_name("notacolor"), None);
+
+        let (r, g, b) = ColorConverter::from_name("tomato").unwrap();
+        let res = ColorConverter::convert("name", &[r, g, b], "hex");
+        assert_eq!(res, Some("#ff6347".to_string()));
+    }
+
     #[test]
     fn test_cross_space_conversion() {
         // HSL -> LAB (Yellow)
@@ -315,7 +563,13 @@ mod color_tests {
 
         // Unknown space
         assert_eq!(
-            ColorConverter::convert("cmyk", &[0.0, 0.0, 0.0, 0.0], "rgb"),
+            ColorConverter::convert("xyz", &[0.0, 0.0, 0.0], "rgb"),
+            None
+        );
+
+        // Not enough values for cmyk
+        assert_eq!(
+            ColorConverter::convert("cmyk", &[0.0, 0.0, 0.0], "rgb"),
             None
         );
 
@@ -0,0 +1,522 @@
+//! Date and duration arithmetic
+//!
+//! Understands shifts like `today + 45 days` or `now + 3h30m` and counts like
+//! `days until 2025-12-24` or `weeks between 2024-01-01 and 2024-06-01`.
+//!
+//! Dates are read as follows:
+//! - `2025-04-03` is always year-month-day
+//! - `3.4.2025` is always day.month.year
+//! - `3/4/2025` is month/day/year, unless the first number can't be a month. `13/4/2025` is
+//!   therefore the 13th of April.
+//!
+//! Days, weeks, months and years are calendar units: they keep the wall-clock time across DST
+//! changes, and `2024-01-31 + 1 month` ends on the last day of February. Hours, minutes and
+//! seconds count elapsed time.
+
+use chrono::{
+    DateTime, Datelike, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeDelta, TimeZone,
+};
+use std::fmt::Display;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DateUnit {
+    Second,
+    Minute,
+    Hour,
+    Day,
+    Week,
+    Month,
+    Year,
+}
+
+impl DateUnit {
+    pub fn from_name(s: &str) -> Option<Self> {
+        match s {
+            "s" | "sec" | "secs" | "second" | "seconds" => Some(Self::Second),
+            "m" | "min" | "mins" | "minute" | "minutes" => Some(Self::Minute),
+            "h" | "hr" | "hrs" | "hour" | "hours" => Some(Self::Hour),
+            "d" | "day" | "days" => Some(Self::Day),
+            "w" | "wk" | "wks" | "week" | "weeks" => Some(Self::Week),
+            "mo" | "month" | "months" => Some(Self::Month),
+            "y" | "yr" | "yrs" | "year" | "years" => Some(Self::Year),
+            _ => None,
+        }
+    }
+
+    fn is_calendar(&self) -> bool {
+        matches!(self, Self::Day | Self::Week | Self::Month | Self::Year)
+    }
+
+    fn name(&self, count: i64) -> &'static str {
+        let (singular, plural) = match self {
+            Self::Second => ("second", "seconds"),
+            Self::Minute => ("minute", "minutes"),
+            Self::Hour => ("hour", "hours"),
+            Self::Day => ("day", "days"),
+            Self::Week => ("week", "weeks"),
+            Self::Month => ("month", "months"),
+            Self::Year => ("year", "years"),
+        };
+        if count.abs() == 1 { singular } else { plural }
+    }
+}
+
+/// Point in time a calculation starts or ends at
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Point {
+    Now,
+    /// Midnight, the given number of days from today
+    Relative(i64),
+    Date(NaiveDate),
+    DateTime(NaiveDateTime),
+}
+
+impl Point {
+    fn parse(s: &str) -> Option<Self> {
+        match s {
+            "now" => return Some(Self::Now),
+            "today" => return Some(Self::Relative(0)),
+            "tomorrow" => return Some(Self::Relative(1)),
+            "yesterday" => return Some(Self::Relative(-1)),
+            _ => {}
+        }
+
+        match s.split_once([' ', 't']) {
+            Some((date, time)) => {
+                let time = time.trim();
+                let time = NaiveTime::parse_from_str(time, "%H:%M:%S")
+                    .or_else(|_| NaiveTime::parse_from_str(time, "%H:%M"))
+                    .ok()?;
+                Some(Self::DateTime(parse_date(date)?.and_time(time)))
+            }
+            None => Some(Self::Date(parse_date(s)?)),
+        }
+    }
+
+    /// Returns the point in the time zone of `now` and whether it carries a time of day
+    fn resolve<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Option<(DateTime<Tz>, bool)> {
+        let naive = match self {
+            Self::Now => return Some((now.clone(), true)),
+            Self::Relative(days) => now
+                .date_naive()
+                .checked_add_signed(TimeDelta::try_days(*days)?)?
+                .and_time(NaiveTime::MIN),
+            Self::Date(date) => date.and_time(NaiveTime::MIN),
+            Self::DateTime(datetime) => *datetime,
+        };
+        let resolved = local(&now.timezone(), naive)?;
+        Some((resolved, matches!(self, Self::DateTime(_))))
+    }
+}
+
+fn parse_date(s: &str) -> Option<NaiveDate> {
+    if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+        return Some(date);
+    }
+
+    let (separator, day_first) = if s.contains('/') {
+        ('/', false)
+    } else if s.contains('.') {
+        ('.', true)
+    } else {
+        return None;
+    };
+    let mut parts = s.split(separator).map(|part| part.parse::<u32>().ok());
+    let (a, b, year) = (parts.next()??, parts.next()??, parts.next()??);
+    // two digit years are too ambiguous
+    if parts.next().is_some() || year < 1000 {
+        return None;
+    }
+
+    let (day, month) = if day_first || a > 12 { (a, b) } else { (b, a) };
+    NaiveDate::from_ymd_opt(year as i32, month, day)
+}
+
+/// Resolves a wall-clock time. Ambiguous times take the earlier offset, times skipped by a DST
+/// change are moved past the gap.
+fn local<Tz: TimeZone>(tz: &Tz, naive: NaiveDateTime) -> Option<DateTime<Tz>> {
+    tz.from_local_datetime(&naive).earliest().or_else(|| {
+        tz.from_local_datetime(&(naive + TimeDelta::hours(1)))
+            .earliest()
+    })
+}
+
+/// Signed offset like `+ 1 month 2 days` or `- 3h30m`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct Span {
+    months: i32,
+    days: i64,
+    seconds: i64,
+}
+
+impl Span {
+    /// Parses one or more `(+|-) <n><unit>...` terms
+    fn parse(s: &str) -> Option<Self> {
+        let mut span = Self::default();
+        let mut rest = s.trim();
+        if rest.is_empty() {
+            return None;
+        }
+
+        while !rest.is_empty() {
+            let sign = match rest.as_bytes()[0] {
+                b'+' => 1,
+                b'-' => -1,
+                _ => return None,
+            };
+            rest = rest[1..].trim_start();
+
+            let mut terms = 0;
+            loop {
+                let digits = rest.bytes().take_while(u8::is_ascii_digit).count();
+                if digits == 0 {
+                    break;
+                }
+                let count: i64 = rest[..digits].parse().ok()?;
+                rest = rest[digits..].trim_start();
+
+                let letters = rest.bytes().take_while(u8::is_ascii_alphabetic).count();
+                let unit = DateUnit::from_name(&rest[..letters])?;
+                rest = rest[letters..].trim_start();
+
+                span.add(unit, sign * count)?;
+                terms += 1;
+            }
+            if terms == 0 {
+                return None;
+            }
+        }
+        Some(span)
+    }
+
+    fn add(&mut self, unit: DateUnit, count: i64) -> Option<()> {
+        let months = |n: i64| i32::try_from(n).ok();
+        match unit {
+            DateUnit::Second => self.seconds = self.seconds.checked_add(count)?,
+            DateUnit::Minute => self.seconds = self.seconds.checked_add(count.checked_mul(60)?)?,
+            DateUnit::Hour => self.seconds = self.seconds.checked_add(count.checked_mul(3600)?)?,
+            DateUnit::Day => self.days = self.days.checked_add(count)?,
+            DateUnit::Week => self.days = self.days.checked_add(count.checked_mul(7)?)?,
+            DateUnit::Month => self.months = self.months.checked_add(months(count)?)?,
+            DateUnit::Year => {
+                self.months = self.months.checked_add(months(count)?.checked_mul(12)?)?
+            }
+        }
+        Some(())
+    }
+
+    fn apply<Tz: TimeZone>(&self, start: DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let shifted = if self.months == 0 && self.days == 0 {
+            start
+        } else {
+            let naive = start.naive_local();
+            let naive = if self.months >= 0 {
+                naive.checked_add_months(Months::new(self.months as u32))
+            } else {
+                naive.checked_sub_months(Months::new(self.months.unsigned_abs()))
+            }?;
+            let naive = naive.checked_add_signed(TimeDelta::try_days(self.days)?)?;
+            local(&start.timezone(), naive)?
+        };
+        shifted.checked_add_signed(TimeDelta::try_seconds(self.seconds)?)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum DateQuery {
+    /// `<point> (+|-) <span>`
+    Shift { start: Point, span: Span },
+    /// `<unit> until|since <point>` or `<unit> between <point> and <point>`
+    Count {
+        unit: DateUnit,
+        from: Point,
+        to: Point,
+    },
+}
+
+impl DateQuery {
+    /// Parses a lowercase query
+    pub fn parse(s: &str) -> Option<Self> {
+        if let Some((first, rest)) = s.split_once(char::is_whitespace)
+            && let Some(unit) = DateUnit::from_name(first)
+        {
+            let (keyword, rest) = rest.trim_start().split_once(char::is_whitespace)?;
+            let rest = rest.trim();
+            // counting calendar units from today ignores the current time of day
+            let today = if unit.is_calendar() {
+                Point::Relative(0)
+            } else {
+                Point::Now
+            };
+            let (from, to) = match keyword {
+                "until" | "till" => (today, Point::parse(rest)?),
+                "since" => (Point::parse(rest)?, today),
+                "between" | "from" => {
+                    let (from, to) = rest
+                        .split_once(" and ")
+                        .or_else(|| rest.split_once(" to "))?;
+                    (Point::parse(from.trim())?, Point::parse(to.trim())?)
+                }
+                _ => return None,
+            };
+            return Some(Self::Count { unit, from, to });
+        }
+
+        let operator = s.char_indices().find(|(i, c)| match c {
+            '+' => true,
+            // dates contain dashes, a minus is separated from them or follows a keyword
+            '-' => s[..*i].ends_with(|c: char| !c.is_ascii_digit()),
+            _ => false,
+        });
+        let (start, span) = s.split_at(operator?.0);
+        Some(Self::Shift {
+            start: Point::parse(start.trim())?,
+            span: Span::parse(span)?,
+        })
+    }
+
+    /// Evaluates the query relative to `now`
+    pub fn eval<Tz: TimeZone>(&self, now: &DateTime<Tz>) -> Option<String>
+    where
+        Tz::Offset: Display,
+    {
+        match self {
+            Self::Shift { start, span } => {
+                let (start, has_time) = start.resolve(now)?;
+                let end = span.apply(start)?;
+                let format = if has_time || span.seconds != 0 {
+                    "%a, %Y-%m-%d %H:%M"
+                } else {
+                    "%a, %Y-%m-%d"
+                };
+                Some(end.format(format).to_string())
+            }
+            Self::Count { unit, from, to } => {
+                let (from, _) = from.resolve(now)?;
+                let (to, _) = to.resolve(now)?;
+                Some(count(*unit, from, to))
+            }
+        }
+    }
+}
+
+fn count<Tz: TimeZone>(unit: DateUnit, from: DateTime<Tz>, to: DateTime<Tz>) -> String {
+    let (sign, from, to) = if to < from {
+        ("-", to, from)
+    } else {
+        ("", from, to)
+    };
+
+    let elapsed = to.clone().signed_duration_since(&from);
+    let parts = match unit {
+        DateUnit::Second => vec![(elapsed.num_seconds(), DateUnit::Second)],
+        DateUnit::Minute => vec![(elapsed.num_minutes(), DateUnit::Minute)],
+        DateUnit::Hour => vec![
+            (elapsed.num_hours(), DateUnit::Hour),
+            (elapsed.num_minutes() % 60, DateUnit::Minute),
+        ],
+        _ => {
+            // wall-clock difference, a day with a DST change still counts as one day
+            let (from, to) = (from.naive_local(), to.naive_local());
+            let months = match unit {
+                DateUnit::Month | DateUnit::Year => months_between(from, to),
+                _ => 0,
+            };
+            let days = from
+                .checked_add_months(Months::new(months))
+                .map_or(0, |from| (to - from).num_days().max(0));
+            let months = i64::from(months);
+            match unit {
+                DateUnit::Week => vec![(days / 7, DateUnit::Week), (days % 7, DateUnit::Day)],
+                DateUnit::Month => vec![(months, DateUnit::Month), (days, DateUnit::Day)],
+                DateUnit::Year => vec![
+                    (months / 12, DateUnit::Year),
+                    (months % 12, DateUnit::Month),
+                    (days, DateUnit::Day),
+                ],
+                _ => vec![(days, DateUnit::Day)],
+            }
+        }
+    };
+
+    let text = parts
+        .iter()
+        .enumerate()
+        .filter(|(i, (count, _))| *i == 0 || *count != 0)
+        .map(|(_, (count, unit))| format!("{} {}", count, unit.name(*count)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("{sign}{text}")
+}
+
+/// Whole months from `from` to `to`
+fn months_between(from: NaiveDateTime, to: NaiveDateTime) -> u32 {
+    let mut months = (to.year() - from.year()) * 12 + to.month() as i32 - from.month() as i32;
+    while months > 0
+        && from
+            .checked_add_months(Months::new(months as u32))
+            .is_none_or(|end| end > to)
+    {
+        months -= 1;
+    }
+    months.max(0) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{FixedOffset, MappedLocalTime};
+
+    /// Central European time with the DST changes of 2025
+    #[derive(Clone, Copy, Debug)]
+    struct Berlin;
+    impl Berlin {
+        fn offset_at(utc: &NaiveDateTime) -> FixedOffset {
+            let start = NaiveDate::from_ymd_opt(2025, 3, 30).unwrap();
+            let end = NaiveDate::from_ymd_opt(2025, 10, 26).unwrap();
+            let summer = (start.and_hms_opt(1, 0, 0).unwrap()..end.and_hms_opt(1, 0, 0).unwrap())
+                .contains(utc);
+            FixedOffset::east_opt(if summer { 7200 } else { 3600 }).unwrap()
+        }
+    }
+    impl TimeZone for Berlin {
+        type Offset = FixedOffset;
+        fn from_offset(_: &FixedOffset) -> Self {
+            Berlin
+        }
+        fn offset_from_local_date(&self, local: &NaiveDate) -> MappedLocalTime<FixedOffset> {
+            self.offset_from_local_datetime(&local.and_time(NaiveTime::MIN))
+        }
+        fn offset_from_local_datetime(
+            &self,
+            local: &NaiveDateTime,
+        ) -> MappedLocalTime<FixedOffset> {
+            let valid: Vec<FixedOffset> = [3600, 7200]
+                .into_iter()
+                .map(|secs| FixedOffset::east_opt(secs).unwrap())
+                .filter(|offset| {
+                    let utc = *local - TimeDelta::seconds(offset.local_minus_utc().into());
+                    Self::offset_at(&utc) == *offset
+                })
+                .collect();
+            match valid[..] {
+                [offset] => MappedLocalTime::Single(offset),
+                [winter, summer] => MappedLocalTime::Ambiguous(summer, winter),
+                _ => MappedLocalTime::None,
+            }
+        }
+        fn offset_from_utc_date(&self, utc: &NaiveDate) -> FixedOffset {
+            Self::offset_at(&utc.and_time(NaiveTime::MIN))
+        }
+        fn offset_from_utc_datetime(&self, utc: &NaiveDateTime) -> FixedOffset {
+            Self::offset_at(utc)
+        }
+    }
+
+    fn eval<Tz: TimeZone>(query: &str, now: &DateTime<Tz>) -> Option<String>
+    where
+        Tz::Offset: Display,
+    {
+        DateQuery::parse(query)?.eval(now)
+    }
+
+    #[test]
+    fn test_shifts() {
+        let now = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2025, 11, 10, 14, 0, 0)
+            .unwrap();
+        assert_eq!(eval("today + 45 days", &now).unwrap(), "Thu, 2025-12-25");
+        assert_eq!(eval("now + 3h30m", &now).unwrap(), "Mon, 2025-11-10 17:30");
+        assert_eq!(eval("now-90m", &now).unwrap(), "Mon, 2025-11-10 12:30");
+        assert_eq!(
+            eval("2025-12-24 - 1 week 1 day", &now).unwrap(),
+            "Tue, 2025-12-16"
+        );
+        assert_eq!(eval("tomorrow + 1 wk", &now).unwrap(), "Tue, 2025-11-18");
+
+        // leap years
+        assert_eq!(
+            eval("2024-01-31 + 1 month", &now).unwrap(),
+            "Thu, 2024-02-29"
+        );
+        assert_eq!(
+            eval("2024-02-29 + 1 year", &now).unwrap(),
+            "Fri, 2025-02-28"
+        );
+        assert_eq!(eval("2024-02-28 + 1 day", &now).unwrap(), "Thu, 2024-02-29");
+
+        // not a calculation
+        assert_eq!(DateQuery::parse("2025-12-24"), None);
+        assert_eq!(DateQuery::parse("today + tomorrow"), None);
+        assert_eq!(DateQuery::parse("3 days to hours"), None);
+    }
+
+    #[test]
+    fn test_counts() {
+        let now = FixedOffset::east_opt(0)
+            .unwrap()
+            .with_ymd_and_hms(2025, 11, 10, 14, 0, 0)
+            .unwrap();
+        assert_eq!(eval("days until 2025-12-24", &now).unwrap(), "44 days");
+        assert_eq!(eval("days since 2025-11-12", &now).unwrap(), "-2 days");
+        assert_eq!(
+            eval("weeks between 2024-01-01 and 2024-06-01", &now).unwrap(),
+            "21 weeks, 5 days"
+        );
+        assert_eq!(
+            eval("months between 2024-01-31 and 2024-02-29", &now).unwrap(),
+            "1 month"
+        );
+        assert_eq!(
+            eval("years between 2020-02-29 and 2025-03-01", &now).unwrap(),
+            "5 years, 1 day"
+        );
+        // leap day
+        assert_eq!(
+            eval("days between 2024-02-28 and 2024-03-01", &now).unwrap(),
+            "2 days"
+        );
+        assert_eq!(
+            eval("hours until 2025-11-11 15:30", &now).unwrap(),
+            "25 hours, 30 minutes"
+        );
+    }
+
+    #[test]
+    fn test_dst() {
+        let now = Berlin.with_ymd_and_hms(2025, 3, 29, 12, 0, 0).unwrap();
+
+        // calendar units keep the wall-clock time, clock units count elapsed time
+        assert_eq!(eval("now + 1 day", &now).unwrap(), "Sun, 2025-03-30 12:00");
+        assert_eq!(eval("now + 24h", &now).unwrap(), "Sun, 2025-03-30 13:00");
+        assert_eq!(
+            eval("hours between 2025-03-30 and 2025-03-31", &now).unwrap(),
+            "23 hours"
+        );
+        assert_eq!(
+            eval("days between 2025-03-29 and 2025-04-01", &now).unwrap(),
+            "3 days"
+        );
+        assert_eq!(
+            eval("hours between 2025-10-26 and 2025-10-27", &now).unwrap(),
+            "25 hours"
+        );
+        // skipped by the change to summer time
+        assert_eq!(
+            eval("2025-03-30 02:30 + 1h", &now).unwrap(),
+            "Sun, 2025-03-30 04:30"
+        );
+    }
+
+    #[test]
+    fn test_ambiguous_dates() {
+        let date = |y, m, d| Some(Point::Date(NaiveDate::from_ymd_opt(y, m, d).unwrap()));
+        assert_eq!(Point::parse("3/4/2025"), date(2025, 3, 4));
+        assert_eq!(Point::parse("13/4/2025"), date(2025, 4, 13));
+        assert_eq!(Point::parse("3.4.2025"), date(2025, 4, 3));
+        assert_eq!(Point::parse("2025-04-03"), date(2025, 4, 3));
+        assert_eq!(Point::parse("13/13/2025"), None);
+        assert_eq!(Point::parse("3/4/25"), None);
+    }
+}
@@ -0,0 +1,675 @@
+//! Unit registry of the converter
+//!
+//! Every unit has a [`Dimension`], made of exponents of the base quantities length, mass, time
+//! and data, and a factor converting it to the SI base of that dimension, e.g. m/s for speeds.
+//! Units of the same dimension convert into each other, and units of inverse dimensions
+//! reciprocally, which covers `l/100km` to `mpg`. Compound units like `MB/s` are built from two
+//! registered units, see [`UnitExpr`].
+//!
+//! Categories group the units behind a capability. Adding a unit is a new line in the table at
+//! the end of this file.
+
+use crate::launcher::calc_launcher::CURRENCIES;
+
+/// Exponents of the base quantities a unit is made of
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct Dimension {
+    length: i8,
+    mass: i8,
+    time: i8,
+    data: i8,
+    temperature: i8,
+    currency: i8,
+}
+
+impl Dimension {
+    pub const NONE: Self = Self::mechanical(0, 0, 0);
+    pub const LENGTH: Self = Self::mechanical(1, 0, 0);
+    pub const MASS: Self = Self::mechanical(0, 1, 0);
+    pub const TIME: Self = Self::mechanical(0, 0, 1);
+    pub const DATA: Self = Self {
+        data: 1,
+        ..Self::NONE
+    };
+    pub const TEMPERATURE: Self = Self {
+        temperature: 1,
+        ..Self::NONE
+    };
+    pub const CURRENCY: Self = Self {
+        currency: 1,
+        ..Self::NONE
+    };
+
+    // derived
+    pub const AREA: Self = Self::mechanical(2, 0, 0);
+    pub const VOLUME: Self = Self::mechanical(3, 0, 0);
+    pub const SPEED: Self = Self::mechanical(1, 0, -1);
+    pub const PRESSURE: Self = Self::mechanical(-1, 1, -2);
+    pub const ENERGY: Self = Self::mechanical(2, 1, -2);
+    /// Volume per distance, e.g. `l/100km`
+    pub const FUEL_CONSUMPTION: Self = Self::mechanical(2, 0, 0);
+    /// Distance per volume, e.g. `mpg`
+    pub const FUEL_ECONOMY: Self = Self::mechanical(-2, 0, 0);
+
+    const fn mechanical(length: i8, mass: i8, time: i8) -> Self {
+        Self {
+            length,
+            mass,
+            time,
+            data: 0,
+            temperature: 0,
+            currency: 0,
+        }
+    }
+
+    pub const fn per(self, other: Self) -> Self {
+        Self {
+            length: self.length - other.length,
+            mass: self.mass - other.mass,
+            time: self.time - other.time,
+            data: self.data - other.data,
+            temperature: self.temperature - other.temperature,
+            currency: self.currency - other.currency,
+        }
+    }
+
+    pub const fn inverse(self) -> Self {
+        Self::NONE.per(self)
+    }
+}
+
+/// Dimension of a unit, the one of its category unless the unit sets its own
+macro_rules! unit_dimension {
+    ($category:expr) => {
+        $category
+    };
+    ($category:expr, $own:expr) => {
+        $own
+    };
+}
+
+macro_rules! define_units {
+    ($(
+        $category:ident, $cap_const:ident {
+            cap: $cap_val:expr,
+            dim: $dim:expr,
+            $($variant:ident: [$($alias:literal),*] => $factor:expr, $canonical_symbol:literal $(; dim: $own_dim:expr)?),* $(,)?
+        }
+    )*) => {
+        #[derive(PartialEq, Eq, Hash)]
+        #[allow(dead_code)]
+        pub enum UnitCategory { $($category),* }
+        #[allow(dead_code)]
+        impl UnitCategory {
+            pub fn capability_mask(&self) -> u32 {
+                match self {
+                    $( UnitCategory::$category => Capabilities::$cap_const, )*
+                }
+            }
+        }
+
+        #[derive(Clone, Debug)]
+        pub struct Capabilities(pub(super) u32);
+        #[allow(dead_code)]
+        impl Capabilities {
+            pub const NONE: u32 = 0;
+            $( pub const $cap_const: u32 = $cap_val; )*
+            pub const EVERYTHING: u32 = u32::MAX;
+
+            #[inline]
+            pub fn allows(&self, cap: u32) -> bool {
+                (self.0 & cap) != 0
+            }
+        }
+
+        impl std::ops::BitOr for Capabilities {
+            type Output = Self;
+            fn bitor(self, rhs: Self) -> Self {
+                Self(self.0 | rhs.0)
+            }
+        }
+
+        impl std::ops::BitOrAssign<u32> for Capabilities {
+            fn bitor_assign(&mut self, rhs: u32) {
+                self.0 |= rhs;
+            }
+        }
+
+        #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+        pub enum Unit {
+            $( $( $variant, )* )*
+        }
+
+        impl Unit {
+            /// All units, in the order ambiguous aliases are resolved in
+            const ALL: &'static [Unit] = &[ $( $( Unit::$variant, )* )* ];
+
+            pub fn category(&self) -> UnitCategory {
+                match self {
+                    $( $(Unit::$variant => UnitCategory::$category,)* )*
+                }
+            }
+
+            pub fn symbol(&self) -> &'static str {
+                match self {
+                    $( $(Unit::$variant => $canonical_symbol,)* )*
+                }
+            }
+
+            pub fn dimension(&self) -> Dimension {
+                match self {
+                    $( $(Unit::$variant => unit_dimension!($dim $(, $own_dim)?),)* )*
+                }
+            }
+
+            /// Lowercase symbols and singular names
+            fn aliases(&self) -> &'static [&'static str] {
+                match self {
+                    $( $(Unit::$variant => &[$($alias),*],)* )*
+                }
+            }
+
+            // The raw factor (for static units)
+            fn raw_factor(&self) -> f64 {
+                match self {
+                    $( $(Unit::$variant => $factor as f64,)* )*
+                }
+            }
+        }
+    };
+}
+
+impl Unit {
+    pub fn factor(&self) -> f64 {
+        // use dynamic factors for currencies
+        if let Some(code) = self.currency_code()
+            && let Some(Some(rates)) = CURRENCIES.get()
+            && let Some(rate) = rates.rate(code)
+        {
+            return 1.0 / rate as f64;
+        }
+        // use hardcoded factor
+        self.raw_factor()
+    }
+
+    /// Lowercase ISO 4217 code of currency units
+    pub fn currency_code(&self) -> Option<&'static str> {
+        let code = match self {
+            Unit::Usd => "usd",
+            Unit::Eur => "eur",
+            Unit::Jpy => "jpy",
+            Unit::Gbp => "gbp",
+            Unit::Aud => "aud",
+            Unit::Cad => "cad",
+            Unit::Chf => "chf",
+            Unit::Cny => "cny",
+            Unit::Nzd => "nzd",
+            Unit::Sek => "sek",
+            Unit::Nok => "nok",
+            Unit::Mxn => "mxn",
+            Unit::Sgd => "sgd",
+            Unit::Hkd => "hkd",
+            Unit::Krw => "krw",
+            Unit::Pln => "pln",
+            _ => return None,
+        };
+        Some(code)
+    }
+
+    /// Temperatures are the only units with an offset, they convert through kelvin
+    fn to_kelvin(self, value: f64) -> f64 {
+        match self {
+            Unit::Celsius => value + 273.15,
+            Unit::Fahrenheit => (value - 32.0) * 5.0 / 9.0 + 273.15,
+            _ => value * self.raw_factor(),
+        }
+    }
+
+    fn of_kelvin(self, kelvin: f64) -> f64 {
+        match self {
+            Unit::Celsius => kelvin - 273.15,
+            Unit::Fahrenheit => (kelvin - 273.15) * 9.0 / 5.0 + 32.0,
+            _ => kelvin / self.raw_factor(),
+        }
+    }
+
+    pub fn parse_with_capabilities(s: &str, caps: &Capabilities) -> Option<Self> {
+        Self::lookup(s, 3, |unit| caps.allows(unit.category().capability_mask()))
+    }
+
+    /// Finds the unit `s` is a symbol or name of among the units `accept` allows. Plural names
+    /// and prefixes of at least `min_prefix` characters are understood as well.
+    fn lookup(s: &str, min_prefix: usize, accept: impl Fn(Unit) -> bool) -> Option<Self> {
+        let s = s.trim().to_lowercase();
+        if s.is_empty() {
+            return None;
+        }
+        let candidates = || Self::ALL.iter().copied().filter(|unit| accept(*unit));
+        let exact = |s: &str| candidates().find(|unit| unit.aliases().contains(&s));
+        // only names are pluralized, so the singular has at least three letters
+        let singular = |suffix: &str| {
+            s.strip_suffix(suffix)
+                .filter(|singular| singular.chars().count() >= 3)
+                .and_then(&exact)
+        };
+
+        exact(&s)
+            .or_else(|| singular("es"))
+            .or_else(|| singular("s"))
+            .or_else(|| {
+                if s.chars().count() < min_prefix {
+                    return None;
+                }
+                candidates().find(|unit| {
+                    unit.aliases()
+                        .iter()
+                        .any(|alias| alias.len() > s.len() && alias.starts_with(&s))
+                })
+            })
+    }
+}
+
+/// A registered unit, optionally divided by another one as in `km/h` or `MB per second`
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub struct UnitExpr {
+    pub unit: Unit,
+    pub per: Option<Unit>,
+}
+
+impl From<Unit> for UnitExpr {
+    fn from(unit: Unit) -> Self {
+        Self { unit, per: None }
+    }
+}
+
+impl UnitExpr {
+    /// Parses both sides of `<from> to <to>`. Ambiguous names like `pound` are resolved by the
+    /// other side, capabilities only restrict `from`.
+    pub fn parse_conversion(from: &str, to: &str, caps: &Capabilities) -> Option<(Self, Self)> {
+        let allowed = |unit: Unit| caps.allows(unit.category().capability_mask());
+        let from = Self::parse(from, 3, allowed, |from| {
+            Self::parse_target(to, from).is_some()
+        })?;
+        Some((from, Self::parse_target(to, &from)?))
+    }
+
+    /// The target has to be of the same dimension as `from`, or of the inverse one
+    fn parse_target(s: &str, from: &UnitExpr) -> Option<Self> {
+        Self::parse(s, 2, |_| true, |to| from.converts_to(to))
+    }
+
+    fn parse(
+        s: &str,
+        min_prefix: usize,
+        accept: impl Fn(Unit) -> bool,
+        fits: impl Fn(&UnitExpr) -> bool,
+    ) -> Option<Self> {
+        // named units first, "km/h" is not kilometers divided by hours
+        if let Some(unit) = Unit::lookup(s, min_prefix, |unit| accept(unit) && fits(&unit.into())) {
+            return Some(unit.into());
+        }
+
+        let (unit, per) = s.split_once('/').or_else(|| s.split_once(" per "))?;
+        let expr = Self {
+            unit: Unit::lookup(unit, min_prefix, &accept)?,
+            per: Some(Unit::lookup(per, min_prefix, &accept)?),
+        };
+        // there is no such thing as °C per hour here
+        let affine = |unit: Unit| unit.dimension() == Dimension::TEMPERATURE;
+        let valid = !affine(expr.unit) && !expr.per.is_some_and(affine);
+        (valid && fits(&expr)).then_some(expr)
+    }
+
+    pub fn dimension(&self) -> Dimension {
+        match self.per {
+            Some(per) => self.unit.dimension().per(per.dimension()),
+            None => self.unit.dimension(),
+        }
+    }
+
+    pub fn factor(&self) -> f64 {
+        self.unit.factor() / self.per.map_or(1.0, |per| per.factor())
+    }
+
+    pub fn symbol(&self) -> String {
+        match self.per {
+            Some(per) => format!("{}/{}", self.unit.symbol(), per.symbol()),
+            None => self.unit.symbol().to_string(),
+        }
+    }
+
+    /// Both sides are plain currencies, which are converted through the exchange rates
+    pub fn currency_codes(&self, to: &UnitExpr) -> Option<(&'static str, &'static str)> {
+        if self.per.is_some() || to.per.is_some() {
+            return None;
+        }
+        Some((self.unit.currency_code()?, to.unit.currency_code()?))
+    }
+
+    pub fn converts_to(&self, to: &UnitExpr) -> bool {
+        let (from, to) = (self.dimension(), to.dimension());
+        from == to || (from == to.inverse() && from != Dimension::NONE)
+    }
+
+    /// Converts `value` to `to`, `None` for units of unrelated dimensions
+    pub fn convert(&self, value: f64, to: &UnitExpr) -> Option<f64> {
+        if !self.converts_to(to) {
+            return None;
+        }
+        if self.per.is_none() && to.per.is_none() && self.dimension() == Dimension::TEMPERATURE {
+            return Some(to.unit.of_kelvin(self.unit.to_kelvin(value)));
+        }
+
+        let base = value * self.factor();
+        if self.dimension() == to.dimension() {
+            Some(base / to.factor())
+        } else {
+            // e.g. l/100km to mpg
+            (base != 0.0).then(|| 1.0 / base / to.factor())
+        }
+    }
+}
+
+impl Capabilities {
+    pub fn from_strings(strs: &[String]) -> Self {
+        let mut mask = Self::NONE;
+        for s in strs {
+            mask |= match s.as_str() {
+                "calc.currencies" => Self::CURRENCY,
+                "calc.math" => Self::MATH,
+                "colors" => Self::COLORS,
+                "calc.base" => Self::BASE,
+                "calc.date" => Self::DATE,
+
+                // all units
+                "calc.units" => {
+                    Self::LENGTH
+                        | Self::VOLUME
+                        | Self::WEIGHT
+                        | Self::TEMPERATURE
+                        | Self::PRESSURE
+                        | Self::DIGITAL
+                        | Self::TIME
+                        | Self::AREA
+                        | Self::SPEED
+                        | Self::ENERGY
+                        | Self::FUEL
+                }
+
+                // individual units
+                "calc.length" => Self::LENGTH,
+                "calc.volume" => Self::VOLUME,
+                "calc.weight" | "calc.mass" => Self::WEIGHT,
+                "calc.temperature" => Self::TEMPERATURE,
+                "calc.pressure" => Self::PRESSURE,
+                "calc.digital" | "calc.data" => Self::DIGITAL,
+                "calc.time" => Self::TIME,
+                "calc.area" => Self::AREA,
+                "calc.speed" => Self::SPEED,
+                "calc.energy" => Self::ENERGY,
+                "calc.fuel" => Self::FUEL,
+
+                _ => Self::NONE,
+            }
+        }
+
+        Self(mask)
+    }
+}
+
+// Factors convert to the SI base of the dimension: meters, kilograms, seconds, bytes, kelvin.
+// Aliases are lowercase, names are singular unless the plural is irregular.
+define_units! {
+    Math, MATH {
+        cap: 1 << 0,
+        dim: Dimension::NONE,
+    }
+    Colors, COLORS {
+        cap: 1 << 1,
+        dim: Dimension::NONE,
+    }
+    Currency, CURRENCY {
+        cap: 1 << 2,
+        dim: Dimension::CURRENCY,
+        Usd: ["usd", "dollar", "bucks", "$"] => 1.0, "$",
+        Eur: ["eur", "euro", "€"] => 1.0, "€",
+        Jpy: ["jpy", "yen", "japanese yen", "¥"] => 1.0, "¥",
+        Gbp: ["gbp", "pound", "sterling", "£"] => 1.0, "£",
+        Aud: ["aud", "australian dollar", "aussie", "a$"] => 1.0, "A$",
+        Cad: ["cad", "canadian dollar", "loonie", "c$"] => 1.0, "C$",
+        Chf: ["chf", "swiss franc", "franc"] => 1.0, "CHF",
+        Cny: ["cny", "chinese yuan", "renminbi", "yuan"] => 1.0, "¥",
+        Nzd: ["nzd", "new zealand dollar", "kiwi", "nz$"] => 1.0, "NZ$",
+        Sek: ["sek", "swedish krona", "krona", "kr"] => 1.0, "kr",
+        Nok: ["nok", "norwegian krone", "krone"] => 1.0, "kr",
+        Mxn: ["mxn", "mexican peso", "peso", "mex$"] => 1.0, "Mex$",
+        Sgd: ["sgd", "singapore dollar", "s$"] => 1.0, "S$",
+        Hkd: ["hkd", "hong kong dollar", "hk$"] => 1.0, "HK$",
+        Krw: ["krw", "south korean won", "won", "₩"] => 1.0, "₩",
+        Pln: ["pln", "polish", "złoty", "zł"] => 1.0, "zł",
+    }
+    Length, LENGTH {
+        cap: 1 << 3,
+        dim: Dimension::LENGTH,
+        Millimeter: ["mm", "millimeter", "millimetre"] => 0.001, "mm",
+        Centimeter: ["cm", "centimeter", "centimetre"] => 0.01, "cm",
+        Meter: ["m", "meter", "metre"] => 1.0, "m",
+        Kilometer: ["km", "kilometer", "kilometre"] => 1000.0, "km",
+        Inch: ["in", "inch", "\""] => 0.0254, "in",
+        Feet: ["ft", "feet", "foot", "'"] => 0.3048, "ft",
+        Yard: ["yd", "yard"] => 0.9144, "yd",
+        Mile: ["mi", "mile"] => 1609.344, "mi",
+        NauticalMile: ["nm", "nmi", "nautical mile"] => 1852.0, "nmi",
+    }
+    Volume, VOLUME {
+        cap: 1 << 4,
+        dim: Dimension::VOLUME,
+        Milliliter: ["ml", "milliliter", "millilitre", "cc"] => 1e-6, "ml",
+        Centiliter: ["cl", "centiliter", "centilitre"] => 1e-5, "cl",
+        Liter: ["l", "liter", "litre"] => 1e-3, "l",
+        Kiloliter: ["kl", "kiloliter", "kilolitre"] => 1.0, "kl",
+        CubicMeter: ["m3", "m³", "cubic meter", "cubic metre"] => 1.0, "m³",
+        // US Liquid
+        Teaspoon: ["tsp", "teaspoon"] => 4.92892e-6, "tsp",
+        Tablespoon: ["tbsp", "tablespoon"] => 1.47868e-5, "tbsp",
+        FluidOunce: ["fl oz", "fluid ounce"] => 2.95735e-5, "fl oz",
+        Cup: ["cup"] => 2.36588e-4, "cup",
+        Pint: ["pt", "pint"] => 4.73176e-4, "pt",
+        Quart: ["qt", "quart"] => 9.46353e-4, "qt",
+        Gallon: ["gal", "gallon"] => 3.785411784e-3, "gal",
+        // Imperial
+        ImperialGallon: ["imp gal", "imperial gallon"] => 4.54609e-3, "imp gal",
+    }
+    Weight, WEIGHT {
+        cap: 1 << 5,
+        dim: Dimension::MASS,
+        Milligram: ["mg", "milligram"] => 0.000001, "mg",
+        Gram: ["g", "gram"] => 0.001, "g",
+        Kilogram: ["kg", "kilogram", "kilo"] => 1.0, "kg",
+        MetricTon: ["t", "tonne", "metric ton"] => 1000.0, "t",
+        // Imperial/US
+        Ounce: ["oz", "ounce"] => 0.0283495, "oz",
+        Pound: ["lb", "lbs", "pound"] => 0.453592, "lb",
+        Stone: ["st", "stone"] => 6.35029, "st",
+        ShortTon: ["ton", "us ton"] => 907.185, "ton",
+        LongTon: ["imperial ton", "uk ton"] => 1016.05, "ton",
+        // Precious Metals
+        TroyOunce: ["ozt", "troy ounce"] => 0.0311035, "ozt",
+    }
+    Temperature, TEMPERATURE {
+        cap: 1 << 6,
+        dim: Dimension::TEMPERATURE,
+        Celsius: ["c", "celsius", "°c", "°", "degree celsius", "degrees celsius"] => 1.0, "°C",
+        Fahrenheit: ["f", "fahrenheit", "°f", "degree fahrenheit", "degrees fahrenheit"] => 1.0, "°F",
+        Kelvin: ["k", "kelvin"] => 1.0, "K",
+    }
+    Pressure, PRESSURE {
+        cap: 1 << 7,
+        dim: Dimension::PRESSURE,
+        Pascal: ["pa", "pascal"] => 1.0, "Pa",
+        Hectopascal: ["hpa", "hectopascal"] => 100.0, "hPa",
+        Kilopascal: ["kpa", "kilopascal"] => 1000.0, "kPa",
+        Megapascal: ["mpa", "megapascal"] => 1e6, "MPa",
+        Millibar: ["mbar", "millibar"] => 100.0, "mbar",
+        Bar: ["bar"] => 1e5, "bar",
+        Atmosphere: ["atm", "atmosphere"] => 101325.0, "atm",
+        Psi: ["psi", "pound per square inch", "pounds per square inch"] => 6894.757, "psi",
+        Torr: ["torr", "mmhg"] => 133.322, "mmHg",
+    }
+    Digital, DIGITAL {
+        cap: 1 << 8,
+        dim: Dimension::DATA,
+        Bit: ["bit"] => 0.125, "bit",
+        Kilobit: ["kbit", "kilobit"] => 125.0, "kbit",
+        Megabit: ["mbit", "megabit"] => 125e3, "Mbit",
+        Gigabit: ["gbit", "gigabit"] => 125e6, "Gbit",
+        Byte: ["b", "byte"] => 1.0, "B",
+        // SI
+        Kilobyte: ["kb", "kilobyte"] => 1e3, "kB",
+        Megabyte: ["mb", "megabyte"] => 1e6, "MB",
+        Gigabyte: ["gb", "gigabyte"] => 1e9, "GB",
+        Terabyte: ["tb", "terabyte"] => 1e12, "TB",
+        Petabyte: ["pb", "petabyte"] => 1e15, "PB",
+        // IEC
+        Kibibyte: ["kib", "kibibyte"] => 1024.0, "KiB",
+        Mebibyte: ["mib", "mebibyte"] => 1048576.0, "MiB",
+        Gibibyte: ["gib", "gibibyte"] => 1073741824.0, "GiB",
+        Tebibyte: ["tib", "tebibyte"] => 1099511627776.0, "TiB",
+        Pebibyte: ["pib", "pebibyte"] => 1125899906842624.0, "PiB",
+        // rates
+        KilobitPerSecond: ["kbps"] => 125.0, "kbps"; dim: Dimension::DATA.per(Dimension::TIME),
+        MegabitPerSecond: ["mbps"] => 125e3, "Mbps"; dim: Dimension::DATA.per(Dimension::TIME),
+        GigabitPerSecond: ["gbps"] => 125e6, "Gbps"; dim: Dimension::DATA.per(Dimension::TIME),
+    }
+    Time, TIME {
+        cap: 1 << 9,
+        dim: Dimension::TIME,
+        Milliseconds: ["ms", "millisecond"] => 0.001, "ms",
+        Seconds: ["s", "sec", "second"] => 1.0, "s",
+        Minutes: ["min", "minute"] => 60.0, "min",
+        Hours: ["h", "hr", "hrs", "hour"] => 3600.0, "h",
+        Days: ["d", "day"] => 86400.0, "d",
+        Weeks: ["wk", "week"] => 604800.0, "wk",
+        Months: ["mo", "month"] => 2629746.0, "mo",
+        Years: ["yr", "year"] => 31556952.0, "yr",
+    }
+    Area, AREA {
+        cap: 1 << 10,
+        dim: Dimension::AREA,
+        SquareMeter: ["m2", "m²", "sq m", "sq meter", "square meter", "square metre"] => 1.0, "m²",
+        SquareKilometer: ["km2", "km²", "sq km", "square kilometer", "square kilometre"] => 1e6, "km²",
+        SquareFoot: ["ft2", "ft²", "sq ft", "sq feet", "square foot", "square feet"] => 0.092903, "ft²",
+        SquareInch: ["in2", "in²", "sq in", "square inch"] => 0.00064516, "in²",
+        SquareMile: ["mi2", "mi²", "sq mi", "square mile"] => 2589988.11, "mi²",
+        Acre: ["acre", "ac"] => 4046.86, "ac",
+        Hectare: ["ha", "hectare"] => 10000.0, "ha",
+    }
+    Speed, SPEED {
+        cap: 1 << 11,
+        dim: Dimension::SPEED,
+        MetersPerSecond: ["m/s", "mps", "meter per second", "meters per second", "metres per second"] => 1.0, "m/s",
+        KilometersPerHour: ["km/h", "kmh", "kph", "kilometer per hour", "kilometers per hour", "kilometres per hour"] => 1.0 / 3.6, "km/h",
+        MilesPerHour: ["mph", "mile per hour", "miles per hour"] => 0.44704, "mph",
+        FeetPerSecond: ["ft/s", "fps", "foot per second", "feet per second"] => 0.3048, "ft/s",
+        Knot: ["kn", "kt", "knot"] => 1852.0 / 3600.0, "kn",
+    }
+    Base, BASE {
+        cap: 1 << 12,
+        dim: Dimension::NONE,
+    }
+    Date, DATE {
+        cap: 1 << 13,
+        dim: Dimension::NONE,
+    }
+    Energy, ENERGY {
+        cap: 1 << 14,
+        dim: Dimension::ENERGY,
+        Joule: ["j", "joule"] => 1.0, "J",
+        Kilojoule: ["kj", "kilojoule"] => 1e3, "kJ",
+        Megajoule: ["mj", "megajoule"] => 1e6, "MJ",
+        Calorie: ["cal", "calorie"] => 4.184, "cal",
+        Kilocalorie: ["kcal", "kilocalorie"] => 4184.0, "kcal",
+        WattHour: ["wh", "watt hour", "watt-hour"] => 3600.0, "Wh",
+        KilowattHour: ["kwh", "kilowatt hour", "kilowatt-hour"] => 3.6e6, "kWh",
+        Electronvolt: ["ev", "electronvolt"] => 1.602176634e-19, "eV",
+        Btu: ["btu", "british thermal unit"] => 1055.06, "BTU",
+    }
+    Fuel, FUEL {
+        cap: 1 << 15,
+        dim: Dimension::FUEL_CONSUMPTION,
+        LitersPer100Km: ["l/100km", "l/100 km", "liters per 100 km", "litres per 100 km"] => 1e-3 / 1e5, "l/100km",
+        MilesPerGallon: ["mpg", "mile per gallon", "miles per gallon"] => 1609.344 / 3.785411784e-3, "mpg"; dim: Dimension::FUEL_ECONOMY,
+        ImperialMilesPerGallon: ["mpg imp", "imp mpg", "uk mpg"] => 1609.344 / 4.54609e-3, "mpg (imp)"; dim: Dimension::FUEL_ECONOMY,
+        KilometersPerLiter: ["km/l", "kmpl", "kilometers per liter", "kilometres per litre"] => 1e6, "km/l"; dim: Dimension::FUEL_ECONOMY,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn convert(value: f64, from: &str, to: &str) -> f64 {
+        let caps = Capabilities(Capabilities::EVERYTHING);
+        let (from, to) = UnitExpr::parse_conversion(from, to, &caps).unwrap();
+        from.convert(value, &to).unwrap()
+    }
+
+    fn assert_close(actual: f64, expected: f64) {
+        assert!(
+            (actual - expected).abs() <= expected.abs() * 1e-4,
+            "{actual} != {expected}"
+        );
+    }
+
+    #[test]
+    fn test_unit_families() {
+        // data sizes
+        assert_close(convert(1.0, "mib", "mb"), 1.048576);
+        assert_close(convert(1.0, "gb", "gib"), 0.931323);
+        assert_close(convert(100.0, "mbps", "mb/s"), 12.5);
+        // speeds, named and compound
+        assert_close(convert(60.0, "km/h", "m/s"), 16.6667);
+        assert_close(convert(60.0, "mph", "km/h"), 96.5606);
+        assert_close(convert(10.0, "m/s", "mph"), 22.3694);
+        assert_close(convert(1.0, "km/min", "km/h"), 60.0);
+        // pressure, energy
+        assert_close(convert(1.0, "atm", "hpa"), 1013.25);
+        assert_close(convert(1.0, "kwh", "j"), 3.6e6);
+        assert_close(convert(100.0, "kcal", "kj"), 418.4);
+        // fuel economy is reciprocal
+        assert_close(convert(10.0, "l/100km", "mpg"), 23.5215);
+        assert_close(convert(30.0, "mpg", "l/100km"), 7.84049);
+        assert_close(convert(20.0, "km/l", "l/100km"), 5.0);
+        // temperatures are affine
+        assert_close(convert(100.0, "c", "f"), 212.0);
+        assert_close(convert(0.0, "k", "c"), -273.15);
+    }
+
+    #[test]
+    fn test_names_and_plurals() {
+        let caps = Capabilities(Capabilities::EVERYTHING);
+        let parse = |s| Unit::parse_with_capabilities(s, &caps);
+        assert_eq!(parse("kilometres"), Some(Unit::Kilometer));
+        assert_eq!(parse("inches"), Some(Unit::Inch));
+        assert_eq!(parse("Pascals"), Some(Unit::Pascal));
+        assert_eq!(parse("kilowatt hours"), Some(Unit::KilowattHour));
+        assert_eq!(parse("MiB"), Some(Unit::Mebibyte));
+        assert_eq!(parse("hrs"), Some(Unit::Hours));
+        // symbols aren't pluralized
+        assert_eq!(parse("ms"), Some(Unit::Milliseconds));
+        assert_eq!(parse("kgs"), None);
+
+        // the target decides between pound sterling and the unit of mass
+        let (from, _) = UnitExpr::parse_conversion("pounds", "kg", &caps).unwrap();
+        assert_eq!(from, Unit::Pound.into());
+        let (from, _) = UnitExpr::parse_conversion("pounds", "eur", &caps).unwrap();
+        assert_eq!(from, Unit::Gbp.into());
+
+        // capabilities restrict the source, the target follows its dimension
+        let length = Capabilities(Capabilities::LENGTH);
+        assert_eq!(Unit::parse_with_capabilities("kwh", &length), None);
+        assert!(UnitExpr::parse_conversion("km", "mi", &length).is_some());
+        assert!(UnitExpr::parse_conversion("km", "kg", &length).is_none());
+        assert!(UnitExpr::parse_conversion("km", "c/h", &length).is_none());
+    }
+}
@@ -1,9 +1,19 @@
+pub mod accent;
+pub mod bidi;
 pub mod cache;
 pub mod command_launch;
+pub mod compositor;
 pub mod config;
 pub mod errors;
 pub mod files;
+pub mod first_run;
+pub mod http_action;
 pub mod intent;
 pub mod logging;
+pub mod palette;
 pub mod paths;
+pub mod sleep;
+pub mod socket;
+pub mod soft_delete;
+pub mod stats;
 pub mod websearch;
@@ -0,0 +1,162 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use gpui::{Hsla, Pixels, Rgba, WindowBackgroundAppearance, px};
+
+use crate::utils::accent::luminance;
+
+/// Contrast the high contrast overlay keeps between text and any surface, well above the 4.5
+/// required by WCAG AA
+const MIN_CONTRAST: f32 = 7.0;
+/// Surfaces are darkened to at most this relative luminance, about the one of `#262626`
+const MAX_SURFACE_LUMINANCE: f32 = 0.02;
+/// Smallest text size in high contrast mode
+const MIN_TEXT_SIZE: f32 = 14.0;
+
+static HIGH_CONTRAST: AtomicBool = AtomicBool::new(false);
+
+/// Overlay applied on top of the colors and text sizes of the active theme
+///
+/// Render code passes its colors through [`Palette::fg`] and [`Palette::bg`]. Without high
+/// contrast both return the color unchanged. With it, foregrounds are lightened and
+/// backgrounds darkened until text reaches [`MIN_CONTRAST`] on any surface. Hues are kept, so
+/// a custom accent stays recognizable while the overlay is active.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Palette {
+    pub high_contrast: bool,
+}
+
+impl Palette {
+    pub fn current() -> Self {
+        Self {
+            high_contrast: HIGH_CONTRAST.load(Ordering::Relaxed),
+        }
+    }
+
+    pub fn set_high_contrast(enabled: bool) {
+        HIGH_CONTRAST.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Flips the high contrast overlay and returns the new state
+    pub fn toggle_high_contrast() -> bool {
+        !HIGH_CONTRAST.fetch_xor(true, Ordering::Relaxed)
+    }
+
+    /// Text and other foreground colors like borders and progress bars
+    pub fn fg(&self, color: impl Into<Hsla>) -> Hsla {
+        let color = color.into();
+        if !self.high_contrast {
+            return color;
+        }
+        let min_luminance = MIN_CONTRAST * (MAX_SURFACE_LUMINANCE + 0.05) - 0.05;
+        adjust_lightness(Hsla { a: 1.0, ..color }, 0.01, |l| l >= min_luminance)
+    }
+
+    /// Background colors. Fully transparent backgrounds stay transparent, all others become
+    /// opaque.
+    pub fn bg(&self, color: impl Into<Hsla>) -> Hsla {
+        let color = color.into();
+        if !self.high_contrast || color.a == 0.0 {
+            return color;
+        }
+        adjust_lightness(Hsla { a: 1.0, ..color }, -0.01, |l| {
+            l <= MAX_SURFACE_LUMINANCE
+        })
+    }
+
+    pub fn text_size(&self, size: Pixels) -> Pixels {
+        if self.high_contrast && size < px(MIN_TEXT_SIZE) {
+            px(MIN_TEXT_SIZE)
+        } else {
+            size
+        }
+    }
+
+    /// Width of the outline around the selected row
+    pub fn selection_border(&self) -> Pixels {
+        if self.high_contrast { px(2.) } else { px(0.) }
+    }
+
+    /// The blurred window background is too busy behind high contrast text
+    pub fn window_background(&self) -> WindowBackgroundAppearance {
+        if self.high_contrast {
+            WindowBackgroundAppearance::Opaque
+        } else {
+            WindowBackgroundAppearance::Blurred
+        }
+    }
+}
+
+/// Steps the lightness of `color` until its luminance satisfies `done`, ending at white or
+/// black if it never does
+fn adjust_lightness(mut color: Hsla, step: f32, done: impl Fn(f32) -> bool) -> Hsla {
+    while !done(relative_luminance(color)) {
+        color.l += step;
+        if !(0.0..=1.0).contains(&color.l) {
+            color.l = color.l.clamp(0.0, 1.0);
+            break;
+        }
+    }
+    color
+}
+
+fn relative_luminance(color: Hsla) -> f32 {
+    let rgba = Rgba::from(color);
+    luminance((rgba.r * 255.0, rgba.g * 255.0, rgba.b * 255.0))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use gpui::{hsla, rgb};
+
+    fn contrast(a: Hsla, b: Hsla) -> f32 {
+        let (la, lb) = (relative_luminance(a), relative_luminance(b));
+        (la.max(lb) + 0.05) / (la.min(lb) + 0.05)
+    }
+
+    #[test]
+    fn test_high_contrast_meets_wcag_aa() {
+        let palette = Palette {
+            high_contrast: true,
+        };
+
+        // colors of the render code, weather backgrounds and a few accents
+        let foregrounds: Vec<Hsla> = [0x666666, 0x6e6e6e, 0x888888, 0xcccccc, 0xddd5d0, 0xffffff]
+            .into_iter()
+            .map(|c| rgb(c).into())
+            .chain([
+                hsla(0.6, 0.0217, 0.3608, 1.0),
+                hsla(1., 1., 1., 0.2),
+                hsla(0.0, 1.0, 0.3, 1.0),
+                hsla(0.66, 1.0, 0.2, 1.0),
+            ])
+            .collect();
+        let backgrounds: Vec<Hsla> = [0x0f0f0f, 0x262626, 0x3a3a3a, 0x87b2e0, 0xc8c8c8, 0xffff00]
+            .into_iter()
+            .map(|c| rgb(c).into())
+            .chain([hsla(0., 0., 0.12, 1.0), hsla(0.16, 0.6, 0.4, 1.0)])
+            .collect();
+
+        for fg in &foregrounds {
+            for bg in &backgrounds {
+                let (fg, bg) = (palette.fg(*fg), palette.bg(*bg));
+                assert!(contrast(fg, bg) >= 4.5, "{fg:?} on {bg:?}");
+            }
+        }
+    }
+
+    #[test]
+    fn test_overlay_is_opt_in() {
+        let palette = Palette::default();
+        let color: Hsla = rgb(0x666666).into();
+        assert_eq!(palette.fg(color), color);
+        assert_eq!(palette.bg(color), color);
+        assert_eq!(palette.text_size(px(11.)), px(11.));
+
+        let high_contrast = Palette {
+            high_contrast: true,
+        };
+        assert_eq!(high_contrast.text_size(px(11.)), px(MIN_TEXT_SIZE));
+        assert_eq!(high_contrast.bg(hsla(0., 0., 0., 0.)).a, 0.0);
+    }
+}
@@ -1,4 +1,4 @@
-use crate::utils::files;
+use crate::utils::{config::ConfigGuard, files};
 use std::{fs, path::PathBuf};
 
 fn get_xdg_dirs() -> xdg::BaseDirectories {
@@ -90,3 +90,24 @@ pub fn get_cache_dir() -> Result<PathBuf, crate::utils::errors::SherlockError> {
     })?;
     Ok(dir)
 }
+
+/// Returns the path of the IPC socket.
+///
+/// A path set through the config (or the `--socket` flag) always takes precedence. Otherwise
+/// this returns `$XDG_RUNTIME_DIR/sherlock/sherlock.sock`, falling back to
+/// `/tmp/sherlock-$UID.sock` if the runtime directory is unavailable.
+pub fn get_socket_path() -> PathBuf {
+    if let Some(socket) = ConfigGuard::read()
+        .ok()
+        .and_then(|config| config.files.socket.clone())
+    {
+        return socket;
+    }
+
+    get_xdg_dirs()
+        .place_runtime_file("sherlock.sock")
+        .unwrap_or_else(|_| {
+            let uid = unsafe { libc::getuid() };
+            PathBuf::from(format!("/tmp/sherlock-{}.sock", uid))
+        })
+}
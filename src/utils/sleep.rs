@@ -0,0 +1,87 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use futures::channel::mpsc::UnboundedSender;
+use zbus::blocking::{Connection, Proxy};
+
+use crate::sherlock_error;
+use crate::utils::errors::{SherlockError, SherlockErrorType};
+
+/// Unix timestamp (seconds) of the last resume from suspend/hibernate
+static LAST_RESUME: AtomicU64 = AtomicU64::new(0);
+
+/// Returns true if the system resumed from sleep after `time`.
+///
+/// Used to invalidate caches whose freshness is based on file modification times, since those
+/// do not account for the time the system spent asleep.
+pub fn resumed_since(time: SystemTime) -> bool {
+    let last = LAST_RESUME.load(Ordering::Relaxed);
+    if last == 0 {
+        return false;
+    }
+    time.duration_since(UNIX_EPOCH)
+        .map_or(true, |since| since.as_secs() < last)
+}
+
+/// Spawns a thread listening to logind's `PrepareForSleep` signal on the system bus.
+///
+/// A message is sent through `on_resume` every time the system wakes up. If the D-Bus
+/// connection drops, the watcher reconnects after a short delay.
+pub fn spawn_sleep_watcher(on_resume: UnboundedSender<()>) {
+    std::thread::spawn(move || {
+        loop {
+            if let Err(e) = watch_sleep_signals(&on_resume) {
+                eprintln!("{e}");
+            }
+            if on_resume.is_closed() {
+                break;
+            }
+            std::thread::sleep(Duration::from_secs(5));
+        }
+    });
+}
+
+fn watch_sleep_signals(on_resume: &UnboundedSender<()>) -> Result<(), SherlockError> {
+    let conn = Connection::system()
+        .map_err(|e| sherlock_error!(SherlockErrorType::DBusConnectionError, e.to_string()))?;
+    let proxy = Proxy::new(
+        &conn,
+        "org.freedesktop.login1",
+        "/org/freedesktop/login1",
+        "org.freedesktop.login1.Manager",
+    )
+    .map_err(|e| {
+        sherlock_error!(
+            SherlockErrorType::DBusMessageConstructError("PrepareForSleep".to_string()),
+            e.to_string()
+        )
+    })?;
+    let signals = proxy.receive_signal("PrepareForSleep").map_err(|e| {
+        sherlock_error!(
+            SherlockErrorType::DBusMessageConstructError("PrepareForSleep".to_string()),
+            e.to_string()
+        )
+    })?;
+
+    // the iterator ends once the connection is lost
+    for message in signals {
+        // true: about to sleep, false: just resumed
+        let Ok(going_to_sleep) = message.body().deserialize::<bool>() else {
+            continue;
+        };
+        if going_to_sleep {
+            continue;
+        }
+
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map_or(0, |d| d.as_secs());
+        LAST_RESUME.store(now, Ordering::Relaxed);
+
+        if on_resume.unbounded_send(()).is_err() {
+            return Ok(());
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,239 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    os::unix::net::UnixStream,
+    path::Path,
+    time::Duration,
+};
+
+use serde::Serialize;
+
+use crate::ui::main_window::LauncherMode;
+
+/// How long a running instance may take to answer `ping`
+const PING_TIMEOUT: Duration = Duration::from_millis(500);
+/// How often an instance that did not answer in time is pinged again
+const PING_ATTEMPTS: usize = 3;
+
+/// Commands the daemon accepts over its unix socket, one line per connection
+///
+/// | line             | effect                                                      |
+/// |------------------|-------------------------------------------------------------|
+/// | `open`           | opens a window, see `behavior.reopen` for an open one       |
+/// | `open <mode>`    | like `open`, starting in the mode with the alias `<mode>`   |
+/// | `toggle`         | closes the open window, or opens one if there is none      |
+/// | `close`          | closes the open window                                      |
+/// | `query <text>`   | types `<text>` into the open window, opening one if needed  |
+/// | `reload`         | reads the launchers again, replies with `ok` or the error   |
+/// | `reload-icons`   | rescans the custom icon paths                               |
+/// | `stats`          | replies with [`Stats`](crate::utils::stats::Stats) as JSON  |
+/// | `sources`        | replies with the provenance of every child as JSON          |
+/// | `accent`         | recomputes the accent color from the wallpaper              |
+/// | `modes`          | replies with the aliases and names of all modes as JSON     |
+/// | `ping`           | replies with `pong`, see [`probe_instance`]                 |
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SocketCommand {
+    Open { mode: Option<String> },
+    Toggle,
+    Close,
+    Query(String),
+    Reload,
+    ReloadIcons,
+    Stats,
+    Sources,
+    Accent,
+    Modes,
+    Ping,
+}
+
+impl SocketCommand {
+    /// Parses the first line of `message`. Commands without an argument reject one.
+    pub fn parse(message: &str) -> Option<Self> {
+        let line = message.lines().next()?.trim();
+        let (command, arg) = match line.split_once(char::is_whitespace) {
+            Some((command, arg)) => (command, arg.trim()),
+            None => (line, ""),
+        };
+
+        let command = match (command, arg) {
+            ("open", "") => Self::Open { mode: None },
+            ("open", mode) => Self::Open {
+                mode: Some(mode.to_string()),
+            },
+            ("query", text) => Self::Query(text.to_string()),
+            (_, "") => match command {
+                "toggle" => Self::Toggle,
+                "close" => Self::Close,
+                "reload" => Self::Reload,
+                "reload-icons" => Self::ReloadIcons,
+                "stats" => Self::Stats,
+                "sources" => Self::Sources,
+                "accent" => Self::Accent,
+                "modes" => Self::Modes,
+                "ping" => Self::Ping,
+                _ => return None,
+            },
+            _ => return None,
+        };
+        Some(command)
+    }
+}
+
+/// State of the instance behind the socket, see [`probe_instance`]
+#[derive(Debug)]
+pub enum Instance {
+    /// A connection to an instance that answered `ping`
+    Live(UnixStream),
+    /// Nothing accepts on the socket, it is missing or was left behind by a crashed instance
+    Stale,
+    /// Something accepts on the socket but did not answer `ping` in time
+    Unresponsive,
+}
+
+/// Probes the instance listening on `path`. Only a socket that refuses connections or does not
+/// exist counts as stale, an instance that is slow to answer keeps its socket.
+pub fn probe_instance(path: &Path) -> Instance {
+    for _ in 0..PING_ATTEMPTS {
+        match ping(path) {
+            // the instance reads one command per connection
+            Ok(true) => {
+                return match UnixStream::connect(path) {
+                    Ok(stream) => Instance::Live(stream),
+                    Err(e) if is_stale(&e) => Instance::Stale,
+                    Err(_) => Instance::Unresponsive,
+                };
+            }
+            Err(e) if is_stale(&e) => return Instance::Stale,
+            _ => {}
+        }
+    }
+    Instance::Unresponsive
+}
+
+/// Sends `ping` on a fresh connection and reports whether the reply was `pong`
+fn ping(path: &Path) -> std::io::Result<bool> {
+    let mut probe = UnixStream::connect(path)?;
+    probe.set_read_timeout(Some(PING_TIMEOUT))?;
+    probe.set_write_timeout(Some(PING_TIMEOUT))?;
+    probe.write_all(b"ping")?;
+
+    let mut reply = [0u8; 4];
+    probe.read_exact(&mut reply)?;
+    Ok(&reply == b"pong")
+}
+
+fn is_stale(e: &std::io::Error) -> bool {
+    matches!(e.kind(), ErrorKind::ConnectionRefused | ErrorKind::NotFound)
+}
+
+/// Entry of the reply to `modes`, e.g. `{"alias": "gg", "name": "Google Search"}`
+#[derive(Debug, PartialEq, Serialize)]
+pub struct ModeEntry<'a> {
+    pub alias: &'a str,
+    pub name: &'a str,
+}
+
+impl<'a> ModeEntry<'a> {
+    /// The modes that can be opened with `open <alias>`
+    pub fn list(modes: &'a [LauncherMode]) -> Vec<Self> {
+        modes
+            .iter()
+            .filter_map(|mode| match mode {
+                LauncherMode::Alias { short, name } => Some(Self { alias: short, name }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_commands() {
+        use SocketCommand::*;
+        let cases = [
+            ("open", Some(Open { mode: None })),
+            ("open\n", Some(Open { mode: None })),
+            (
+                "open pm",
+                Some(Open {
+                    mode: Some(String::from("pm")),
+                }),
+            ),
+            ("toggle", Some(Toggle)),
+            (" close ", Some(Close)),
+            (
+                "query  firefox nightly",
+                Some(Query(String::from("firefox nightly"))),
+            ),
+            ("query", Some(Query(String::new()))),
+            ("reload\nignored", Some(Reload)),
+            ("reload-icons", Some(ReloadIcons)),
+            ("stats", Some(Stats)),
+            ("modes", Some(Modes)),
+            ("ping", Some(Ping)),
+            ("toggle now", None),
+            ("launch", None),
+            ("", None),
+        ];
+        for (line, expected) in cases {
+            assert_eq!(SocketCommand::parse(line), expected, "{line:?}");
+        }
+    }
+
+    #[test]
+    fn test_probe_instance() {
+        use std::os::unix::net::UnixListener;
+
+        let dir = std::env::temp_dir().join(format!("sherlock-socket-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+
+        // nothing listens on the path
+        let missing = dir.join("missing.sock");
+        assert!(matches!(probe_instance(&missing), Instance::Stale));
+
+        // the socket file of a crashed instance refuses connections
+        let crashed = dir.join("crashed.sock");
+        drop(UnixListener::bind(&crashed).unwrap());
+        assert!(crashed.exists());
+        assert!(matches!(probe_instance(&crashed), Instance::Stale));
+
+        // a listener that accepts but never answers still owns its socket
+        let silent = dir.join("silent.sock");
+        let _silent_listener = UnixListener::bind(&silent).unwrap();
+        assert!(matches!(probe_instance(&silent), Instance::Unresponsive));
+
+        let live = dir.join("live.sock");
+        let listener = UnixListener::bind(&live).unwrap();
+        let instance = std::thread::spawn(move || {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut buf = [0u8; 16];
+            let n = stream.read(&mut buf).unwrap();
+            assert_eq!(
+                SocketCommand::parse(&String::from_utf8_lossy(&buf[..n])),
+                Some(SocketCommand::Ping)
+            );
+            stream.write_all(b"pong").unwrap();
+            // the connection for the actual command
+            listener.accept().unwrap();
+        });
+        assert!(matches!(probe_instance(&live), Instance::Live(_)));
+        instance.join().unwrap();
+
+        std::fs::remove_dir_all(dir).ok();
+    }
+
+    #[test]
+    fn test_mode_entries() {
+        let modes = [
+            LauncherMode::Home,
+            LauncherMode::Alias {
+                short: "gg".into(),
+                name: "Google Search".into(),
+            },
+        ];
+        let json = serde_json::to_string(&ModeEntry::list(&modes)).unwrap();
+        assert_eq!(json, r#"[{"alias":"gg","name":"Google Search"}]"#);
+    }
+}
@@ -0,0 +1,190 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant},
+};
+
+use crate::utils::errors::SherlockError;
+
+/// Time a destructive change can be undone before it is written to its store
+pub const GRACE_PERIOD: Duration = Duration::from_secs(5);
+
+static STAGED: Mutex<SoftDeletes> = Mutex::new(SoftDeletes::new());
+
+/// A destructive change that takes effect in the UI right away, but is only persisted once
+/// its grace period is over
+///
+/// Stores plug into the soft-delete mechanism by implementing this trait. `apply` and `revert`
+/// only touch in-memory state, `persist` writes to disk.
+pub trait StagedChange: Send {
+    /// Footer message, e.g. `Hidden 'Avahi Browser'`
+    fn describe(&self) -> String;
+    fn apply(&self);
+    fn revert(&self);
+    fn persist(&self) -> Result<(), SherlockError>;
+}
+
+struct Staged {
+    change: Box<dyn StagedChange>,
+    staged_at: Instant,
+}
+
+/// Stack of staged changes, the most recent one is undone first
+pub struct SoftDeletes {
+    staged: Vec<Staged>,
+}
+
+impl SoftDeletes {
+    const fn new() -> Self {
+        Self { staged: Vec::new() }
+    }
+    fn stage(&mut self, change: Box<dyn StagedChange>, now: Instant) {
+        change.apply();
+        self.staged.push(Staged {
+            change,
+            staged_at: now,
+        });
+    }
+    fn undo(&mut self) -> bool {
+        match self.staged.pop() {
+            Some(staged) => {
+                staged.change.revert();
+                true
+            }
+            None => false,
+        }
+    }
+    fn latest(&self) -> Option<String> {
+        self.staged.last().map(|staged| staged.change.describe())
+    }
+    /// Removes the changes staged before `deadline`, oldest first
+    fn take_until(&mut self, deadline: Option<Instant>) -> Vec<Staged> {
+        let split = match deadline {
+            Some(deadline) => self
+                .staged
+                .iter()
+                .position(|staged| staged.staged_at > deadline)
+                .unwrap_or(self.staged.len()),
+            None => self.staged.len(),
+        };
+        self.staged.drain(..split).collect()
+    }
+}
+
+/// Applies `change` and keeps it revertible for [`GRACE_PERIOD`]
+pub fn stage(change: Box<dyn StagedChange>) {
+    if let Ok(mut staged) = STAGED.lock() {
+        staged.stage(change, Instant::now());
+    }
+}
+
+/// Reverts the most recently staged change. Returns false if there was nothing to undo.
+pub fn undo() -> bool {
+    STAGED.lock().is_ok_and(|mut staged| staged.undo())
+}
+
+/// Description of the most recent change that can still be undone
+pub fn latest() -> Option<String> {
+    STAGED.lock().ok().and_then(|staged| staged.latest())
+}
+
+/// Persists the changes whose grace period is over
+pub fn persist_expired() {
+    persist(Instant::now().checked_sub(GRACE_PERIOD));
+}
+
+/// Persists every staged change, e.g. when the window closes
+pub fn persist_all() {
+    persist(None);
+}
+
+fn persist(deadline: Option<Instant>) {
+    // persist outside of the lock, stores might take a while to write
+    let due = match STAGED.lock() {
+        Ok(mut staged) => staged.take_until(deadline),
+        Err(_) => return,
+    };
+    for staged in due {
+        if let Err(e) = staged.change.persist() {
+            eprintln!("{e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+
+    struct Remove {
+        store: Arc<Mutex<Vec<&'static str>>>,
+        item: &'static str,
+        persisted: Arc<Mutex<Vec<&'static str>>>,
+    }
+    impl StagedChange for Remove {
+        fn describe(&self) -> String {
+            format!("Removed '{}'", self.item)
+        }
+        fn apply(&self) {
+            self.store.lock().unwrap().retain(|i| *i != self.item);
+        }
+        fn revert(&self) {
+            self.store.lock().unwrap().push(self.item);
+        }
+        fn persist(&self) -> Result<(), SherlockError> {
+            self.persisted.lock().unwrap().push(self.item);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_undo_most_recent_first() {
+        let store = Arc::new(Mutex::new(vec!["a", "b", "c"]));
+        let persisted = Arc::new(Mutex::new(Vec::new()));
+        let remove = |item| {
+            Box::new(Remove {
+                store: Arc::clone(&store),
+                item,
+                persisted: Arc::clone(&persisted),
+            })
+        };
+
+        let start = Instant::now();
+        let mut deletes = SoftDeletes::new();
+        deletes.stage(remove("a"), start);
+        deletes.stage(remove("b"), start + Duration::from_secs(1));
+        assert_eq!(*store.lock().unwrap(), vec!["c"]);
+        assert_eq!(deletes.latest().as_deref(), Some("Removed 'b'"));
+
+        assert!(deletes.undo());
+        assert_eq!(*store.lock().unwrap(), vec!["c", "b"]);
+        assert_eq!(deletes.latest().as_deref(), Some("Removed 'a'"));
+
+        assert!(deletes.undo());
+        assert!(!deletes.undo());
+        assert!(persisted.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_only_expired_changes_are_taken() {
+        let store = Arc::new(Mutex::new(vec!["a", "b"]));
+        let persisted = Arc::new(Mutex::new(Vec::new()));
+        let start = Instant::now();
+
+        let mut deletes = SoftDeletes::new();
+        for (i, item) in ["a", "b"].into_iter().enumerate() {
+            deletes.stage(
+                Box::new(Remove {
+                    store: Arc::clone(&store),
+                    item,
+                    persisted: Arc::clone(&persisted),
+                }),
+                start + Duration::from_secs(i as u64 * 3),
+            );
+        }
+
+        let due = deletes.take_until(Some(start + Duration::from_secs(1)));
+        assert_eq!(due.len(), 1);
+        assert_eq!(deletes.latest().as_deref(), Some("Removed 'b'"));
+        assert_eq!(deletes.take_until(None).len(), 1);
+    }
+}
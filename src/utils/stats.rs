@@ -0,0 +1,80 @@
+use std::{
+    sync::atomic::{AtomicU64, Ordering},
+    time::Duration,
+};
+
+use serde::Serialize;
+
+static ICON_CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static ICON_CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static ASYNC_CACHE_HITS: AtomicU64 = AtomicU64::new(0);
+static ASYNC_CACHE_MISSES: AtomicU64 = AtomicU64::new(0);
+static LAST_FILTER_US: AtomicU64 = AtomicU64::new(0);
+
+/// Counts a lookup in the in-memory icon cache
+pub fn record_icon_lookup(hit: bool) {
+    let counter = if hit {
+        &ICON_CACHE_HITS
+    } else {
+        &ICON_CACHE_MISSES
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+/// Counts an async fetch that was either served from the disk cache or went to the network
+pub fn record_async_fetch(cached: bool) {
+    let counter = if cached {
+        &ASYNC_CACHE_HITS
+    } else {
+        &ASYNC_CACHE_MISSES
+    };
+    counter.fetch_add(1, Ordering::Relaxed);
+}
+
+pub fn record_filter_duration(duration: Duration) {
+    LAST_FILTER_US.store(duration.as_micros() as u64, Ordering::Relaxed);
+}
+
+/// Response of the `stats` socket command
+///
+/// All counters are cumulative since the daemon started and are not reset by reading them.
+/// ```json
+/// {
+///   "launchers": 12,
+///   "children": 431,
+///   "icon_cache_hits": 5120,
+///   "icon_cache_misses": 410,
+///   "async_cache_hits": 7,
+///   "async_cache_misses": 2,
+///   "last_filter_us": 830
+/// }
+/// ```
+#[derive(Serialize, Debug)]
+pub struct Stats {
+    /// Number of loaded launchers that produced at least one child
+    pub launchers: usize,
+    /// Total number of children across all launchers
+    pub children: usize,
+    pub icon_cache_hits: u64,
+    pub icon_cache_misses: u64,
+    /// Async fetches (weather data, cover art) served from the disk cache
+    pub async_cache_hits: u64,
+    /// Async fetches that had to go to the network
+    pub async_cache_misses: u64,
+    /// Duration of the most recent search in microseconds
+    pub last_filter_us: u64,
+}
+
+impl Stats {
+    pub fn collect(launchers: usize, children: usize) -> Self {
+        Self {
+            launchers,
+            children,
+            icon_cache_hits: ICON_CACHE_HITS.load(Ordering::Relaxed),
+            icon_cache_misses: ICON_CACHE_MISSES.load(Ordering::Relaxed),
+            async_cache_hits: ASYNC_CACHE_HITS.load(Ordering::Relaxed),
+            async_cache_misses: ASYNC_CACHE_MISSES.load(Ordering::Relaxed),
+            last_filter_us: LAST_FILTER_US.load(Ordering::Relaxed),
+        }
+    }
+}
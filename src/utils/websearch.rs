@@ -2,7 +2,8 @@ use std::collections::HashMap;
 
 use gpui::SharedString;
 
-use super::command_launch::spawn_detached;
+use super::command_launch::{Substitution, spawn_detached};
+use crate::launcher::url_launcher::parse_url;
 use crate::utils::{
     config::{ConfigGuard, ConstantDefaults},
     errors::SherlockError,
@@ -12,7 +13,7 @@ pub fn websearch(
     mut engine: &str,
     query: &str,
     browser: Option<&str>,
-    variables: &[(SharedString, SharedString)],
+    variables: &[(SharedString, Substitution)],
 ) -> Result<(), SherlockError> {
     if is_url(query) {
         engine = "plain";
@@ -50,9 +51,16 @@ pub fn websearch(
         }
     };
 
-    let url = url_template.replace("{keyword}", &query.replace(" ", "+"));
-    let command = if browser.contains("%u") {
-        browser.replace("%u", &format!(r#" "{}""#, url))
+    let keyword = query.replace(" ", "+");
+    // templates of bang engines may use a bare `{}`
+    let url = if url_template.contains("{keyword}") {
+        url_template.replace("{keyword}", &keyword)
+    } else {
+        url_template.replace("{}", &keyword)
+    };
+    // browsers read from their desktop entry take the url through a field code
+    let command = if let Some(code) = ["%u", "%U"].into_iter().find(|c| browser.contains(c)) {
+        browser.replace(code, &format!(r#" "{}""#, url))
     } else {
         browser.push_str(&format!(r#" "{}""#, url));
         browser
@@ -61,74 +69,9 @@ pub fn websearch(
     spawn_detached(&command, query, variables)
 }
 
+/// Urls are opened as they are instead of being searched for, see [`parse_url`]
 fn is_url(input: &str) -> bool {
-    let s = input.trim();
-
-    if s.is_empty() {
-        return false;
-    }
-
-    let bytes = s.as_bytes();
-
-    // Check for <scheme>: (http:, https:, ...)
-    if let Some(colon_pos) = memchr::memchr(b':', bytes) {
-        if colon_pos == 0 {
-            return false;
-        }
-
-        if colon_pos + 2 >= bytes.len() {
-            return false;
-        }
-
-        if bytes.get(colon_pos + 1) == Some(&b'/') && bytes.get(colon_pos + 2) == Some(&b'/') {
-            return true;
-        }
-
-        return false;
-    }
-
-    if s.eq_ignore_ascii_case("localhost") {
-        return true;
-    }
-
-    // IPv4 detection
-    // Format: ddd.ddd.ddd.ddd with up to three digits & three dots
-    let mut dot_count = 0;
-    let mut digit_count = 0;
-    for &c in bytes {
-        if c == b'.' {
-            dot_count += 1;
-            if dot_count > 3 || digit_count == 0 {
-                break;
-            }
-            digit_count = 0;
-        } else if c.is_ascii_digit() {
-            digit_count += 1;
-            if digit_count > 3 {
-                break;
-            }
-        } else {
-            dot_count = 0;
-            break;
-        }
-    }
-    if dot_count == 3 {
-        return true;
-    }
-
-    // Has a single dot and no spaces → domain.com
-    if !s.contains(' ') && !s.ends_with('.') && memchr::memchr(b'.', bytes).is_some() {
-        return true;
-    }
-
-    // host:port (only digits after colon)
-    if let Some((host, port)) = s.split_once(':') {
-        if !host.contains(' ') && port.chars().all(|c| c.is_ascii_digit()) {
-            return true;
-        }
-    }
-
-    false
+    parse_url(input).is_some()
 }
 
 #[test]
@@ -142,4 +85,5 @@ fn test_url_detector() {
     assert!(!is_url("hello"));
     assert!(!is_url("rust regex"));
     assert!(!is_url("a b.com"));
+    assert!(!is_url("node.js"));
 }